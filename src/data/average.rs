@@ -3,6 +3,7 @@
 use crate::{
     clone,
     core::{Int, Real},
+    data::AverageSnapshot,
 };
 use std::ops::AddAssign;
 
@@ -15,11 +16,14 @@ pub struct Average {
     counts: Int,
     /// The total value of all accumulated samples.
     total: Real,
+    /// The total squared value of all accumulated samples.
+    total_sq: Real,
 }
 
 impl Average {
     clone!(counts: Int);
     clone!(total: Real);
+    clone!(total_sq: Real);
 
     /// This constructs a new instance of the Average struct, setting all fields
     /// to zero.
@@ -29,6 +33,7 @@ impl Average {
         Self {
             counts: 0,
             total: 0.0,
+            total_sq: 0.0,
         }
     }
 
@@ -42,12 +47,38 @@ impl Average {
             0.0
         }
     }
+
+    /// Returns the population variance of all accumulated samples.
+    #[inline]
+    #[must_use]
+    pub fn var(&self) -> Real {
+        if self.counts > 0 {
+            let ave = self.ave();
+            (self.total_sq / Real::from(self.counts) - ave * ave).max(0.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// Take a snapshot of the current statistics, leaving the accumulation unchanged.
+    #[inline]
+    #[must_use]
+    pub fn snapshot(&self) -> AverageSnapshot {
+        AverageSnapshot::new(self.counts, self.ave(), self.var())
+    }
+
+    /// Clear all accumulated samples, so statistics can be gathered over a new interval.
+    #[inline]
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
 }
 
 impl AddAssign for Average {
     #[inline]
     fn add_assign(&mut self, rhs: Self) {
         self.total += rhs.total;
+        self.total_sq += rhs.total_sq;
         self.counts += rhs.counts;
     }
 }
@@ -56,6 +87,7 @@ impl AddAssign<&Self> for Average {
     #[inline]
     fn add_assign(&mut self, rhs: &Self) {
         self.total += rhs.total;
+        self.total_sq += rhs.total_sq;
         self.counts += rhs.counts;
     }
 }
@@ -64,6 +96,7 @@ impl AddAssign<Real> for Average {
     #[inline]
     fn add_assign(&mut self, rhs: Real) {
         self.total += rhs;
+        self.total_sq += rhs * rhs;
         self.counts += 1;
     }
 }
@@ -153,4 +186,35 @@ mod tests {
         a += b;
         assert_eq!(a.counts, 300);
     }
+
+    /// This test checks that taking a snapshot leaves the accumulation unchanged,
+    /// and that resetting clears it.
+    #[test]
+    fn test_snapshot_and_reset() {
+        let mut a = Average::new();
+        for n in 1..=4 {
+            a += Real::from(n);
+        }
+
+        let snap = a.snapshot();
+        assert_eq!(snap.counts(), 4);
+        assert_approx_eq!(snap.mean(), 2.5);
+        assert_approx_eq!(snap.variance(), 1.25);
+
+        // Accumulation continues as if no snapshot was taken.
+        a += 5.0;
+        assert_eq!(a.counts, 5);
+        assert_approx_eq!(a.ave(), 3.0);
+        assert_approx_eq!(a.var(), 2.0);
+        assert_eq!(snap.counts(), 4);
+
+        a.reset();
+        assert_eq!(a.counts, 0);
+        assert_approx_eq!(a.total, 0.0);
+        assert_approx_eq!(a.total_sq, 0.0);
+        assert_eq!(a.snapshot(), AverageSnapshot::new(0, 0.0, 0.0));
+
+        a += 7.0;
+        assert_approx_eq!(a.ave(), 7.0);
+    }
 }
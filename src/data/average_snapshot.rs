@@ -0,0 +1,47 @@
+//! Point-in-time average statistics.
+
+use crate::{
+    clone,
+    core::{Int, Real},
+    fmt_report,
+};
+use std::fmt::{Display, Formatter};
+
+/// Statistics of an `Average` at the moment they were taken.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AverageSnapshot {
+    /// Number of accumulated samples.
+    counts: Int,
+    /// Mean of the accumulated samples.
+    mean: Real,
+    /// Population variance of the accumulated samples.
+    variance: Real,
+}
+
+impl AverageSnapshot {
+    clone!(counts: Int);
+    clone!(mean: Real);
+    clone!(variance: Real);
+
+    /// Construct a new instance.
+    #[inline]
+    #[must_use]
+    pub const fn new(counts: Int, mean: Real, variance: Real) -> Self {
+        Self {
+            counts,
+            mean,
+            variance,
+        }
+    }
+}
+
+impl Display for AverageSnapshot {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        writeln!(fmt, "...")?;
+        fmt_report!(fmt, self.counts, "counts");
+        fmt_report!(fmt, self.mean, "mean");
+        fmt_report!(fmt, self.variance, "variance");
+        Ok(())
+    }
+}
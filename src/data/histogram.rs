@@ -1,14 +1,16 @@
 //! Histogram implementation.
 
 use crate::{
-    access,
+    access, clone,
     data::HistogramIterator,
     err::Error,
     fmt_report,
     fs::Save,
-    tools::{Binner, Range},
+    tools::{Binner, OutOfRange, Range},
 };
 use ndarray::Array1;
+use rand::{distributions::Distribution, Rng};
+use statrs::distribution::Poisson;
 use std::{
     fmt::{Display, Formatter},
     fs::File,
@@ -24,11 +26,17 @@ pub struct Histogram {
     binner: Binner,
     /// Count data.
     counts: Array1<f64>,
+    /// Weight recorded below the range, under the overflow policy.
+    underflow: f64,
+    /// Weight recorded above the range, under the overflow policy.
+    overflow: f64,
 }
 
 impl Histogram {
     access!(binner: Binner);
     access!(counts: Array1<f64>);
+    clone!(underflow: f64);
+    clone!(overflow: f64);
 
     /// Construct a new instance.
     #[inline]
@@ -37,39 +45,70 @@ impl Histogram {
         debug_assert!(min < max);
         debug_assert!(bins > 0);
 
-        Self {
-            binner: Binner::new(Range::new(min, max), bins),
-            counts: Array1::zeros(bins as usize),
-        }
+        Self::new_binner(Binner::new(Range::new(min, max), bins))
     }
 
     /// Construct a new instance using a range.
     #[inline]
     #[must_use]
     pub fn new_range(range: Range, bins: usize) -> Self {
+        Self::new_binner(Binner::new(range, bins))
+    }
+
+    /// Construct a new instance using a binner, honouring its out-of-range policy.
+    #[inline]
+    #[must_use]
+    pub fn new_binner(binner: Binner) -> Self {
+        let bins = binner.bins();
+
         Self {
-            binner: Binner::new(range, bins),
-            counts: Array1::zeros(bins as usize),
+            binner,
+            counts: Array1::zeros(bins),
+            underflow: 0.0,
+            overflow: 0.0,
         }
     }
 
     /// Increment the bin corresponding to x by unity.
+    /// Values outside of the range are treated according to the binner's policy.
     #[inline]
     pub fn collect(&mut self, x: f64) {
-        debug_assert!(self.binner.range().contains(x));
-
-        let index = self.binner.bin(x);
-        self.counts[index] += 1.0;
+        self.collect_weighted(x, 1.0);
     }
 
     /// Increment the bin corresponding to x by a given weight.
+    /// Values outside of the range are treated according to the binner's policy.
     #[inline]
-    pub fn collect_weight(&mut self, x: f64, weight: f64) {
-        debug_assert!(self.binner.range().contains(x));
+    pub fn collect_weighted(&mut self, x: f64, weight: f64) {
         debug_assert!(weight > 0.0);
 
-        let index = self.binner.bin(x);
-        self.counts[index] += weight;
+        self.record(x, weight);
+    }
+
+    /// Record a weight at x, applying the out-of-range policy.
+    #[inline]
+    fn record(&mut self, x: f64, weight: f64) {
+        if self.binner.range().contains(x) {
+            let index = self.binner.bin(x);
+            self.counts[index] += weight;
+            return;
+        }
+
+        let below = x < self.binner.range().min();
+        match self.binner.policy() {
+            OutOfRange::Clamp => {
+                let index = if below { 0 } else { self.binner.bins() - 1 };
+                self.counts[index] += weight;
+            }
+            OutOfRange::Drop => {}
+            OutOfRange::Overflow => {
+                if below {
+                    self.underflow += weight;
+                } else {
+                    self.overflow += weight;
+                }
+            }
+        }
     }
 
     /// Increment the bin corresponding to x by unity if x is contained within the range.
@@ -80,7 +119,7 @@ impl Histogram {
         }
     }
 
-    /// Increment the bin corresponding to x by unity if x is contained within the range.
+    /// Increment the bin corresponding to x by a given weight if x is contained within the range.
     #[inline]
     pub fn try_collect_weight(&mut self, x: f64, weight: f64) {
         if let Some(index) = self.binner.try_bin(x) {
@@ -88,6 +127,45 @@ impl Histogram {
         }
     }
 
+    /// Normalise the counts to a probability density, so that the histogram integrates to unity.
+    /// Each bin is divided by the total in-range count and its own width.
+    /// Weight recorded as underflow or overflow does not contribute to the total.
+    #[inline]
+    #[must_use]
+    pub fn as_density(&self) -> Array1<f64> {
+        let total = self.counts.sum();
+        if total <= 0.0 {
+            return Array1::zeros(self.counts.len());
+        }
+
+        let density: Vec<_> = self
+            .counts
+            .iter()
+            .enumerate()
+            .map(|(index, count)| {
+                let (lower, upper) = self.binner.bin_edges(index);
+                count / (total * (upper - lower))
+            })
+            .collect();
+        Array1::from(density)
+    }
+
+    /// Replace each in-range bin count with a draw from a Poisson distribution of that mean,
+    /// emulating the counting noise of a photon-counting detector.
+    /// Empty bins remain empty.
+    #[allow(clippy::expect_used)]
+    #[inline]
+    pub fn apply_poisson_noise<R: Rng>(&mut self, rng: &mut R) {
+        for count in self.counts.iter_mut() {
+            if *count > 0.0 {
+                *count = Poisson::new(*count)
+                    .expect("Invalid Poisson mean.")
+                    .sample(rng);
+            }
+        }
+    }
+
+    /// Iterate over the lower edges of the bins and their accumulated weights.
     #[inline]
     pub fn iter(&self) -> HistogramIterator {
         HistogramIterator::new(self)
@@ -101,6 +179,8 @@ impl AddAssign<&Self> for Histogram {
         debug_assert!(self.counts.len() == rhs.counts.len());
 
         self.counts += &rhs.counts;
+        self.underflow += rhs.underflow;
+        self.overflow += rhs.overflow;
     }
 }
 
@@ -109,11 +189,8 @@ impl Save for Histogram {
     fn save_data(&self, path: &Path) -> Result<(), Error> {
         let mut file = File::create(path)?;
 
-        let mut center = self.binner.range().min();
-        let delta = self.binner.range().width() / (self.counts.len() - 1) as f64;
-        for count in &self.counts {
-            center += delta;
-            writeln!(file, "{:>32}, {:<32}", center, count)?;
+        for (index, count) in self.counts.iter().enumerate() {
+            writeln!(file, "{:>32}, {:<32}", self.binner.bin_centre(index), count)?;
         }
 
         Ok(())
@@ -125,6 +202,8 @@ impl Display for Histogram {
     fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
         fmt_report!(fmt, self.binner, "binner");
         fmt_report!(fmt, self.counts.sum(), "total counts");
+        fmt_report!(fmt, self.underflow, "underflow");
+        fmt_report!(fmt, self.overflow, "overflow");
         Ok(())
     }
 }
@@ -134,7 +213,12 @@ mod tests {
     use std::io::Read;
 
     use super::Histogram;
-    use crate::{tools::Range, fs::Save};
+    use crate::{
+        fs::Save,
+        tools::{Binner, OutOfRange, Range},
+    };
+    use assert_approx_eq::assert_approx_eq;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
     use tempfile::NamedTempFile;
 
     #[test]
@@ -187,10 +271,10 @@ mod tests {
     }
 
     #[test]
-    fn test_collect_weight() {
+    fn test_collect_weighted() {
         let mut hist = Histogram::new(0.0, 1.0, 10);
         // Try collecting something in the range.
-        hist.collect_weight(0.55, 0.5);
+        hist.collect_weighted(0.55, 0.5);
 
         // Check that we only have one sample, weighted by the correct weight. 
         assert_eq!(hist.iter().map(|(_, count)| count).sum::<f64>(), 0.5);
@@ -199,6 +283,21 @@ mod tests {
         hist.iter().for_each(|(bin, count)| assert_eq!(count, if bin == 0.5 { 0.5 } else {0.0}));
     }
 
+    #[test]
+    fn test_collect_weighted_doubles() {
+        let mut unit = Histogram::new(0.0, 1.0, 10);
+        let mut double = Histogram::new(0.0, 1.0, 10);
+        unit.collect(0.55);
+        unit.collect_weighted(0.25, 1.0);
+        double.collect_weighted(0.55, 2.0);
+        double.collect_weighted(0.25, 2.0);
+
+        for ((_, a), (_, b)) in unit.iter().zip(double.iter()) {
+            assert_eq!(b, 2.0 * a);
+        }
+        assert_eq!(double.counts().sum(), 4.0);
+    }
+
     #[test]
     fn test_try_collect_weight() {
         let mut hist = Histogram::new(0.0, 1.0, 10);
@@ -254,4 +353,73 @@ mod tests {
         assert!(fileop.read_to_string(&mut buf).is_ok());
         assert_eq!(buf.lines().count(), 10);
     }
+
+    #[test]
+    fn test_out_of_range_policies() {
+        let binner = Binner::new(Range::new(0.0, 1.0), 10);
+
+        let mut overflow = Histogram::new_binner(binner.clone().with_policy(OutOfRange::Overflow));
+        overflow.collect(-0.5);
+        overflow.collect_weighted(1.5, 2.0);
+        overflow.collect(0.55);
+        assert_eq!(overflow.underflow(), 1.0);
+        assert_eq!(overflow.overflow(), 2.0);
+        assert_eq!(overflow.counts().sum(), 1.0);
+
+        let mut drop = Histogram::new_binner(binner.clone().with_policy(OutOfRange::Drop));
+        drop.collect(-0.5);
+        assert_eq!(drop.underflow(), 0.0);
+        assert_eq!(drop.counts().sum(), 0.0);
+
+        let mut clamp = Histogram::new_binner(binner);
+        clamp.collect(-0.5);
+        assert_eq!(clamp.counts()[0], 1.0);
+        assert_eq!(clamp.underflow(), 0.0);
+    }
+
+    #[test]
+    fn test_as_density_uniform() {
+        let mut rng = rand::thread_rng();
+        let mut hist = Histogram::new(2.0, 6.0, 8);
+        for _ in 0..100_000 {
+            hist.collect(rng.gen_range(2.0..6.0));
+        }
+
+        let density = hist.as_density();
+        for d in density.iter() {
+            assert_approx_eq!(*d, 0.25, 0.01);
+        }
+
+        let integral: f64 = density.iter().map(|d| d * hist.binner().bin_width()).sum();
+        assert_approx_eq!(integral, 1.0, 1.0e-9);
+    }
+
+    #[test]
+    fn test_poisson_noise_statistics() {
+        let mut clean = Histogram::new(0.0, 3.0, 3);
+        clean.collect_weighted(0.5, 4.0);
+        clean.collect_weighted(1.5, 25.0);
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let num_real = 20_000;
+        let mut sums = [0.0; 3];
+        let mut sq_sums = [0.0; 3];
+        for _ in 0..num_real {
+            let mut noisy = clean.clone();
+            noisy.apply_poisson_noise(&mut rng);
+            for (index, count) in noisy.counts().iter().enumerate() {
+                assert_eq!(count.fract(), 0.0);
+                sums[index] += count;
+                sq_sums[index] += count * count;
+            }
+        }
+
+        // The mean matches the noise-free count, and the variance equals the mean.
+        for (index, expected) in clean.counts().iter().enumerate() {
+            let mean = sums[index] / f64::from(num_real);
+            let var = (sq_sums[index] / f64::from(num_real)) - (mean * mean);
+            assert_approx_eq!(mean, expected, 0.02 * expected.max(1.0));
+            assert_approx_eq!(var, expected, 0.05 * expected.max(1.0));
+        }
+    }
 }
@@ -1,8 +1,12 @@
 //! Data organisation and reduction structures.
 
 pub mod average;
+pub mod average_snapshot;
 pub mod histogram;
 pub mod histogram_iter;
+pub mod reduce;
 pub mod table;
 
-pub use self::{average::*, histogram::*, histogram_iter::*, table::*};
+pub use self::{
+    average::*, average_snapshot::*, histogram::*, histogram_iter::*, reduce::*, table::*,
+};
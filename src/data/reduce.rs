@@ -0,0 +1,84 @@
+//! Volume reduction functions.
+
+use crate::{
+    err::Error,
+    ord::{X, Y, Z},
+};
+use arctk_attr::file;
+use ndarray::{s, Array3};
+
+/// Method used to combine blocks of voxels.
+#[file]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Reduction {
+    /// Sum the block, conserving the total of the volume.
+    Sum,
+    /// Average the block, conserving the mean of the volume.
+    Mean,
+}
+
+/// Downsample a volume by combining blocks of `factor` voxels along each axis.
+/// # Errors
+/// if a factor is zero, or does not divide the resolution of the volume.
+#[inline]
+pub fn downsample(
+    vol: &Array3<f64>,
+    factor: [usize; 3],
+    reduction: Reduction,
+) -> Result<Array3<f64>, Error> {
+    let res = vol.shape();
+    for dim in [X, Y, Z].iter() {
+        if factor[*dim] == 0 || res[*dim] % factor[*dim] != 0 {
+            return Err(Error::Text(format!(
+                "Volume resolution {:?} is not divisible by downsampling factor {:?}.",
+                res, factor
+            )));
+        }
+    }
+
+    let block_size = (factor[X] * factor[Y] * factor[Z]) as f64;
+    let out_res = [res[X] / factor[X], res[Y] / factor[Y], res[Z] / factor[Z]];
+
+    Ok(Array3::from_shape_fn(out_res, |(xi, yi, zi)| {
+        let block = vol.slice(s![
+            (xi * factor[X])..((xi + 1) * factor[X]),
+            (yi * factor[Y])..((yi + 1) * factor[Y]),
+            (zi * factor[Z])..((zi + 1) * factor[Z])
+        ]);
+
+        match reduction {
+            Reduction::Sum => block.sum(),
+            Reduction::Mean => block.sum() / block_size,
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_downsample_sum_conserves_total() {
+        let vol = Array3::from_shape_fn([4, 6, 2], |(x, y, z)| (x + (2 * y) + (3 * z)) as f64);
+        let coarse = downsample(&vol, [2, 2, 2], Reduction::Sum).unwrap();
+
+        assert_eq!(coarse.shape(), &[2, 3, 1]);
+        assert!((coarse.sum() - vol.sum()).abs() < 1.0e-9);
+    }
+
+    #[test]
+    fn test_downsample_mean() {
+        let vol = Array3::from_elem([2, 2, 2], 3.0);
+        let coarse = downsample(&vol, [2, 2, 2], Reduction::Mean).unwrap();
+
+        assert_eq!(coarse.shape(), &[1, 1, 1]);
+        assert_eq!(coarse[[0, 0, 0]], 3.0);
+    }
+
+    #[test]
+    fn test_downsample_non_divisible() {
+        let vol = Array3::<f64>::zeros([3, 2, 2]);
+        assert!(downsample(&vol, [2, 2, 2], Reduction::Sum).is_err());
+        assert!(downsample(&vol, [0, 1, 1], Reduction::Sum).is_err());
+    }
+}
@@ -19,6 +19,7 @@ pub struct Table<T> {
 }
 
 impl<T> Table<T> {
+    access!(headings: Vec<String>);
     access!(rows: Vec<Vec<T>>);
 
     /// Construct a new instance.
@@ -24,6 +24,8 @@ pub enum Error {
     WriteJson(serde_json::Error),
     /// Png writing error.
     WritePng(png::EncodingError),
+    /// OpenEXR reading or writing error.
+    Exr(exr::error::Error),
     /// Shape error.
     InvalidShape(ndarray::ShapeError),
     /// Min/max error.
@@ -32,6 +34,17 @@ pub enum Error {
     NetCdf(netcdf::error::Error),
     /// Lidrs Error.
     Lidrs(lidrs::err::Error),
+    /// Error with the path of the input field that caused it.
+    Context(String, Box<Error>),
+    /// Mismatched array shapes when combining or resampling data.
+    ShapeMismatch {
+        /// Expected shape.
+        expected: Vec<usize>,
+        /// Shape found.
+        found: Vec<usize>,
+        /// Operation being performed.
+        context: String,
+    },
 }
 
 macro_rules! impl_from_for_err {
@@ -45,6 +58,42 @@ macro_rules! impl_from_for_err {
     };
 }
 
+/// Check that a shape matches the expected shape.
+/// # Errors
+/// if the shapes differ, describing the operation and both shapes.
+#[inline]
+pub fn check_shape(context: &str, expected: &[usize], found: &[usize]) -> Result<(), Error> {
+    if expected == found {
+        Ok(())
+    } else {
+        Err(Error::ShapeMismatch {
+            expected: expected.to_vec(),
+            found: found.to_vec(),
+            context: context.to_owned(),
+        })
+    }
+}
+
+impl Error {
+    /// Prefix the error with a segment of the path to the field that caused it.
+    /// Index segments (beginning with `[`) are joined directly, while names are joined with a `.`.
+    #[inline]
+    #[must_use]
+    pub fn with_context(self, segment: &str) -> Self {
+        match self {
+            Self::Context(path, err) => {
+                let path = if path.starts_with('[') {
+                    format!("{}{}", segment, path)
+                } else {
+                    format!("{}.{}", segment, path)
+                };
+                Self::Context(path, err)
+            }
+            err => Self::Context(segment.to_owned(), Box::new(err)),
+        }
+    }
+}
+
 impl From<&str> for Error {
     #[inline]
     fn from(err: &str) -> Self {
@@ -70,6 +119,7 @@ impl_from_for_err!(Self::InvalidShape, ndarray::ShapeError);
 impl_from_for_err!(Self::MinMax, ndarray_stats::errors::MinMaxError);
 impl_from_for_err!(Self::NetCdf, netcdf::error::Error);
 impl_from_for_err!(Self::WritePng, png::EncodingError);
+impl_from_for_err!(Self::Exr, exr::error::Error);
 impl_from_for_err!(Self::Lidrs, lidrs::err::Error);
 
 impl Debug for Error {
@@ -89,10 +139,13 @@ impl Debug for Error {
                 Self::ReadJson { .. } => "Json reading",
                 Self::WriteJson { .. } => "Json writing",
                 Self::WritePng { .. } => "PNG writing",
+                Self::Exr { .. } => "OpenEXR IO",
                 Self::InvalidShape { .. } => "Invalid array shape",
                 Self::MinMax { .. } => "MinMax",
                 Self::NetCdf { .. } => "NetCDF IO",
                 Self::Lidrs { .. } => "Lidrs",
+                Self::Context { .. } => "Input field",
+                Self::ShapeMismatch { .. } => "Shape mismatch",
             },
             match *self {
                 Self::Text { 0: ref err } => format!("{:?}", err),
@@ -105,10 +158,17 @@ impl Debug for Error {
                 Self::ReadJson { 0: ref err } => format!("{:?}", err),
                 Self::WriteJson { 0: ref err } => format!("{:?}", err),
                 Self::WritePng { 0: ref err } => format!("{:?}", err),
+                Self::Exr { 0: ref err } => format!("{:?}", err),
                 Self::InvalidShape { 0: ref err } => format!("{:?}", err),
                 Self::MinMax { 0: ref err } => format!("{:?}", err),
                 Self::NetCdf { 0: ref err } => format!("{:?}", err),
                 Self::Lidrs { 0: ref err } => format!("{:?}", err),
+                Self::Context(ref path, ref err) => format!("{}: {:?}", path, err),
+                Self::ShapeMismatch {
+                    ref expected,
+                    ref found,
+                    ref context,
+                } => format!("{}: expected {:?}, found {:?}", context, expected, found),
             }
         )
     }
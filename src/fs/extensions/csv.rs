@@ -1,17 +1,18 @@
 //! Comma-Separated-Variable file handling.
 
-use crate::{data::Table, err::Error, fs::File};
-use std::{
-    io::{BufRead, BufReader},
-    path::Path,
-    str::FromStr,
+use crate::{
+    data::Table,
+    err::Error,
+    fs::{open_reader, File},
+    phys::{MeasuredBrdf, RamanSpectrum},
 };
+use std::{io::BufRead, path::Path, str::FromStr};
 
 impl<T: FromStr> File for Table<T> {
     #[inline]
     fn load(path: &Path) -> Result<Self, Error> {
         // Load all of the lines into a vector of lines.
-        let mut lines: Vec<_> = BufReader::new(std::fs::File::open(path)?)
+        let mut lines: Vec<_> = open_reader(path)?
             .lines()
             .map(Result::unwrap)
             .filter(|line| !line.starts_with("//"))
@@ -39,3 +40,17 @@ impl<T: FromStr> File for Table<T> {
         Ok(Self::new(headings, rows))
     }
 }
+
+impl File for MeasuredBrdf {
+    #[inline]
+    fn load(path: &Path) -> Result<Self, Error> {
+        Self::from_table(&Table::load(path)?)
+    }
+}
+
+impl File for RamanSpectrum {
+    #[inline]
+    fn load(path: &Path) -> Result<Self, Error> {
+        Self::from_table(&Table::load(path)?)
+    }
+}
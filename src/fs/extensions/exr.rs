@@ -0,0 +1,80 @@
+//! OpenEXR image reading and writing.
+
+use crate::{
+    err::Error,
+    img::{Colour, Image},
+};
+use exr::prelude::{read_first_rgba_layer_from_file, write_rgba_file};
+use ndarray::Array2;
+use std::path::Path;
+
+impl Image {
+    /// Save the image as an OpenEXR file, retaining the full floating-point precision of the linear colour values.
+    /// Unlike png output, values outside of the unit range are preserved, allowing tone mapping at a later stage.
+    /// # Errors
+    /// if the file can not be created or the image data can not be written.
+    #[inline]
+    pub fn save_exr(&self, path: &Path) -> Result<(), Error> {
+        println!("[SAVE] {}", path.display());
+
+        let res = (self.pixels().shape()[0], self.pixels().shape()[1]);
+        write_rgba_file(path, res.0, res.1, |x, y| {
+            let col = self.pixels()[(x, res.1 - y - 1)];
+            (col.red, col.green, col.blue, col.alpha)
+        })?;
+
+        Ok(())
+    }
+
+    /// Load an image from the first rgba layer of an OpenEXR file.
+    /// # Errors
+    /// if the file can not be read or does not contain an rgba layer.
+    #[inline]
+    pub fn load_exr(path: &Path) -> Result<Self, Error> {
+        let file = read_first_rgba_layer_from_file(
+            path,
+            |res, _| {
+                Array2::from_elem((res.width(), res.height()), Colour::new(0.0, 0.0, 0.0, 0.0))
+            },
+            |pixels: &mut Array2<Colour>, pos, (r, g, b, a): (f32, f32, f32, f32)| {
+                let height = pixels.shape()[1];
+                pixels[(pos.x(), height - pos.y() - 1)] = Colour::new(r, g, b, a);
+            },
+        )?;
+
+        Ok(Self::new(file.layer_data.channel_data.pixels))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::img::{Colour, Image};
+    use ndarray::arr2;
+
+    #[test]
+    fn test_exr_round_trip() {
+        let img = Image::new(arr2(&[
+            [
+                Colour::new(4.0, 0.5, 100.0, 1.0),
+                Colour::new(-0.25, 0.0, 1.0e-6, 0.5),
+            ],
+            [
+                Colour::new(1.5, 65_504.0, 0.125, 1.0),
+                Colour::new(0.1, 0.2, 0.3, 0.0),
+            ],
+            [
+                Colour::new(3.0, 2.0, 1.0, 1.0),
+                Colour::new(1.0e5, 7.25, 0.333, 0.75),
+            ],
+        ]));
+        let file = tempfile::Builder::new().suffix(".exr").tempfile().unwrap();
+
+        img.save_exr(file.path()).unwrap();
+        let loaded = Image::load_exr(file.path()).unwrap();
+
+        assert_eq!(loaded.pixels().shape(), img.pixels().shape());
+        for (a, b) in img.pixels().iter().zip(loaded.pixels().iter()) {
+            assert_eq!(a, b);
+        }
+    }
+}
@@ -3,11 +3,12 @@
 //! JSON file handling through the [`serde`](https://crates.io/crates/serde) crate.
 //! Note that this supports JSON, as well as the JSON5 dialect, through the [`json5`](https://crates.io/crates/json5) crate.
 
-use crate::err::Error;
+use crate::{err::Error, fs::read_to_string};
 use serde::Deserialize;
-use std::{fs::read_to_string, path::Path};
+use std::path::Path;
 
 /// Deserialise the type in json format.
+/// Gzip-compressed files are decompressed transparently.
 /// # Errors
 /// if file can not be opened or read string can not be serialised into an instance of the required type.
 #[inline]
@@ -33,6 +34,7 @@ where
 #[cfg(test)]
 mod test {
     use super::{from_json, from_json_str};
+    use flate2::{write::GzEncoder, Compression};
     use serde_derive::Deserialize;
     use std::io::Write;
     use tempfile::NamedTempFile;
@@ -83,4 +85,25 @@ mod test {
             }
         );
     }
+
+    /// Testing to see whether a gzip-compressed JSON file deserialises
+    /// identically to the plain file.
+    #[test]
+    fn test_gzip_file_deserialisation() {
+        let mut plain_file = NamedTempFile::new().unwrap();
+        plain_file.write_all(JSON_STR.as_bytes()).unwrap();
+
+        let mut gz_file = tempfile::Builder::new()
+            .suffix(".json.gz")
+            .tempfile()
+            .unwrap();
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(JSON_STR.as_bytes()).unwrap();
+        gz_file.write_all(&encoder.finish().unwrap()).unwrap();
+
+        let plain: TestStruct = from_json(plain_file.path()).unwrap();
+        let compressed: TestStruct = from_json(gz_file.path()).unwrap();
+
+        assert_eq!(plain, compressed);
+    }
 }
@@ -1,5 +1,86 @@
-use crate::{err::Error, fs::File, math::stat::SphericalCdf};
-use std::path::Path;
+use crate::{
+    err::{check_shape, Error},
+    fs::{File, Save},
+    math::stat::SphericalCdf,
+};
+use lidrs::photweb::{IntensityUnits, PhotometricWeb, Plane, PlaneOrientation};
+use ndarray::Array2;
+use std::{fs::write, path::Path};
+
+/// Construct a photometric web from angularly resolved intensity data.
+/// Azimuthal and polar angles are given in radians,
+/// and intensities are indexed by azimuthal plane then polar angle.
+/// # Errors
+/// if the intensity data does not match the number of angles.
+#[inline]
+pub fn photweb_from_angular(
+    azimuths: &[f64],
+    polars: &[f64],
+    intensities: &Array2<f64>,
+) -> Result<PhotometricWeb, Error> {
+    check_shape(
+        "angular intensities",
+        &[azimuths.len(), polars.len()],
+        intensities.shape(),
+    )?;
+
+    let polars_deg: Vec<f64> = polars.iter().map(|ang| ang.to_degrees()).collect();
+    let planes = azimuths
+        .iter()
+        .zip(intensities.outer_iter())
+        .map(|(azim, intens)| {
+            let mut plane = Plane::new();
+            plane.set_angles_degrees(&polars_deg);
+            plane.set_intensities(intens.to_vec());
+            plane.set_angle_degrees(azim.to_degrees());
+            plane.set_units(IntensityUnits::Candela);
+            plane.set_orientation(PlaneOrientation::Vertical);
+            plane
+        })
+        .collect();
+
+    let mut photweb = PhotometricWeb::new();
+    photweb.set_planes(planes);
+    Ok(photweb)
+}
+
+impl Save for PhotometricWeb {
+    /// Save the web as an IESNA LM-63-2002 file using absolute photometry.
+    #[inline]
+    fn save_data(&self, path: &Path) -> Result<(), Error> {
+        let planes = self.planes();
+        let first = planes
+            .first()
+            .ok_or("Photometric web contains no planes.")?;
+
+        let join = |vals: &mut dyn Iterator<Item = f64>| {
+            vals.map(|val| format!("{}", val))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+
+        let mut lines = vec![
+            "IESNA:LM-63-2002".to_owned(),
+            "[TEST] Aetherus simulation".to_owned(),
+            "TILT=NONE".to_owned(),
+            format!("1 -1 1 {} {} 1 2 0 0 0", first.angles().len(), planes.len()),
+            "1 1 0".to_owned(),
+            join(&mut first.angles().iter().map(|ang| ang.to_degrees())),
+            join(&mut planes.iter().map(|plane| plane.angle().to_degrees())),
+        ];
+        for plane in planes {
+            if plane.angles().len() != first.angles().len() {
+                return Err(Error::Text(
+                    "Photometric web planes must share the same polar angles.".to_owned(),
+                ));
+            }
+            lines.push(join(&mut plane.intensities().iter().copied()));
+        }
+
+        write(path, lines.join("\n") + "\n")?;
+        Ok(())
+    }
+}
 
 impl File for SphericalCdf {
     #[inline]
@@ -16,7 +97,14 @@ mod tests {
     use std::io::Write;
     use tempfile::tempdir;
 
-    use crate::{fs::File, math::stat::SphericalCdf};
+    use super::photweb_from_angular;
+    use crate::{
+        fs::{File, Save},
+        math::stat::SphericalCdf,
+    };
+    use lidrs::photweb::PhotometricWebBuilder;
+    use ndarray::Array2;
+    use std::f64::consts::PI;
 
     const IES_STR: &str = "IESNA: LM-63-2002 
 [TEST] ABC1234 
@@ -79,4 +167,28 @@ TILT=INCLUDE
 
         let _ = dir.close();
     }
+
+    #[test]
+    fn test_export_lambertian_photweb() {
+        let azimuths: Vec<f64> = (0..=4).map(|i| f64::from(i) * PI / 2.0).collect();
+        let polars: Vec<f64> = (0..=18).map(|i| f64::from(i) * PI / 18.0).collect();
+        let intens = Array2::from_shape_fn([azimuths.len(), polars.len()], |(_, pi)| {
+            polars[pi].cos().max(0.0)
+        });
+
+        let photweb = photweb_from_angular(&azimuths, &polars, &intens).unwrap();
+        assert_eq!(photweb.n_planes(), azimuths.len());
+        assert!(photweb_from_angular(&azimuths[1..], &polars, &intens).is_err());
+
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("lambertian.ies");
+        photweb.save_data(&file_path).unwrap();
+
+        let reloaded = PhotometricWebBuilder::from_file(&file_path)
+            .build()
+            .unwrap();
+        assert_eq!(reloaded.n_planes(), azimuths.len());
+
+        let _ = dir.close();
+    }
 }
@@ -1,6 +1,10 @@
 //! Png writing.
 
-use crate::{err::Error, fs::Save, img::Image};
+use crate::{
+    err::Error,
+    fs::Save,
+    img::{Colour, Image, ToneMap},
+};
 use ndarray::{Array2, ShapeBuilder};
 use palette::{Pixel, Srgba};
 use png::{BitDepth, ColorType, Encoder};
@@ -10,27 +14,70 @@ use std::{fs::File, io::BufWriter, path::Path};
 impl Save for Image {
     #[inline]
     fn save_data(&self, path: &Path) -> Result<(), Error> {
-        let res = (self.pixels().shape()[0], self.pixels().shape()[1]);
-        let mut data: Array2<[u8; 4]> = Array2::from_elem((res.0, res.1).f(), [0; 4]);
-        for xi in 0..res.0 {
-            for yi in 0..res.1 {
-                let col = self.pixels()[(xi, yi)];
-                data[(xi, res.1 - yi - 1)] = Srgba::from_linear(col).into_format().into_raw();
+        write_png(self, path, |col| {
+            Srgba::from_linear(col).into_format().into_raw()
+        })
+    }
+}
+
+impl Image {
+    /// Tone-map and save the image as a png.
+    /// If a gamma is given the display transform is the power law `x^(1/gamma)`,
+    /// otherwise the standard sRGB transfer function is used.
+    /// # Errors
+    /// if the file can not be created or the image data can not be written.
+    #[inline]
+    pub fn save_png(
+        &self,
+        path: &Path,
+        op: ToneMap,
+        exposure: f32,
+        gamma: Option<f32>,
+    ) -> Result<(), Error> {
+        println!("[SAVE] {}", path.display());
+
+        let mapped = self.tone_mapped(op, exposure);
+        match gamma {
+            None => mapped.save_data(path),
+            Some(gamma) => {
+                debug_assert!(gamma > 0.0);
+
+                let encode = |x: f32| (x.clamp(0.0, 1.0).powf(1.0 / gamma) * 255.0).round() as u8;
+                write_png(&mapped, path, |col| {
+                    [
+                        encode(col.red),
+                        encode(col.green),
+                        encode(col.blue),
+                        (col.alpha.clamp(0.0, 1.0) * 255.0).round() as u8,
+                    ]
+                })
             }
         }
+    }
+}
 
-        let file = File::create(path)?;
-        let w = BufWriter::new(file);
+/// Write an image to a png file, using the given function to encode each linear colour.
+#[inline]
+fn write_png<F: Fn(Colour) -> [u8; 4]>(img: &Image, path: &Path, encode: F) -> Result<(), Error> {
+    let res = (img.pixels().shape()[0], img.pixels().shape()[1]);
+    let mut data: Array2<[u8; 4]> = Array2::from_elem((res.0, res.1).f(), [0; 4]);
+    for xi in 0..res.0 {
+        for yi in 0..res.1 {
+            data[(xi, res.1 - yi - 1)] = encode(img.pixels()[(xi, yi)]);
+        }
+    }
 
-        let mut encoder = Encoder::new(w, res.0 as u32, res.1 as u32);
-        encoder.set_color(ColorType::Rgba);
-        encoder.set_depth(BitDepth::Eight);
-        let mut writer = encoder.write_header()?;
+    let file = File::create(path)?;
+    let w = BufWriter::new(file);
 
-        writer.write_image_data(data.into_raw_vec().flat())?;
+    let mut encoder = Encoder::new(w, res.0 as u32, res.1 as u32);
+    encoder.set_color(ColorType::Rgba);
+    encoder.set_depth(BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
 
-        Ok(())
-    }
+    writer.write_image_data(data.into_raw_vec().flat())?;
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -50,4 +97,16 @@ mod tests {
         // Clean-up the written image. 
         std::fs::remove_file(path).unwrap();
     }
+
+    #[test]
+    fn test_save_png_tone_mapped() {
+        let pixel = Colour::new(4.0, 0.5, 100.0, 1.0);
+        let img = Image::new(arr2(&[[pixel, pixel], [pixel, pixel]]));
+        let file = tempfile::Builder::new().suffix(".png").tempfile().unwrap();
+
+        assert!(img
+            .save_png(file.path(), ToneMap::Reinhard, 0.0, Some(2.2))
+            .is_ok());
+        assert!(img.save_png(file.path(), ToneMap::Aces, -1.0, None).is_ok());
+    }
 }
\ No newline at end of file
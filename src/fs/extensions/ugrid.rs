@@ -0,0 +1,188 @@
+//! UGRID unstructured mesh export.
+
+use crate::{
+    access,
+    err::{check_shape, Error},
+    fs::Save,
+    geom::Grid,
+    math::Point3,
+    ord::{X, Y, Z},
+};
+use ndarray::Array3;
+use std::path::Path;
+
+/// Number of nodes of a hexahedral cell.
+const HEX_NODES: usize = 8;
+
+/// Unstructured hexahedral mesh of grid cells, with per-cell scalar fields.
+/// Neighbouring cells share their corner nodes.
+/// Saved as a NetCDF file following the UGRID conventions.
+pub struct Ugrid {
+    /// Node positions.
+    nodes: Vec<Point3>,
+    /// Node indices of each cell.
+    cells: Vec<[usize; HEX_NODES]>,
+    /// Grid resolution.
+    res: [usize; 3],
+    /// Named cell data.
+    fields: Vec<(String, Vec<f64>)>,
+}
+
+impl Ugrid {
+    access!(nodes: Vec<Point3>);
+    access!(cells: Vec<[usize; HEX_NODES]>);
+    access!(fields: Vec<(String, Vec<f64>)>);
+
+    /// Construct a new instance from the cells of a grid.
+    #[inline]
+    #[must_use]
+    pub fn new(grid: &Grid) -> Self {
+        let res = *grid.res();
+        let mins = grid.boundary().mins();
+        let size = grid.voxel_size();
+
+        let node_index =
+            |xi: usize, yi: usize, zi: usize| (((xi * (res[Y] + 1)) + yi) * (res[Z] + 1)) + zi;
+
+        let mut nodes = Vec::with_capacity((res[X] + 1) * (res[Y] + 1) * (res[Z] + 1));
+        for xi in 0..=res[X] {
+            for yi in 0..=res[Y] {
+                for zi in 0..=res[Z] {
+                    nodes.push(Point3::new(
+                        size.x().mul_add(xi as f64, mins.x()),
+                        size.y().mul_add(yi as f64, mins.y()),
+                        size.z().mul_add(zi as f64, mins.z()),
+                    ));
+                }
+            }
+        }
+
+        // Lower face anticlockwise, followed by the upper face.
+        let mut cells = Vec::with_capacity(res[X] * res[Y] * res[Z]);
+        for xi in 0..res[X] {
+            for yi in 0..res[Y] {
+                for zi in 0..res[Z] {
+                    cells.push([
+                        node_index(xi, yi, zi),
+                        node_index(xi + 1, yi, zi),
+                        node_index(xi + 1, yi + 1, zi),
+                        node_index(xi, yi + 1, zi),
+                        node_index(xi, yi, zi + 1),
+                        node_index(xi + 1, yi, zi + 1),
+                        node_index(xi + 1, yi + 1, zi + 1),
+                        node_index(xi, yi + 1, zi + 1),
+                    ]);
+                }
+            }
+        }
+
+        Self {
+            nodes,
+            cells,
+            res,
+            fields: Vec::new(),
+        }
+    }
+
+    /// Attach a named scalar field, such as material id or deposited energy, with one value per cell.
+    /// # Errors
+    /// if the data does not match the resolution of the grid.
+    #[inline]
+    pub fn add_field(&mut self, name: &str, data: &Array3<f64>) -> Result<(), Error> {
+        check_shape(&format!("ugrid field '{}'", name), &self.res, data.shape())?;
+
+        self.fields
+            .push((name.to_owned(), data.iter().copied().collect()));
+        Ok(())
+    }
+}
+
+impl Save for Ugrid {
+    #[inline]
+    fn save_data(&self, path: &Path) -> Result<(), Error> {
+        let mut file = netcdf::create(path)?;
+        file.add_attribute("Conventions", "UGRID-1.0")?;
+
+        file.add_dimension("nMesh_node", self.nodes.len())?;
+        file.add_dimension("nMesh_volume", self.cells.len())?;
+        file.add_dimension("nMaxMesh_volume_nodes", HEX_NODES)?;
+
+        let mut mesh = file.add_variable::<i32>("Mesh", &[])?;
+        mesh.add_attribute("cf_role", "mesh_topology")?;
+        mesh.add_attribute("topology_dimension", 3)?;
+        mesh.add_attribute("node_coordinates", "Mesh_node_x Mesh_node_y Mesh_node_z")?;
+        mesh.add_attribute("volume_node_connectivity", "Mesh_volume_nodes")?;
+        mesh.add_attribute("volume_shape_type", "Mesh_volume_types")?;
+
+        for (dim, name) in ["Mesh_node_x", "Mesh_node_y", "Mesh_node_z"]
+            .iter()
+            .enumerate()
+        {
+            let coors: Vec<f64> = self.nodes.iter().map(|node| node[dim]).collect();
+            let mut var = file.add_variable::<f64>(name, &["nMesh_node"])?;
+            var.add_attribute("units", "m")?;
+            var.put_values(&coors, ..)?;
+        }
+
+        let connectivity: Vec<i32> = self
+            .cells
+            .iter()
+            .flat_map(|cell| cell.iter().map(|node| *node as i32))
+            .collect();
+        let mut var = file.add_variable::<i32>(
+            "Mesh_volume_nodes",
+            &["nMesh_volume", "nMaxMesh_volume_nodes"],
+        )?;
+        var.add_attribute("cf_role", "volume_node_connectivity")?;
+        var.add_attribute("start_index", 0)?;
+        var.put_values(&connectivity, ..)?;
+
+        let mut var = file.add_variable::<i32>("Mesh_volume_types", &["nMesh_volume"])?;
+        var.add_attribute("cf_role", "volume_shape_type")?;
+        var.add_attribute("flag_meanings", "hexahedron")?;
+        var.put_values(&vec![0; self.cells.len()], ..)?;
+
+        for (name, data) in &self.fields {
+            let mut var = file.add_variable::<f64>(name, &["nMesh_volume"])?;
+            var.add_attribute("mesh", "Mesh")?;
+            var.add_attribute("location", "volume")?;
+            var.put_values(data, ..)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geom::Cube;
+
+    #[test]
+    fn test_shared_nodes() {
+        let grid = Grid::new(
+            Cube::new(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0)),
+            [2, 2, 2],
+        );
+        let mut ugrid = Ugrid::new(&grid);
+
+        assert_eq!(ugrid.cells().len(), 8);
+        assert_eq!(ugrid.nodes().len(), 27);
+
+        // The central node is a corner of every cell.
+        let centre = ugrid
+            .nodes()
+            .iter()
+            .position(|node| *node == Point3::new(0.5, 0.5, 0.5))
+            .unwrap();
+        assert!(ugrid.cells().iter().all(|cell| cell.contains(&centre)));
+
+        assert!(ugrid
+            .add_field("energy", &Array3::from_elem([2, 2, 2], 1.0))
+            .is_ok());
+        assert!(ugrid
+            .add_field("material", &Array3::zeros([2, 2, 1]))
+            .is_err());
+        assert_eq!(ugrid.fields().len(), 1);
+    }
+}
@@ -2,19 +2,16 @@
 
 use crate::{
     err::Error,
-    fs::File,
+    fs::{open_reader, File},
     geom::{Mesh, SmoothTriangle},
     math::{Dir3, Point3},
 };
-use std::{
-    io::{BufRead, BufReader},
-    path::Path,
-};
+use std::{io::BufRead, path::Path};
 
 impl File for Mesh {
     #[inline]
     fn load(path: &Path) -> Result<Self, Error> {
-        let vertex_lines: Vec<_> = BufReader::new(std::fs::File::open(path)?)
+        let vertex_lines: Vec<_> = open_reader(path)?
             .lines()
             .map(Result::unwrap)
             .filter(|line| line.starts_with("v "))
@@ -32,7 +29,7 @@ impl File for Mesh {
             verts.push(Point3::new(px, py, pz));
         }
 
-        let normal_lines: Vec<_> = BufReader::new(std::fs::File::open(path)?)
+        let normal_lines: Vec<_> = open_reader(path)?
             .lines()
             .map(Result::unwrap)
             .filter(|line| line.starts_with("vn "))
@@ -50,48 +47,84 @@ impl File for Mesh {
             norms.push(Dir3::new(nx, ny, nz));
         }
 
-        let face_lines: Vec<_> = BufReader::new(std::fs::File::open(path)?)
+        let uv_lines: Vec<_> = open_reader(path)?
             .lines()
             .map(Result::unwrap)
-            .filter(|line| line.starts_with("f "))
+            .filter(|line| line.starts_with("vt "))
             .collect();
 
-        let mut faces = Vec::with_capacity(face_lines.len());
-        for line in face_lines {
-            let line = line.replace("//", " ");
+        let mut uvs = Vec::with_capacity(uv_lines.len());
+        for line in uv_lines {
             let mut words = line.split_whitespace();
             words.next();
 
-            let fx = words.next().ok_or("Missing face word.")?.parse::<usize>()? - 1;
-            let nx = words
+            let u = words
                 .next()
-                .ok_or("Missing normal word.")?
-                .parse::<usize>()?
-                - 1;
-            let fy = words.next().ok_or("Missing face word.")?.parse::<usize>()? - 1;
-            let ny = words
+                .ok_or("Missing texture word.")?
+                .parse::<f64>()?;
+            let v = words
                 .next()
-                .ok_or("Missing normal word.")?
-                .parse::<usize>()?
-                - 1;
-            let fz = words.next().ok_or("Missing face word.")?.parse::<usize>()? - 1;
-            let nz = words
-                .next()
-                .ok_or("Missing normal word.")?
-                .parse::<usize>()?
-                - 1;
+                .ok_or("Missing texture word.")?
+                .parse::<f64>()?;
+
+            uvs.push([u, v]);
+        }
+
+        let face_lines: Vec<_> = open_reader(path)?
+            .lines()
+            .map(Result::unwrap)
+            .filter(|line| line.starts_with("f "))
+            .collect();
+
+        let mut tris = Vec::with_capacity(face_lines.len());
+        for line in face_lines {
+            let mut words = line.split_whitespace();
+            words.next();
+
+            // Each face word is of the form `v//vn` or `v/vt/vn`.
+            let mut vert_ids = [0; 3];
+            let mut uv_ids = [None; 3];
+            let mut norm_ids = [0; 3];
+            for ((vert_id, uv_id), norm_id) in vert_ids
+                .iter_mut()
+                .zip(uv_ids.iter_mut())
+                .zip(norm_ids.iter_mut())
+            {
+                let mut parts = words.next().ok_or("Missing face word.")?.split('/');
+
+                *vert_id = parts.next().ok_or("Missing face word.")?.parse::<usize>()? - 1;
+                *uv_id = match parts.next() {
+                    Some(word) if !word.is_empty() => Some(word.parse::<usize>()? - 1),
+                    _ => None,
+                };
+                *norm_id = parts
+                    .next()
+                    .ok_or("Missing normal word.")?
+                    .parse::<usize>()?
+                    - 1;
+            }
+
+            let tri = SmoothTriangle::new_from_verts(
+                [verts[vert_ids[0]], verts[vert_ids[1]], verts[vert_ids[2]]],
+                [norms[norm_ids[0]], norms[norm_ids[1]], norms[norm_ids[2]]],
+            );
 
-            faces.push(((fx, fy, fz), (nx, ny, nz)));
+            tris.push(match uv_ids {
+                [Some(a), Some(b), Some(c)] => tri.with_uvs([uvs[a], uvs[b], uvs[c]]),
+                _ => tri,
+            });
         }
 
-        let mut tris = Vec::with_capacity(faces.len());
-        for face in faces {
-            tris.push(SmoothTriangle::new_from_verts(
-                [verts[(face.0).0], verts[(face.0).1], verts[(face.0).2]],
-                [norms[(face.1).0], norms[(face.1).1], norms[(face.1).2]],
-            ));
+        let mesh =
+            Self::try_new(tris).map_err(|err| err.with_context(&path.display().to_string()))?;
+        if mesh.num_dropped() > 0 {
+            println!(
+                "[WARN] Dropped {} degenerate triangles from: {}",
+                mesh.num_dropped(),
+                path.display()
+            );
         }
 
-        Ok(Self::new(tris))
+        Ok(mesh)
     }
 }
@@ -2,12 +2,14 @@
 //!
 //! In this module we have implemented:
 //! - Foundational traits to enabled file load / save / redirect operations.
+//! - Transparent decompression of gzipped input files.
 
 /// Include the foundational traits and types.
 pub mod file;
 pub mod load;
+pub mod read;
 pub mod save;
-pub use self::{file::*, load::*, save::*};
+pub use self::{file::*, load::*, read::*, save::*};
 
 /// File redirection type.
 pub mod redirect;
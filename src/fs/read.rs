@@ -0,0 +1,42 @@
+//! Input file reading.
+//!
+//! Opens input files for the loaders, transparently decompressing gzip-compressed files.
+//! A file is treated as compressed if it has a `.gz` extension or begins with the gzip magic bytes,
+//! all other files are passed through unchanged.
+
+use crate::err::Error;
+use flate2::bufread::MultiGzDecoder;
+use std::{
+    io::{BufRead, BufReader, Read},
+    path::Path,
+};
+
+/// Leading bytes of a gzip stream.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Open a buffered reader to the given file, decompressing it if it is gzipped.
+/// # Errors
+/// if the file can not be opened or its header can not be read.
+#[inline]
+pub fn open_reader(path: &Path) -> Result<Box<dyn BufRead>, Error> {
+    let mut reader = BufReader::new(std::fs::File::open(path)?);
+
+    let gz_ext = path.extension().map_or(false, |ext| ext == "gz");
+    let gz_magic = reader.fill_buf()?.starts_with(&GZIP_MAGIC);
+
+    if gz_ext || gz_magic {
+        Ok(Box::new(BufReader::new(MultiGzDecoder::new(reader))))
+    } else {
+        Ok(Box::new(reader))
+    }
+}
+
+/// Read the entire contents of the given file into a string, decompressing it if it is gzipped.
+/// # Errors
+/// if the file can not be opened, decompressed, or is not valid UTF-8.
+#[inline]
+pub fn read_to_string(path: &Path) -> Result<String, Error> {
+    let mut s = String::new();
+    let _ = open_reader(path)?.read_to_string(&mut s)?;
+    Ok(s)
+}
@@ -65,14 +65,17 @@
 
 use crate::{
     err::Error,
-    fs::{as_json, from_json, File, Load, Save},
+    fs::{as_json, from_json, read_to_string, File, Load, Save},
 };
 use serde::{Deserialize, Serialize};
 use std::{
     fmt::{Display, Formatter},
-    path::Path,
+    path::{Path, PathBuf},
 };
 
+/// Default maximum number of chained redirections followed before loading is abandoned.
+pub const MAX_REDIRECT_DEPTH: usize = 16;
+
 /// Possible file redirection structure.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub enum Redirect<T> {
@@ -99,18 +102,76 @@ impl<T: Serialize> Save for Redirect<T> {
     }
 }
 
-impl<T: File> Load for Redirect<T> {
+impl<T: File> Redirect<T>
+where
+    for<'de> T: Deserialize<'de>,
+{
+    /// Resolve the redirection, following chains of redirect files up to the given depth.
+    /// A target file which itself contains a redirection is followed, otherwise it is loaded as the value type.
+    /// # Errors
+    /// if a redirection cycle is detected,
+    /// if the chain is deeper than `max_depth`,
+    /// or if a target can not be read or parsed, in which case the path of the target is given.
+    #[inline]
+    pub fn resolve(self, in_dir: &Path, max_depth: usize) -> Result<T, Error> {
+        let mut visited: Vec<PathBuf> = Vec::new();
+        let mut current = self;
+
+        loop {
+            match current {
+                Self::Here(val) => return Ok(val),
+                Self::There(path) => {
+                    let path = in_dir.join(path);
+                    let key = path.canonicalize().unwrap_or_else(|_| path.clone());
+
+                    if visited.contains(&key) {
+                        let chain: Vec<String> = visited
+                            .iter()
+                            .chain(std::iter::once(&key))
+                            .map(|p| p.display().to_string())
+                            .collect();
+                        return Err(Error::Text(format!(
+                            "Redirect cycle detected: {}",
+                            chain.join(" -> ")
+                        )));
+                    }
+                    if visited.len() >= max_depth {
+                        return Err(Error::Text(format!(
+                            "Redirect chain exceeded the maximum depth of {} at: {}",
+                            max_depth,
+                            path.display()
+                        )));
+                    }
+                    visited.push(key);
+
+                    println!("[LOAD] {}", path.display());
+                    let context = path.display().to_string();
+                    let value: serde_json::Value = json5::from_str(&read_to_string(&path)?)
+                        .map_err(|err| Error::from(err).with_context(&context))?;
+
+                    // A target holding a single `There` or `Here` entry is itself a redirection.
+                    match Self::deserialize(&value) {
+                        Ok(next) => current = next,
+                        Err(_) => {
+                            return T::deserialize(value)
+                                .map_err(|err| Error::Text(err.to_string()).with_context(&context))
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<T: File> Load for Redirect<T>
+where
+    for<'de> T: Deserialize<'de>,
+{
     type Inst = T;
 
     #[inline]
     fn load(self, in_dir: &Path) -> Result<Self::Inst, Error> {
-        match self {
-            Self::There(path) => {
-                let path = in_dir.join(path);
-                T::new_from_file(&path)
-            }
-            Self::Here(val) => Ok(val),
-        }
+        self.resolve(in_dir, MAX_REDIRECT_DEPTH)
     }
 }
 
@@ -192,4 +253,98 @@ mod tests {
         assert_eq!(out.struct2.val1, 4.0);
         assert_eq!(out.struct2.val2, 5.0);
     }
+
+    #[test]
+    fn test_redirect_chain() {
+        use super::{Load, Redirect};
+        use arctk_attr::file;
+        use std::fs::write;
+        use tempfile::tempdir;
+
+        #[file]
+        struct NestedStruct {
+            pub val1: f32,
+        }
+
+        let dir = tempdir().unwrap();
+        write(dir.path().join("a.json"), "{ There: \"b.json\" }").unwrap();
+        write(dir.path().join("b.json"), "{ val1: 3.0 }").unwrap();
+
+        let out = Redirect::<NestedStruct>::There("a.json".to_owned())
+            .load(dir.path())
+            .unwrap();
+        assert_eq!(out.val1, 3.0);
+    }
+
+    #[test]
+    fn test_redirect_cycle() {
+        use super::{Load, Redirect};
+        use crate::err::Error;
+        use arctk_attr::file;
+        use std::fs::write;
+        use tempfile::tempdir;
+
+        #[file]
+        struct NestedStruct {
+            pub val1: f32,
+        }
+
+        let dir = tempdir().unwrap();
+        write(dir.path().join("a.json"), "{ There: \"b.json\" }").unwrap();
+        write(dir.path().join("b.json"), "{ There: \"a.json\" }").unwrap();
+
+        let res = Redirect::<NestedStruct>::There("a.json".to_owned()).load(dir.path());
+        assert!(matches!(res, Err(Error::Text(_))));
+    }
+
+    #[test]
+    fn test_redirect_max_depth() {
+        use super::Redirect;
+        use crate::err::Error;
+        use arctk_attr::file;
+        use std::fs::write;
+        use tempfile::tempdir;
+
+        #[file]
+        struct NestedStruct {
+            pub val1: f32,
+        }
+
+        let dir = tempdir().unwrap();
+        write(dir.path().join("a.json"), "{ There: \"b.json\" }").unwrap();
+        write(dir.path().join("b.json"), "{ There: \"c.json\" }").unwrap();
+        write(dir.path().join("c.json"), "{ val1: 1.0 }").unwrap();
+
+        let res = Redirect::<NestedStruct>::There("a.json".to_owned()).resolve(dir.path(), 2);
+        assert!(matches!(res, Err(Error::Text(_))));
+
+        let out = Redirect::<NestedStruct>::There("a.json".to_owned())
+            .resolve(dir.path(), 3)
+            .unwrap();
+        assert_eq!(out.val1, 1.0);
+    }
+
+    #[test]
+    fn test_redirect_parse_error_path() {
+        use super::Redirect;
+        use arctk_attr::file;
+        use std::fs::write;
+        use tempfile::tempdir;
+
+        #[file]
+        struct NestedStruct {
+            pub val1: f32,
+        }
+
+        let dir = tempdir().unwrap();
+        write(dir.path().join("a.json"), "{ There: \"b.json\" }").unwrap();
+        write(dir.path().join("b.json"), "{ val2: 1.0 }").unwrap();
+
+        let err = Redirect::<NestedStruct>::There("a.json".to_owned())
+            .resolve(dir.path(), 4)
+            .unwrap_err();
+        let msg = format!("{:?}", err);
+        assert!(msg.contains("b.json"));
+        assert!(msg.contains("val1"));
+    }
 }
@@ -0,0 +1,44 @@
+//! Surface emission angular profile.
+
+use crate::geom::Ray;
+use arctk_attr::file;
+use rand::Rng;
+use std::{
+    f64::consts::PI,
+    fmt::{Display, Formatter},
+};
+
+/// Angular distribution of rays emitted from a surface, relative to the outward surface normal.
+#[file]
+#[derive(Clone, Copy, PartialEq)]
+pub enum AngularProfile {
+    /// Rays leave along the outward normal.
+    Outward,
+    /// Rays leave with a cosine-weighted (Lambertian) distribution over the outward hemisphere.
+    Cosine,
+}
+
+impl AngularProfile {
+    /// Redirect a ray, cast along the outward normal, according to the profile.
+    #[inline]
+    pub fn apply<R: Rng>(self, rng: &mut R, ray: &mut Ray) {
+        match self {
+            Self::Outward => {}
+            Self::Cosine => {
+                let pitch = rng.gen::<f64>().sqrt().acos();
+                ray.rotate(pitch, rng.gen_range(0.0..(2.0 * PI)));
+            }
+        }
+    }
+}
+
+impl Display for AngularProfile {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        let kind = match *self {
+            Self::Outward => "Outward",
+            Self::Cosine => "Cosine",
+        };
+        write!(fmt, "{}", kind)
+    }
+}
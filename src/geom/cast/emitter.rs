@@ -1,14 +1,15 @@
 //! Optical material.
 
 use crate::{
-    geom::{Emit, Grid, Mesh, Ray},
-    math::{rand_isotropic_dir, Dir3, Point3, SphericalCdf, Trans3},
+    geom::{AngularProfile, Emit, Grid, Mesh, Orient, Ray, Sphere},
+    math::{rand_isotropic_dir, sample_rayleigh, Dir3, Point3, SphericalCdf, Trans3},
     tools::linear_to_three_dim,
 };
+use nalgebra::{Translation3, UnitQuaternion};
 use ndarray::Array3;
 use rand::Rng;
 use std::{
-    f64::consts::PI,
+    f64::consts::{FRAC_PI_2, PI},
     fmt::{Display, Error, Formatter},
 };
 
@@ -17,12 +18,16 @@ use std::{
 pub enum Emitter {
     /// Single beam.
     Beam(Ray),
+    /// Gaussian beam, with a waist radius [m] and beam quality factor (M^2).
+    GaussianBeam(Ray, f64, f64),
     /// Points.
     Points(Vec<Point3>),
     /// Weighted points.
     WeightedPoints(Vec<Point3>, Vec<f64>),
     /// Surface mesh.
     Surface(Mesh),
+    /// Analytic sphere surface, with the angular profile of emission about the outward normal.
+    Sphere(Sphere, AngularProfile),
     /// Volume map.
     Volume(Array3<f64>, Grid),
     /// Non-isotropic point source.
@@ -37,6 +42,28 @@ impl Emitter {
         Self::Beam(ray)
     }
 
+    /// Construct a new gaussian beam instance.
+    /// The waist is the 1/e^2 intensity radius, and a beam quality factor of unity is diffraction limited.
+    #[inline]
+    #[must_use]
+    pub fn new_gaussian_beam(ray: Ray, waist: f64, m2: f64) -> Self {
+        debug_assert!(waist > 0.0);
+        debug_assert!(m2 >= 1.0);
+
+        Self::GaussianBeam(ray, waist, m2)
+    }
+
+    /// Far-field divergence half-angle [rad] of a gaussian beam.
+    #[inline]
+    #[must_use]
+    pub fn gaussian_divergence(waist: f64, m2: f64, wavelength: f64) -> f64 {
+        debug_assert!(waist > 0.0);
+        debug_assert!(m2 >= 1.0);
+        debug_assert!(wavelength > 0.0);
+
+        (m2 * wavelength) / (PI * waist)
+    }
+
     /// Construct a new points instance.
     #[inline]
     #[must_use]
@@ -71,6 +98,13 @@ impl Emitter {
         Self::Surface(mesh)
     }
 
+    /// Construct a new sphere surface instance.
+    #[inline]
+    #[must_use]
+    pub const fn new_sphere(sphere: Sphere, profile: AngularProfile) -> Self {
+        Self::Sphere(sphere, profile)
+    }
+
     /// Construct a new volume instance.
     #[inline]
     #[must_use]
@@ -88,12 +122,68 @@ impl Emitter {
         Self::NonIsotropic(cdf, trans)
     }
 
+    /// Create a copy of the emitter positioned and pointed according to an orientation.
+    /// Beams are emitted from the orientation position along its forward direction,
+    /// and non-isotropic sources are centred on the position with their downward axis turned to face forward.
+    /// Emitters without a single position or direction are returned unchanged.
+    #[inline]
+    #[must_use]
+    pub fn steered(&self, orient: &Orient) -> Self {
+        match *self {
+            Self::Beam(_) => Self::Beam(orient.forward_ray()),
+            Self::GaussianBeam(_, waist, m2) => Self::GaussianBeam(orient.forward_ray(), waist, m2),
+            Self::NonIsotropic(ref cdf, ref trans) => {
+                // Map the z-axis to the backward direction, so that downwards emission faces forwards.
+                let rot = UnitQuaternion::face_towards(&orient.back().data(), &orient.up().data());
+                let pos = orient.pos();
+                Self::NonIsotropic(
+                    cdf.clone(),
+                    Trans3::from_parts(
+                        Translation3::new(pos.x(), pos.y(), pos.z()),
+                        rot,
+                        trans.scaling(),
+                    ),
+                )
+            }
+            _ => self.clone(),
+        }
+    }
+
+    /// Emit a new ray of a given wavelength.
+    /// Gaussian beams diverge according to the wavelength, all other emitters are independent of it.
+    #[inline]
+    #[must_use]
+    pub fn emit_spectral<R: Rng>(&self, rng: &mut R, wavelength: f64) -> Ray {
+        match *self {
+            Self::GaussianBeam(_, waist, m2) => {
+                let mut ray = self.emit(rng);
+                let theta = Self::gaussian_divergence(waist, m2, wavelength);
+                ray.rotate(
+                    sample_rayleigh(rng, theta / 2.0),
+                    rng.gen_range(0.0..(2.0 * PI)),
+                );
+                ray
+            }
+            _ => self.emit(rng),
+        }
+    }
+
     /// Emit a new ray.
+    /// Gaussian beams are emitted across the waist, parallel to the beam axis.
     #[inline]
     #[must_use]
     pub fn emit<R: Rng>(&self, rng: &mut R) -> Ray {
         match *self {
             Self::Beam(ref ray) => ray.clone(),
+            Self::GaussianBeam(ref ray, waist, _) => {
+                let mut offset = ray.clone();
+                offset.rotate(FRAC_PI_2, rng.gen_range(0.0..(2.0 * PI)));
+
+                Ray::new(
+                    *ray.pos() + (*offset.dir() * sample_rayleigh(rng, waist / 2.0)),
+                    *ray.dir(),
+                )
+            }
             Self::Points(ref ps) => {
                 Ray::new(ps[rng.gen_range(0..ps.len())], rand_isotropic_dir(rng))
             }
@@ -107,6 +197,11 @@ impl Emitter {
                 unreachable!("Failed to determine weighted point to emit from.");
             }
             Self::Surface(ref mesh) => mesh.cast(rng),
+            Self::Sphere(ref sphere, profile) => {
+                let mut ray = sphere.cast(rng);
+                profile.apply(rng, &mut ray);
+                ray
+            }
             Self::Volume(ref map, ref grid) => {
                 let r = rng.gen_range(0.0..map.sum());
                 let mut total = 0.0;
@@ -149,9 +244,11 @@ impl Display for Emitter {
     fn fmt(&self, fmt: &mut Formatter) -> Result<(), Error> {
         let kind = match *self {
             Self::Beam { .. } => "Beam",
+            Self::GaussianBeam { .. } => "GaussianBeam",
             Self::Points { .. } => "Points",
             Self::WeightedPoints { .. } => "WeightedPoints",
             Self::Surface { .. } => "Surface",
+            Self::Sphere { .. } => "Sphere",
             Self::Volume { .. } => "Volume",
             Self::NonIsotropic { .. } => "Non-isotropic",
         };
@@ -165,7 +262,7 @@ mod tests {
     use rand;
     use assert_approx_eq::assert_approx_eq;
     use crate::{
-        geom::{Ray, Mesh, SmoothTriangle, Triangle}, 
+        geom::{AngularProfile, Ray, Mesh, SmoothTriangle, Sphere, Triangle}, 
         data::Average,
         math::{Point3, Dir3},
     };
@@ -264,4 +361,54 @@ mod tests {
         assert!(emitted_ray.pos().x() >= 0.0 && emitted_ray.pos().x() <= 1.0);
         assert!(emitted_ray.pos().y() >= 0.0 && emitted_ray.pos().y() <= 1.0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_diffraction_limited_gaussian_beam() {
+        let mut rng = rand::thread_rng();
+        let waist = 1.0e-3;
+        let wavelength = 1.0e-6;
+        let emitter = Emitter::new_gaussian_beam(
+            Ray::new(Point3::new(0.0, 0.0, 0.0), Dir3::new(1.0, 0.0, 0.0)),
+            waist,
+            1.0,
+        );
+
+        let theta = Emitter::gaussian_divergence(waist, 1.0, wavelength);
+        assert_approx_eq!(theta, wavelength / (std::f64::consts::PI * waist), 1.0e-12);
+
+        // The mean square angle of a gaussian far-field profile is half the square divergence.
+        let mut ave_sq_ang = Average::new();
+        let mut ave_sq_rad = Average::new();
+        for _ in 0..100_000 {
+            let ray = emitter.emit_spectral(&mut rng, wavelength);
+            let dir = ray.dir();
+            ave_sq_ang += (dir.y() * dir.y()) + (dir.z() * dir.z());
+
+            let pos = ray.pos();
+            assert_approx_eq!(pos.x(), 0.0, 1.0e-12);
+            ave_sq_rad += (pos.y() * pos.y()) + (pos.z() * pos.z());
+        }
+
+        assert_approx_eq!((2.0 * ave_sq_ang.ave()).sqrt() / theta, 1.0, 0.02);
+        assert_approx_eq!((2.0 * ave_sq_rad.ave()).sqrt() / waist, 1.0, 0.02);
+    }
+
+    #[test]
+    fn test_sphere_cosine_emitter() {
+        let mut rng = rand::thread_rng();
+        let centre = Point3::new(0.0, 0.0, 1.0);
+        let emitter = Emitter::new_sphere(Sphere::new(centre, 0.5), AngularProfile::Cosine);
+
+        // A cosine profile has a mean cosine to the normal of two thirds.
+        let mut ave_cos = Average::new();
+        for _ in 0..100_000 {
+            let ray = emitter.emit(&mut rng);
+            let norm = Dir3::from(*ray.pos() - centre);
+            let cos = ray.dir().dot(&norm);
+            assert!(cos > 0.0);
+            ave_cos += cos;
+        }
+
+        assert_approx_eq!(ave_cos.ave(), 2.0 / 3.0, 0.01);
+    }
+}
@@ -4,7 +4,7 @@ use crate::{
     data::Table,
     err::Error,
     fs::{File, Load, Redirect},
-    geom::{Emitter, GridBuilder, MeshLoader, Ray},
+    geom::{AngularProfile, Emitter, GridBuilder, MeshLoader, Ray, Sphere},
     math::{stat::SphericalCdf, Dir3, Point3, Trans3Builder},
     ord::{Build, X, Y, Z},
 };
@@ -20,12 +20,16 @@ use std::{
 pub enum EmitterLoader {
     /// Single beam.
     Beam(Point3, Dir3),
+    /// Gaussian beam, with a waist radius [m] and beam quality factor.
+    GaussianBeam(Point3, Dir3, f64, f64),
     /// Point list.
     Points(PathBuf),
     /// Weighted point list.
     WeightedPoints(PathBuf, PathBuf),
     /// Surface mesh.
     Surface(MeshLoader),
+    /// Analytic sphere surface, with the angular profile of emission about the outward normal.
+    Sphere(Sphere, AngularProfile),
     /// Volume map.
     Volume(PathBuf, Redirect<GridBuilder>),
     /// Non-isotropic point source.
@@ -39,6 +43,9 @@ impl Load for EmitterLoader {
     fn load(self, in_dir: &Path) -> Result<Self::Inst, Error> {
         Ok(match self {
             Self::Beam(pos, dir) => Self::Inst::new_beam(Ray::new(pos, dir)),
+            Self::GaussianBeam(pos, dir, waist, m2) => {
+                Self::Inst::new_gaussian_beam(Ray::new(pos, dir), waist, m2)
+            }
             Self::Points(points_path) => {
                 let table = Table::new_from_file(&in_dir.join(points_path))?;
                 let points = table
@@ -63,6 +70,7 @@ impl Load for EmitterLoader {
                 Self::Inst::new_weighted_points(points, &weights)
             }
             Self::Surface(mesh) => Self::Inst::new_surface(mesh.load(in_dir)?),
+            Self::Sphere(sphere, profile) => Self::Inst::new_sphere(sphere, profile),
             Self::Volume(spatial_map, grid) => {
                 let spatial_map: Array3<f64> = Array3::new_from_file(&in_dir.join(spatial_map))?;
                 Self::Inst::new_volume(spatial_map, grid.load(in_dir)?.build())
@@ -82,9 +90,11 @@ impl Display for EmitterLoader {
     fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
         let kind = match *self {
             Self::Beam { .. } => "Beam",
+            Self::GaussianBeam { .. } => "GaussianBeam",
             Self::Points { .. } => "Points",
             Self::WeightedPoints { .. } => "WeightedPoints",
             Self::Surface { .. } => "Surface",
+            Self::Sphere { .. } => "Sphere",
             Self::Volume { .. } => "Volume",
             Self::NonIsotropic { .. } => "NonIsotropic",
         };
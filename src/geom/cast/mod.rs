@@ -1,8 +1,9 @@
 //! Ray-casting module.
 
+pub mod angular_profile;
 pub mod camera;
 pub mod camera_builder;
 pub mod emitter;
 pub mod emitter_loader;
 
-pub use self::{camera::*, camera_builder::*, emitter::*, emitter_loader::*};
+pub use self::{angular_profile::*, camera::*, camera_builder::*, emitter::*, emitter_loader::*};
@@ -2,20 +2,24 @@
 
 use crate::{
     access, fmt_report,
-    geom::Cube,
-    math::{Point3, Vec3},
+    geom::{Collide, Cube, Mesh, Ray, Trace, VoxelTraversal},
+    math::{Dir3, Point3, Vec3},
     ord::{X, Y, Z},
 };
+use ndarray::Array3;
 use std::fmt::{Display, Formatter};
 
+/// Fraction of the voxel size within which points are considered to lie on a voxel face.
+const VOXEL_TOL: f64 = 1.0e-9;
+
 /// Regular Cartesian-grid structure.
 #[derive(Clone)]
 pub struct Grid {
     /// Boundary.
     boundary: Cube,
-    /// Resolution.
+    /// Resolution along each axis.
     res: [usize; 3],
-    /// Voxel size.
+    /// Voxel size, which may differ between axes.
     voxel_size: Vec3,
 }
 
@@ -58,22 +62,32 @@ impl Grid {
         self.res[X] * self.res[Y] * self.res[Z]
     }
 
-    /// If the given position is contained within the grid,
+    /// If the given position is contained within the grid, allowing for rounding at its faces,
     /// generate the index for the given position within the grid.
     #[inline]
     #[must_use]
     pub fn gen_index(&self, p: &Point3) -> Option<[usize; 3]> {
-        self.boundary.contains(p).then(|| {
+        let tol = VOXEL_TOL
+            * self
+                .voxel_size
+                .x()
+                .min(self.voxel_size.y())
+                .min(self.voxel_size.z());
+        self.boundary.contains_eps(p, tol).then(|| {
             let mins = self.boundary.mins();
             let maxs = self.boundary.maxs();
 
+            // Points lying on the maximum faces belong to the final cell.
             [
-                (((p.x() - mins.x()) / (maxs.x() - mins.x())) * self.res[X] as f64).floor()
-                    as usize,
-                (((p.y() - mins.y()) / (maxs.y() - mins.y())) * self.res[Y] as f64).floor()
-                    as usize,
-                (((p.z() - mins.z()) / (maxs.z() - mins.z())) * self.res[Z] as f64).floor()
-                    as usize,
+                ((((p.x() - mins.x()) / (maxs.x() - mins.x())) * self.res[X] as f64).floor()
+                    as usize)
+                    .min(self.res[X] - 1),
+                ((((p.y() - mins.y()) / (maxs.y() - mins.y())) * self.res[Y] as f64).floor()
+                    as usize)
+                    .min(self.res[Y] - 1),
+                ((((p.z() - mins.z()) / (maxs.z() - mins.z())) * self.res[Z] as f64).floor()
+                    as usize)
+                    .min(self.res[Z] - 1),
             ]
         })
     }
@@ -116,13 +130,63 @@ impl Grid {
             *min.z_mut() += self.voxel_size[Z] * index[Z] as f64;
 
             let boundary = Cube::new(min, min + self.voxel_size);
-            debug_assert!(boundary.contains(p));
+            // Tolerances are set per axis, so they remain tight along the fine axes of anisotropic voxels.
+            debug_assert!((0..3).all(|n| {
+                let tol = VOXEL_TOL * self.voxel_size[n];
+                p[n] >= boundary.mins()[n] - tol && p[n] <= boundary.maxs()[n] + tol
+            }));
 
             Some((index, boundary))
         } else {
             None
         }
     }
+
+    /// Iterate over the voxels crossed by a ray, with the path length of the ray within each.
+    #[inline]
+    #[must_use]
+    pub fn voxel_traversal(&self, ray: &Ray) -> VoxelTraversal {
+        VoxelTraversal::new(self, ray)
+    }
+
+    /// Determine which cells lie within the given closed mesh.
+    /// A cell is inside if a ray cast from its centre crosses the mesh an odd number of times.
+    /// Cells intersected by the mesh surface are also marked, so features thinner than a voxel are not lost.
+    #[inline]
+    #[must_use]
+    pub fn voxelise(&self, mesh: &Mesh) -> Array3<bool> {
+        // Skewed to avoid grazing shared triangle edges.
+        let dir = Dir3::new(1.0, 0.123, 0.0471);
+
+        let mut cells = Array3::from_elem(self.res, false);
+        for (index, cell) in cells.indexed_iter_mut() {
+            let voxel = self.gen_voxel(&[index.0, index.1, index.2]);
+
+            if !mesh.boundary().overlap(&voxel) {
+                continue;
+            }
+
+            let ray = Ray::new(voxel.centre(), dir);
+            let crossings = mesh.tris().iter().filter(|tri| tri.hit(&ray)).count();
+
+            *cell = (crossings % 2 == 1) || mesh.overlap(&voxel);
+        }
+
+        cells
+    }
+
+    /// Mark the cells within, or intersected by, the given mesh with a material id.
+    /// Cells outside of the mesh are left unchanged.
+    #[inline]
+    pub fn voxelise_into(&self, mesh: &Mesh, id: usize, cells: &mut Array3<Option<usize>>) {
+        debug_assert!(cells.shape() == self.res);
+
+        for (cell, inside) in cells.iter_mut().zip(self.voxelise(mesh).iter()) {
+            if *inside {
+                *cell = Some(id);
+            }
+        }
+    }
 }
 
 impl Display for Grid {
@@ -155,6 +219,7 @@ mod tests {
     use crate::{
         geom::Cube,
     };
+    use assert_approx_eq::assert_approx_eq;
 
     #[test]
     fn test_new() {
@@ -196,6 +261,10 @@ mod tests {
         let p = Point3::new(2.0, 2.0, 2.0);
         let result = grid.gen_index_voxel(&p);
         assert!(result.is_none());
+
+        // Points rounded just beyond a face still belong to the grid.
+        let p = Point3::new(-1.0e-12, 0.5, 1.0 + 1.0e-12);
+        assert_eq!(grid.gen_index(&p), Some([0, 1, 1]));
     }
 
     #[test]
@@ -212,6 +281,48 @@ mod tests {
         assert_eq!(voxel, Cube::new(Point3::new(0.5, 0.5, 0.5), Point3::new(1.0, 1.0, 1.0)));
     }
 
+    #[test]
+    fn test_anisotropic_slab() {
+        let boundary = Cube::new(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 0.01));
+        let res = [4, 4, 100];
+        let grid = Grid::new(boundary.clone(), res);
+
+        assert_approx_eq!(grid.voxel_size().x(), 0.25);
+        assert_approx_eq!(grid.voxel_size().z(), 1.0e-4);
+        assert_approx_eq!(grid.voxel_vol(), 0.25 * 0.25 * 1.0e-4);
+        assert_eq!(grid.num_cells(), 1600);
+
+        for k in 0..res[Z] {
+            let index = [1, 2, k];
+
+            // Cell centres lie midway between the fine-axis voxel faces.
+            let centre = boundary.uniform_pos(&res, &index);
+            assert_approx_eq!(centre.x(), 0.375);
+            assert_approx_eq!(centre.y(), 0.625);
+            assert_approx_eq!(centre.z(), (k as f64 + 0.5) * 1.0e-4);
+
+            let voxel = grid.gen_voxel(&index);
+            assert_approx_eq!(voxel.centre().z(), centre.z());
+            assert_approx_eq!(voxel.widths().z(), 1.0e-4);
+
+            let (found, found_voxel) = grid.gen_index_voxel(&centre).unwrap();
+            assert_eq!(found, index);
+            assert!(found_voxel.contains(&centre));
+
+            // Points just inside either fine-axis face resolve to the same cell.
+            let below = Point3::new(0.3, 0.6, (k as f64 + 0.01) * 1.0e-4);
+            let above = Point3::new(0.3, 0.6, (k as f64 + 0.99) * 1.0e-4);
+            assert_eq!(grid.gen_index(&below), Some(index));
+            assert_eq!(grid.gen_index(&above), Some(index));
+        }
+
+        // The top face belongs to the final cell.
+        assert_eq!(
+            grid.gen_index(&Point3::new(1.0, 1.0, 0.01)),
+            Some([3, 3, 99])
+        );
+    }
+
     #[test]
     fn test_clone() {
         let boundary = Cube::new(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0));
@@ -223,4 +334,63 @@ mod tests {
         assert_eq!(grid.boundary(), cloned.boundary());
         assert_eq!(grid.res(), cloned.res());
     }
-}
\ No newline at end of file
+
+    /// Construct an axis-aligned cube mesh spanning the given bounds.
+    fn cube_mesh(min: f64, max: f64) -> Mesh {
+        use crate::geom::SmoothTriangle;
+
+        let p = |x: usize, y: usize, z: usize| {
+            let c = |i: usize| if i == 0 { min } else { max };
+            Point3::new(c(x), c(y), c(z))
+        };
+        let quads = [
+            [p(0, 0, 0), p(0, 1, 0), p(0, 1, 1), p(0, 0, 1)],
+            [p(1, 0, 0), p(1, 1, 0), p(1, 1, 1), p(1, 0, 1)],
+            [p(0, 0, 0), p(1, 0, 0), p(1, 0, 1), p(0, 0, 1)],
+            [p(0, 1, 0), p(1, 1, 0), p(1, 1, 1), p(0, 1, 1)],
+            [p(0, 0, 0), p(1, 0, 0), p(1, 1, 0), p(0, 1, 0)],
+            [p(0, 0, 1), p(1, 0, 1), p(1, 1, 1), p(0, 1, 1)],
+        ];
+        let norms = [
+            Dir3::new(-1.0, 0.0, 0.0),
+            Dir3::new(1.0, 0.0, 0.0),
+            Dir3::new(0.0, -1.0, 0.0),
+            Dir3::new(0.0, 1.0, 0.0),
+            Dir3::new(0.0, 0.0, -1.0),
+            Dir3::new(0.0, 0.0, 1.0),
+        ];
+
+        let mut tris = Vec::with_capacity(12);
+        for (q, n) in quads.iter().zip(norms.iter()) {
+            tris.push(SmoothTriangle::new_from_verts([q[0], q[1], q[2]], [*n; 3]));
+            tris.push(SmoothTriangle::new_from_verts([q[0], q[2], q[3]], [*n; 3]));
+        }
+
+        Mesh::new(tris)
+    }
+
+    #[test]
+    fn test_voxelise_cube() {
+        let boundary = Cube::new(Point3::new(0.0, 0.0, 0.0), Point3::new(4.0, 4.0, 4.0));
+        let grid = Grid::new(boundary, [8, 8, 8]);
+        let cells = grid.voxelise(&cube_mesh(1.25, 2.75));
+
+        for ((xi, yi, zi), inside) in cells.indexed_iter() {
+            let expected = [xi, yi, zi].iter().all(|i| (2..=5).contains(i));
+            assert_eq!(*inside, expected, "cell [{}, {}, {}]", xi, yi, zi);
+        }
+    }
+
+    #[test]
+    fn test_voxelise_into() {
+        let boundary = Cube::new(Point3::new(0.0, 0.0, 0.0), Point3::new(4.0, 4.0, 4.0));
+        let grid = Grid::new(boundary, [4, 4, 4]);
+        let mut cells = Array3::from_elem([4, 4, 4], None);
+        grid.voxelise_into(&cube_mesh(1.5, 2.5), 3, &mut cells);
+
+        assert_eq!(cells[[1, 1, 1]], Some(3));
+        assert_eq!(cells[[2, 2, 2]], Some(3));
+        assert_eq!(cells[[0, 0, 0]], None);
+        assert_eq!(cells[[3, 1, 2]], None);
+    }
+}
@@ -14,7 +14,8 @@ use std::fmt::{Display, Formatter};
 pub struct GridBuilder {
     /// Boundary.
     boundary: Cube,
-    /// Resolution.
+    /// Resolution along each axis.
+    /// Axes are independent, so thin geometries may be finely resolved across their thickness alone.
     res: [usize; 3],
 }
 
@@ -5,10 +5,12 @@ pub mod grid_builder;
 pub mod surface;
 pub mod surface_linker;
 pub mod surface_linker_loader;
+pub mod traversal_stats;
 pub mod tree;
 pub mod tree_settings;
+pub mod voxel_traversal;
 
 pub use self::{
-    grid::*, grid_builder::*, surface::*, surface_linker::*, surface_linker_loader::*, tree::*,
-    tree_settings::*,
+    grid::*, grid_builder::*, surface::*, surface_linker::*, surface_linker_loader::*,
+    traversal_stats::*, tree::*, tree_settings::*, voxel_traversal::*,
 };
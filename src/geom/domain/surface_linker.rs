@@ -38,9 +38,10 @@ impl<'a, T: 'a> Link<'a, T> for SurfaceLinker {
         let attr = self.attr;
         Ok(Surface::new(
             self.mesh,
-            attrs
-                .get(&attr)
-                .unwrap_or_else(|| panic!("Failed to link surface-attribute key: {}", attr)),
+            attrs.get(&attr).ok_or_else(|| {
+                Error::Text(format!("Failed to link attribute key: {}", attr))
+                    .with_context("attribute")
+            })?,
         ))
     }
 }
@@ -16,6 +16,8 @@ pub struct SurfaceLinkerLoader {
     mesh: MeshLoader,
     /// Attribute name.
     attr: Name,
+    /// Optionally shade each triangle flat with its geometric normal, rather than interpolating the vertex normals.
+    flat: Option<bool>,
 }
 
 impl Load for SurfaceLinkerLoader {
@@ -23,7 +25,10 @@ impl Load for SurfaceLinkerLoader {
 
     #[inline]
     fn load(self, in_dir: &Path) -> Result<Self::Inst, Error> {
-        let mesh = self.mesh.load(in_dir)?;
+        let mesh = self
+            .mesh
+            .load(in_dir)?
+            .with_flat_shading(self.flat.unwrap_or(false));
         Ok(Self::Inst::new(mesh, self.attr))
     }
 }
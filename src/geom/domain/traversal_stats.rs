@@ -0,0 +1,94 @@
+//! Tree traversal diagnostics.
+
+use crate::{clone, fmt_report};
+use std::{
+    fmt::{Display, Formatter},
+    ops::AddAssign,
+};
+
+/// Aggregate cost of tracing rays through a tree.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TraversalStats {
+    /// Number of rays traced.
+    rays: u64,
+    /// Total number of tree cells visited.
+    node_visits: u64,
+    /// Total number of ray-triangle intersection tests.
+    tri_tests: u64,
+    /// Greatest number of cells visited by a single ray.
+    max_node_visits: u64,
+    /// Greatest number of triangle tests performed for a single ray.
+    max_tri_tests: u64,
+}
+
+impl TraversalStats {
+    clone!(rays: u64);
+    clone!(node_visits: u64);
+    clone!(tri_tests: u64);
+    clone!(max_node_visits: u64);
+    clone!(max_tri_tests: u64);
+
+    /// Record the cost of tracing a single ray.
+    #[inline]
+    pub fn record(&mut self, node_visits: u64, tri_tests: u64) {
+        self.rays += 1;
+        self.node_visits += node_visits;
+        self.tri_tests += tri_tests;
+        self.max_node_visits = self.max_node_visits.max(node_visits);
+        self.max_tri_tests = self.max_tri_tests.max(tri_tests);
+    }
+
+    /// Mean number of cells visited per ray.
+    #[inline]
+    #[must_use]
+    pub fn mean_node_visits(&self) -> f64 {
+        if self.rays == 0 {
+            return 0.0;
+        }
+        self.node_visits as f64 / self.rays as f64
+    }
+
+    /// Mean number of triangle tests per ray.
+    #[inline]
+    #[must_use]
+    pub fn mean_tri_tests(&self) -> f64 {
+        if self.rays == 0 {
+            return 0.0;
+        }
+        self.tri_tests as f64 / self.rays as f64
+    }
+}
+
+impl AddAssign<&Self> for TraversalStats {
+    #[inline]
+    fn add_assign(&mut self, rhs: &Self) {
+        self.rays += rhs.rays;
+        self.node_visits += rhs.node_visits;
+        self.tri_tests += rhs.tri_tests;
+        self.max_node_visits = self.max_node_visits.max(rhs.max_node_visits);
+        self.max_tri_tests = self.max_tri_tests.max(rhs.max_tri_tests);
+    }
+}
+
+impl Display for TraversalStats {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        writeln!(fmt, "...")?;
+        fmt_report!(fmt, self.rays, "rays traced");
+        fmt_report!(
+            fmt,
+            &format!(
+                "{:.2} (max {})",
+                self.mean_node_visits(),
+                self.max_node_visits
+            ),
+            "cell visits per ray"
+        );
+        fmt_report!(
+            fmt,
+            &format!("{:.2} (max {})", self.mean_tri_tests(), self.max_tri_tests),
+            "triangle tests per ray"
+        );
+        Ok(())
+    }
+}
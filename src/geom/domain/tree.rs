@@ -2,7 +2,9 @@
 
 use crate::{
     fmt_report,
-    geom::{Collide, Cube, Hit, Ray, Scan, SmoothTriangle, Surface, Trace, TreeSettings},
+    geom::{
+        Collide, Cube, Hit, Ray, Scan, SmoothTriangle, Surface, Trace, TraversalStats, TreeSettings,
+    },
     math::Point3,
     ord::Set,
     tools::ProgressBar,
@@ -22,13 +24,14 @@ pub enum Tree<'a, T> {
     Leaf {
         /// Boundary.
         boundary: Cube,
-        /// Intersecting triangles and their corresponding mesh index.
-        tris: Vec<(&'a SmoothTriangle, &'a T)>,
+        /// Intersecting triangles, their attributes, and the index of their owning surface.
+        tris: Vec<(&'a SmoothTriangle, &'a T, usize)>,
     },
 }
 
 impl<'a, T> Tree<'a, T> {
     /// Construct a new instance.
+    /// Each surface is identified by its index within the set, and hits report the identifier of the surface struck.
     #[inline]
     #[must_use]
     pub fn new(sett: &TreeSettings, surfs: &'a Set<Surface<T>>) -> Self {
@@ -36,10 +39,10 @@ impl<'a, T> Tree<'a, T> {
         boundary.expand(sett.padding());
 
         let mut tris = Vec::new();
-        for surf in surfs.values() {
+        for (id, surf) in surfs.values().enumerate() {
             tris.reserve(surf.mesh().tris().len());
             for tri in surf.mesh().tris() {
-                tris.push((tri, surf.attr()));
+                tris.push((tri, surf.attr(), id));
             }
         }
 
@@ -106,7 +109,7 @@ impl<'a, T> Tree<'a, T> {
         sett: &TreeSettings,
         parent_boundary: &Cube,
         depth: u32,
-        potential_tris: &[(&'a SmoothTriangle, &'a T)],
+        potential_tris: &[(&'a SmoothTriangle, &'a T, usize)],
     ) -> [Self; 8] {
         debug_assert!(depth <= sett.max_depth());
         debug_assert!(!potential_tris.is_empty());
@@ -145,7 +148,7 @@ impl<'a, T> Tree<'a, T> {
         sett: &TreeSettings,
         boundary: Cube,
         depth: u32,
-        potential_tris: &[(&'a SmoothTriangle, &'a T)],
+        potential_tris: &[(&'a SmoothTriangle, &'a T, usize)],
     ) -> Tree<'a, T> {
         debug_assert!(depth <= sett.max_depth());
 
@@ -153,9 +156,9 @@ impl<'a, T> Tree<'a, T> {
         detection_vol.expand(sett.padding());
 
         let mut tris = Vec::new();
-        for &(tri, attr) in potential_tris {
+        for &(tri, attr, id) in potential_tris {
             if tri.overlap(&detection_vol) {
-                tris.push((tri, attr));
+                tris.push((tri, attr, id));
             }
         }
 
@@ -249,8 +252,16 @@ impl<'a, T> Tree<'a, T> {
     #[must_use]
     #[inline]
     pub fn find_leaf(&self, pos: &Point3) -> &Self {
+        self.descend(pos, &mut 0)
+    }
+
+    /// Determine the terminal leaf cell containing the given position, counting the cells visited on the way.
+    #[must_use]
+    #[inline]
+    fn descend(&self, pos: &Point3, visits: &mut u64) -> &Self {
         debug_assert!(self.boundary().contains(pos));
 
+        *visits += 1;
         match *self {
             Self::Leaf { .. } => self,
             Self::Branch {
@@ -269,15 +280,16 @@ impl<'a, T> Tree<'a, T> {
                 if pos.z() >= c.z() {
                     index += 4;
                 }
-                children[index].find_leaf(pos)
+                children[index].descend(pos, visits)
             }
         }
     }
 
     /// Scan for what a given Ray, known to be within the cell, would observe.
+    /// The number of triangles tested is added to the given count.
     #[inline]
     #[must_use]
-    fn leaf_scan(&self, ray: &Ray, bump_dist: f64) -> Scan<T> {
+    fn leaf_scan(&self, ray: &Ray, bump_dist: f64, tri_tests: &mut u64) -> Scan<T> {
         debug_assert!(self.boundary().contains(ray.pos()));
         debug_assert!(bump_dist > 0.0);
 
@@ -290,19 +302,17 @@ impl<'a, T> Tree<'a, T> {
                 ref tris,
             } => {
                 let boundary_dist = boundary.dist(ray).unwrap();
+                *tri_tests += tris.len() as u64;
                 if tris.is_empty() {
                     return Scan::new_boundary(boundary_dist);
                 }
 
                 let mut nearest: Option<Hit<T>> = None;
-                for &(tri, attr) in tris {
-                    if let Some((dist, side)) = tri.dist_side(ray) {
-                        if let Some(ref hit) = nearest {
-                            if dist < hit.dist() {
-                                nearest = Some(Hit::new(attr, dist, side));
-                            }
-                        } else {
-                            nearest = Some(Hit::new(attr, dist, side));
+                for &(tri, attr, id) in tris {
+                    if let Some((dist, side, uv)) = tri.dist_side_uv(ray) {
+                        if nearest.as_ref().map_or(true, |hit| dist < hit.dist()) {
+                            nearest =
+                                Some(Hit::new(attr, dist, side).with_uv(uv).with_object(Some(id)));
                         }
                     }
                 }
@@ -322,7 +332,43 @@ impl<'a, T> Tree<'a, T> {
     /// The maximum distance provided does not guarantee that any hit retrieved is less than the given distance.
     #[inline]
     #[must_use]
-    pub fn scan(&self, mut ray: Ray, bump_dist: f64, max_dist: f64) -> Option<Hit<T>> {
+    pub fn scan(&self, ray: Ray, bump_dist: f64, max_dist: f64) -> Option<Hit<T>> {
+        self.scan_with(ray, bump_dist, max_dist, None)
+    }
+
+    /// Determine what a given Ray would observe, as `scan`,
+    /// recording the number of cells visited and triangles tested if statistics are given.
+    #[inline]
+    #[must_use]
+    pub fn scan_with(
+        &self,
+        ray: Ray,
+        bump_dist: f64,
+        max_dist: f64,
+        stats: Option<&mut TraversalStats>,
+    ) -> Option<Hit<T>> {
+        let mut node_visits = 0;
+        let mut tri_tests = 0;
+        let hit = self.traverse(ray, bump_dist, max_dist, &mut node_visits, &mut tri_tests);
+
+        if let Some(stats) = stats {
+            stats.record(node_visits, tri_tests);
+        }
+
+        hit
+    }
+
+    /// Trace a ray through the tree, counting the cells visited and triangles tested.
+    #[inline]
+    #[must_use]
+    fn traverse(
+        &self,
+        mut ray: Ray,
+        bump_dist: f64,
+        max_dist: f64,
+        node_visits: &mut u64,
+        tri_tests: &mut u64,
+    ) -> Option<Hit<T>> {
         debug_assert!(bump_dist > 0.0);
         debug_assert!(max_dist > 0.0);
 
@@ -339,12 +385,13 @@ impl<'a, T> Tree<'a, T> {
             }
         }
 
-        while let Some(cell) = self.try_find_leaf(ray.pos()) {
+        while self.boundary().contains(ray.pos()) {
+            let cell = self.descend(ray.pos(), node_visits);
             if dist_travelled > max_dist {
                 return None;
             }
 
-            match cell.leaf_scan(&ray, bump_dist) {
+            match cell.leaf_scan(&ray, bump_dist, tri_tests) {
                 Scan::Surface(mut hit) => {
                     *hit.dist_mut() += dist_travelled;
                     return Some(hit);
@@ -456,4 +503,98 @@ mod tests {
         assert_eq!(tree.num_tris(), 7);
 
     }
+
+    /// Hits report the index of the surface which owns the struck triangle.
+    #[test]
+    fn test_scan_object_id() {
+        let surfs = Set::new(make_test_surfs());
+        let tree_settings = TreeSettings::new(1, 1, 1e-6);
+        let tree: Tree<'_, Attribute<'_>> = Tree::new(&tree_settings, &surfs);
+
+        let first = tree
+            .scan(
+                Ray::new(Point3::new(0.2, 0.2, 0.5), Dir3::new(0.0, 0.0, -1.0)),
+                1e-9,
+                10.0,
+            )
+            .unwrap();
+        assert_approx_eq!(first.dist(), 0.5);
+        assert_eq!(first.object(), Some(0));
+
+        let second = tree
+            .scan(
+                Ray::new(Point3::new(1.2, 1.2, 0.5), Dir3::new(0.0, 0.0, 1.0)),
+                1e-9,
+                10.0,
+            )
+            .unwrap();
+        assert_approx_eq!(second.dist(), 0.5);
+        assert_eq!(second.object(), Some(1));
+    }
+
+    /// Make a surface of two parallel layers of small triangles, one above the other.
+    fn make_layered_surfs(cells: usize) -> BTreeMap<Name, Surface<'static, Attribute<'static>>> {
+        let norm = Dir3::new(0.0, 0.0, 1.0);
+        let mut tris = Vec::new();
+        for z in &[0.0, 1.0] {
+            for i in 0..cells {
+                for j in 0..cells {
+                    let (x, y) = (i as f64, j as f64);
+                    tris.push(SmoothTriangle::new(
+                        Triangle::new([
+                            Point3::new(x, y, *z),
+                            Point3::new(x + 1.0, y, *z),
+                            Point3::new(x, y + 1.0, *z),
+                        ]),
+                        [norm, norm, norm],
+                    ));
+                    tris.push(SmoothTriangle::new(
+                        Triangle::new([
+                            Point3::new(x + 1.0, y, *z),
+                            Point3::new(x + 1.0, y + 1.0, *z),
+                            Point3::new(x, y + 1.0, *z),
+                        ]),
+                        [norm, norm, norm],
+                    ));
+                }
+            }
+        }
+
+        let mut surfs_map = BTreeMap::new();
+        surfs_map.insert(
+            Name::new("layers"),
+            Surface::new(Mesh::new(tris), &Attribute::Mirror(0.5)),
+        );
+        surfs_map
+    }
+
+    /// A tree which is a single leaf tests every triangle, whilst a well-split tree tests few.
+    #[test]
+    fn test_traversal_stats() {
+        let surfs = Set::new(make_layered_surfs(4));
+
+        let flat: Tree<'_, Attribute<'_>> = Tree::new(&TreeSettings::new(1000, 1, 0.01), &surfs);
+        let split: Tree<'_, Attribute<'_>> = Tree::new(&TreeSettings::new(2, 4, 0.01), &surfs);
+        assert_eq!(flat.num_cells(), 1);
+        assert!(split.num_cells() > 1);
+
+        let mut flat_stats = TraversalStats::default();
+        let mut split_stats = TraversalStats::default();
+        for n in 0..16 {
+            let pos = Point3::new(0.3 + (n % 4) as f64, 0.2 + (n / 4) as f64, 0.5);
+            let ray = Ray::new(pos, Dir3::new(0.0, 0.0, -1.0));
+
+            let a = flat.scan_with(ray.clone(), 1e-9, 10.0, Some(&mut flat_stats));
+            let b = split.scan_with(ray, 1e-9, 10.0, Some(&mut split_stats));
+            assert_approx_eq!(a.unwrap().dist(), 0.5);
+            assert_approx_eq!(b.unwrap().dist(), 0.5);
+        }
+
+        assert_eq!(flat_stats.rays(), 16);
+        assert_eq!(split_stats.rays(), 16);
+        assert_eq!(flat_stats.max_tri_tests(), 64);
+        assert_approx_eq!(flat_stats.mean_node_visits(), 1.0);
+        assert!(split_stats.mean_node_visits() > 1.0);
+        assert!(split_stats.mean_tri_tests() < flat_stats.mean_tri_tests());
+    }
 }
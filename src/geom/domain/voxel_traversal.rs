@@ -0,0 +1,222 @@
+//! Ray-marching iterator over the voxels of a grid.
+
+use crate::{
+    geom::{Grid, Ray},
+    ord::{X, Y, Z},
+};
+
+/// Iterator over the voxels of a grid crossed by a ray, using a three-dimensional digital differential analyser.
+/// Each item is the index of a crossed voxel and the path length of the ray within it.
+/// Voxels are visited in the order the ray crosses them, starting from the ray origin,
+/// or its point of entry into the grid.
+#[derive(Clone, Debug)]
+pub struct VoxelTraversal {
+    /// Resolution of the grid.
+    res: [usize; 3],
+    /// Index of the current voxel.
+    index: [usize; 3],
+    /// Direction of index steps along each axis.
+    step: [isize; 3],
+    /// Distance along the ray at which the next voxel boundary of each axis is crossed.
+    next: [f64; 3],
+    /// Distance along the ray between voxel boundaries of each axis.
+    delta: [f64; 3],
+    /// Distance along the ray travelled so far.
+    dist: f64,
+    /// Distance along the ray at which it leaves the grid.
+    exit: f64,
+}
+
+impl VoxelTraversal {
+    /// Construct a new instance.
+    #[inline]
+    #[must_use]
+    pub fn new(grid: &Grid, ray: &Ray) -> Self {
+        let mins = grid.boundary().mins();
+        let maxs = grid.boundary().maxs();
+        let size = grid.voxel_size();
+        let res = *grid.res();
+
+        let pos = [ray.pos().x(), ray.pos().y(), ray.pos().z()];
+        let dir = [ray.dir().x(), ray.dir().y(), ray.dir().z()];
+        let lower = [mins.x(), mins.y(), mins.z()];
+        let upper = [maxs.x(), maxs.y(), maxs.z()];
+
+        // Distances along the ray at which it enters and leaves the grid.
+        let mut entry: f64 = 0.0;
+        let mut exit = f64::INFINITY;
+        for n in 0..3 {
+            if dir[n] == 0.0 {
+                if pos[n] < lower[n] || pos[n] > upper[n] {
+                    exit = -1.0;
+                }
+                continue;
+            }
+            let t_0 = (lower[n] - pos[n]) / dir[n];
+            let t_1 = (upper[n] - pos[n]) / dir[n];
+            entry = entry.max(t_0.min(t_1));
+            exit = exit.min(t_0.max(t_1));
+        }
+
+        let mut index = [0; 3];
+        let mut step = [0; 3];
+        let mut next = [f64::INFINITY; 3];
+        let mut delta = [f64::INFINITY; 3];
+        if entry < exit {
+            for n in 0..3 {
+                let p = dir[n].mul_add(entry, pos[n]);
+                let i = ((p - lower[n]) / size[n]).floor().max(0.0) as usize;
+                index[n] = i.min(res[n] - 1);
+
+                if dir[n] > 0.0 {
+                    step[n] = 1;
+                    next[n] = (size[n].mul_add((index[n] + 1) as f64, lower[n]) - pos[n]) / dir[n];
+                    delta[n] = size[n] / dir[n];
+                } else if dir[n] < 0.0 {
+                    step[n] = -1;
+                    next[n] = (size[n].mul_add(index[n] as f64, lower[n]) - pos[n]) / dir[n];
+                    delta[n] = -size[n] / dir[n];
+                }
+            }
+        }
+
+        Self {
+            res,
+            index,
+            step,
+            next,
+            delta,
+            dist: entry,
+            exit,
+        }
+    }
+
+    /// Stop the traversal once the ray has travelled the given distance [m] from its origin.
+    #[inline]
+    #[must_use]
+    pub fn limit(mut self, dist: f64) -> Self {
+        self.exit = self.exit.min(dist);
+        self
+    }
+}
+
+impl Iterator for VoxelTraversal {
+    type Item = ([usize; 3], f64);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.dist < self.exit {
+            // Axis of the nearest voxel boundary.
+            let axis = if self.next[X] <= self.next[Y] && self.next[X] <= self.next[Z] {
+                X
+            } else if self.next[Y] <= self.next[Z] {
+                Y
+            } else {
+                Z
+            };
+
+            let index = self.index;
+            let end = self.next[axis].min(self.exit);
+            let len = end - self.dist;
+            self.dist = end;
+
+            // Step into the neighbouring voxel, unless the ray leaves the grid.
+            if end < self.exit {
+                let i = self.index[axis] as isize + self.step[axis];
+                if i < 0 || i >= self.res[axis] as isize {
+                    self.exit = end;
+                } else {
+                    self.index[axis] = i as usize;
+                    self.next[axis] += self.delta[axis];
+                }
+            }
+
+            // Rays passing exactly through voxel edges and corners visit voxels for zero length.
+            if len > 0.0 {
+                return Some((index, len));
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        geom::Cube,
+        math::{Dir3, Point3},
+    };
+    use assert_approx_eq::assert_approx_eq;
+
+    fn grid() -> Grid {
+        Grid::new(
+            Cube::new(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0)),
+            [2, 2, 2],
+        )
+    }
+
+    #[test]
+    fn test_diagonal_traversal() {
+        let ray = Ray::new(Point3::new(-1.0, -1.0, -1.0), Dir3::new(1.0, 1.0, 1.0));
+        let visited: Vec<_> = grid().voxel_traversal(&ray).collect();
+
+        assert_eq!(visited.len(), 2);
+        assert_eq!(visited[0].0, [0, 0, 0]);
+        assert_eq!(visited[1].0, [1, 1, 1]);
+        assert_approx_eq!(visited[0].1, 0.75_f64.sqrt());
+        assert_approx_eq!(visited[1].1, 0.75_f64.sqrt());
+    }
+
+    #[test]
+    fn test_skewed_traversal() {
+        let ray = Ray::new(Point3::new(-0.5, 0.1, 0.2), Dir3::new(1.0, 0.5, 0.3));
+        let visited: Vec<_> = grid().voxel_traversal(&ray).collect();
+
+        assert_eq!(visited.first().unwrap().0, [0, 0, 0]);
+        assert_eq!(visited.last().unwrap().0, [1, 1, 1]);
+        for pair in visited.windows(2) {
+            let steps: usize = (0..3)
+                .map(|n| (pair[0].0[n] as isize - pair[1].0[n] as isize).abs() as usize)
+                .sum();
+            assert_eq!(steps, 1);
+        }
+
+        // The path lengths sum to the chord of the ray through the grid.
+        let dir = ray.dir();
+        let entry = 0.5 / dir.x();
+        let exit = (0.8 / dir.z()).min(0.9 / dir.y()).min(1.5 / dir.x());
+        let total: f64 = visited.iter().map(|(_, len)| len).sum();
+        assert_approx_eq!(total, exit - entry);
+    }
+
+    #[test]
+    fn test_traversal_from_inside() {
+        let ray = Ray::new(Point3::new(0.25, 0.25, 0.75), Dir3::new(1.0, 0.0, 0.0));
+        let visited: Vec<_> = grid().voxel_traversal(&ray).collect();
+
+        assert_eq!(visited.len(), 2);
+        assert_eq!(visited[0].0, [0, 0, 1]);
+        assert_eq!(visited[1].0, [1, 0, 1]);
+        assert_approx_eq!(visited[0].1, 0.25);
+        assert_approx_eq!(visited[1].1, 0.5);
+    }
+
+    #[test]
+    fn test_limited_traversal() {
+        let ray = Ray::new(Point3::new(-0.5, 0.25, 0.25), Dir3::new(1.0, 0.0, 0.0));
+        let visited: Vec<_> = grid().voxel_traversal(&ray).limit(1.2).collect();
+
+        assert_eq!(visited.len(), 2);
+        assert_approx_eq!(visited[0].1, 0.5);
+        assert_approx_eq!(visited[1].1, 0.2);
+        assert_eq!(grid().voxel_traversal(&ray).limit(0.4).count(), 0);
+    }
+
+    #[test]
+    fn test_miss() {
+        let ray = Ray::new(Point3::new(2.0, 0.5, 0.5), Dir3::new(1.0, 0.0, 0.0));
+        assert_eq!(grid().voxel_traversal(&ray).count(), 0);
+    }
+}
@@ -12,6 +12,29 @@ use std::f64::consts::PI;
 pub trait Emit {
     /// Cast a new ray.
     fn cast<R: Rng>(&self, rng: &mut R) -> Ray;
+
+    /// Sample an emission point and its geometric coupling toward a target.
+    /// This is intended for surface emitters, which cast rays along their surface normal.
+    /// Returns the emission point, the direction from it to the target,
+    /// and the geometry term `cos(theta) / distance^2`, which is zero for points facing away from the target.
+    #[inline]
+    #[must_use]
+    fn sample_toward<R: Rng>(&self, target: &Point3, rng: &mut R) -> (Point3, Dir3, f64) {
+        let (pos, norm) = self.cast(rng).destruct();
+
+        let sep = target - pos;
+        let dist_sq = sep.dot(&sep);
+        let dir = Dir3::from(sep);
+        let cos_theta = norm.dot(&dir);
+
+        let geom = if cos_theta > 0.0 && dist_sq > 0.0 {
+            cos_theta / dist_sq
+        } else {
+            0.0
+        };
+
+        (pos, dir, geom)
+    }
 }
 
 /// Provides a concrete implementation of ray casting for a Point3.
@@ -39,9 +62,37 @@ impl Emit for Point3 {
 #[cfg(test)]
 mod tests {
     use super::Emit;
-    use crate::{data::Average, math::Point3};
+    use crate::{
+        data::Average,
+        geom::{Mesh, SmoothTriangle},
+        math::{Dir3, Point3},
+    };
     use assert_approx_eq::assert_approx_eq;
 
+    /// Construct a flat, upward facing, square emitter of the given side length, centred on the origin.
+    fn square(side: f64) -> Mesh {
+        let h = side * 0.5;
+        let norm = Dir3::new(0.0, 0.0, 1.0);
+        Mesh::new(vec![
+            SmoothTriangle::new_from_verts(
+                [
+                    Point3::new(-h, -h, 0.0),
+                    Point3::new(h, -h, 0.0),
+                    Point3::new(h, h, 0.0),
+                ],
+                [norm; 3],
+            ),
+            SmoothTriangle::new_from_verts(
+                [
+                    Point3::new(-h, -h, 0.0),
+                    Point3::new(h, h, 0.0),
+                    Point3::new(-h, h, 0.0),
+                ],
+                [norm; 3],
+            ),
+        ])
+    }
+
     /// As the default mode of emission is isotropic, I will test this using the
     /// Point3 impl for this above.
     #[test]
@@ -65,4 +116,30 @@ mod tests {
         assert_approx_eq!(y_ave.ave(), 0.0, 0.025);
         assert_approx_eq!(z_ave.ave(), 0.0, 0.025);
     }
+
+    #[test]
+    fn test_sample_toward_on_axis() {
+        let mut rng = rand::thread_rng();
+        let emitter = square(1.0);
+        let height = 2.0;
+        let target = Point3::new(0.0, 0.0, height);
+
+        for _ in 0..100 {
+            let (pos, dir, geom) = emitter.sample_toward(&target, &mut rng);
+            assert_approx_eq!(pos.z(), 0.0);
+
+            // For a flat emitter, cos(theta) = h / d, so the geometry term is h / d^3.
+            let dist = ((pos.x() * pos.x()) + (pos.y() * pos.y()) + (height * height)).sqrt();
+            assert_approx_eq!(geom, height / dist.powi(3));
+            assert_approx_eq!(dir.z(), height / dist);
+        }
+
+        // A small emitter reduces to the inverse square law.
+        let (_, _, geom) = square(1.0e-4).sample_toward(&target, &mut rng);
+        assert_approx_eq!(geom, 1.0 / (height * height), 1.0e-6);
+
+        // Targets behind the emitter receive nothing.
+        let (_, _, geom) = emitter.sample_toward(&Point3::new(0.0, 0.0, -height), &mut rng);
+        assert_eq!(geom, 0.0);
+    }
 }
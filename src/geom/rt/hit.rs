@@ -15,12 +15,18 @@ pub struct Hit<'a, T> {
     dist: f64,
     /// Normal of the surface.
     side: Side,
+    /// Interpolated texture coordinates of the surface, if it has any.
+    uv: Option<[f64; 2]>,
+    /// Identifier of the scene object owning the surface, if known.
+    object: Option<usize>,
 }
 
 impl<'a, T> Hit<'a, T> {
     access!(tag: T);
     clone!(dist, dist_mut: f64);
     access!(side: Side);
+    clone!(uv: Option<[f64; 2]>);
+    clone!(object: Option<usize>);
 
     /// Construct a new instance.
     #[inline]
@@ -28,7 +34,29 @@ impl<'a, T> Hit<'a, T> {
     pub fn new(tag: &'a T, dist: f64, side: Side) -> Self {
         debug_assert!(dist > 0.0);
 
-        Self { tag, dist, side }
+        Self {
+            tag,
+            dist,
+            side,
+            uv: None,
+            object: None,
+        }
+    }
+
+    /// Set the texture coordinates of the hit.
+    #[inline]
+    #[must_use]
+    pub fn with_uv(mut self, uv: Option<[f64; 2]>) -> Self {
+        self.uv = uv;
+        self
+    }
+
+    /// Set the identifier of the scene object owning the surface.
+    #[inline]
+    #[must_use]
+    pub fn with_object(mut self, object: Option<usize>) -> Self {
+        self.object = object;
+        self
     }
 }
 
@@ -95,4 +123,16 @@ mod tests {
         let hit = Hit::new(&tag, dist, side.clone());
         assert_eq!(*hit.tag(), tag);
     }
+
+    /// Hits carry no texture coordinates unless they are provided.
+    #[test]
+    fn test_uv() {
+        let tag = Attribute::Mirror(0.5);
+        let side = Side::Inside(Dir3::new(0.0, 0.0, 1.0));
+        let hit = Hit::new(&tag, 1.0, side);
+        assert_eq!(hit.uv(), None);
+
+        let hit = hit.with_uv(Some([0.25, 0.75]));
+        assert_eq!(hit.uv(), Some([0.25, 0.75]));
+    }
 }
\ No newline at end of file
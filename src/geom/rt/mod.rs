@@ -11,8 +11,17 @@
 
 pub mod hit;
 pub mod orient;
+pub mod oriented_plane;
+pub mod plane;
 pub mod ray;
 pub mod scan;
 pub mod side;
+pub mod trajectory;
 
-pub use self::{hit::*, orient::*, ray::*, scan::*, side::*};
+// Builders
+pub mod orient_builder;
+
+pub use self::{
+    hit::*, orient::*, orient_builder::*, oriented_plane::*, plane::*, ray::*, scan::*, side::*,
+    trajectory::*,
+};
@@ -5,13 +5,14 @@ use crate::{
     geom::Ray,
     math::{Dir3, Point3, Vec3},
 };
+use serde_derive::{Deserialize, Serialize};
 use std::fmt::{Display, Error, Formatter};
 
 /// # Orientation
 ///
 /// Contains orientation information about an object.
 /// The struct contains the forward, right and up directions.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Orient {
     /// Position.
     pos: Point3,
@@ -72,6 +73,38 @@ impl Orient {
         Self::new(Ray::new(pos, Dir3::from(tar - pos)))
     }
 
+    /// Construct from Euler angles [rad], applied as a yaw about the z-axis, measured from the x-axis towards the y-axis,
+    /// then a pitch of the forward direction above the xy-plane, then a roll about the forward direction.
+    /// With all angles zero, the orientation faces along the x-axis with the z-axis up, as `Orient::new`.
+    ///
+    /// The pitch should lie within [-pi/2, pi/2].
+    /// At its limits the forward direction lies along the z-axis, and yaw and roll both rotate about it (gimbal lock),
+    /// so only their sum affects the orientation.
+    /// The right direction is determined from the yaw alone, so these cases remain well defined.
+    #[inline]
+    #[must_use]
+    pub fn new_euler(pos: Point3, yaw: f64, pitch: f64, roll: f64) -> Self {
+        debug_assert!(pitch.abs() <= std::f64::consts::FRAC_PI_2 + f64::EPSILON);
+
+        let (sin_yaw, cos_yaw) = yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = pitch.sin_cos();
+        let (sin_roll, cos_roll) = roll.sin_cos();
+
+        let forward = Dir3::new(cos_pitch * cos_yaw, cos_pitch * sin_yaw, sin_pitch);
+        let level_right = Dir3::new(sin_yaw, -cos_yaw, 0.0);
+        let level_up = Dir3::from(level_right.cross(&forward));
+
+        let right = Dir3::from((level_right * cos_roll) - (level_up * sin_roll));
+        let up = Dir3::from((level_right * sin_roll) + (level_up * cos_roll));
+
+        Self {
+            pos,
+            forward,
+            right,
+            up,
+        }
+    }
+
     /// Reference the backward direction.
     #[inline]
     #[must_use]
@@ -182,7 +215,10 @@ mod tests {
         math::{Dir3, Point3},
     };
     use assert_approx_eq::assert_approx_eq;
-    use std::f64;
+    use std::f64::{
+        self,
+        consts::{FRAC_PI_2, FRAC_PI_4, FRAC_PI_6},
+    };
 
     /// Checks that we can initialise and pull back the correct orientation information
     /// using an Orient object.
@@ -279,4 +315,67 @@ mod tests {
         assert_eq!(orient.right(), &Dir3::new(0.0, 1.0, 0.0));
         assert_eq!(orient.up(), &Dir3::new(1.0, 0.0, 0.0));
     }
+
+    /// Check that orientations built from Euler angles face in the expected directions.
+    #[test]
+    fn euler_test() {
+        let pos = Point3::new(1.0, 2.0, 3.0);
+
+        // No rotation matches the default orientation along the x-axis.
+        let orient = Orient::new_euler(pos, 0.0, 0.0, 0.0);
+        let default = Orient::new(Ray::new(pos, Dir3::new(1.0, 0.0, 0.0)));
+        for (a, b) in &[
+            (orient.forward(), default.forward()),
+            (orient.right(), default.right()),
+            (orient.up(), default.up()),
+        ] {
+            assert_approx_eq!(a.dot(b), 1.0);
+        }
+
+        // Yaw a quarter turn towards y, then pitch up by a sixth of a half turn.
+        let orient = Orient::new_euler(pos, FRAC_PI_2, FRAC_PI_6, 0.0);
+        assert_eq!(orient.pos(), &pos);
+        assert_approx_eq!(orient.forward().x(), 0.0);
+        assert_approx_eq!(orient.forward().y(), 0.75_f64.sqrt());
+        assert_approx_eq!(orient.forward().z(), 0.5);
+        assert_approx_eq!(orient.right().x(), 1.0);
+        assert!(orient.up().z() > 0.0);
+
+        // Rolling turns the up direction towards the right.
+        let orient = Orient::new_euler(pos, 0.0, 0.0, FRAC_PI_4);
+        assert_approx_eq!(orient.forward().x(), 1.0);
+        assert_approx_eq!(orient.up().y(), -FRAC_PI_4.sin());
+        assert_approx_eq!(orient.up().z(), FRAC_PI_4.cos());
+        assert_approx_eq!(orient.right().dot(orient.up()), 0.0);
+    }
+
+    /// Check that looking straight up, where yaw and roll coincide, still gives an orthonormal frame.
+    #[test]
+    fn euler_gimbal_lock_test() {
+        let pos = Point3::new(0.0, 0.0, 0.0);
+        let a = Orient::new_euler(pos, FRAC_PI_6, FRAC_PI_2, 0.0);
+        let b = Orient::new_euler(pos, 0.0, FRAC_PI_2, FRAC_PI_6);
+
+        assert_approx_eq!(a.forward().z(), 1.0);
+        for orient in &[&a, &b] {
+            assert!(orient.right().x().is_finite());
+            assert_approx_eq!(orient.right().dot(orient.up()), 0.0);
+            assert_approx_eq!(orient.right().dot(orient.forward()), 0.0);
+        }
+
+        // Only the sum of the yaw and roll matters.
+        assert_approx_eq!(a.right().dot(b.right()), 1.0);
+        assert_approx_eq!(a.up().dot(b.up()), 1.0);
+    }
+
+    /// Check that an orientation survives serialisation exactly.
+    #[test]
+    fn serde_round_trip_test() {
+        let orient = Orient::new_euler(Point3::new(0.1, -2.5, 7.0), 0.3, -0.7, 1.1);
+        let text = json5::to_string(&orient).unwrap();
+        let loaded: Orient = json5::from_str(&text).unwrap();
+        assert_eq!(loaded, orient);
+        assert_eq!(loaded.forward().x(), orient.forward().x());
+        assert_eq!(loaded.up().z(), orient.up().z());
+    }
 }
@@ -0,0 +1,49 @@
+//! Orientation builder structure.
+
+use crate::{fmt_report, geom::Orient, math::Point3, ord::Build};
+use arctk_attr::file;
+use std::fmt::{Display, Error, Formatter};
+
+/// Loadable orientation, given by Euler angles.
+#[file]
+#[derive(Clone)]
+pub struct OrientBuilder {
+    /// Position.
+    pos: Point3,
+    /// Rotation about the z-axis, from the x-axis towards the y-axis (deg).
+    yaw: f64,
+    /// Elevation of the forward direction above the xy-plane (deg).
+    pitch: f64,
+    /// Optional rotation about the forward direction (deg).
+    roll: Option<f64>,
+}
+
+impl Build for OrientBuilder {
+    type Inst = Orient;
+
+    #[inline]
+    fn build(self) -> Self::Inst {
+        Self::Inst::new_euler(
+            self.pos,
+            self.yaw.to_radians(),
+            self.pitch.to_radians(),
+            self.roll.unwrap_or(0.0).to_radians(),
+        )
+    }
+}
+
+impl Display for OrientBuilder {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), Error> {
+        writeln!(fmt, "...")?;
+        fmt_report!(
+            fmt,
+            &format!("({}, {}, {})", self.pos.x(), self.pos.y(), self.pos.z()),
+            "position (m)"
+        );
+        fmt_report!(fmt, self.yaw, "yaw (deg)");
+        fmt_report!(fmt, self.pitch, "pitch (deg)");
+        fmt_report!(fmt, self.roll.unwrap_or(0.0), "roll (deg)");
+        Ok(())
+    }
+}
@@ -0,0 +1,159 @@
+//! Arbitrarily oriented plane.
+
+use crate::{
+    access, clone, fmt_report,
+    geom::{ray_plane_intersection, Orient, Ray},
+    math::Point3,
+};
+use std::fmt::{Display, Error, Formatter};
+
+/// # Oriented plane
+///
+/// A finite rectangular plane of arbitrary orientation.
+/// The plane is centred on the orientation position, faces along the forward direction,
+/// and uses the right and up directions as its in-plane horizontal and vertical axes.
+/// Points on the plane can be mapped into plane-local coordinates and pixel indices.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OrientedPlane {
+    /// Centre and in-plane axes.
+    orient: Orient,
+    /// Full width along the right direction [m].
+    width: f64,
+    /// Full height along the up direction [m].
+    height: f64,
+}
+
+impl OrientedPlane {
+    access!(orient: Orient);
+    clone!(width: f64);
+    clone!(height: f64);
+
+    /// Construct a new instance.
+    #[inline]
+    #[must_use]
+    pub fn new(orient: Orient, width: f64, height: f64) -> Self {
+        debug_assert!(width > 0.0);
+        debug_assert!(height > 0.0);
+
+        Self {
+            orient,
+            width,
+            height,
+        }
+    }
+
+    /// Plane-local coordinates of a point, measured from the plane centre along the right and up axes.
+    /// Points off the plane are projected onto it along the normal.
+    #[inline]
+    #[must_use]
+    pub fn local(&self, p: &Point3) -> [f64; 2] {
+        let rel = p - self.orient.pos();
+        [
+            self.orient.right().dot_vec(&rel),
+            self.orient.up().dot_vec(&rel),
+        ]
+    }
+
+    /// Normalised plane coordinates, in the range [0, 1] for points within the plane bounds.
+    #[inline]
+    #[must_use]
+    pub fn uv(&self, p: &Point3) -> Option<[f64; 2]> {
+        let [x, y] = self.local(p);
+        let u = (x / self.width) + 0.5;
+        let v = (y / self.height) + 0.5;
+
+        if (0.0..=1.0).contains(&u) && (0.0..=1.0).contains(&v) {
+            Some([u, v])
+        } else {
+            None
+        }
+    }
+
+    /// Pixel index of a point for the given resolution, if it falls within the plane bounds.
+    #[inline]
+    #[must_use]
+    pub fn pixel(&self, p: &Point3, res: [usize; 2]) -> Option<[usize; 2]> {
+        self.uv(p).map(|[u, v]| {
+            [
+                ((u * res[0] as f64) as usize).min(res[0] - 1),
+                ((v * res[1] as f64) as usize).min(res[1] - 1),
+            ]
+        })
+    }
+
+    /// Distance along a ray to the plane, if the ray hits within the plane bounds.
+    #[inline]
+    #[must_use]
+    pub fn dist(&self, ray: &Ray) -> Option<f64> {
+        let res = ray_plane_intersection(ray, self.orient.pos(), self.orient.forward());
+        let p = res.point(ray)?;
+        self.uv(&p).and(res.dist())
+    }
+
+    /// Pixel index hit by a ray for the given resolution, if the ray hits within the plane bounds.
+    #[inline]
+    #[must_use]
+    pub fn hit_pixel(&self, ray: &Ray, res: [usize; 2]) -> Option<[usize; 2]> {
+        let p = ray_plane_intersection(ray, self.orient.pos(), self.orient.forward()).point(ray)?;
+        self.pixel(&p, res)
+    }
+}
+
+impl Display for OrientedPlane {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), Error> {
+        writeln!(fmt, "...")?;
+        fmt_report!(fmt, self.orient, "orientation");
+        fmt_report!(fmt, self.width, "width (m)");
+        fmt_report!(fmt, self.height, "height (m)");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Dir3;
+    use assert_approx_eq::assert_approx_eq;
+
+    /// A plane tilted at 45 degrees about the x-axis.
+    fn tilted_plane() -> OrientedPlane {
+        let orient = Orient::new_up(
+            Ray::new(Point3::new(0.0, 0.0, 0.0), Dir3::new(0.0, 1.0, 1.0)),
+            &Dir3::new(0.0, -1.0, 1.0),
+        );
+        OrientedPlane::new(orient, 2.0, 2.0)
+    }
+
+    #[test]
+    fn test_tilted_plane_axes() {
+        let plane = tilted_plane();
+        assert_approx_eq!(plane.orient().right().x(), 1.0);
+        assert_approx_eq!(plane.orient().up().y(), -1.0 / 2.0_f64.sqrt());
+        assert_approx_eq!(plane.orient().up().z(), 1.0 / 2.0_f64.sqrt());
+    }
+
+    #[test]
+    fn test_tilted_plane_pixel() {
+        let plane = tilted_plane();
+
+        // Half a unit right, and half a unit down the tilted plane.
+        let s = 0.5 / 2.0_f64.sqrt();
+        let p = Point3::new(0.5, s, -s);
+        let [x, y] = plane.local(&p);
+        assert_approx_eq!(x, 0.5);
+        assert_approx_eq!(y, -0.5);
+        assert_eq!(plane.pixel(&p, [4, 4]), Some([3, 1]));
+
+        // A ray travelling straight down hits the same point.
+        let ray = Ray::new(Point3::new(0.5, s, 1.0), Dir3::new(0.0, 0.0, -1.0));
+        assert_eq!(plane.hit_pixel(&ray, [4, 4]), Some([3, 1]));
+        assert_approx_eq!(plane.dist(&ray).unwrap(), 1.0 + s);
+    }
+
+    #[test]
+    fn test_out_of_bounds() {
+        let plane = tilted_plane();
+        assert_eq!(plane.pixel(&Point3::new(2.0, 0.0, 0.0), [4, 4]), None);
+    }
+}
@@ -0,0 +1,96 @@
+//! Ray-plane intersection.
+
+use crate::{
+    geom::Ray,
+    math::{Dir3, Point3},
+};
+
+/// Tolerance below which a ray is considered parallel to a plane.
+const PARALLEL_TOLERANCE: f64 = 1.0e-12;
+
+/// # Ray-plane intersection result.
+///
+/// Describes the outcome of intersecting a ray with an infinite plane,
+/// distinguishing the reasons a ray may fail to reach the plane.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlaneIntersection {
+    /// The ray runs parallel to the plane and never meets it.
+    Parallel,
+    /// The plane lies behind the ray origin.
+    Behind,
+    /// The ray meets the plane at the given distance along its direction.
+    Hit(f64),
+}
+
+impl PlaneIntersection {
+    /// Distance to the intersection, if the plane is hit.
+    #[inline]
+    #[must_use]
+    pub const fn dist(&self) -> Option<f64> {
+        match *self {
+            Self::Hit(dist) => Some(dist),
+            Self::Parallel | Self::Behind => None,
+        }
+    }
+
+    /// Point of intersection along the given ray, if the plane is hit.
+    #[inline]
+    #[must_use]
+    pub fn point(&self, ray: &Ray) -> Option<Point3> {
+        self.dist().map(|dist| *ray.pos() + *ray.dir() * dist)
+    }
+}
+
+/// Intersect a ray with the infinite plane passing through `point` with normal `norm`.
+/// The sign of the normal does not matter.
+#[inline]
+#[must_use]
+pub fn ray_plane_intersection(ray: &Ray, point: &Point3, norm: &Dir3) -> PlaneIntersection {
+    let denom = ray.dir().dot(norm);
+    if denom.abs() < PARALLEL_TOLERANCE {
+        return PlaneIntersection::Parallel;
+    }
+
+    let dist = (point - ray.pos()).dot_dir3(norm) / denom;
+    if dist < 0.0 {
+        PlaneIntersection::Behind
+    } else {
+        PlaneIntersection::Hit(dist)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn test_perpendicular_hit() {
+        let ray = Ray::new(Point3::new(0.0, 0.0, -2.0), Dir3::new(0.0, 0.0, 1.0));
+        let res = ray_plane_intersection(&ray, &Point3::new(1.0, 1.0, 0.0), &Dir3::z_axis());
+
+        assert_approx_eq!(res.dist().unwrap(), 2.0);
+        let hit = res.point(&ray).unwrap();
+        assert_approx_eq!(hit.x(), 0.0);
+        assert_approx_eq!(hit.y(), 0.0);
+        assert_approx_eq!(hit.z(), 0.0);
+    }
+
+    #[test]
+    fn test_parallel_ray() {
+        let ray = Ray::new(Point3::new(0.0, 0.0, 1.0), Dir3::new(1.0, 1.0, 0.0));
+        let res = ray_plane_intersection(&ray, &Point3::new(0.0, 0.0, 0.0), &Dir3::z_axis());
+
+        assert_eq!(res, PlaneIntersection::Parallel);
+        assert!(res.point(&ray).is_none());
+    }
+
+    #[test]
+    fn test_ray_pointing_away() {
+        let ray = Ray::new(Point3::new(0.0, 0.0, 1.0), Dir3::new(0.0, 0.0, 1.0));
+        let res = ray_plane_intersection(&ray, &Point3::new(0.0, 0.0, 0.0), &Dir3::z_axis());
+
+        assert_eq!(res, PlaneIntersection::Behind);
+        assert!(res.dist().is_none());
+    }
+}
@@ -5,6 +5,9 @@ use crate::{
     math::{Dir3, Point3, Rot3, Vec3},
 };
 
+/// Offset applied to rays leaving a surface, relative to the magnitude of their position.
+pub const SURFACE_EPSILON: f64 = 1.0e-9;
+
 /// Ray structure.
 ///
 /// This is the type at the core of our ray tracing / hit scan implementation.
@@ -44,6 +47,18 @@ impl Ray {
         self.pos += self.dir * dist;
     }
 
+    /// Offset the ray from a surface it has just left, so it can not immediately re-hit that surface.
+    /// The ray is moved along the surface normal, towards the side it is travelling into,
+    /// by an amount scaled by the largest component of its position.
+    #[inline]
+    pub fn offset_from_surface(&mut self, norm: &Dir3) {
+        let (x, y, z) = (self.pos.x().abs(), self.pos.y().abs(), self.pos.z().abs());
+        let mag = x.max(y).max(z);
+        let side = if self.dir.dot(norm) >= 0.0 { 1.0 } else { -1.0 };
+
+        self.pos += *norm * (side * SURFACE_EPSILON * (1.0 + mag));
+    }
+
     /// Rotate the photon with a given pitch and subsequent roll manoeuvre.
     #[inline]
     pub fn rotate(&mut self, pitch: f64, roll: f64) {
@@ -108,6 +123,21 @@ mod tests {
         assert_approx_eq!(ray.pos().z(), (0.1 / norm) * dist, 0.001);
     }
 
+    /// Check that rays are pushed off a surface towards the side they are travelling into.
+    #[test]
+    fn offset_from_surface_test() {
+        let norm = Dir3::new(0.0, 0.0, 1.0);
+
+        let mut ray = Ray::new(Point3::new(1.0e3, 0.0, 0.0), Dir3::new(1.0, 0.0, 1.0));
+        ray.offset_from_surface(&norm);
+        assert!(ray.pos().z() > 0.0);
+        assert_approx_eq!(ray.pos().z(), 1.001e-6, 1.0e-15);
+
+        let mut ray = Ray::new(Point3::new(0.0, 0.0, 0.0), Dir3::new(1.0, 0.0, -1.0));
+        ray.offset_from_surface(&norm);
+        assert!(ray.pos().z() < 0.0);
+    }
+
     /// Check that we can correctly rotate the ray.
     #[test]
     fn ray_rotate_test() {
@@ -0,0 +1,108 @@
+//! Keyframed orientation trajectory.
+
+use crate::{
+    access,
+    geom::{Orient, Ray},
+    math::Dir3,
+};
+use nalgebra::{UnitQuaternion, Vector3};
+
+/// Path of an orientation through a sequence of keyframes.
+/// Positions are linearly interpolated between keyframes, and directions are spherically interpolated.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trajectory {
+    /// Keyframe orientations, evenly spaced along the path.
+    keyframes: Vec<Orient>,
+}
+
+impl Trajectory {
+    access!(keyframes: Vec<Orient>);
+
+    /// Construct a new instance.
+    #[inline]
+    #[must_use]
+    pub fn new(keyframes: Vec<Orient>) -> Self {
+        debug_assert!(!keyframes.is_empty());
+
+        Self { keyframes }
+    }
+
+    /// Rotation taking the z-axis to the forward direction and the y-axis to the up direction.
+    #[inline]
+    #[must_use]
+    fn rotation(orient: &Orient) -> UnitQuaternion<f64> {
+        UnitQuaternion::face_towards(&orient.forward().data(), &orient.up().data())
+    }
+
+    /// Sample the orientation at a fraction of the way along the path.
+    #[inline]
+    #[must_use]
+    pub fn sample(&self, f: f64) -> Orient {
+        debug_assert!((0.0..=1.0).contains(&f));
+
+        let segments = self.keyframes.len() - 1;
+        if segments == 0 {
+            return self.keyframes[0].clone();
+        }
+
+        let s = f * segments as f64;
+        let index = (s as usize).min(segments - 1);
+        let t = s - index as f64;
+
+        let (start, end) = (&self.keyframes[index], &self.keyframes[index + 1]);
+        let pos = start.pos().lerp(end.pos(), t);
+        let rot = Self::rotation(start).slerp(&Self::rotation(end), t);
+
+        Orient::new_up(
+            Ray::new(pos, Dir3::from(rot * Vector3::z())),
+            &Dir3::from(rot * Vector3::y()),
+        )
+    }
+
+    /// Sample the orientation of the nth of a number of evenly spaced frames.
+    #[inline]
+    #[must_use]
+    pub fn sample_frame(&self, n: usize, max: usize) -> Orient {
+        debug_assert!(n < max);
+
+        if max == 1 {
+            return self.sample(0.0);
+        }
+        self.sample(n as f64 / (max - 1) as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Trajectory;
+    use crate::{
+        geom::{Orient, Ray},
+        math::{Dir3, Point3},
+    };
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn test_three_frame_trajectory() {
+        let trajectory = Trajectory::new(vec![
+            Orient::new(Ray::new(Point3::new(0.0, 0.0, 0.0), Dir3::new(1.0, 0.0, 0.0))),
+            Orient::new(Ray::new(Point3::new(2.0, 4.0, 0.0), Dir3::new(0.0, 1.0, 0.0))),
+        ]);
+
+        let start = Point3::new(0.0, 0.0, 0.0);
+        let end = Point3::new(2.0, 4.0, 0.0);
+        for n in 0..3 {
+            let orient = trajectory.sample_frame(n, 3);
+            let expected = start.lerp(&end, n as f64 * 0.5);
+            assert_approx_eq!(orient.pos().x(), expected.x());
+            assert_approx_eq!(orient.pos().y(), expected.y());
+            assert_approx_eq!(orient.pos().z(), expected.z());
+            assert_approx_eq!(orient.up().z(), 1.0);
+        }
+
+        // Halfway between facing along x and along y.
+        let mid = trajectory.sample_frame(1, 3);
+        assert_approx_eq!(mid.forward().x(), 0.5_f64.sqrt());
+        assert_approx_eq!(mid.forward().y(), 0.5_f64.sqrt());
+        assert_approx_eq!(mid.forward().z(), 0.0);
+    }
+}
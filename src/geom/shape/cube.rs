@@ -136,6 +136,17 @@ impl Cube {
         p >= &self.mins && p <= &self.maxs
     }
 
+    /// Determine if the given point is contained, treating points within `tol` of a face as inside.
+    #[inline]
+    #[must_use]
+    pub fn contains_eps(&self, p: &Point3, tol: f64) -> bool {
+        debug_assert!(tol >= 0.0);
+
+        p.iter()
+            .zip(self.mins.iter().zip(self.maxs.iter()))
+            .all(|(x, (min, max))| *x >= (min - tol) && *x <= (max + tol))
+    }
+
     /// Shrink the aabb by a fraction of its lengths, maintaining the central position.
     #[inline]
     pub fn shrink(&mut self, f: f64) {
@@ -385,6 +396,18 @@ mod tests {
         assert_approx_eq!(cube.centre().z(), 0.0);
     }
 
+    #[test]
+    fn test_contains_eps() {
+        let cube = Cube::new(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0));
+
+        let near = Point3::new(1.0 + 1.0e-9, 0.5, 0.5);
+        assert!(!cube.contains(&near));
+        assert!(cube.contains_eps(&near, 1.0e-6));
+
+        let far = Point3::new(0.5, -1.0, 0.5);
+        assert!(!cube.contains_eps(&far, 1.0e-6));
+    }
+
     #[test]
     fn test_cube_area() {
         let cube = Cube::new(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0));
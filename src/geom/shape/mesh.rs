@@ -1,8 +1,12 @@
 //! Smooth triangle-mesh implementation.
 
 use crate::{
-    access, clone, fmt_report,
-    geom::{Collide, Cube, Emit, Ray, Side, SmoothTriangle, Trace, Transformable},
+    access, clone,
+    err::Error,
+    fmt_report,
+    geom::{
+        Collide, Cube, Emit, MeshHit, Ray, Side, SmoothTriangle, Trace, Transformable, Triangle,
+    },
     math::Trans3,
     ord::{ALPHA, X},
 };
@@ -21,24 +25,51 @@ pub struct Mesh {
     tris: Vec<SmoothTriangle>,
     /// Total surface area.
     area: f64,
+    /// Number of degenerate triangles dropped during construction.
+    num_dropped: usize,
 }
 
 impl Mesh {
     access!(boundary: Cube);
     access!(tris: Vec<SmoothTriangle>);
     clone!(area: f64);
+    clone!(num_dropped: usize);
 
     /// Construct a new instance.
+    /// Degenerate triangles, which have no well-defined normal, are dropped.
+    /// # Panics
+    /// if every triangle is degenerate.
+    #[allow(clippy::expect_used)]
     #[inline]
     #[must_use]
     pub fn new(tris: Vec<SmoothTriangle>) -> Self {
+        Self::try_new(tris).expect("Could not construct mesh.")
+    }
+
+    /// Construct a new instance, unless every triangle is degenerate.
+    /// Degenerate triangles, which have no well-defined normal, are dropped.
+    /// # Errors
+    /// if no triangles remain once the degenerate triangles are dropped.
+    #[inline]
+    pub fn try_new(mut tris: Vec<SmoothTriangle>) -> Result<Self, Error> {
+        let total = tris.len();
+        tris.retain(|tri| !Triangle::is_degenerate(tri.tri().verts()));
+        let num_dropped = total - tris.len();
+        if tris.is_empty() {
+            return Err(Error::Text(format!(
+                "Mesh has no non-degenerate triangles, of {} given.",
+                total
+            )));
+        }
+
         let area = tris.iter().map(|tri| tri.tri().area()).sum();
 
-        Self {
+        Ok(Self {
             boundary: Self::init_boundary(&tris),
             tris,
             area,
-        }
+            num_dropped,
+        })
     }
 
     /// Initialise the bounding box for the mesh.
@@ -68,6 +99,23 @@ impl Mesh {
         Cube::new(mins, maxs)
     }
 
+    /// Select flat shading, using the geometric face normal of each triangle,
+    /// or smooth shading, interpolating the vertex normals across each triangle.
+    #[inline]
+    pub fn set_flat_shading(&mut self, flat: bool) {
+        for tri in &mut self.tris {
+            tri.set_flat_shading(flat);
+        }
+    }
+
+    /// Select flat or smooth shading, as `set_flat_shading`.
+    #[inline]
+    #[must_use]
+    pub fn with_flat_shading(mut self, flat: bool) -> Self {
+        self.set_flat_shading(flat);
+        self
+    }
+
     /// Destruct the instance and retrieve the list of triangles.
     #[allow(clippy::missing_const_for_fn)]
     #[inline]
@@ -75,6 +123,29 @@ impl Mesh {
     pub fn into_tris(self) -> Vec<SmoothTriangle> {
         self.tris
     }
+
+    /// Determine the nearest intersection of a ray with the mesh,
+    /// including the shading surface normal and the index of the triangle hit.
+    #[inline]
+    #[must_use]
+    pub fn intersect(&self, ray: &Ray) -> Option<MeshHit> {
+        if !self.boundary.hit(ray) {
+            return None;
+        }
+
+        self.tris
+            .iter()
+            .enumerate()
+            .filter_map(|(index, tri)| {
+                tri.tri()
+                    .intersection_coors(ray)
+                    .map(|(dist, coors)| (index, dist, coors))
+            })
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+            .map(|(index, dist, coors)| {
+                MeshHit::new(dist, self.tris[index].norm_at(coors), index, coors)
+            })
+    }
 }
 
 impl Collide for Mesh {
@@ -150,14 +221,8 @@ impl Trace for Mesh {
     #[inline]
     #[must_use]
     fn dist_side(&self, ray: &Ray) -> Option<(f64, Side)> {
-        if !self.boundary.hit(ray) {
-            return None;
-        }
-
-        self.tris
-            .iter()
-            .filter_map(|tri| tri.dist_side(ray))
-            .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap())
+        self.intersect(ray)
+            .map(|hit| (hit.dist(), Side::new(ray.dir(), *hit.norm())))
     }
 }
 
@@ -167,7 +232,201 @@ impl Display for Mesh {
         writeln!(fmt, "...")?;
         fmt_report!(fmt, self.boundary, "boundary");
         fmt_report!(fmt, self.tris.len(), "num triangles");
+        fmt_report!(fmt, self.num_dropped, "num dropped triangles");
         fmt_report!(fmt, self.area, "area (m)");
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::{Dir3, Point3};
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn test_intersect_interpolated_normal() {
+        let norms = [
+            Dir3::new(-0.5, 0.0, 1.0),
+            Dir3::new(0.5, 0.0, 1.0),
+            Dir3::new(0.0, 0.5, 1.0),
+        ];
+        let flat = SmoothTriangle::new_from_verts(
+            [
+                Point3::new(10.0, 0.0, 0.0),
+                Point3::new(11.0, 0.0, 0.0),
+                Point3::new(10.0, 1.0, 0.0),
+            ],
+            [Dir3::new(0.0, 0.0, 1.0); 3],
+        );
+        let smooth = SmoothTriangle::new_from_verts(
+            [
+                Point3::new(0.0, 0.0, 0.0),
+                Point3::new(1.0, 0.0, 0.0),
+                Point3::new(0.0, 1.0, 0.0),
+            ],
+            norms,
+        );
+        let mesh = Mesh::new(vec![flat, smooth]);
+
+        // Strike the centroid of the smoothed triangle from above.
+        let ray = Ray::new(
+            Point3::new(1.0 / 3.0, 1.0 / 3.0, 1.0),
+            Dir3::new(0.0, 0.0, -1.0),
+        );
+        let hit = mesh.intersect(&ray).unwrap();
+
+        assert_eq!(hit.index(), 1);
+        assert_approx_eq!(hit.dist(), 1.0);
+        for coor in &hit.coors() {
+            assert_approx_eq!(*coor, 1.0 / 3.0);
+        }
+
+        let expected = Dir3::from((norms[0] * 1.0) + (norms[1] * 1.0) + (norms[2] * 1.0));
+        assert_approx_eq!(hit.norm().x(), expected.x());
+        assert_approx_eq!(hit.norm().y(), expected.y());
+        assert_approx_eq!(hit.norm().z(), expected.z());
+        assert!(hit.norm().y() > 0.0);
+
+        let (dist, side) = mesh.dist_side(&ray).unwrap();
+        assert_approx_eq!(dist, 1.0);
+        assert_approx_eq!(side.norm().y(), expected.y());
+    }
+
+    #[test]
+    fn test_overlap() {
+        let cube = Cube::new(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0));
+        let norms = [Dir3::new(0.0, 0.0, 1.0); 3];
+        let outside = SmoothTriangle::new_from_verts(
+            [
+                Point3::new(2.0, 2.0, 2.0),
+                Point3::new(3.0, 2.0, 2.0),
+                Point3::new(2.0, 3.0, 2.0),
+            ],
+            norms,
+        );
+        let crossing = SmoothTriangle::new_from_verts(
+            [
+                Point3::new(0.5, 0.5, 0.5),
+                Point3::new(1.5, 0.5, 0.5),
+                Point3::new(0.5, 1.5, 0.5),
+            ],
+            norms,
+        );
+
+        assert!(!Mesh::new(vec![outside.clone()]).overlap(&cube));
+        assert!(Mesh::new(vec![outside, crossing]).overlap(&cube));
+    }
+
+    /// Construct a latitude-longitude tessellated unit sphere, with vertex normals pointing radially outwards.
+    fn sphere_mesh(slices: usize, stacks: usize) -> Mesh {
+        use std::f64::consts::PI;
+
+        let vert = |i: usize, j: usize| {
+            let theta = PI * i as f64 / stacks as f64;
+            let phi = 2.0 * PI * j as f64 / slices as f64;
+            Point3::new(
+                theta.sin() * phi.cos(),
+                theta.sin() * phi.sin(),
+                theta.cos(),
+            )
+        };
+        let tri = |verts: [Point3; 3]| {
+            let norms = [
+                Dir3::new(verts[0].x(), verts[0].y(), verts[0].z()),
+                Dir3::new(verts[1].x(), verts[1].y(), verts[1].z()),
+                Dir3::new(verts[2].x(), verts[2].y(), verts[2].z()),
+            ];
+            SmoothTriangle::new_from_verts(verts, norms)
+        };
+
+        let mut tris = Vec::new();
+        for i in 0..stacks {
+            for j in 0..slices {
+                let (a, b, c, d) = (
+                    vert(i, j),
+                    vert(i + 1, j),
+                    vert(i + 1, j + 1),
+                    vert(i, j + 1),
+                );
+                if i != 0 {
+                    tris.push(tri([a, b, d]));
+                }
+                if i != stacks - 1 {
+                    tris.push(tri([b, c, d]));
+                }
+            }
+        }
+
+        Mesh::new(tris)
+    }
+
+    #[test]
+    fn test_drop_degenerate_triangles() {
+        let norm = Dir3::new(0.0, 0.0, 1.0);
+        let valid = SmoothTriangle::new_from_verts(
+            [
+                Point3::new(0.0, 0.0, 0.0),
+                Point3::new(1.0, 0.0, 0.0),
+                Point3::new(0.0, 1.0, 0.0),
+            ],
+            [norm; 3],
+        );
+        let collinear = SmoothTriangle::new_from_verts(
+            [
+                Point3::new(0.0, 0.0, 0.0),
+                Point3::new(1.0, 0.0, 0.0),
+                Point3::new(2.0, 0.0, 0.0),
+            ],
+            [norm; 3],
+        );
+
+        let mesh = Mesh::new(vec![valid, collinear.clone(), collinear.clone()]);
+        assert_eq!(mesh.tris().len(), 1);
+        assert_eq!(mesh.num_dropped(), 2);
+        assert_approx_eq!(mesh.area(), 0.5);
+
+        assert!(Mesh::try_new(vec![collinear.clone(), collinear]).is_err());
+        assert!(Mesh::try_new(vec![]).is_err());
+    }
+
+    #[test]
+    fn test_flat_shading() {
+        let smooth = sphere_mesh(8, 6);
+        let flat = sphere_mesh(8, 6).with_flat_shading(true);
+        let centre = Point3::new(0.0, 0.0, 0.0);
+
+        for (index, tri) in smooth.tris().iter().enumerate() {
+            // Cast rays from the centre through two different points of the triangle.
+            let verts = tri.tri().verts();
+            let points = [
+                (verts[0] * 0.6) + (verts[1] * 0.2) + (verts[2] * 0.2),
+                (verts[0] * 0.2) + (verts[1] * 0.2) + (verts[2] * 0.6),
+            ];
+
+            let mut smooth_norms = Vec::new();
+            for p in &points {
+                let ray = Ray::new(centre, Dir3::from(*p - centre));
+
+                let smooth_hit = smooth.intersect(&ray).unwrap();
+                let flat_hit = flat.intersect(&ray).unwrap();
+                assert_eq!(smooth_hit.index(), index);
+                assert_eq!(flat_hit.index(), index);
+
+                // Flat shading returns the outward facing geometric normal.
+                let face = tri.tri().plane_norm();
+                assert_approx_eq!(flat_hit.norm().dot(face).abs(), 1.0);
+                assert!(flat_hit.norm().dot(&ray.dir()) > 0.0);
+
+                smooth_norms.push(*smooth_hit.norm());
+            }
+
+            // Smooth shading interpolates the normals across the face.
+            assert!(smooth_norms[0].dot(&smooth_norms[1]) < 1.0 - 1.0e-6);
+        }
+
+        // Shading may be switched back to smooth.
+        let restored = flat.with_flat_shading(false);
+        assert!(restored.tris().iter().all(|tri| !tri.is_flat_shaded()));
+    }
+}
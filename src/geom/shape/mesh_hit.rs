@@ -0,0 +1,37 @@
+//! Mesh intersection structure.
+
+use crate::{access, clone, math::Dir3};
+
+/// Intersection of a ray with a triangle of a mesh.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MeshHit {
+    /// Distance along the ray to the intersection.
+    dist: f64,
+    /// Shading normal of the surface at the intersection.
+    norm: Dir3,
+    /// Index of the intersected triangle within the mesh.
+    index: usize,
+    /// Barycentric coordinates of the intersection within the triangle.
+    coors: [f64; 3],
+}
+
+impl MeshHit {
+    clone!(dist: f64);
+    access!(norm: Dir3);
+    clone!(index: usize);
+    clone!(coors: [f64; 3]);
+
+    /// Construct a new instance.
+    #[inline]
+    #[must_use]
+    pub fn new(dist: f64, norm: Dir3, index: usize, coors: [f64; 3]) -> Self {
+        debug_assert!(dist > 0.0);
+
+        Self {
+            dist,
+            norm,
+            index,
+            coors,
+        }
+    }
+}
@@ -5,9 +5,14 @@
 
 pub mod cube;
 pub mod mesh;
+pub mod mesh_hit;
 pub mod mesh_loader;
 pub mod smooth_triangle;
+pub mod sphere;
 pub mod track;
 pub mod triangle;
 
-pub use self::{cube::*, mesh::*, mesh_loader::*, smooth_triangle::*, track::*, triangle::*};
+pub use self::{
+    cube::*, mesh::*, mesh_hit::*, mesh_loader::*, smooth_triangle::*, sphere::*, track::*,
+    triangle::*,
+};
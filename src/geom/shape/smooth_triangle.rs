@@ -3,7 +3,7 @@
 use crate::{
     access,
     geom::{Collide, Cube, Emit, Ray, Side, Trace, Transformable, Triangle},
-    math::{Dir3, Point3, Trans3},
+    math::{Dir3, Point3, Trans3, Vec3},
     ord::{ALPHA, BETA, GAMMA},
 };
 use rand::Rng;
@@ -15,11 +15,20 @@ pub struct SmoothTriangle {
     tri: Triangle,
     /// Normal vectors.
     norms: [Dir3; 3],
+    /// Optional texture coordinates of each vertex.
+    uvs: Option<[[f64; 2]; 3]>,
+    /// Optional tangent vectors of each vertex.
+    tangents: Option<[Dir3; 3]>,
+    /// Face normal used in place of the interpolated normals when flat shaded.
+    flat_norm: Option<Dir3>,
 }
 
 impl SmoothTriangle {
     access!(tri: Triangle);
     access!(norms: [Dir3; 3]);
+    access!(uvs: Option<[[f64; 2]; 3]>);
+    access!(tangents: Option<[Dir3; 3]>);
+    access!(flat_norm: Option<Dir3>);
 
     /// Construct a new instance.
     #[inline]
@@ -29,7 +38,13 @@ impl SmoothTriangle {
             println!("[WARN] Reverse triangle.");
         }
 
-        Self { tri, norms }
+        Self {
+            tri,
+            norms,
+            uvs: None,
+            tangents: None,
+            flat_norm: None,
+        }
     }
 
     /// Construct a new instance from vertices.
@@ -38,6 +53,125 @@ impl SmoothTriangle {
     pub fn new_from_verts(verts: [Point3; 3], norms: [Dir3; 3]) -> Self {
         Self::new(Triangle::new(verts), norms)
     }
+
+    /// Set the texture coordinates of each vertex.
+    #[inline]
+    #[must_use]
+    pub fn with_uvs(mut self, uvs: [[f64; 2]; 3]) -> Self {
+        self.uvs = Some(uvs);
+        self
+    }
+
+    /// Set the tangent vectors of each vertex.
+    #[inline]
+    #[must_use]
+    pub fn with_tangents(mut self, tangents: [Dir3; 3]) -> Self {
+        self.tangents = Some(tangents);
+        self
+    }
+
+    /// Select flat shading, using the geometric face normal at every point of the triangle,
+    /// or smooth shading, interpolating the vertex normals.
+    /// The face normal is oriented to the same side as the vertex normals.
+    #[inline]
+    pub fn set_flat_shading(&mut self, flat: bool) {
+        self.flat_norm = if flat {
+            let norm = *self.tri.plane_norm();
+            let facing: f64 = self.norms.iter().map(|n| norm.dot(n)).sum();
+            Some(if facing < 0.0 { -norm } else { norm })
+        } else {
+            None
+        };
+    }
+
+    /// Check if the triangle is flat shaded.
+    #[inline]
+    #[must_use]
+    pub const fn is_flat_shaded(&self) -> bool {
+        self.flat_norm.is_some()
+    }
+
+    /// Determine the shading normal at the given barycentric coordinates.
+    /// This is the face normal for flat shaded triangles, and the interpolated normal otherwise.
+    #[inline]
+    #[must_use]
+    pub fn norm_at(&self, coors: [f64; 3]) -> Dir3 {
+        match self.flat_norm {
+            Some(norm) => norm,
+            None => self.interpolated_norm(coors),
+        }
+    }
+
+    /// Interpolate the texture coordinates at the given barycentric coordinates.
+    /// Returns `None` if the triangle has no texture coordinates.
+    #[inline]
+    #[must_use]
+    pub fn uv_at(&self, [u, v, w]: [f64; 3]) -> Option<[f64; 2]> {
+        self.uvs.map(|uvs| {
+            let mut uv = [0.0; 2];
+            for (k, x) in uv.iter_mut().enumerate() {
+                *x = (uvs[BETA][k] * u) + (uvs[GAMMA][k] * v) + (uvs[ALPHA][k] * w);
+            }
+            uv
+        })
+    }
+
+    /// Determine the distance, facing side and interpolated texture coordinates of a ray intersection.
+    #[inline]
+    #[must_use]
+    pub fn dist_side_uv(&self, ray: &Ray) -> Option<(f64, Side, Option<[f64; 2]>)> {
+        self.tri.intersection_coors(ray).map(|(dist, coors)| {
+            (
+                dist,
+                Side::new(ray.dir(), self.norm_at(coors)),
+                self.uv_at(coors),
+            )
+        })
+    }
+
+    /// Interpolate the tangent at the given barycentric coordinates.
+    /// If no tangents are stored they are derived from the texture coordinates.
+    /// The tangent is made orthogonal to the interpolated normal by Gram-Schmidt.
+    /// Returns `None` if the triangle has neither tangents nor non-degenerate texture coordinates.
+    #[inline]
+    #[must_use]
+    pub fn tangent_at(&self, coors: [f64; 3]) -> Option<Dir3> {
+        let [u, v, w] = coors;
+        let tangent = match self.tangents {
+            Some(tangents) => (tangents[BETA] * u) + (tangents[GAMMA] * v) + (tangents[ALPHA] * w),
+            None => Vec3::from(self.uv_tangent()?),
+        };
+
+        let norm = self.norm_at(coors);
+        Some(Dir3::from(tangent - (norm * norm.dot_vec(&tangent))))
+    }
+
+    /// Calculate the tangent of the face, pointing along the direction of increasing u texture coordinate.
+    #[inline]
+    #[must_use]
+    fn uv_tangent(&self) -> Option<Dir3> {
+        let uvs = self.uvs?;
+        let verts = self.tri.verts();
+
+        let edge_a_b = verts[BETA] - verts[ALPHA];
+        let edge_a_c = verts[GAMMA] - verts[ALPHA];
+        let (du_b, dv_b) = (uvs[BETA][0] - uvs[ALPHA][0], uvs[BETA][1] - uvs[ALPHA][1]);
+        let (du_c, dv_c) = (uvs[GAMMA][0] - uvs[ALPHA][0], uvs[GAMMA][1] - uvs[ALPHA][1]);
+
+        let det = (du_b * dv_c) - (du_c * dv_b);
+        if det.abs() <= f64::EPSILON {
+            return None;
+        }
+
+        Some(Dir3::from(((edge_a_b * dv_c) - (edge_a_c * dv_b)) / det))
+    }
+
+    /// Interpolate the normal at the given barycentric coordinates.
+    #[inline]
+    #[must_use]
+    pub fn interpolated_norm(&self, [u, v, w]: [f64; 3]) -> Dir3 {
+        Dir3::from((self.norms[BETA] * u) + (self.norms[GAMMA] * v) + (self.norms[ALPHA] * w))
+    }
 }
 
 impl Collide for SmoothTriangle {
@@ -64,19 +198,9 @@ impl Trace for SmoothTriangle {
     #[inline]
     #[must_use]
     fn dist_side(&self, ray: &Ray) -> Option<(f64, Side)> {
-        if let Some((dist, [u, v, w])) = self.tri.intersection_coors(ray) {
-            Some((
-                dist,
-                Side::new(
-                    ray.dir(),
-                    Dir3::from(
-                        (self.norms[BETA] * u) + (self.norms[GAMMA] * v) + (self.norms[ALPHA] * w),
-                    ),
-                ),
-            ))
-        } else {
-            None
-        }
+        self.tri
+            .intersection_coors(ray)
+            .map(|(dist, coors)| (dist, Side::new(ray.dir(), self.norm_at(coors))))
     }
 }
 
@@ -88,6 +212,16 @@ impl Transformable for SmoothTriangle {
         for n in &mut self.norms {
             *n = Dir3::from(trans.transform_vector(&n.data()));
         }
+
+        if let Some(ref mut tangents) = self.tangents {
+            for t in tangents {
+                *t = Dir3::from(trans.transform_vector(&t.data()));
+            }
+        }
+
+        if self.is_flat_shaded() {
+            self.set_flat_shading(true);
+        }
     }
 }
 
@@ -108,9 +242,54 @@ impl Emit for SmoothTriangle {
         let edge_a_c = self.tri.verts()[GAMMA] - self.tri.verts()[ALPHA];
 
         let pos = self.tri.verts()[ALPHA] + (edge_a_b * u) + (edge_a_c * v);
-        let dir =
-            Dir3::from((self.norms[BETA] * u) + (self.norms[GAMMA] * v) + (self.norms[ALPHA] * w));
+        Ray::new(pos, self.norm_at([u, v, w]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tangent_at_centroid() {
+        let norm = Dir3::new(0.0, 0.0, 1.0);
+        let tri = SmoothTriangle::new_from_verts(
+            [
+                Point3::new(0.0, 0.0, 0.0),
+                Point3::new(1.0, 0.0, 0.0),
+                Point3::new(0.0, 1.0, 0.0),
+            ],
+            [Dir3::new(0.1, 0.0, 1.0), norm, Dir3::new(0.0, 0.1, 1.0)],
+        )
+        .with_tangents([
+            Dir3::new(1.0, 0.1, 0.0),
+            Dir3::new(1.0, -0.1, 0.0),
+            Dir3::new(1.0, 0.0, 0.1),
+        ]);
+
+        let centroid = [1.0 / 3.0; 3];
+        let tangent = tri.tangent_at(centroid).unwrap();
+        let interp_norm = tri.interpolated_norm(centroid);
+
+        assert!(tangent.dot(&interp_norm).abs() < 1.0e-12);
+        assert!(tangent.x() > 0.99);
+        assert!((Vec3::from(tangent).mag() - 1.0).abs() < 1.0e-12);
+    }
+
+    #[test]
+    fn test_tangent_from_uvs() {
+        let norm = Dir3::new(0.0, 0.0, 1.0);
+        let verts = [
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(0.0, 1.0, 0.0),
+        ];
+        let tri = SmoothTriangle::new_from_verts(verts, [norm; 3]);
+        assert!(tri.tangent_at([1.0 / 3.0; 3]).is_none());
 
-        Ray::new(pos, dir)
+        // Texture u runs along the y axis.
+        let tri = tri.with_uvs([[0.0, 0.0], [0.0, 1.0], [1.0, 0.0]]);
+        let tangent = tri.tangent_at([1.0 / 3.0; 3]).unwrap();
+        assert!((tangent.y() - 1.0).abs() < 1.0e-12);
     }
 }
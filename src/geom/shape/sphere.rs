@@ -0,0 +1,131 @@
+//! Sphere implementation.
+
+use crate::{
+    access, clone, fmt_report,
+    geom::{Emit, Ray},
+    math::{Dir3, Point3},
+};
+use arctk_attr::file;
+use rand::Rng;
+use std::{
+    f64::consts::PI,
+    fmt::{Display, Formatter},
+};
+
+/// Sphere geometry.
+#[file]
+#[derive(Clone, PartialEq)]
+pub struct Sphere {
+    /// Centre.
+    centre: Point3,
+    /// Radius.
+    radius: f64,
+}
+
+impl Sphere {
+    access!(centre: Point3);
+    clone!(radius: f64);
+
+    /// Construct a new instance.
+    #[inline]
+    #[must_use]
+    pub fn new(centre: Point3, radius: f64) -> Self {
+        debug_assert!(radius > 0.0);
+
+        Self { centre, radius }
+    }
+
+    /// Calculate the surface area.
+    #[inline]
+    #[must_use]
+    pub fn area(&self) -> f64 {
+        4.0 * PI * self.radius * self.radius
+    }
+
+    /// Calculate the volume.
+    #[inline]
+    #[must_use]
+    pub fn vol(&self) -> f64 {
+        (4.0 / 3.0) * PI * self.radius.powi(3)
+    }
+}
+
+impl Emit for Sphere {
+    /// Cast a ray from a point uniformly distributed over the surface, along the outward normal.
+    /// The height of the point is sampled uniformly, which by Archimedes' hat-box theorem
+    /// gives a uniform density over the surface, without clustering at the poles.
+    #[inline]
+    #[must_use]
+    fn cast<R: Rng>(&self, rng: &mut R) -> Ray {
+        let theta = rng.gen_range(0.0..(2.0 * PI));
+        let z: f64 = rng.gen_range(-1.0..1.0);
+        let v = (1.0 - (z * z)).sqrt();
+
+        let norm = Dir3::new(v * theta.cos(), v * theta.sin(), z);
+
+        Ray::new(self.centre + (norm * self.radius), norm)
+    }
+}
+
+impl Display for Sphere {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        writeln!(fmt, "...")?;
+        fmt_report!(
+            fmt,
+            &format!(
+                "({}, {}, {})",
+                self.centre.x(),
+                self.centre.y(),
+                self.centre.z()
+            ),
+            "centre (m)"
+        );
+        fmt_report!(fmt, self.radius, "radius (m)");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn test_uniform_surface_emission() {
+        let centre = Point3::new(1.0, -2.0, 0.5);
+        let radius = 2.0;
+        let sphere = Sphere::new(centre, radius);
+        let mut rng = rand::thread_rng();
+
+        // Equal height bands of a sphere have equal area, so receive equal numbers of points.
+        let num_points = 100_000;
+        let num_bands = 10;
+        let mut bands = vec![0; num_bands];
+        let mut octants = [0; 8];
+        for _ in 0..num_points {
+            let ray = sphere.cast(&mut rng);
+            let rel = *ray.pos() - centre;
+            assert_approx_eq!(rel.mag(), radius, 1.0e-9);
+
+            assert!(rel.dot_dir3(ray.dir()) > 0.0);
+
+            let h = ((rel.z() / radius) + 1.0) * 0.5;
+            bands[((h * num_bands as f64) as usize).min(num_bands - 1)] += 1;
+
+            let octant = usize::from(rel.x() > 0.0)
+                + (2 * usize::from(rel.y() > 0.0))
+                + (4 * usize::from(rel.z() > 0.0));
+            octants[octant] += 1;
+        }
+
+        for count in bands {
+            let frac = f64::from(count) / f64::from(num_points);
+            assert_approx_eq!(frac, 1.0 / num_bands as f64, 0.005);
+        }
+        for count in &octants {
+            let frac = f64::from(*count) / f64::from(num_points);
+            assert_approx_eq!(frac, 0.125, 0.005);
+        }
+    }
+}
@@ -14,12 +14,16 @@
 
 use crate::{
     access,
+    err::Error,
     geom::{Collide, Cube, Emit, Ray, Side, Trace, Transformable},
     math::{Dir3, Point3, Trans3, Vec3},
     ord::{ALPHA, BETA, GAMMA},
 };
 use rand::Rng;
 
+/// Area, relative to the square of the longest side, below which a triangle is considered degenerate.
+const DEGENERATE_TOLERANCE: f64 = 1.0e-12;
+
 /// Triangle.
 #[derive(Clone)]
 pub struct Triangle {
@@ -42,6 +46,37 @@ impl Triangle {
         Self { verts, plane_norm }
     }
 
+    /// Construct a new instance, unless the vertices are collinear or coincident.
+    /// # Errors
+    /// if the triangle area is below tolerance, and a plane normal can not be determined.
+    #[inline]
+    pub fn try_new(verts: [Point3; 3]) -> Result<Self, Error> {
+        if Self::is_degenerate(&verts) {
+            return Err(Error::Text(format!(
+                "Degenerate triangle with vertices: {:?}",
+                verts
+            )));
+        }
+
+        Ok(Self::new(verts))
+    }
+
+    /// Check if the vertices form a degenerate triangle, of zero area.
+    #[inline]
+    #[must_use]
+    pub fn is_degenerate(verts: &[Point3; 3]) -> bool {
+        let area = 0.5
+            * (verts[BETA] - verts[ALPHA])
+                .cross(&(verts[GAMMA] - verts[ALPHA]))
+                .mag();
+        let longest = (verts[BETA] - verts[ALPHA])
+            .mag()
+            .max((verts[GAMMA] - verts[BETA]).mag())
+            .max((verts[ALPHA] - verts[GAMMA]).mag());
+
+        !area.is_finite() || area <= DEGENERATE_TOLERANCE * longest * longest
+    }
+
     /// Initialise the plane normal.
     #[inline]
     #[must_use]
@@ -294,7 +329,7 @@ impl Emit for Triangle {
 mod tests {
     // We implement the transformable for the triangle primitive, so we shall use this for tests.
     use super::{Trans3, Transformable};
-    use crate::{geom::{Triangle, Trace}, math::Point3};
+    use crate::{geom::{Collide, Cube, Triangle, Trace}, math::Point3};
     use nalgebra::Vector3;
     use std::f64;
     use assert_approx_eq::assert_approx_eq;
@@ -392,6 +427,27 @@ mod tests {
         assert_eq!(tri.verts()[2][2], -1.0);
     }
 
+    #[test]
+    fn degenerate_test() {
+        let tri = Triangle::try_new([
+            Point3::new(0., 0., 0.),
+            Point3::new(1., 1., 1.),
+            Point3::new(3., 3., 3.),
+        ]);
+        assert!(tri.is_err());
+
+        let tri = Triangle::try_new([
+            Point3::new(1., 1., 1.),
+            Point3::new(1., 1., 1.),
+            Point3::new(0., 1., 0.),
+        ]);
+        assert!(tri.is_err());
+
+        let tri = Triangle::try_new(*unit_triangle().verts()).unwrap();
+        assert_eq!(*tri.plane_norm(), *unit_triangle().plane_norm());
+        assert_approx_eq!(tri.area(), 0.5);
+    }
+
     #[test]
     fn perimeter_test() {
         let tri = unit_triangle();
@@ -452,4 +508,42 @@ mod tests {
         );
         assert!(tri.hit(&ray));
     }
+
+    #[test]
+    fn test_overlap_crossing_face() {
+        let cube = Cube::new(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0));
+        let tri = Triangle::new([
+            Point3::new(0.5, 0.5, 0.5),
+            Point3::new(1.5, 0.5, 0.5),
+            Point3::new(0.5, 1.5, 0.5),
+        ]);
+        assert!(tri.overlap(&cube));
+
+        // A triangle lying in the plane of a face still overlaps.
+        let tri = Triangle::new([
+            Point3::new(1.0, 0.2, 0.2),
+            Point3::new(1.0, 0.8, 0.2),
+            Point3::new(1.0, 0.5, 0.8),
+        ]);
+        assert!(tri.overlap(&cube));
+    }
+
+    #[test]
+    fn test_overlap_outside() {
+        let cube = Cube::new(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0));
+        let tri = Triangle::new([
+            Point3::new(2.0, 2.0, 2.0),
+            Point3::new(3.0, 2.0, 2.0),
+            Point3::new(2.0, 3.0, 2.0),
+        ]);
+        assert!(!tri.overlap(&cube));
+
+        // The bounding box of this triangle overlaps the cube, but the triangle passes beyond its corner.
+        let tri = Triangle::new([
+            Point3::new(2.2, 0.0, -1.0),
+            Point3::new(0.0, 2.2, -1.0),
+            Point3::new(1.1, 1.1, 2.0),
+        ]);
+        assert!(!tri.overlap(&cube));
+    }
 }
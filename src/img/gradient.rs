@@ -1,6 +1,179 @@
-//! Gradient alias.
+//! Colour gradient.
 
-use crate::img::Colour;
+use crate::{access, clone, err::Error, img::Colour};
 
-/// Colour gradient alias.
-pub type Gradient = palette::Gradient<Colour>;
+/// Out-of-range sampling behaviour.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GradientMode {
+    /// Samples outside [0, 1] take the nearest end colour.
+    Clamp,
+    /// Samples outside [0, 1] wrap around to the opposite end.
+    Wrap,
+}
+
+/// Colour gradient of evenly spaced colour stops.
+#[derive(Clone, Debug)]
+pub struct Gradient {
+    /// Colour stops.
+    cols: Vec<Colour>,
+    /// Out-of-range behaviour.
+    mode: GradientMode,
+}
+
+impl Gradient {
+    access!(cols: Vec<Colour>);
+    clone!(mode, mode_mut: GradientMode);
+
+    /// Construct a new instance which clamps out-of-range samples.
+    #[inline]
+    #[must_use]
+    pub fn new(cols: Vec<Colour>) -> Self {
+        debug_assert!(!cols.is_empty());
+
+        Self {
+            cols,
+            mode: GradientMode::Clamp,
+        }
+    }
+
+    /// Construct a named preset gradient.
+    /// Available presets are `viridis`, `magma`, `grayscale` (or `greyscale`) and `jet`.
+    /// # Errors
+    /// if the name does not match a preset.
+    #[inline]
+    pub fn preset(name: &str) -> Result<Self, Error> {
+        let hexes: &[&str] = match name.to_lowercase().as_str() {
+            "viridis" => &[
+                "440154", "482878", "3e4989", "31688e", "26828e", "1f9e89", "35b779", "6ece58",
+                "b5de2b", "fde725",
+            ],
+            "magma" => &[
+                "000004", "180f3d", "440f76", "721f81", "9e2f7f", "cd4071", "f1605d", "fd9668",
+                "feca8d", "fcfdbf",
+            ],
+            "grayscale" | "greyscale" => &["000000", "ffffff"],
+            "jet" => &[
+                "00007f", "0000ff", "007fff", "00ffff", "7fff7f", "ffff00", "ff7f00", "ff0000",
+                "7f0000",
+            ],
+            _ => return Err(Error::Text(format!("Unknown gradient preset: {}", name))),
+        };
+
+        Ok(Self::new(
+            hexes.iter().map(|hex| Self::hex_col(hex)).collect(),
+        ))
+    }
+
+    /// Convert an RGB hexadecimal string to an opaque colour.
+    #[inline]
+    #[must_use]
+    fn hex_col(hex: &str) -> Colour {
+        let col_arr = hex::decode(hex)
+            .unwrap_or_else(|_| panic!("Failed to parse hexidecimal string: {}.", hex));
+
+        Colour::new(
+            f32::from(col_arr[0]) / 255.0,
+            f32::from(col_arr[1]) / 255.0,
+            f32::from(col_arr[2]) / 255.0,
+            1.0,
+        )
+    }
+
+    /// Set the out-of-range behaviour.
+    #[inline]
+    #[must_use]
+    pub fn with_mode(mut self, mode: GradientMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Create a copy of the gradient running in the opposite direction.
+    #[inline]
+    #[must_use]
+    pub fn reversed(&self) -> Self {
+        let mut cols = self.cols.clone();
+        cols.reverse();
+
+        Self {
+            cols,
+            mode: self.mode,
+        }
+    }
+
+    /// Sample the colour at the given fraction along the gradient.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, x: f32) -> Colour {
+        let x = if (0.0..=1.0).contains(&x) {
+            x
+        } else {
+            match self.mode {
+                GradientMode::Clamp => x.clamp(0.0, 1.0),
+                GradientMode::Wrap => x.rem_euclid(1.0),
+            }
+        };
+
+        if self.cols.len() == 1 {
+            return self.cols[0];
+        }
+
+        let segs = (self.cols.len() - 1) as f32;
+        let index = ((x * segs) as usize).min(self.cols.len() - 2);
+        let t = x.mul_add(segs, -(index as f32));
+
+        let a = self.cols[index];
+        let b = self.cols[index + 1];
+        Colour::new(
+            (b.red - a.red).mul_add(t, a.red),
+            (b.green - a.green).mul_add(t, a.green),
+            (b.blue - a.blue).mul_add(t, a.blue),
+            (b.alpha - a.alpha).mul_add(t, a.alpha),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Gradient, GradientMode};
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn test_viridis_endpoints() {
+        let grad = Gradient::preset("viridis").unwrap();
+
+        let start = grad.get(0.0);
+        assert_approx_eq!(start.red, 68.0 / 255.0);
+        assert_approx_eq!(start.green, 1.0 / 255.0);
+        assert_approx_eq!(start.blue, 84.0 / 255.0);
+
+        let end = grad.get(1.0);
+        assert_approx_eq!(end.red, 253.0 / 255.0);
+        assert_approx_eq!(end.green, 231.0 / 255.0);
+        assert_approx_eq!(end.blue, 37.0 / 255.0);
+    }
+
+    #[test]
+    fn test_reversed() {
+        let grad = Gradient::preset("viridis").unwrap();
+        let rev = grad.reversed();
+
+        assert_eq!(rev.get(0.0), grad.get(1.0));
+        assert_eq!(rev.get(1.0), grad.get(0.0));
+    }
+
+    #[test]
+    fn test_unknown_preset() {
+        assert!(Gradient::preset("not-a-gradient").is_err());
+    }
+
+    #[test]
+    fn test_out_of_range_modes() {
+        let grad = Gradient::preset("grayscale").unwrap();
+        assert_approx_eq!(grad.get(1.25).red, 1.0);
+        assert_approx_eq!(grad.get(-0.5).red, 0.0);
+
+        let grad = grad.with_mode(GradientMode::Wrap);
+        assert_approx_eq!(grad.get(1.25).red, 0.25);
+        assert_approx_eq!(grad.get(-0.25).red, 0.75);
+    }
+}
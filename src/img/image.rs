@@ -2,10 +2,11 @@
 
 use crate::{
     access,
-    img::Colour,
+    err::{check_shape, Error},
+    img::{Colour, ResampleFilter, ToneMap},
     ord::{X, Y},
 };
-use ndarray::Array2;
+use ndarray::{s, Array2, Zip};
 use std::ops::AddAssign;
 
 /// Image builder.
@@ -36,6 +37,179 @@ impl Image {
             pixels: Array2::from_elem(res, base),
         }
     }
+
+    /// Create a tone-mapped copy of the image.
+    /// Colour channels are scaled by `2^exposure` before the operator is applied, alpha is left unchanged.
+    #[inline]
+    #[must_use]
+    pub fn tone_mapped(&self, op: ToneMap, exposure: f32) -> Self {
+        let scale = exposure.exp2();
+
+        Self {
+            pixels: self.pixels.mapv(|col| {
+                Colour::new(
+                    op.map(col.red * scale),
+                    op.map(col.green * scale),
+                    op.map(col.blue * scale),
+                    col.alpha,
+                )
+            }),
+        }
+    }
+
+    /// Check that another image has the same resolution as this one.
+    #[inline]
+    fn check_res(&self, other: &Self) -> Result<(), Error> {
+        check_shape(
+            "image resolution",
+            self.pixels.shape(),
+            other.pixels.shape(),
+        )
+    }
+
+    /// Composite this image over a background using the alpha "over" operator.
+    /// # Errors
+    /// if the images do not have the same resolution.
+    #[inline]
+    pub fn composite_over(&self, background: &Self) -> Result<Self, Error> {
+        self.check_res(background)?;
+
+        let mut pixels = Array2::from_elem(self.pixels.raw_dim(), Colour::new(0.0, 0.0, 0.0, 0.0));
+        Zip::from(&mut pixels)
+            .and(&self.pixels)
+            .and(&background.pixels)
+            .for_each(|out, fg, bg| {
+                let bg_weight = bg.alpha * (1.0 - fg.alpha);
+                let alpha = fg.alpha + bg_weight;
+
+                *out = if alpha > 0.0 {
+                    Colour::new(
+                        fg.red.mul_add(fg.alpha, bg.red * bg_weight) / alpha,
+                        fg.green.mul_add(fg.alpha, bg.green * bg_weight) / alpha,
+                        fg.blue.mul_add(fg.alpha, bg.blue * bg_weight) / alpha,
+                        alpha,
+                    )
+                } else {
+                    Colour::new(0.0, 0.0, 0.0, 0.0)
+                };
+            });
+
+        Ok(Self { pixels })
+    }
+
+    /// Linearly mix this image with another, including alpha.
+    /// A factor of zero returns this image, and a factor of one returns the other.
+    /// # Errors
+    /// if the images do not have the same resolution.
+    #[inline]
+    pub fn blend(&self, other: &Self, factor: f32) -> Result<Self, Error> {
+        debug_assert!((0.0..=1.0).contains(&factor));
+
+        self.check_res(other)?;
+
+        let mut pixels = Array2::from_elem(self.pixels.raw_dim(), Colour::new(0.0, 0.0, 0.0, 0.0));
+        Zip::from(&mut pixels)
+            .and(&self.pixels)
+            .and(&other.pixels)
+            .for_each(|out, a, b| {
+                *out = Colour::new(
+                    (b.red - a.red).mul_add(factor, a.red),
+                    (b.green - a.green).mul_add(factor, a.green),
+                    (b.blue - a.blue).mul_add(factor, a.blue),
+                    (b.alpha - a.alpha).mul_add(factor, a.alpha),
+                );
+            });
+
+        Ok(Self { pixels })
+    }
+
+    /// Copy the pixels of a smaller image, such as a rendered tile, into this image at a pixel offset.
+    /// # Errors
+    /// if the tile does not fit within the image at the given offset.
+    #[inline]
+    pub fn paste(&mut self, offset: [usize; 2], tile: &Self) -> Result<(), Error> {
+        let res = self.pixels.shape();
+        let tile_res = tile.pixels.shape();
+        if offset[X] + tile_res[X] > res[X] || offset[Y] + tile_res[Y] > res[Y] {
+            return Err(Error::Text(format!(
+                "Tile of resolution {:?} at offset {:?} does not fit within resolution {:?}",
+                tile_res, offset, res
+            )));
+        }
+
+        self.pixels
+            .slice_mut(s![
+                offset[X]..(offset[X] + tile_res[X]),
+                offset[Y]..(offset[Y] + tile_res[Y])
+            ])
+            .assign(&tile.pixels);
+        Ok(())
+    }
+
+    /// Reduce the resolution by an integer factor, filtering the source pixels into each output pixel.
+    /// Each output pixel covers a `factor x factor` block of source pixels,
+    /// any remainder rows or columns that do not fill a whole block are discarded.
+    /// # Errors
+    /// if the factor is zero or larger than the image resolution.
+    #[inline]
+    pub fn downsample(&self, factor: usize, filter: ResampleFilter) -> Result<Self, Error> {
+        let res = [self.pixels.shape()[X], self.pixels.shape()[Y]];
+        if factor == 0 || factor > res[X] || factor > res[Y] {
+            return Err(Error::Text(format!(
+                "Invalid downsampling factor {} for resolution {:?}",
+                factor, res
+            )));
+        }
+
+        let out_res = [res[X] / factor, res[Y] / factor];
+        let reach = (filter.radius() * factor) as isize;
+
+        let mut pixels = Array2::from_elem(out_res, Colour::new(0.0, 0.0, 0.0, 0.0));
+        for ((xi, yi), out) in pixels.indexed_iter_mut() {
+            let start = [
+                (xi * factor) as isize - reach,
+                (yi * factor) as isize - reach,
+            ];
+            let end = [
+                ((xi + 1) * factor) as isize + reach,
+                ((yi + 1) * factor) as isize + reach,
+            ];
+            let centre = [
+                (xi as f32 + 0.5) * factor as f32,
+                (yi as f32 + 0.5) * factor as f32,
+            ];
+
+            let mut total = [0.0; 4];
+            let mut norm = 0.0;
+            for sx in start[X].max(0)..end[X].min(res[X] as isize) {
+                for sy in start[Y].max(0)..end[Y].min(res[Y] as isize) {
+                    let weight = filter.weight(
+                        sx as f32 + 0.5 - centre[X],
+                        sy as f32 + 0.5 - centre[Y],
+                        factor,
+                    );
+                    let col = self.pixels[[sx as usize, sy as usize]];
+
+                    total[0] += col.red * weight;
+                    total[1] += col.green * weight;
+                    total[2] += col.blue * weight;
+                    total[3] += col.alpha * weight;
+                    norm += weight;
+                }
+            }
+
+            if norm > 0.0 {
+                *out = Colour::new(
+                    total[0] / norm,
+                    total[1] / norm,
+                    total[2] / norm,
+                    total[3] / norm,
+                );
+            }
+        }
+
+        Ok(Self { pixels })
+    }
 }
 
 impl AddAssign<&Self> for Image {
@@ -44,3 +218,93 @@ impl AddAssign<&Self> for Image {
         self.pixels += &rhs.pixels;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Image;
+    use crate::img::{Colour, ResampleFilter};
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn test_composite_over() {
+        let fg = Image::new_blank([2, 3], Colour::new(1.0, 0.0, 0.0, 0.5));
+        let bg = Image::new_blank([2, 3], Colour::new(0.0, 0.0, 1.0, 1.0));
+
+        let out = fg.composite_over(&bg).unwrap();
+        for col in out.pixels().iter() {
+            assert_approx_eq!(col.red, 0.5);
+            assert_approx_eq!(col.green, 0.0);
+            assert_approx_eq!(col.blue, 0.5);
+            assert_approx_eq!(col.alpha, 1.0);
+        }
+    }
+
+    #[test]
+    fn test_blend() {
+        let a = Image::new_blank([2, 2], Colour::new(0.0, 0.0, 0.0, 1.0));
+        let b = Image::new_blank([2, 2], Colour::new(1.0, 0.5, 0.0, 0.0));
+
+        let out = a.blend(&b, 0.25).unwrap();
+        for col in out.pixels().iter() {
+            assert_approx_eq!(col.red, 0.25);
+            assert_approx_eq!(col.green, 0.125);
+            assert_approx_eq!(col.blue, 0.0);
+            assert_approx_eq!(col.alpha, 0.75);
+        }
+    }
+
+    #[test]
+    fn test_mismatched_resolution() {
+        let a = Image::new_blank([2, 2], Colour::new(0.0, 0.0, 0.0, 1.0));
+        let b = Image::new_blank([3, 2], Colour::new(0.0, 0.0, 0.0, 1.0));
+
+        assert!(a.composite_over(&b).is_err());
+        assert!(a.blend(&b, 0.5).is_err());
+    }
+
+    /// A black and white checkerboard of single-pixel squares.
+    fn checkerboard(res: [usize; 2]) -> Image {
+        let mut img = Image::new_blank(res, Colour::new(0.0, 0.0, 0.0, 1.0));
+        for ((xi, yi), col) in img.pixels_mut().indexed_iter_mut() {
+            if (xi + yi) % 2 == 0 {
+                *col = Colour::new(1.0, 1.0, 1.0, 1.0);
+            }
+        }
+        img
+    }
+
+    #[test]
+    fn test_downsample_box_checkerboard() {
+        let out = checkerboard([8, 6])
+            .downsample(2, ResampleFilter::Box)
+            .unwrap();
+
+        assert_eq!(out.pixels().shape(), &[4, 3]);
+        for col in out.pixels().iter() {
+            assert_approx_eq!(col.red, 0.5);
+            assert_approx_eq!(col.green, 0.5);
+            assert_approx_eq!(col.blue, 0.5);
+            assert_approx_eq!(col.alpha, 1.0);
+        }
+    }
+
+    #[test]
+    fn test_downsample_gaussian_uniform() {
+        let img = Image::new_blank([9, 9], Colour::new(0.2, 0.4, 0.6, 1.0));
+        let out = img.downsample(3, ResampleFilter::Gaussian).unwrap();
+
+        assert_eq!(out.pixels().shape(), &[3, 3]);
+        for col in out.pixels().iter() {
+            assert_approx_eq!(col.red, 0.2);
+            assert_approx_eq!(col.green, 0.4);
+            assert_approx_eq!(col.blue, 0.6);
+        }
+    }
+
+    #[test]
+    fn test_downsample_invalid_factor() {
+        let img = checkerboard([4, 4]);
+        assert!(img.downsample(0, ResampleFilter::Box).is_err());
+        assert!(img.downsample(5, ResampleFilter::Box).is_err());
+    }
+}
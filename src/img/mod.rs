@@ -5,5 +5,12 @@ pub mod colour;
 pub mod gradient;
 pub mod gradient_builder;
 pub mod image;
+pub mod resample_filter;
+pub mod splat_kernel;
+pub mod texture;
+pub mod tone_map;
 
-pub use self::{aspect_ratio::*, colour::*, gradient::*, gradient_builder::*, image::*};
+pub use self::{
+    aspect_ratio::*, colour::*, gradient::*, gradient_builder::*, image::*, resample_filter::*,
+    splat_kernel::*, texture::*, tone_map::*,
+};
@@ -0,0 +1,58 @@
+//! Image reconstruction filters.
+
+use arctk_attr::file;
+use std::fmt::{Display, Formatter, Result};
+
+/// Reconstruction filter used when resampling images.
+#[file]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ResampleFilter {
+    /// Equal-weight average of the source pixels covered by each output pixel.
+    Box,
+    /// Gaussian-weighted average, with a standard deviation of half the output pixel width (in source pixels).
+    Gaussian,
+}
+
+impl ResampleFilter {
+    /// Weight of a source pixel at the given offset (in source pixels) from the output pixel centre.
+    /// `factor` is the output pixel width in source pixels.
+    #[inline]
+    #[must_use]
+    pub fn weight(&self, dx: f32, dy: f32, factor: usize) -> f32 {
+        let half = factor as f32 * 0.5;
+
+        match *self {
+            Self::Box => {
+                if dx.abs() < half && dy.abs() < half {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            Self::Gaussian => {
+                let sigma = half;
+                (-dx.mul_add(dx, dy * dy) / (2.0 * sigma * sigma)).exp()
+            }
+        }
+    }
+
+    /// Radius of source pixels contributing to each output pixel, in units of output pixels.
+    #[inline]
+    #[must_use]
+    pub const fn radius(&self) -> usize {
+        match *self {
+            Self::Box => 0,
+            Self::Gaussian => 1,
+        }
+    }
+}
+
+impl Display for ResampleFilter {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        match *self {
+            Self::Box => write!(fmt, "Box"),
+            Self::Gaussian => write!(fmt, "Gaussian"),
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! Anti-aliasing splat kernels.
+
+use crate::ord::{X, Y};
+use arctk_attr::file;
+use std::fmt::{Display, Formatter, Result};
+
+/// Kernel used to distribute a point sample over neighbouring pixels.
+/// Radii are given in pixels, and a zero radius deposits the whole sample into the pixel containing it.
+#[file]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SplatKernel {
+    /// Linearly decreasing weight, reaching zero at the radius.
+    Tent(f64),
+    /// Gaussian weight, with a standard deviation of half the radius, truncated at the radius.
+    Gaussian(f64),
+}
+
+impl SplatKernel {
+    /// Radius of the kernel [pixels].
+    #[inline]
+    #[must_use]
+    pub const fn radius(&self) -> f64 {
+        match *self {
+            Self::Tent(radius) | Self::Gaussian(radius) => radius,
+        }
+    }
+
+    /// Unnormalised weight of a pixel centre at the given offset [pixels] from the sample.
+    #[inline]
+    #[must_use]
+    fn weight(&self, dx: f64, dy: f64) -> f64 {
+        match *self {
+            Self::Tent(radius) => {
+                (1.0 - (dx.abs() / radius)).max(0.0) * (1.0 - (dy.abs() / radius)).max(0.0)
+            }
+            Self::Gaussian(radius) => {
+                if dx.abs() >= radius || dy.abs() >= radius {
+                    return 0.0;
+                }
+                let sigma = radius * 0.5;
+                (-dx.mul_add(dx, dy * dy) / (2.0 * sigma * sigma)).exp()
+            }
+        }
+    }
+
+    /// Distribute a sample, at a continuous pixel position, over the pixels of an image of the given resolution.
+    /// Pixel `[i, j]` covers positions `[i, i + 1)` by `[j, j + 1)`.
+    /// The returned weights sum to unity, unless the sample lies outside of the image.
+    #[inline]
+    #[must_use]
+    pub fn splat(&self, pos: [f64; 2], res: [usize; 2]) -> Vec<([usize; 2], f64)> {
+        let radius = self.radius();
+        debug_assert!(radius >= 0.0);
+
+        if radius <= 0.0 {
+            if pos[X] < 0.0 || pos[Y] < 0.0 {
+                return vec![];
+            }
+            let pixel = [pos[X] as usize, pos[Y] as usize];
+            return if pixel[X] < res[X] && pixel[Y] < res[Y] {
+                vec![(pixel, 1.0)]
+            } else {
+                vec![]
+            };
+        }
+
+        let min_x = (pos[X] - radius - 0.5).floor().max(0.0) as usize;
+        let min_y = (pos[Y] - radius - 0.5).floor().max(0.0) as usize;
+        let max_x =
+            ((pos[X] + radius - 0.5).ceil().max(0.0) as usize).min(res[X].saturating_sub(1));
+        let max_y =
+            ((pos[Y] + radius - 0.5).ceil().max(0.0) as usize).min(res[Y].saturating_sub(1));
+
+        let mut weights = Vec::new();
+        for x in min_x..=max_x {
+            for y in min_y..=max_y {
+                let w = self.weight(x as f64 + 0.5 - pos[X], y as f64 + 0.5 - pos[Y]);
+                if w > 0.0 {
+                    weights.push(([x, y], w));
+                }
+            }
+        }
+
+        let total: f64 = weights.iter().map(|(_, w)| w).sum();
+        if total > 0.0 {
+            for (_, w) in &mut weights {
+                *w /= total;
+            }
+        }
+
+        weights
+    }
+}
+
+impl Display for SplatKernel {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        match *self {
+            Self::Tent(radius) => write!(fmt, "Tent ({} px)", radius),
+            Self::Gaussian(radius) => write!(fmt, "Gaussian ({} px)", radius),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn test_zero_radius_nearest_pixel() {
+        let weights = SplatKernel::Tent(0.0).splat([2.7, 1.2], [4, 4]);
+        assert_eq!(weights, vec![([2, 1], 1.0)]);
+        assert!(SplatKernel::Tent(0.0).splat([4.1, 1.2], [4, 4]).is_empty());
+    }
+
+    #[test]
+    fn test_tent_boundary_split() {
+        // A hit on the boundary between pixels [1, 1] and [2, 1].
+        let weights = SplatKernel::Tent(1.0).splat([2.0, 1.5], [4, 4]);
+        assert_eq!(weights.len(), 2);
+        assert_eq!(weights[0].0, [1, 1]);
+        assert_eq!(weights[1].0, [2, 1]);
+        assert_approx_eq!(weights[0].1, 0.5);
+        assert_approx_eq!(weights[1].1, 0.5);
+    }
+
+    #[test]
+    fn test_gaussian_normalised() {
+        let weights = SplatKernel::Gaussian(2.0).splat([1.3, 2.8], [4, 4]);
+        assert!(weights.len() > 1);
+        assert_approx_eq!(weights.iter().map(|(_, w)| w).sum::<f64>(), 1.0);
+    }
+}
@@ -0,0 +1,140 @@
+//! Scalar surface texture.
+
+use crate::{
+    access, clone, fmt_report,
+    img::Image,
+    ord::{X, Y},
+};
+use ndarray::Array2;
+use std::fmt::{Display, Formatter};
+
+/// Scalar texture map, sampled using surface texture coordinates.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Texture {
+    /// Texel values.
+    texels: Array2<f64>,
+    /// Value used where the surface has no texture coordinates.
+    fallback: f64,
+}
+
+impl Texture {
+    access!(texels: Array2<f64>);
+    clone!(fallback: f64);
+
+    /// Construct a new instance.
+    #[inline]
+    #[must_use]
+    pub fn new(texels: Array2<f64>, fallback: f64) -> Self {
+        debug_assert!(!texels.is_empty());
+
+        Self { texels, fallback }
+    }
+
+    /// Construct a new instance from the mean colour channel value of each image pixel.
+    #[inline]
+    #[must_use]
+    pub fn from_image(img: &Image, fallback: f64) -> Self {
+        Self::new(
+            img.pixels()
+                .mapv(|col| f64::from(col.red + col.green + col.blue) / 3.0),
+            fallback,
+        )
+    }
+
+    /// Sample the nearest texel to the given texture coordinates.
+    /// Coordinates outside [0, 1) wrap around, and missing coordinates yield the fallback value.
+    #[inline]
+    #[must_use]
+    pub fn value(&self, uv: Option<[f64; 2]>) -> f64 {
+        match uv {
+            Some(uv) => {
+                let res = self.texels.shape();
+                let x = (uv[X] * res[X] as f64).floor() as isize;
+                let y = (uv[Y] * res[Y] as f64).floor() as isize;
+
+                self.texels[[
+                    x.rem_euclid(res[X] as isize) as usize,
+                    y.rem_euclid(res[Y] as isize) as usize,
+                ]]
+            }
+            None => self.fallback,
+        }
+    }
+}
+
+impl Display for Texture {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        let res = self.texels.shape();
+        writeln!(fmt, "...")?;
+        fmt_report!(fmt, &format!("[{} x {}]", res[X], res[Y]), "resolution");
+        fmt_report!(fmt, self.fallback, "fallback");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        geom::{Ray, SmoothTriangle},
+        math::{Dir3, Point3},
+    };
+    use ndarray::arr2;
+
+    #[test]
+    fn test_missing_uv_fallback() {
+        let tex = Texture::new(arr2(&[[0.2, 0.4]]), 0.7);
+        assert_eq!(tex.value(None), 0.7);
+        assert_eq!(tex.value(Some([0.0, 0.4])), 0.2);
+        assert_eq!(tex.value(Some([0.0, 1.1])), 0.2);
+    }
+
+    /// A two-texel checkerboard mapped across a unit quad should alternate albedo along it.
+    #[test]
+    fn test_checkerboard_across_mesh() {
+        let tex = Texture::new(arr2(&[[0.0], [1.0]]), 0.5);
+        let norm = Dir3::new(0.0, 0.0, 1.0);
+
+        // The quad repeats the texture twice along x.
+        let tris = [
+            SmoothTriangle::new_from_verts(
+                [
+                    Point3::new(0.0, 0.0, 0.0),
+                    Point3::new(1.0, 0.0, 0.0),
+                    Point3::new(1.0, 1.0, 0.0),
+                ],
+                [norm; 3],
+            )
+            .with_uvs([[0.0, 0.0], [2.0, 0.0], [2.0, 1.0]]),
+            SmoothTriangle::new_from_verts(
+                [
+                    Point3::new(0.0, 0.0, 0.0),
+                    Point3::new(1.0, 1.0, 0.0),
+                    Point3::new(0.0, 1.0, 0.0),
+                ],
+                [norm; 3],
+            )
+            .with_uvs([[0.0, 0.0], [2.0, 1.0], [0.0, 1.0]]),
+        ];
+
+        let albedo = |x: f64, y: f64| {
+            let ray = Ray::new(Point3::new(x, y, 1.0), Dir3::new(0.0, 0.0, -1.0));
+            let (_dist, _side, uv) = tris.iter().find_map(|tri| tri.dist_side_uv(&ray)).unwrap();
+            tex.value(uv)
+        };
+
+        for y in [0.3, 0.7].iter() {
+            assert_eq!(albedo(0.1, *y), 0.0);
+            assert_eq!(albedo(0.35, *y), 1.0);
+            assert_eq!(albedo(0.6, *y), 0.0);
+            assert_eq!(albedo(0.9, *y), 1.0);
+        }
+
+        // Without texture coordinates the fallback is used.
+        let plain = SmoothTriangle::new_from_verts(*tris[0].tri().verts(), [norm; 3]);
+        let ray = Ray::new(Point3::new(0.5, 0.2, 1.0), Dir3::new(0.0, 0.0, -1.0));
+        let (_dist, _side, uv) = plain.dist_side_uv(&ray).unwrap();
+        assert_eq!(tex.value(uv), 0.5);
+    }
+}
@@ -0,0 +1,79 @@
+//! Tone-mapping operators.
+
+use arctk_attr::file;
+use std::fmt::{Display, Formatter, Result};
+
+/// Tone-mapping operator enumeration.
+/// Maps unbounded linear radiance values into the displayable range [0, 1].
+#[file]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ToneMap {
+    /// No compression, values are clamped to [0, 1].
+    Linear,
+    /// Reinhard operator. x / (1 + x)
+    Reinhard,
+    /// ACES filmic curve approximation (Narkowicz, 2015).
+    Aces,
+}
+
+impl ToneMap {
+    /// Map a linear channel value into [0, 1].
+    #[inline]
+    #[must_use]
+    pub fn map(&self, x: f32) -> f32 {
+        let x = x.max(0.0);
+
+        match *self {
+            Self::Linear => x.min(1.0),
+            Self::Reinhard => x / (1.0 + x),
+            Self::Aces => {
+                let num = x * 2.51_f32.mul_add(x, 0.03);
+                let den = x.mul_add(2.43_f32.mul_add(x, 0.59), 0.14);
+                (num / den).clamp(0.0, 1.0)
+            }
+        }
+    }
+}
+
+impl Display for ToneMap {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        match *self {
+            Self::Linear => write!(fmt, "Linear"),
+            Self::Reinhard => write!(fmt, "Reinhard"),
+            Self::Aces => write!(fmt, "ACES"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ToneMap;
+
+    #[test]
+    fn test_reinhard_monotonic_ramp() {
+        // Kept below x ~ 1e3, beyond which successive f32 outputs are no longer distinguishable.
+        let mut prev = -1.0;
+        for i in 0..700 {
+            let x = 1.0e-3 * 1.02_f32.powi(i);
+            let y = ToneMap::Reinhard.map(x);
+
+            assert!((0.0..=1.0).contains(&y));
+            assert!(y > prev);
+            prev = y;
+        }
+    }
+
+    #[test]
+    fn test_aces_bounds() {
+        assert_eq!(ToneMap::Aces.map(0.0), 0.0);
+        assert_eq!(ToneMap::Aces.map(1.0e6), 1.0);
+    }
+
+    #[test]
+    fn test_linear_clamps() {
+        assert_eq!(ToneMap::Linear.map(-1.0), 0.0);
+        assert_eq!(ToneMap::Linear.map(0.5), 0.5);
+        assert_eq!(ToneMap::Linear.map(4.0), 1.0);
+    }
+}
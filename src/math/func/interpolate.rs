@@ -0,0 +1,64 @@
+//! Interpolation functions.
+
+use crate::ord::{X, Y};
+use ndarray::Array2;
+
+/// Bilinearly interpolate a grid of values at normalised coordinates.
+/// The grid values lie on the nodes, such that `(0, 0)` samples the first element
+/// and `(1, 1)` samples the last. Coordinates outside [0, 1] are clamped to the edges.
+#[inline]
+#[must_use]
+pub fn bilinear(grid: &Array2<f64>, u: f64, v: f64) -> f64 {
+    debug_assert!(!grid.is_empty());
+
+    let shape = grid.shape();
+    let (xi, fx) = node_weight(u, shape[X]);
+    let (yi, fy) = node_weight(v, shape[Y]);
+
+    let xj = (xi + 1).min(shape[X] - 1);
+    let yj = (yi + 1).min(shape[Y] - 1);
+
+    let low = (grid[[xj, yi]] - grid[[xi, yi]]).mul_add(fx, grid[[xi, yi]]);
+    let high = (grid[[xj, yj]] - grid[[xi, yj]]).mul_add(fx, grid[[xi, yj]]);
+
+    (high - low).mul_add(fy, low)
+}
+
+/// Determine the lower node index and the fractional position towards the next node
+/// for a normalised coordinate along an axis with the given number of nodes.
+#[inline]
+#[must_use]
+fn node_weight(t: f64, nodes: usize) -> (usize, f64) {
+    if nodes < 2 {
+        return (0, 0.0);
+    }
+
+    let pos = t.clamp(0.0, 1.0) * (nodes - 1) as f64;
+    let index = (pos.floor() as usize).min(nodes - 2);
+
+    (index, pos - index as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::bilinear;
+    use assert_approx_eq::assert_approx_eq;
+    use ndarray::arr2;
+
+    #[test]
+    fn test_centre_is_corner_mean() {
+        let grid = arr2(&[[1.0, 2.0], [3.0, 6.0]]);
+        assert_approx_eq!(bilinear(&grid, 0.5, 0.5), 3.0);
+    }
+
+    #[test]
+    fn test_corners_and_clamping() {
+        let grid = arr2(&[[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+
+        assert_approx_eq!(bilinear(&grid, 0.0, 0.0), 1.0);
+        assert_approx_eq!(bilinear(&grid, 1.0, 1.0), 6.0);
+        assert_approx_eq!(bilinear(&grid, 0.0, 0.25), 1.5);
+        assert_approx_eq!(bilinear(&grid, -1.0, 2.0), 3.0);
+        assert_approx_eq!(bilinear(&grid, 2.0, -1.0), 4.0);
+    }
+}
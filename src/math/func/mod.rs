@@ -2,5 +2,6 @@
 
 pub mod formula;
 pub mod formula_builder;
+pub mod interpolate;
 
-pub use self::{formula::*, formula_builder::*};
+pub use self::{formula::*, formula_builder::*, interpolate::*};
@@ -34,6 +34,14 @@ impl Point3 {
         Vec4::from(self.data.to_homogeneous())
     }
 
+    /// Linearly interpolate towards another point.
+    /// A factor of zero returns this point, and a factor of one returns the other.
+    #[inline]
+    #[must_use]
+    pub fn lerp(&self, other: &Self, t: Real) -> Self {
+        *self + (other - self) * t
+    }
+
     /// Access the first component.
     #[inline]
     #[must_use]
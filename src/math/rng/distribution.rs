@@ -10,20 +10,60 @@ lazy_static! {
     static ref GOLDEN_RATIO: f64 = (1.0 + 5.0_f64.sqrt()) / 2.0;
 }
 
-/// Sample the Henyey-Greenstein phase function with a given asymmetry parameter.
-#[inline]
-#[must_use]
-pub fn sample_henyey_greenstein<R: Rng>(rng: &mut R, asym: f64) -> f64 {
-    debug_assert!(asym.abs() <= 1.0);
+/// Angular scattering distributions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Distribution {
+    /// Henyey-Greenstein phase function.
+    HenyeyGreenstein {
+        /// Asymmetry parameter.
+        g: f64,
+    },
+}
 
-    if asym.abs() < 1.0e-6 {
-        return rng.gen_range(-1.0_f64..1.0).acos();
+impl Distribution {
+    /// Sample the cosine of the scattering angle, within the range [-1, 1].
+    #[inline]
+    #[must_use]
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> f64 {
+        match *self {
+            Self::HenyeyGreenstein { g } => {
+                debug_assert!(g.abs() <= 1.0);
+
+                if g.abs() < 1.0e-6 {
+                    return rng.gen_range(-1.0_f64..1.0);
+                }
+
+                let g_sq = g * g;
+
+                let a = (1.0 - g_sq) / g.mul_add(rng.gen_range(-1.0..1.0), 1.0);
+                ((1.0 + g_sq - (a * a)) / (2.0 * g)).max(-1.0).min(1.0)
+            }
+        }
     }
 
-    let asym_sq = asym * asym;
+    /// Calculate the probability density of a given scattering cosine.
+    /// The density is normalised over the cosine range [-1, 1].
+    #[inline]
+    #[must_use]
+    pub fn pdf(&self, cos_theta: f64) -> f64 {
+        debug_assert!(cos_theta.abs() <= 1.0);
+
+        match *self {
+            Self::HenyeyGreenstein { g } => {
+                let g_sq = g * g;
+                0.5 * ((1.0 - g_sq) / (1.0 + g_sq - (2.0 * g * cos_theta)).powf(1.5))
+            }
+        }
+    }
+}
 
-    let a = (1.0 - asym_sq) / asym.mul_add(rng.gen_range(-1.0..1.0), 1.0);
-    ((1.0 + asym_sq - (a * a)) / (2.0 * asym)).acos()
+/// Sample the Henyey-Greenstein phase function with a given asymmetry parameter.
+#[inline]
+#[must_use]
+pub fn sample_henyey_greenstein<R: Rng>(rng: &mut R, asym: f64) -> f64 {
+    Distribution::HenyeyGreenstein { g: asym }
+        .sample(rng)
+        .acos()
 }
 
 /// Sample the normal distribution.
@@ -47,6 +87,16 @@ pub fn sample_gaussian<R: Rng>(rng: &mut R, mu: f64, sigma: f64) -> f64 {
     sample_normal(rng).mul_add(sigma, mu)
 }
 
+/// Sample the radius of a circularly symmetric two-dimensional gaussian,
+/// with standard deviation sigma along each axis.
+#[inline]
+#[must_use]
+pub fn sample_rayleigh<R: Rng>(rng: &mut R, sigma: f64) -> f64 {
+    debug_assert!(sigma > 0.0);
+
+    sigma * (-2.0 * (1.0 - rng.gen::<f64>()).ln()).sqrt()
+}
+
 /// Create a random unit vector.
 #[inline]
 #[must_use]
@@ -98,3 +148,29 @@ pub fn rand_hemisphere_point(n: i32, max: i32) -> (f64, f64) {
 
     rand_sphere_point(n, max * 2)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::thread_rng;
+
+    #[test]
+    fn test_henyey_greenstein() {
+        for g in [-0.5, 0.0, 0.3, 0.9].iter() {
+            let dist = Distribution::HenyeyGreenstein { g: *g };
+
+            // Midpoint integration of the pdf over the cosine range.
+            let n = 100_000;
+            let delta = 2.0 / n as f64;
+            let total: f64 = (0..n)
+                .map(|i| dist.pdf((i as f64 + 0.5).mul_add(delta, -1.0)) * delta)
+                .sum();
+            assert!((total - 1.0).abs() < 1.0e-3);
+
+            let mut rng = thread_rng();
+            let samples = 200_000;
+            let mean = (0..samples).map(|_| dist.sample(&mut rng)).sum::<f64>() / samples as f64;
+            assert!((mean - g).abs() < 1.0e-2);
+        }
+    }
+}
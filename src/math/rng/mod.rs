@@ -1,9 +1,11 @@
 //! Random number module.
 
 pub mod distribution;
+pub mod pdf_fn;
 pub mod probability;
+pub mod substream;
 
 // Builders
 pub mod probability_builder;
 
-pub use self::{distribution::*, probability::*, probability_builder::*};
+pub use self::{distribution::*, pdf_fn::*, probability::*, probability_builder::*, substream::*};
@@ -0,0 +1,34 @@
+//! Callable probability density function.
+
+use std::{
+    fmt::{Debug, Formatter},
+    sync::Arc,
+};
+
+/// Shareable closed-form probability density function.
+/// The function need not be normalised.
+#[derive(Clone)]
+pub struct PdfFn(Arc<dyn Fn(f64) -> f64 + Send + Sync>);
+
+impl PdfFn {
+    /// Construct a new instance.
+    #[inline]
+    #[must_use]
+    pub fn new<F: Fn(f64) -> f64 + Send + Sync + 'static>(pdf: F) -> Self {
+        Self(Arc::new(pdf))
+    }
+
+    /// Evaluate the density at the given value.
+    #[inline]
+    #[must_use]
+    pub fn eval(&self, x: f64) -> f64 {
+        (self.0)(x)
+    }
+}
+
+impl Debug for PdfFn {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> std::fmt::Result {
+        write!(fmt, "PdfFn")
+    }
+}
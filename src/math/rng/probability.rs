@@ -1,18 +1,31 @@
 //! Probability distribution implementation.
 
 use crate::{
+    data::Histogram,
     err::Error,
-    math::{distribution, Formula},
+    math::{distribution, Formula, PdfFn},
+    tools::Range,
 };
 use ndarray::Array1;
 use rand::Rng;
+use statrs::function::erf::erf_inv;
 use std::{
     fmt::{Display, Formatter},
     fs::File,
     io::Write,
     result::Result,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
 };
 
+/// Number of points at which a rejection sampled density is checked against its bound on construction.
+const REJECTION_CHECKS: usize = 1001;
+
+/// Number of draws found to exceed the rejection bound before a warning is issued.
+const REJECTION_WARNING: usize = 100;
+
 /// Probability distribution formulae.
 ///
 /// This enum provides easy sampling from and manipulation of probability distribution functions (PDFs).
@@ -25,6 +38,8 @@ use std::{
 /// - `Probability::Gaussian`: A Gaussian (normal) distribution.
 /// - `Probability::ConstantSpline`: Sample from a CDF whose value is determined by a `Formula`.
 /// - `Probability::LinearSpline`: Sample from a PDF where an arbitrary dataset is represented by (N - 1) linear splines.
+/// - `Probability::Binned`: Select a bin in proportion to its weight, then sample uniformly within it.
+/// - `Probability::Rejection`: Rejection sample a closed-form PDF within a range.
 
 #[derive(Clone, Debug)]
 pub enum Probability {
@@ -83,6 +98,28 @@ pub enum Probability {
         /// The values that correspond to the CDF.
         xs: Array1<f64>,
     },
+    /// Piecewise constant bins.
+    Binned {
+        /// Bin edges.
+        edges: Array1<f64>,
+        /// Cumulative probability at the upper edge of each bin.
+        cdf: Array1<f64>,
+    },
+    /// Rejection sampled function.
+    Rejection {
+        /// Probability density function.
+        pdf: PdfFn,
+        /// Minimum value.
+        min: f64,
+        /// Maximum value.
+        max: f64,
+        /// Upper bound of the density within the range.
+        max_pdf: f64,
+        /// Number of draws at which the density was found to exceed its bound.
+        exceeded: Arc<AtomicUsize>,
+        /// Cumulative distribution function, tabulated at evenly spaced points across the range.
+        cdf: Array1<f64>,
+    },
 }
 
 impl Probability {
@@ -218,6 +255,101 @@ impl Probability {
         }
     }
 
+    /// Construct a new binned instance from the bin counts and edges of a histogram.
+    /// # Errors
+    /// if the histogram contains no counts.
+    #[inline]
+    pub fn from_histogram(hist: &Histogram) -> Result<Self, Error> {
+        let counts = hist.counts();
+        debug_assert!(counts.iter().all(|c| *c >= 0.0));
+
+        let total = counts.sum();
+        if total <= 0.0 {
+            return Err(
+                "Can not construct a probability distribution from an empty histogram.".into(),
+            );
+        }
+
+        let mut sum = 0.0;
+        let cdf = counts
+            .iter()
+            .map(|c| {
+                sum += c;
+                sum / total
+            })
+            .collect();
+
+        Ok(Self::Binned {
+            edges: Array1::from(hist.binner().edges()),
+            cdf,
+        })
+    }
+
+    /// Construct a new instance sampling a closed-form, not necessarily normalised, density function via rejection.
+    /// The density must not exceed `max_pdf` anywhere within the range.
+    /// A warning is issued if sampling repeatedly finds the density above this bound.
+    /// # Errors
+    /// if the bound is not positive, the density exceeds the bound when checked across the range,
+    /// or the density is nowhere positive.
+    #[inline]
+    pub fn from_fn<F: Fn(f64) -> f64 + Send + Sync + 'static>(
+        pdf: F,
+        range: Range,
+        max_pdf: f64,
+    ) -> Result<Self, Error> {
+        if max_pdf <= 0.0 {
+            return Err(Error::Text(format!(
+                "Rejection sampling bound must be positive: {}",
+                max_pdf
+            )));
+        }
+
+        let mut cdf = Vec::with_capacity(REJECTION_CHECKS);
+        let mut total = 0.0;
+        let mut prev = 0.0;
+        for n in 0..REJECTION_CHECKS {
+            let x = range.min() + (range.width() * n as f64 / (REJECTION_CHECKS - 1) as f64);
+            let p = pdf(x);
+            if p > max_pdf {
+                return Err(Error::Text(format!(
+                    "Probability density of {} at {} exceeds the rejection sampling bound: {}",
+                    p, x, max_pdf
+                )));
+            }
+            if n > 0 {
+                total += 0.5 * (prev + p.max(0.0));
+            }
+            prev = p.max(0.0);
+            cdf.push(total);
+        }
+        if total <= 0.0 {
+            return Err("Probability density is nowhere positive within the range.".into());
+        }
+        let mut cdf = Array1::from(cdf);
+        cdf /= total;
+
+        Ok(Self::Rejection {
+            pdf: PdfFn::new(pdf),
+            min: range.min(),
+            max: range.max(),
+            max_pdf,
+            exceeded: Arc::new(AtomicUsize::new(0)),
+            cdf,
+        })
+    }
+
+    /// Sample a binned distribution at a given cumulative probability.
+    #[inline]
+    #[must_use]
+    fn sample_binned(edges: &Array1<f64>, cdf: &Array1<f64>, ps: f64) -> f64 {
+        // Empty bins are never selected, as their cumulative probability equals that of the previous bin.
+        let index = cdf.iter().position(|c| ps < *c).unwrap_or(cdf.len() - 1);
+        let lower = if index == 0 { 0.0 } else { cdf[index - 1] };
+        let frac = ((ps - lower) / (cdf[index] - lower)).min(1.0);
+
+        frac.mul_add(edges[index + 1] - edges[index], edges[index])
+    }
+
     /// Sample a number from the described distribution.
     #[inline]
     #[must_use]
@@ -272,6 +404,34 @@ impl Probability {
                 }
                 0.0
             }
+            Self::Binned { ref edges, ref cdf } => {
+                Self::sample_binned(edges, cdf, rng.gen_range(0.0..1.0))
+            }
+            Self::Rejection {
+                ref pdf,
+                min,
+                max,
+                max_pdf,
+                ref exceeded,
+                cdf: _,
+            } => loop {
+                let x = rng.gen_range(min..max);
+                let p = pdf.eval(x);
+
+                if p > max_pdf {
+                    let count = exceeded.fetch_add(1, Ordering::Relaxed) + 1;
+                    if count == REJECTION_WARNING {
+                        println!(
+                            "[WARN] Probability density repeatedly exceeds its rejection sampling bound of {}.",
+                            max_pdf
+                        );
+                    }
+                }
+
+                if rng.gen_range(0.0..max_pdf) < p {
+                    return x;
+                }
+            },
         }
     }
 
@@ -283,7 +443,7 @@ impl Probability {
     pub fn sample_at(&self, ps: f64) -> f64 {
         match *self {
             Self::Point { ref c } => *c,
-            Self::Points { ref cs } => cs[ps as usize],
+            Self::Points { ref cs } => cs[((ps * cs.len() as f64) as usize).min(cs.len() - 1)],
             Self::Uniform { ref min, ref max } => min + (max - min) * ps,
             Self::Linear {
                 grad,
@@ -301,7 +461,12 @@ impl Probability {
                     - intercept)
                     / grad
             }
-            Self::Gaussian { mu: _, sigma: _ } => todo!(),
+            Self::Gaussian { mu, sigma } => {
+                debug_assert!(ps >= 0.0);
+                debug_assert!(ps <= 1.0);
+
+                (sigma * std::f64::consts::SQRT_2).mul_add(erf_inv(2.0f64.mul_add(ps, -1.0)), mu)
+            }
             Self::ConstantSpline { ref cdf } => cdf.y(ps),
             Self::LinearSpline {
                 ref grads,
@@ -343,6 +508,34 @@ impl Probability {
                 }
                 0.0
             }
+            Self::Binned { ref edges, ref cdf } => {
+                debug_assert!(ps >= 0.0);
+                debug_assert!(ps <= 1.0);
+
+                Self::sample_binned(edges, cdf, ps)
+            }
+            Self::Rejection {
+                min, max, ref cdf, ..
+            } => {
+                debug_assert!(ps >= 0.0);
+                debug_assert!(ps <= 1.0);
+
+                // Invert the tabulated distribution, interpolating linearly between its points.
+                let index = cdf
+                    .iter()
+                    .skip(1)
+                    .position(|c| ps <= *c)
+                    .unwrap_or(cdf.len() - 2);
+                let (lower, upper) = (cdf[index], cdf[index + 1]);
+                let frac = if upper > lower {
+                    ((ps - lower) / (upper - lower)).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+
+                let step = (max - min) / (cdf.len() - 1) as f64;
+                (index as f64 + frac).mul_add(step, min)
+            }
         }
     }
 
@@ -416,6 +609,8 @@ impl Display for Probability {
             Self::Gaussian { .. } => "Gaussian",
             Self::ConstantSpline { .. } => "Constant Spline",
             Self::LinearSpline { .. } => "Linear Spline",
+            Self::Binned { .. } => "Binned",
+            Self::Rejection { .. } => "Rejection",
         };
         write!(fmt, "{}", kind)
     }
@@ -424,6 +619,10 @@ impl Display for Probability {
 #[cfg(test)]
 pub mod tests {
     use super::Probability;
+    use crate::{
+        data::{Average, Histogram},
+        tools::Range,
+    };
     use assert_approx_eq::assert_approx_eq;
     use ndarray::Array1;
     use std::f64::consts::PI;
@@ -450,4 +649,90 @@ pub mod tests {
             })
             .collect();
     }
+
+    /// Resampling a histogram of a gaussian should reproduce the mean and spread of the gaussian.
+    #[test]
+    fn histogram_resampling_check() {
+        let mut rng = rand::thread_rng();
+        let gauss = Probability::new_gaussian(2.0, 0.5);
+
+        let mut hist = Histogram::new(0.0, 4.0, 80);
+        for _ in 0..100_000 {
+            hist.try_collect(gauss.sample(&mut rng));
+        }
+        let resampler = Probability::from_histogram(&hist).unwrap();
+
+        let mut ave = Average::new();
+        let mut ave_sq = Average::new();
+        for _ in 0..100_000 {
+            let x = resampler.sample(&mut rng);
+            assert!((0.0..=4.0).contains(&x));
+            ave += x;
+            ave_sq += (x - 2.0) * (x - 2.0);
+        }
+        assert_approx_eq!(ave.ave(), 2.0, 0.01);
+        assert_approx_eq!(ave_sq.ave().sqrt(), 0.5, 0.01);
+
+        assert!(Probability::from_histogram(&Histogram::new(0.0, 1.0, 10)).is_err());
+    }
+
+    /// Rejection sampling a closed-form sine density should reproduce its integral over each bin.
+    #[test]
+    fn rejection_sampling_check() {
+        let mut rng = rand::thread_rng();
+        let pdf = Probability::from_fn(f64::sin, Range::new(0.0, PI), 1.0).unwrap();
+
+        let bins = 20;
+        let num_samples = 200_000;
+        let mut hist = Histogram::new(0.0, PI, bins);
+        for _ in 0..num_samples {
+            hist.try_collect(pdf.sample(&mut rng));
+        }
+
+        let width = PI / bins as f64;
+        for (index, count) in hist.counts().iter().enumerate() {
+            let lower = width * index as f64;
+            let expected = 0.5 * (lower.cos() - (lower + width).cos());
+            assert_approx_eq!(count / num_samples as f64, expected, 0.005);
+        }
+    }
+
+    /// Sampling a gaussian or a set of points at a cumulative probability should invert their distributions.
+    #[test]
+    fn gaussian_and_points_sample_at_check() {
+        let gauss = Probability::new_gaussian(2.0, 0.5);
+        assert_approx_eq!(gauss.sample_at(0.5), 2.0, 1.0e-9);
+        // One standard deviation either side of the mean.
+        assert_approx_eq!(gauss.sample_at(0.841_344_746), 2.5, 1.0e-6);
+        assert_approx_eq!(gauss.sample_at(0.158_655_254), 1.5, 1.0e-6);
+
+        let points = Probability::new_points(Array1::from(vec![1.0, 2.0, 3.0, 4.0]));
+        assert_approx_eq!(points.sample_at(0.0), 1.0);
+        assert_approx_eq!(points.sample_at(0.3), 2.0);
+        assert_approx_eq!(points.sample_at(0.6), 3.0);
+        assert_approx_eq!(points.sample_at(1.0), 4.0);
+    }
+
+    /// Sampling a rejection distribution at a cumulative probability should invert its integral.
+    #[test]
+    fn rejection_sample_at_check() {
+        let pdf = Probability::from_fn(f64::sin, Range::new(0.0, PI), 1.0).unwrap();
+
+        assert_approx_eq!(pdf.sample_at(0.0), 0.0, 1.0e-6);
+        assert_approx_eq!(pdf.sample_at(1.0), PI, 1.0e-6);
+        for n in 1..10 {
+            let ps = n as f64 / 10.0;
+            // The CDF of the normalised sine density is (1 - cos(x)) / 2.
+            assert_approx_eq!(pdf.sample_at(ps), (1.0 - (2.0 * ps)).acos(), 1.0e-3);
+        }
+    }
+
+    /// An underestimated or invalid bound should be rejected on construction.
+    #[test]
+    fn rejection_bound_check() {
+        assert!(Probability::from_fn(|x| 2.0 * x, Range::new(0.0, 1.0), 1.0).is_err());
+        assert!(Probability::from_fn(|x| 2.0 * x, Range::new(0.0, 1.0), 0.0).is_err());
+        assert!(Probability::from_fn(|_| 0.0, Range::new(0.0, 1.0), 1.0).is_err());
+        assert!(Probability::from_fn(|x| 2.0 * x, Range::new(0.0, 1.0), 2.0).is_ok());
+    }
 }
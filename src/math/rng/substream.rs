@@ -0,0 +1,90 @@
+//! Deterministic random number substreams.
+
+use rand::{rngs::StdRng, SeedableRng};
+
+/// Generate the independent random number generator of a single item of work.
+/// The stream depends only on the global seed and the item index,
+/// so any item can be reproduced in isolation, irrespective of scheduling.
+#[inline]
+#[must_use]
+pub fn substream(seed: u64, index: u64) -> StdRng {
+    StdRng::seed_from_u64(mix(seed ^ mix(index)))
+}
+
+/// SplitMix64 finalisation, decorrelating neighbouring inputs.
+#[inline]
+#[must_use]
+const fn mix(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        geom::{Cube, Grid, Ray},
+        math::{Dir3, Point3},
+        ord::{Map, Name, Register, Set},
+        sim::{fixture, multi_thread, Attribute, Engine, EventLog, Input},
+    };
+
+    #[test]
+    fn test_photon_independent_of_thread_and_order() {
+        let seed = 42;
+        let phot_id = 12_345;
+
+        // A scattering medium, so the path of each photon depends on many draws from its stream.
+        let mirror = Attribute::Mirror(0.5);
+        let surfs = fixture::far_mirror(&mirror);
+        let tree = fixture::single_cell_tree(&surfs);
+
+        let name = Name::new("fog");
+        let mats = fixture::material_set(&name, fixture::material(4.0, Some(0.1), 0.5));
+        let mat = mats.get(&name).unwrap();
+
+        let reg = Register::new(vec![]);
+        let attrs = Set::new(Map::new());
+        let light = fixture::beam(
+            1.0,
+            Ray::new(Point3::new(0.0, 0.0, 0.0), Dir3::new(0.0, 0.0, 1.0)),
+            mat,
+        );
+        let bounds = Cube::new(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0));
+        let grid = Grid::new(bounds.clone(), [1, 1, 1]);
+        let sett = fixture::settings(&format!(
+            "num_phot: 12400, num_threads: 4, seed: {}, trace_interval: {}",
+            seed, phot_id
+        ));
+        let input = Input::new(&reg, &mats, &attrs, light, &tree, &grid, &sett);
+        let base = fixture::blank_output(bounds.clone(), [1, 1, 1], &reg);
+
+        // The photon's events within a seeded run, shared between threads.
+        let data = multi_thread(&Engine::Standard, input.clone(), &base).unwrap();
+        let traced: Vec<_> = data
+            .log
+            .unwrap()
+            .records()
+            .iter()
+            .filter(|rec| rec.phot_id() == phot_id)
+            .cloned()
+            .collect();
+        assert!(traced.len() > 2);
+
+        // The photon replayed alone, from its own stream.
+        let mut replay = fixture::blank_output(bounds, [1, 1, 1], &reg);
+        let mut log = EventLog::new(phot_id);
+        log.begin(phot_id);
+        replay.log = Some(log);
+
+        let mut rng = substream(seed, phot_id as u64);
+        let sources = input.sources();
+        let source = &sources[input.select_light(&mut rng)];
+        let phot = source.light.emit_nth(&mut rng, 1.0 / 12_400.0, phot_id);
+        Engine::Standard.run(source, &mut replay, &mut rng, phot);
+
+        assert_eq!(traced, *replay.log.unwrap().records());
+    }
+}
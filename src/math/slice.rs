@@ -1,5 +1,7 @@
 //! Ordering functions.
 
+use nalgebra::{DMatrix, DVector};
+
 /// Determine the minimum value within a list.
 #[inline]
 #[must_use]
@@ -65,3 +67,228 @@ pub fn kinds<T: Clone + Eq + std::hash::Hash>(numbers: &[T]) -> usize {
     }
     counts.len()
 }
+
+/// Determine the index of the maximum value within a list.
+/// NaN values are ignored, returns `None` if there are no comparable values.
+#[inline]
+#[must_use]
+pub fn argmax(vec: &[f64]) -> Option<usize> {
+    vec.iter()
+        .enumerate()
+        .filter(|(_, x)| !x.is_nan())
+        .fold(None, |best: Option<(usize, f64)>, (i, &x)| match best {
+            Some((_, b)) if b >= x => best,
+            _ => Some((i, x)),
+        })
+        .map(|(i, _)| i)
+}
+
+/// Determine the index of the minimum value within a list.
+/// NaN values are ignored, returns `None` if there are no comparable values.
+#[inline]
+#[must_use]
+pub fn argmin(vec: &[f64]) -> Option<usize> {
+    vec.iter()
+        .enumerate()
+        .filter(|(_, x)| !x.is_nan())
+        .fold(None, |best: Option<(usize, f64)>, (i, &x)| match best {
+            Some((_, b)) if b <= x => best,
+            _ => Some((i, x)),
+        })
+        .map(|(i, _)| i)
+}
+
+/// Find the indices of local maxima with at least the given prominence.
+/// The prominence of a peak is its height above the higher of the two lowest points
+/// separating it from a taller value (or the end of the list) on either side.
+/// For flat-topped peaks the first index of the plateau is returned.
+#[inline]
+#[must_use]
+pub fn find_peaks(vec: &[f64], prominence: f64) -> Vec<usize> {
+    let mut peaks = Vec::new();
+
+    let n = vec.len();
+    let mut i = 1;
+    while i + 1 < n {
+        if vec[i] > vec[i - 1] {
+            // Walk across any plateau.
+            let mut j = i;
+            while j + 1 < n && (vec[j + 1] - vec[i]).abs() <= 0.0 {
+                j += 1;
+            }
+
+            if j + 1 < n && vec[j + 1] < vec[i] {
+                let height = vec[i];
+
+                let mut left_min = height;
+                for x in vec[..i].iter().rev() {
+                    if *x > height {
+                        break;
+                    }
+                    left_min = left_min.min(*x);
+                }
+
+                let mut right_min = height;
+                for x in &vec[(j + 1)..] {
+                    if *x > height {
+                        break;
+                    }
+                    right_min = right_min.min(*x);
+                }
+
+                if height - left_min.max(right_min) >= prominence {
+                    peaks.push(i);
+                }
+            }
+
+            i = j + 1;
+        } else {
+            i += 1;
+        }
+    }
+
+    peaks
+}
+
+/// Smooth a list with a centred moving average over an odd-sized window.
+/// Near the ends of the list the window is truncated to the available values,
+/// so each output is the mean of the values within half a window of it.
+#[inline]
+#[must_use]
+pub fn moving_average(vec: &[f64], window: usize) -> Vec<f64> {
+    debug_assert!(window % 2 == 1);
+
+    let half = window / 2;
+    let n = vec.len();
+    (0..n)
+        .map(|i| {
+            let lower = i.saturating_sub(half);
+            let upper = (i + half + 1).min(n);
+            vec[lower..upper].iter().sum::<f64>() / (upper - lower) as f64
+        })
+        .collect()
+}
+
+/// Smooth a list with a Savitzky-Golay filter, fitting a polynomial of the given order
+/// by least-squares over an odd-sized window about each value.
+/// Near the ends of the list the window is shifted to lie within it,
+/// and the polynomial fitted to the first or last full window is evaluated at each edge value.
+/// A window longer than the list is shortened to the longest odd window within it,
+/// and the order is lowered, if required, to remain below the window size.
+#[allow(clippy::expect_used)]
+#[inline]
+#[must_use]
+pub fn savitzky_golay(vec: &[f64], window: usize, order: usize) -> Vec<f64> {
+    debug_assert!(window % 2 == 1);
+
+    let n = vec.len();
+    if n == 0 {
+        return Vec::new();
+    }
+    let window = window.min(if n % 2 == 0 { n - 1 } else { n });
+    let order = order.min(window - 1);
+
+    let half = window / 2;
+    (0..n)
+        .map(|i| {
+            let start = i.saturating_sub(half).min(n - window);
+
+            let design = DMatrix::from_fn(window, order + 1, |row, col| {
+                ((start + row) as f64 - i as f64).powi(col as i32)
+            });
+            let values = DVector::from_column_slice(&vec[start..(start + window)]);
+
+            let normal = design.transpose() * &design;
+            let coeffs = normal
+                .lu()
+                .solve(&(design.transpose() * values))
+                .expect("Singular Savitzky-Golay system.");
+            coeffs[0]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{argmax, argmin, find_peaks, moving_average, savitzky_golay};
+    use assert_approx_eq::assert_approx_eq;
+    use rand::Rng;
+
+    const DATA: [f64; 11] = [0.0, 1.0, 5.0, 1.0, 0.5, 2.0, 3.0, 2.5, 2.8, 0.0, -1.0];
+
+    #[test]
+    fn test_argmax_argmin() {
+        assert_eq!(argmax(&DATA), Some(2));
+        assert_eq!(argmin(&DATA), Some(10));
+        assert_eq!(argmax(&[]), None);
+        assert_eq!(argmax(&[std::f64::NAN, 1.0]), Some(1));
+    }
+
+    #[test]
+    fn test_find_peaks() {
+        // Local maxima at 2, 6 and 8, the last of which is only marginally prominent.
+        assert_eq!(find_peaks(&DATA, 0.0), vec![2, 6, 8]);
+        assert_eq!(find_peaks(&DATA, 1.0), vec![2, 6]);
+        assert_eq!(find_peaks(&DATA, 4.0), vec![2]);
+    }
+
+    #[test]
+    fn test_find_peaks_plateau() {
+        assert_eq!(find_peaks(&[0.0, 2.0, 2.0, 2.0, 0.0], 1.0), vec![1]);
+        assert!(find_peaks(&[0.0, 1.0, 2.0, 3.0], 0.0).is_empty());
+    }
+
+    /// Mean squared deviation of a list from a value.
+    fn mean_sq_dev(vec: &[f64], val: f64) -> f64 {
+        vec.iter().map(|x| (x - val).powi(2)).sum::<f64>() / vec.len() as f64
+    }
+
+    #[test]
+    fn test_moving_average_noisy_constant() {
+        let mut rng = rand::thread_rng();
+        let noisy: Vec<f64> = (0..10_000)
+            .map(|_| 5.0 + rng.gen_range(-1.0..1.0))
+            .collect();
+
+        assert_eq!(moving_average(&noisy, 1), noisy);
+
+        let narrow = moving_average(&noisy, 5);
+        let wide = moving_average(&noisy, 51);
+        assert_eq!(wide.len(), noisy.len());
+
+        let mean = wide.iter().sum::<f64>() / wide.len() as f64;
+        assert_approx_eq!(mean, 5.0, 0.05);
+
+        // Wider windows give smoother results.
+        assert!(mean_sq_dev(&narrow, 5.0) < mean_sq_dev(&noisy, 5.0));
+        assert!(mean_sq_dev(&wide, 5.0) < mean_sq_dev(&narrow, 5.0));
+
+        // Edge values average over a truncated window.
+        assert_approx_eq!(narrow[0], noisy[..3].iter().sum::<f64>() / 3.0);
+    }
+
+    #[test]
+    fn test_savitzky_golay_preserves_quadratic() {
+        let quadratic: Vec<f64> = (0..20)
+            .map(|i| {
+                let x = i as f64;
+                0.5 * x * x - 3.0 * x + 2.0
+            })
+            .collect();
+
+        for (smooth, exact) in savitzky_golay(&quadratic, 7, 2).iter().zip(&quadratic) {
+            assert_approx_eq!(smooth, exact, 1.0e-6);
+        }
+    }
+
+    #[test]
+    fn test_savitzky_golay_short_list() {
+        let line = [1.0, 2.0, 3.0, 4.0];
+        for (smooth, exact) in savitzky_golay(&line, 7, 2).iter().zip(&line) {
+            assert_approx_eq!(smooth, exact, 1.0e-6);
+        }
+
+        assert_eq!(savitzky_golay(&[2.0], 5, 3), vec![2.0]);
+        assert!(savitzky_golay(&[], 5, 2).is_empty());
+    }
+}
@@ -0,0 +1,86 @@
+//! Curve fitting.
+
+use crate::err::Error;
+
+/// Fit a straight line to the given points using ordinary least-squares.
+/// Returns the slope, the intercept, and the coefficient of determination (r^2).
+/// # Errors
+/// if the inputs differ in length, contain fewer than two points,
+/// or if all `x` values are identical.
+#[inline]
+pub fn linear_fit(x: &[f64], y: &[f64]) -> Result<(f64, f64, f64), Error> {
+    if x.len() != y.len() {
+        return Err(Error::Text(format!(
+            "Fit inputs differ in length: {} and {}",
+            x.len(),
+            y.len()
+        )));
+    }
+    if x.len() < 2 {
+        return Err(Error::Text(
+            "Fitting requires at least two points.".to_owned(),
+        ));
+    }
+
+    let n = x.len() as f64;
+    let x_mean = x.iter().sum::<f64>() / n;
+    let y_mean = y.iter().sum::<f64>() / n;
+
+    let mut sxx = 0.0;
+    let mut sxy = 0.0;
+    let mut syy = 0.0;
+    for (xi, yi) in x.iter().zip(y) {
+        let dx = xi - x_mean;
+        let dy = yi - y_mean;
+        sxx += dx * dx;
+        sxy += dx * dy;
+        syy += dy * dy;
+    }
+
+    if sxx <= 0.0 {
+        return Err(Error::Text(
+            "Fitting requires at least two distinct x values.".to_owned(),
+        ));
+    }
+
+    let slope = sxy / sxx;
+    let intercept = slope.mul_add(-x_mean, y_mean);
+    let r2 = if syy > 0.0 {
+        (sxy * sxy) / (sxx * syy)
+    } else {
+        1.0
+    };
+
+    Ok((slope, intercept, r2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::linear_fit;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn test_noisy_line() {
+        let x: Vec<f64> = (0..100).map(f64::from).collect();
+        let y: Vec<f64> = x
+            .iter()
+            .enumerate()
+            .map(|(i, x)| {
+                let noise = if i % 2 == 0 { 0.05 } else { -0.05 };
+                2.5f64.mul_add(*x, -3.0) + noise
+            })
+            .collect();
+
+        let (slope, intercept, r2) = linear_fit(&x, &y).unwrap();
+        assert_approx_eq!(slope, 2.5, 1.0e-3);
+        assert_approx_eq!(intercept, -3.0, 1.0e-1);
+        assert!(r2 > 0.999 && r2 <= 1.0);
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        assert!(linear_fit(&[0.0, 1.0], &[1.0]).is_err());
+        assert!(linear_fit(&[0.0], &[1.0]).is_err());
+        assert!(linear_fit(&[1.0, 1.0], &[0.0, 2.0]).is_err());
+    }
+}
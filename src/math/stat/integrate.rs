@@ -0,0 +1,84 @@
+//! Numerical integration.
+
+use crate::err::Error;
+
+/// Compute the running integral of `y` over `x` using the trapezoidal rule.
+/// The returned list is the same length as the inputs and begins at zero.
+/// # Errors
+/// if the inputs differ in length, contain fewer than two points,
+/// or if `x` is not strictly ascending.
+#[inline]
+pub fn cumulative_trapezoid(x: &[f64], y: &[f64]) -> Result<Vec<f64>, Error> {
+    if x.len() != y.len() {
+        return Err(Error::Text(format!(
+            "Integration inputs differ in length: {} and {}",
+            x.len(),
+            y.len()
+        )));
+    }
+    if x.len() < 2 {
+        return Err(Error::Text(
+            "Integration requires at least two points.".to_owned(),
+        ));
+    }
+
+    let mut total = 0.0;
+    let mut integral = Vec::with_capacity(x.len());
+    integral.push(total);
+    for ((x_curr, x_next), (y_curr, y_next)) in x
+        .iter()
+        .zip(x.iter().skip(1))
+        .zip(y.iter().zip(y.iter().skip(1)))
+    {
+        let dx = x_next - x_curr;
+        if dx <= 0.0 || dx.is_nan() {
+            return Err(Error::Text(format!(
+                "Integration abscissae are not strictly ascending at {}",
+                x_next
+            )));
+        }
+
+        total += 0.5 * (y_curr + y_next) * dx;
+        integral.push(total);
+    }
+
+    Ok(integral)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::cumulative_trapezoid;
+    use assert_approx_eq::assert_approx_eq;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_constant_gives_ramp() {
+        let x: Vec<f64> = (0..11).map(|i| f64::from(i) * 0.1).collect();
+        let y = vec![2.0; x.len()];
+
+        let integral = cumulative_trapezoid(&x, &y).unwrap();
+        assert_eq!(integral.len(), x.len());
+        for (xi, fi) in x.iter().zip(&integral) {
+            assert_approx_eq!(*fi, 2.0 * xi);
+        }
+    }
+
+    #[test]
+    fn test_sine_gives_one_minus_cosine() {
+        let n = 1000;
+        let x: Vec<f64> = (0..=n).map(|i| PI * f64::from(i) / f64::from(n)).collect();
+        let y: Vec<f64> = x.iter().map(|x| x.sin()).collect();
+
+        let integral = cumulative_trapezoid(&x, &y).unwrap();
+        for (xi, fi) in x.iter().zip(&integral) {
+            assert_approx_eq!(*fi, 1.0 - xi.cos(), 1.0e-5);
+        }
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        assert!(cumulative_trapezoid(&[0.0, 2.0, 1.0], &[1.0, 1.0, 1.0]).is_err());
+        assert!(cumulative_trapezoid(&[0.0, 1.0], &[1.0]).is_err());
+        assert!(cumulative_trapezoid(&[0.0], &[1.0]).is_err());
+    }
+}
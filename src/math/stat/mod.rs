@@ -1,4 +1,6 @@
 pub mod cdf;
+pub mod fit;
+pub mod integrate;
 pub mod spherical_cdf;
 
-pub use self::{cdf::*, spherical_cdf::*};
+pub use self::{cdf::*, fit::*, integrate::*, spherical_cdf::*};
@@ -15,6 +15,9 @@ use std::f64::consts::PI;
 /// to ensure that we can sample the sin(theta) area term well enough.
 const TARGET_NANGLES: usize = 360;
 
+/// The number of equal azimuthal bins used to index the planes of the spherical CDF.
+const LOOKUP_BINS: usize = 720;
+
 #[derive(Debug, Clone)]
 pub struct SphericalCdfPlane {
     /// The central azimurhal angle of the plane.
@@ -65,6 +68,9 @@ impl SphericalCdfPlane {
 pub struct SphericalCdf {
     planes: Vec<SphericalCdfPlane>,
     azimuth_cdf: Probability,
+    /// For each of the equal azimuthal bins, the indices of the planes which may contain an angle within it, in ascending order.
+    /// If empty, planes are found by a linear search.
+    plane_lookup: Vec<Vec<usize>>,
 }
 
 impl SphericalCdf {
@@ -76,7 +82,53 @@ impl SphericalCdf {
         Self {
             planes: vec![],
             azimuth_cdf: Probability::new_point(0.0),
+            plane_lookup: vec![],
+        }
+    }
+
+    /// Build the azimuthal lookup table of the planes, so that the plane containing a sampled azimuthal angle
+    /// can be found in constant time. This must be called again if the planes are modified.
+    pub fn index_planes(&mut self) {
+        let bin_width = 2.0 * PI / LOOKUP_BINS as Real;
+
+        self.plane_lookup = (0..LOOKUP_BINS)
+            .map(|ibin| {
+                let bin_centre = (ibin as Real + 0.5) * bin_width;
+                self.planes
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, pl)| {
+                        let half_width =
+                            pl.delta_aziumuth().lower().max(pl.delta_aziumuth().upper());
+                        let dist = (bin_centre - pl.azimuth_angle()).rem_euclid(2.0 * PI);
+                        // Allow a bin of margin either side, for rounding in the plane test.
+                        dist.min(2.0 * PI - dist) <= half_width + 1.5 * bin_width
+                    })
+                    .map(|(iplane, _)| iplane)
+                    .collect()
+            })
+            .collect();
+    }
+
+    /// Find the index of the first plane containing the given azimuthal angle.
+    fn find_plane(&self, azimuthal_angle: Real) -> Option<usize> {
+        if self.plane_lookup.is_empty() {
+            return self.find_plane_linear(azimuthal_angle);
         }
+
+        let ibin =
+            ((azimuthal_angle.rem_euclid(2.0 * PI) / (2.0 * PI)) * LOOKUP_BINS as Real) as usize;
+        self.plane_lookup[ibin.min(LOOKUP_BINS - 1)]
+            .iter()
+            .copied()
+            .find(|iplane| self.planes[*iplane].azimuthal_angle_in_plane(azimuthal_angle))
+    }
+
+    /// Find the index of the first plane containing the given azimuthal angle, by checking each plane in turn.
+    fn find_plane_linear(&self, azimuthal_angle: Real) -> Option<usize> {
+        self.planes
+            .iter()
+            .position(|pl| pl.azimuthal_angle_in_plane(azimuthal_angle))
     }
 
     /// Returns true if the distribution is spherically symmetric - in this case there will only be one plane.
@@ -95,10 +147,7 @@ impl SphericalCdf {
             azim_draw = self.azimuth_cdf.sample(rng);
 
             // Now find the plane for which this azimuthal angle corresponds, so that we can sampe polar angle.
-            let iplane = self
-                .planes
-                .iter()
-                .position(|pl| pl.azimuthal_angle_in_plane(azim_draw));
+            let iplane = self.find_plane(azim_draw);
             // Some searches come back with a `None`. To avoid this crashing the code, let's instead perform an iteration
             if iplane.is_none() { 
                 println!("Unable to find plane for azimuthal angle: {} rad. Trying again. ", azim_draw);
@@ -218,6 +267,7 @@ impl From<PhotometricWeb> for SphericalCdf {
         *cdf.planes_mut() = cdf_planes;
         *cdf.azimuth_cdf_mut() =
             Probability::new_linear_spline(&Array1::from(azim_angles), &Array1::from(azim_probs));
+        cdf.index_planes();
         cdf
     }
 }
@@ -288,6 +338,34 @@ pub mod tests {
         assert_approx_eq!(pol_ave.ave(), PI / 2.0, 0.1);
     }
 
+    /// Tests that the indexed plane lookup selects the same plane as a linear scan over the planes.
+    #[test]
+    fn spherical_cdf_indexed_lookup_test() {
+        let planes = (0..360)
+            .step_by(10)
+            .map(|ang| {
+                let mut plane = Plane::new();
+                plane.set_angles_degrees(&vec![0.0, 90.0, 180.0]);
+                plane.set_intensities(vec![1.0, 1.0, 1.0]);
+                plane.set_angle_degrees(ang as f64);
+                plane.set_units(lidrs::photweb::IntensityUnits::Candela);
+                plane.set_orientation(lidrs::photweb::PlaneOrientation::Vertical);
+                plane
+            })
+            .collect();
+
+        let mut photweb = PhotometricWeb::new();
+        photweb.set_planes(planes);
+        let cdf: SphericalCdf = photweb.into();
+
+        let azimuths: Vec<f64> = (0..100_000)
+            .map(|i| -PI + (4.0 * PI * i as f64 / 100_000.0))
+            .collect();
+        for azim in &azimuths {
+            assert_eq!(cdf.find_plane(*azim), cdf.find_plane_linear(*azim));
+        }
+    }
+
     /// Tests that when we create a CDF with all probability concentrated in the lower hemisphere
     /// the output distribution of reflective of that. In this case, we want to check that all
     /// photons are emitted from the lower half of the hemisphere (polar angle < PI / 2 radians).
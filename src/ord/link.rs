@@ -37,8 +37,11 @@ impl<'a, T, S: Link<'a, T>> Link<'a, T> for Vec<S> {
     fn link(self, set: &'a Set<T>) -> Result<Self::Inst, Error> {
         let mut list = Vec::with_capacity(self.len());
 
-        for x in self {
-            list.push(x.link(set)?);
+        for (i, x) in self.into_iter().enumerate() {
+            let inst = x
+                .link(set)
+                .map_err(|err| err.with_context(&format!("[{}]", i)))?;
+            list.push(inst);
         }
 
         Ok(list)
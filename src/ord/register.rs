@@ -1,7 +1,7 @@
 //! Register structure.
 
 use crate::{
-    fmt_report,
+    err, fmt_report,
     ord::{Map, Name, Set},
 };
 use std::fmt::{Display, Error, Formatter};
@@ -27,6 +27,25 @@ impl Register {
         Self(Set::new(map))
     }
 
+    /// Construct a new instance, requiring that each name is unique.
+    /// # Errors
+    /// if a name appears more than once.
+    #[inline]
+    pub fn new_unique(names: Vec<Name>) -> Result<Self, err::Error> {
+        let mut sorted = names.clone();
+        sorted.sort();
+        for pair in sorted.windows(2) {
+            if pair[0] == pair[1] {
+                return Err(err::Error::Text(format!(
+                    "Duplicate register entry: {}",
+                    pair[0]
+                )));
+            }
+        }
+
+        Ok(Self::new(names))
+    }
+
     /// Find if the number of entries is zero.
     #[inline]
     #[must_use]
@@ -66,3 +85,17 @@ impl Display for Register {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_unique_duplicate() {
+        let names = vec![Name::new("a"), Name::new("b"), Name::new("a")];
+        assert!(Register::new_unique(names).is_err());
+
+        let reg = Register::new_unique(vec![Name::new("b"), Name::new("a")]).unwrap();
+        assert_eq!(reg.len(), 2);
+    }
+}
@@ -169,7 +169,10 @@ impl<'a, T, S: Link<'a, T>> Link<'a, T> for Set<S> {
     fn link(self, set: &'a Set<T>) -> Result<Self::Inst, Error> {
         let mut list = Vec::with_capacity(self.0.len());
         for (name, val) in self.0 {
-            list.push((name, val.link(set)?));
+            let inst = val
+                .link(set)
+                .map_err(|err| err.with_context(&name.as_string()))?;
+            list.push((name, inst));
         }
         Self::Inst::from_pairs(list)
     }
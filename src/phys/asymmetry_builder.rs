@@ -0,0 +1,93 @@
+//! Asymmetry factor builder.
+
+use crate::{
+    err::Error,
+    fmt_report,
+    math::{Formula, FormulaBuilder},
+    ord::Build,
+    phys::{Spectrum, SpectrumBuilder},
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// Loadable scattering asymmetry factor.
+/// May be given as a single value, which applies at all wavelengths, as a spectrum,
+/// or as any formula of wavelength, as accepted before asymmetry spectra.
+/// Forms shared by spectra and formulae, such as `Constant`, are read as spectra.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum AsymmetryBuilder {
+    /// Wavelength independent value.
+    Scalar(f64),
+    /// Wavelength dependent spectrum.
+    Spectrum(SpectrumBuilder),
+    /// Wavelength dependent formula.
+    Formula(FormulaBuilder),
+}
+
+impl AsymmetryBuilder {
+    /// Build the asymmetry spectrum.
+    /// Formulae give an isotropic spectrum, which is overridden by the formula itself.
+    /// # Errors
+    /// if the spectrum can not be built.
+    #[inline]
+    pub fn build(&self) -> Result<Spectrum, Error> {
+        match *self {
+            Self::Scalar(g) => Ok(Spectrum::new_constant(g)),
+            Self::Spectrum(ref spec) => spec.build(),
+            Self::Formula(..) => Ok(Spectrum::new_constant(0.0)),
+        }
+    }
+
+    /// Build the asymmetry formula, if the factor was given as one.
+    #[inline]
+    #[must_use]
+    pub fn formula(&self) -> Option<Formula> {
+        match *self {
+            Self::Formula(ref formula) => Some(formula.clone().build()),
+            Self::Scalar(..) | Self::Spectrum(..) => None,
+        }
+    }
+}
+
+impl Display for AsymmetryBuilder {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match *self {
+            Self::Scalar(g) => {
+                writeln!(fmt, "Scalar: ")?;
+                fmt_report!(fmt, g, "value");
+                Ok(())
+            }
+            Self::Spectrum(ref spec) => write!(fmt, "{}", spec),
+            Self::Formula(ref formula) => write!(fmt, "{}", formula),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_scalar_and_spectrum() {
+        let scalar: AsymmetryBuilder = json5::from_str("0.8").unwrap();
+        assert_eq!(scalar.build().unwrap(), Spectrum::new_constant(0.8));
+
+        let spec: AsymmetryBuilder =
+            json5::from_str("{ Linear: [400.0, 700.0, 0.9, 0.5] }").unwrap();
+        assert_eq!(
+            spec.build().unwrap(),
+            Spectrum::new_linear(400.0, 700.0, 0.9, 0.5)
+        );
+        assert_eq!(spec.formula(), None);
+
+        let line: AsymmetryBuilder = json5::from_str("{ Line: [0.1, 1.0e6] }").unwrap();
+        assert_eq!(line.formula(), Some(Formula::Line { c: 0.1, m: 1.0e6 }));
+
+        let spline: AsymmetryBuilder =
+            json5::from_str("{ LinearSplineAuto: [[400.0e-9, 800.0e-9], [0.9, 0.5]] }").unwrap();
+        let formula = spline.formula().unwrap();
+        assert!((formula.y(600.0e-9) - 0.7).abs() < 1.0e-9);
+    }
+}
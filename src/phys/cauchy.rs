@@ -0,0 +1,108 @@
+//! Cauchy refractive index dispersion.
+
+use crate::{access, err::Error, fmt_report};
+use std::{
+    fmt::{Display, Formatter},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+/// Cauchy dispersion relation, `n(λ) = A + B/λ² + C/λ⁴`, with wavelengths in metres.
+/// The relation is only valid over a limited range of wavelengths;
+/// wavelengths outside of it are clamped to the nearest limit.
+#[derive(Debug, Clone)]
+pub struct Cauchy {
+    /// Coefficients A, B [m^2] and C [m^4].
+    coeffs: [f64; 3],
+    /// Wavelength range [m] over which the relation is valid.
+    range: [f64; 2],
+    /// Set once a wavelength outside of the valid range has been clamped.
+    clamped: Arc<AtomicBool>,
+}
+
+impl Cauchy {
+    access!(coeffs: [f64; 3]);
+    access!(range: [f64; 2]);
+
+    /// Construct a new instance.
+    /// # Errors
+    /// if the wavelength range is not positive and increasing.
+    #[inline]
+    pub fn new(coeffs: [f64; 3], range: [f64; 2]) -> Result<Self, Error> {
+        if !(range[0] > 0.0 && range[0] < range[1]) {
+            return Err(Error::Text(format!(
+                "Cauchy dispersion range [{}, {}] must be positive and increasing.",
+                range[0], range[1]
+            )));
+        }
+
+        Ok(Self {
+            coeffs,
+            range,
+            clamped: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    /// Evaluate the refractive index at a given wavelength [m].
+    /// A warning is printed the first time a wavelength is clamped to the valid range.
+    #[inline]
+    #[must_use]
+    pub fn ref_index(&self, w: f64) -> f64 {
+        let [min, max] = self.range;
+        if !(min..=max).contains(&w) && !self.clamped.swap(true, Ordering::Relaxed) {
+            println!(
+                "[WARN] Wavelength {} is outside of the Cauchy dispersion range [{}, {}], and has been clamped.",
+                w, min, max
+            );
+        }
+
+        let inv_sq = w.clamp(min, max).powi(-2);
+        let [a, b, c] = self.coeffs;
+        c.mul_add(inv_sq, b).mul_add(inv_sq, a)
+    }
+
+    /// Check if any wavelength has been clamped to the valid range.
+    #[inline]
+    #[must_use]
+    pub fn clamped(&self) -> bool {
+        self.clamped.load(Ordering::Relaxed)
+    }
+}
+
+impl PartialEq for Cauchy {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.coeffs == other.coeffs && self.range == other.range
+    }
+}
+
+impl Display for Cauchy {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        writeln!(fmt, "...")?;
+        fmt_report!(fmt, self.coeffs[0], "A");
+        fmt_report!(fmt, self.coeffs[1], "B (m^2)");
+        fmt_report!(fmt, self.coeffs[2], "C (m^4)");
+        fmt_report!(
+            fmt,
+            format!("[{}, {}]", self.range[0], self.range[1]),
+            "valid range (m)"
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_range_rejected() {
+        assert!(Cauchy::new([1.5, 0.0, 0.0], [350.0e-9, 1000.0e-9]).is_ok());
+        assert!(Cauchy::new([1.5, 0.0, 0.0], [1000.0e-9, 350.0e-9]).is_err());
+        assert!(Cauchy::new([1.5, 0.0, 0.0], [0.0, 1000.0e-9]).is_err());
+        assert!(Cauchy::new([1.5, 0.0, 0.0], [f64::NAN, 1000.0e-9]).is_err());
+    }
+}
@@ -0,0 +1,44 @@
+//! Cauchy dispersion builder.
+
+use crate::{err::Error, fmt_report, phys::Cauchy};
+use arctk_attr::file;
+use std::fmt::{Display, Formatter};
+
+/// Loadable Cauchy dispersion relation.
+#[file]
+pub struct CauchyBuilder {
+    /// Constant coefficient.
+    a: f64,
+    /// Second order coefficient [m^2].
+    b: f64,
+    /// Fourth order coefficient [m^4].
+    c: Option<f64>,
+    /// Wavelength range [m] over which the relation is valid.
+    range: [f64; 2],
+}
+
+impl CauchyBuilder {
+    /// Build the dispersion relation.
+    /// # Errors
+    /// if the wavelength range is not positive and increasing.
+    #[inline]
+    pub fn build(&self) -> Result<Cauchy, Error> {
+        Cauchy::new([self.a, self.b, self.c.unwrap_or(0.0)], self.range)
+    }
+}
+
+impl Display for CauchyBuilder {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        writeln!(fmt, "...")?;
+        fmt_report!(fmt, self.a, "A");
+        fmt_report!(fmt, self.b, "B (m^2)");
+        fmt_report!(fmt, self.c.unwrap_or(0.0), "C (m^4)");
+        fmt_report!(
+            fmt,
+            format!("[{}, {}]", self.range[0], self.range[1]),
+            "valid range (m)"
+        );
+        Ok(())
+    }
+}
@@ -1,12 +1,18 @@
 //! Crossing implementation.
 
-use crate::{access, clone, math::Dir3};
+use crate::{access, clone, fmt_report, math::Dir3};
+use std::{
+    fmt::{Display, Formatter},
+    ops::AddAssign,
+};
 
 /// Optical interface crossing information.
 /// Calculates trajectory information.
 pub struct Crossing {
     /// Probability of reflection.
     ref_prob: f64,
+    /// Probability of transmission.
+    trans_prob: f64,
     /// Reflection direction.
     ref_dir: Dir3,
     /// Transmission (refraction) direction.
@@ -15,6 +21,7 @@ pub struct Crossing {
 
 impl Crossing {
     clone!(ref_prob: f64);
+    clone!(trans_prob: f64);
     access!(ref_dir: Dir3);
     access!(trans_dir: Option<Dir3>);
 
@@ -22,8 +29,6 @@ impl Crossing {
     #[inline]
     #[must_use]
     pub fn new(inc: &Dir3, norm: &Dir3, n_curr: f64, n_next: f64) -> Self {
-        debug_assert!(n_curr >= 1.0);
-        debug_assert!(n_next >= 1.0);
         debug_assert!(inc.dot(norm) < 0.0);
 
         let ci = -inc.dot(norm);
@@ -35,20 +40,23 @@ impl Crossing {
             Some((n_next / n_curr).asin())
         };
 
-        let (ref_prob, trans_dir) = if crit_ang.is_some() && (ci.acos() >= crit_ang.unwrap()) {
-            (1.0, None)
-        } else {
-            let s2t = (n * n) * (1.0 - (ci * ci));
-            let ct = (1.0 - s2t).sqrt();
+        let (ref_prob, trans_prob, trans_dir) =
+            if crit_ang.is_some() && (ci.acos() >= crit_ang.unwrap()) {
+                (1.0, 0.0, None)
+            } else {
+                let s2t = (n * n) * (1.0 - (ci * ci));
+                let ct = (1.0 - s2t).sqrt();
 
-            (
-                Self::init_ref_prob(n_curr, n_next, ci, ct),
-                Some(Self::init_trans_dir(inc, norm, n, ci, ct)),
-            )
-        };
+                (
+                    Self::init_ref_prob(n_curr, n_next, ci, ct),
+                    Self::init_trans_prob(n_curr, n_next, ci, ct),
+                    Some(Self::init_trans_dir(inc, norm, n, ci, ct)),
+                )
+            };
 
         Self {
             ref_prob,
+            trans_prob,
             ref_dir: Self::init_ref_dir(inc, norm, ci),
             trans_dir,
         }
@@ -74,6 +82,24 @@ impl Crossing {
         (r_norm + r_tran) / 2.0
     }
 
+    /// Calculate the transmission probability from the Fresnel transmission coefficients.
+    /// Computed independently of the reflection probability, so that the two can be checked against each other.
+    #[inline]
+    #[must_use]
+    fn init_trans_prob(n1: f64, n2: f64, ci: f64, ct: f64) -> f64 {
+        let n1_c_i = n1 * ci;
+        let n2_c_t = n2 * ct;
+        let t_norm_sqrt = (2.0 * n1_c_i) / (n1_c_i + n2_c_t);
+        let t_norm = t_norm_sqrt * t_norm_sqrt;
+
+        let n2_c_i = n2 * ci;
+        let n1_c_t = n1 * ct;
+        let t_tran_sqrt = (2.0 * n1_c_i) / (n2_c_i + n1_c_t);
+        let t_tran = t_tran_sqrt * t_tran_sqrt;
+
+        (n2_c_t / n1_c_i) * (t_norm + t_tran) / 2.0
+    }
+
     /// Calculate the reflection direction.
     #[inline]
     #[must_use]
@@ -94,11 +120,101 @@ impl Crossing {
     fn init_trans_dir(inc: &Dir3, norm: &Dir3, n: f64, ci: f64, ct: f64) -> Dir3 {
         ((n * inc.into_inner()) + ((n * ci) - ct) * norm.into_inner()).into()
     }
+}
+
+/// Diagnostic tally of the energy balance across optical interfaces.
+/// Reflected, transmitted and absorbed fractions should sum to the incident energy.
+#[derive(Clone, Debug)]
+pub struct EnergyCheck {
+    /// Tolerance on the fractional weight imbalance.
+    tol: f64,
+    /// Number of interactions checked.
+    checked: usize,
+    /// Number of interactions which created or destroyed weight beyond the tolerance.
+    violations: usize,
+    /// Largest fractional imbalance found.
+    max_error: f64,
+}
 
-    /// Get the transmission probability.
+impl EnergyCheck {
+    clone!(tol: f64);
+    clone!(checked: usize);
+    clone!(violations: usize);
+    clone!(max_error: f64);
+
+    /// Construct a new instance.
     #[inline]
     #[must_use]
-    pub fn trans_prob(&self) -> f64 {
-        1.0 - self.ref_prob
+    pub fn new(tol: f64) -> Self {
+        debug_assert!(tol > 0.0);
+
+        Self {
+            tol,
+            checked: 0,
+            violations: 0,
+            max_error: 0.0,
+        }
+    }
+
+    /// Tally the balance of an interaction, returning true if weight is conserved within tolerance.
+    #[inline]
+    pub fn check(
+        &mut self,
+        incident: f64,
+        reflected: f64,
+        transmitted: f64,
+        absorbed: f64,
+    ) -> bool {
+        debug_assert!(incident > 0.0);
+
+        let error = ((reflected + transmitted + absorbed - incident) / incident).abs();
+        self.checked += 1;
+        self.max_error = self.max_error.max(error);
+
+        // NaN imbalances also fail the comparison, and are counted as violations.
+        let conserved = error <= self.tol;
+        if !conserved {
+            self.violations += 1;
+        }
+        conserved
+    }
+}
+
+impl AddAssign<&Self> for EnergyCheck {
+    #[inline]
+    fn add_assign(&mut self, rhs: &Self) {
+        self.checked += rhs.checked;
+        self.violations += rhs.violations;
+        self.max_error = self.max_error.max(rhs.max_error);
+    }
+}
+
+impl Display for EnergyCheck {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        writeln!(fmt, "...")?;
+        fmt_report!(fmt, self.tol, "tolerance");
+        fmt_report!(fmt, self.checked, "interactions checked");
+        fmt_report!(fmt, self.violations, "violations");
+        fmt_report!(fmt, self.max_error, "maximum error");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mis_specified_interface_flagged() {
+        let mut check = EnergyCheck::new(1.0e-6);
+
+        // Reflected and transmitted fractions which together exceed the incident weight.
+        assert!(!check.check(1.0, 0.6, 0.6, 0.0));
+        assert!(!check.check(1.0, 0.2, 0.5, 0.1));
+        assert!(check.check(1.0, 0.2, 0.5, 0.3));
+
+        assert_eq!(check.violations(), 2);
+        assert!((check.max_error() - 0.2).abs() < 1.0e-9);
     }
 }
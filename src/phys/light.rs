@@ -1,8 +1,8 @@
 //! Light surface structure.
 
 use crate::{
-    access, clone, fmt_report,
-    geom::Emitter,
+    access, clone, err, fmt_report,
+    geom::{Emitter, Orient},
     math::Probability,
     phys::{Material, Photon},
 };
@@ -20,10 +20,13 @@ pub struct Light<'a> {
     spec: Probability,
     /// Emitting material.
     mat: &'a Material,
+    /// Optional cumulative probability limits of the wavelength bands photons are emitted from in turn.
+    bands: Option<Vec<[f64; 2]>>,
 }
 
 impl<'a> Light<'a> {
     clone!(power: f64);
+    access!(emitter: Emitter);
     access!(spec: Probability);
     access!(mat: Material);
 
@@ -38,20 +41,127 @@ impl<'a> Light<'a> {
             emitter,
             spec,
             mat,
+            bands: None,
         }
     }
 
+    /// Stratify emission by dividing the spectral range into bands of equal width,
+    /// from which photons are emitted in turn with weights compensating for the probability of each band.
+    /// Bands in which the spectrum has no emission are skipped.
+    /// # Errors
+    /// if the spectrum is unbounded, or is a set of points which are not in ascending order,
+    /// as it can then not be divided into bands of wavelength.
+    #[inline]
+    pub fn with_bands(mut self, num_bands: usize) -> Result<Self, err::Error> {
+        debug_assert!(num_bands > 0);
+
+        match self.spec {
+            Probability::Gaussian { .. } => {
+                return Err(
+                    "Can not stratify the emission of an unbounded gaussian spectrum.".into(),
+                );
+            }
+            Probability::Points { ref cs }
+                if cs.iter().zip(cs.iter().skip(1)).any(|(a, b)| a > b) =>
+            {
+                return Err(
+                    "Can not stratify the emission of a points spectrum which is not in ascending order."
+                        .into(),
+                );
+            }
+            _ => {}
+        }
+
+        // Some distributions exclude a cumulative probability of exactly one, so approach the limit from below.
+        let min = self.spec.sample_at(0.0);
+        let max = self.spec.sample_at(1.0 - f64::EPSILON);
+        let width = (max - min) / num_bands as f64;
+
+        let mut bands = Vec::with_capacity(num_bands);
+        let mut lower = 0.0;
+        for n in 1..=num_bands {
+            let upper = if n == num_bands {
+                1.0
+            } else {
+                self.cdf_at(width.mul_add(n as f64, min))
+            };
+            if upper > lower {
+                bands.push([lower, upper]);
+            }
+            lower = upper;
+        }
+
+        self.bands = Some(bands);
+        Ok(self)
+    }
+
+    /// Create a copy of the light with its emitter positioned and pointed according to an orientation.
+    /// Used to move a source between frames along a `Trajectory`.
+    #[inline]
+    #[must_use]
+    pub fn steered(&self, orient: &Orient) -> Self {
+        Self {
+            emitter: self.emitter.steered(orient),
+            ..self.clone()
+        }
+    }
+
+    /// Determine the cumulative probability of the emission spectrum at a wavelength, by bisection.
+    #[inline]
+    #[must_use]
+    fn cdf_at(&self, wavelength: f64) -> f64 {
+        let (mut lower, mut upper) = (0.0, 1.0);
+        for _ in 0..64 {
+            let mid = 0.5 * (lower + upper);
+            if self.spec.sample_at(mid) < wavelength {
+                lower = mid;
+            } else {
+                upper = mid;
+            }
+        }
+        0.5 * (lower + upper)
+    }
+
+    /// Number of wavelength bands used for stratified emission, if any.
+    #[inline]
+    #[must_use]
+    pub fn num_bands(&self) -> Option<usize> {
+        self.bands.as_ref().map(Vec::len)
+    }
+
     /// Emit a new photon.
     #[inline]
     #[must_use]
     pub fn emit<R: Rng>(&self, mut rng: &mut R, power: f64) -> Photon {
         debug_assert!(power > 0.0);
 
-        let ray = self.emitter.emit(&mut rng);
         let wavelength = self.spec.sample(&mut rng);
+        let ray = self.emitter.emit_spectral(&mut rng, wavelength);
 
         Photon::new(ray, wavelength, power)
     }
+
+    /// Emit the nth photon of a simulation.
+    /// With stratified emission the photon is drawn from band n, cycling through the bands,
+    /// otherwise it is emitted as with `emit`.
+    #[inline]
+    #[must_use]
+    pub fn emit_nth<R: Rng>(&self, mut rng: &mut R, power: f64, n: usize) -> Photon {
+        debug_assert!(power > 0.0);
+
+        let bands = match self.bands {
+            Some(ref bands) if !bands.is_empty() => bands,
+            _ => return self.emit(rng, power),
+        };
+
+        let [lower, upper] = bands[n % bands.len()];
+        let wavelength = self.spec.sample_at(rng.gen_range(lower..upper));
+        let ray = self.emitter.emit_spectral(&mut rng, wavelength);
+
+        let mut phot = Photon::new(ray, wavelength, power);
+        *phot.weight_mut() = (upper - lower) * bands.len() as f64;
+        phot
+    }
 }
 
 impl<'a> Display for Light<'a> {
@@ -62,6 +172,9 @@ impl<'a> Display for Light<'a> {
         fmt_report!(fmt, self.emitter, "emitter");
         fmt_report!(fmt, self.spec, "emission spectrum");
         fmt_report!(fmt, self.mat, "emission material");
+        if let Some(num_bands) = self.num_bands() {
+            fmt_report!(fmt, num_bands, "stratified wavelength bands");
+        }
         Ok(())
     }
 }
@@ -72,7 +185,7 @@ mod tests {
     use rand;
     use crate::{
         math::{Formula, Probability, Point3, Dir3},
-        phys::{Material},
+        phys::{Material, Spectrum},
         geom::{Emitter, Ray, Mesh, SmoothTriangle, Triangle},
     };
     use assert_approx_eq::assert_approx_eq;
@@ -83,7 +196,7 @@ mod tests {
             Formula::Constant { c: 1.0e-6 }, 
             None, 
             None, 
-            Formula::Constant { c: 0.1 }
+            Spectrum::new_constant(0.1)
         )
     }
 
@@ -171,4 +284,71 @@ mod tests {
         assert_eq!(photon.ray().dir(), &norm);
         assert_approx_eq!(photon.ray().pos().z(), 0.0, 1.0e-6);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_stratified_bands() {
+        let mut rng = rand::thread_rng();
+        let ray = Ray::new(Point3::new(0.0, 0.0, 0.0), Dir3::new(1.0, 0.0, 0.0));
+        let mat = get_air_material();
+        let light = Light::new(
+            1.0,
+            Emitter::new_beam(ray),
+            Probability::new_linear([400.0e-9, 800.0e-9], [1.0, 3.0]),
+            &mat,
+        )
+        .with_bands(4)
+        .unwrap();
+        assert_eq!(light.num_bands(), Some(4));
+
+        let n_samples = 40_002;
+        let mut counts = [0; 4];
+        let mut weights = [0.0; 4];
+        let mut weighted_mean = 0.0;
+        for n in 0..n_samples {
+            let photon = light.emit_nth(&mut rng, 1.0, n);
+            let band = (((photon.wavelength() - 400.0e-9) / 100.0e-9) as usize).min(3);
+            counts[band] += 1;
+            weights[band] += photon.weight();
+            weighted_mean += photon.weight() * photon.wavelength();
+        }
+
+        // Each band receives the same number of photons, up to rounding.
+        assert!(counts.iter().all(|c| *c == 10_000 || *c == 10_001));
+
+        // The weighted photons reproduce the probability of each band, and the mean of the spectrum.
+        let masses = [0.15625, 0.21875, 0.28125, 0.34375];
+        for (weight, mass) in weights.iter().zip(masses.iter()) {
+            assert_approx_eq!(weight / n_samples as f64, mass, 1.0e-3);
+        }
+        assert_approx_eq!(
+            weighted_mean / n_samples as f64,
+            (7.0 / 12.0_f64).mul_add(400.0e-9, 400.0e-9),
+            2.0e-9
+        );
+    }
+
+    #[test]
+    fn test_stratified_bands_rejects_uninvertible_spectra() {
+        use ndarray::Array1;
+
+        let ray = Ray::new(Point3::new(0.0, 0.0, 0.0), Dir3::new(1.0, 0.0, 0.0));
+        let mat = get_air_material();
+        let light = |spec| Light::new(1.0, Emitter::new_beam(ray.clone()), spec, &mat);
+
+        assert!(light(Probability::new_gaussian(600.0e-9, 50.0e-9))
+            .with_bands(4)
+            .is_err());
+        assert!(light(Probability::new_points(Array1::from(vec![
+            700.0e-9, 500.0e-9
+        ])))
+        .with_bands(2)
+        .is_err());
+
+        let light = light(Probability::new_points(Array1::from(vec![
+            500.0e-9, 600.0e-9, 700.0e-9,
+        ])))
+        .with_bands(3)
+        .unwrap();
+        assert_eq!(light.num_bands(), Some(3));
+    }
+}
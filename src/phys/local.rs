@@ -3,6 +3,7 @@
 use crate::clone;
 
 /// Localised optical environment properties.
+#[derive(Clone)]
 pub struct Local {
     /// Refractive index.
     ref_index: f64,
@@ -33,7 +34,6 @@ impl Local {
         shift_coeff: f64,
         asym: f64,
     ) -> Self {
-        debug_assert!(ref_index >= 1.0);
         debug_assert!(scat_coeff > 0.0);
         debug_assert!(abs_coeff >= 0.0);
         debug_assert!(shift_coeff >= 0.0);
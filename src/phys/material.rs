@@ -1,6 +1,10 @@
 //! Optical material.
 
-use crate::{access, fmt_report, math::Formula, phys::Local};
+use crate::{
+    access, fmt_report,
+    math::Formula,
+    phys::{Cauchy, Local, Spectrum},
+};
 use std::fmt::{Display, Error, Formatter};
 
 /// Optical properties.
@@ -8,22 +12,28 @@ use std::fmt::{Display, Error, Formatter};
 pub struct Material {
     /// Refractive index.
     ref_index: Formula,
+    /// Optional Cauchy dispersion relation, overriding the refractive index formula.
+    cauchy: Option<Cauchy>,
     /// Scattering coefficient [1/m].
     scat_coeff: Formula,
     /// Absorption coefficient [1/m].
     abs_coeff: Option<Formula>,
     /// Shifting coefficient [1/m].
     shift_coeff: Option<Formula>,
-    /// Asymmetry factor.
-    asym_fact: Formula,
+    /// Asymmetry factor, as a function of wavelength.
+    asym_fact: Spectrum,
+    /// Optional asymmetry factor formula, overriding the asymmetry spectrum.
+    asym_formula: Option<Formula>,
 }
 
 impl Material {
     access!(ref_index: Formula);
+    access!(cauchy: Option<Cauchy>);
     access!(scat_coeff: Formula);
     access!(abs_coeff: Option<Formula>);
     access!(shift_coeff: Option<Formula>);
-    access!(asym_fact: Formula);
+    access!(asym_fact: Spectrum);
+    access!(asym_formula: Option<Formula>);
 
     /// Construct a new instance.
     #[inline]
@@ -33,22 +43,49 @@ impl Material {
         scat_coeff: Formula,
         abs_coeff: Option<Formula>,
         shift_coeff: Option<Formula>,
-        asym_fact: Formula,
+        asym_fact: Spectrum,
     ) -> Self {
         Self {
             ref_index,
+            cauchy: None,
             scat_coeff,
             abs_coeff,
             shift_coeff,
             asym_fact,
+            asym_formula: None,
         }
     }
 
+    /// Use a Cauchy dispersion relation for the refractive index, in place of the formula.
+    #[inline]
+    #[must_use]
+    pub fn with_cauchy(mut self, cauchy: Cauchy) -> Self {
+        self.cauchy = Some(cauchy);
+        self
+    }
+
+    /// Use a formula for the asymmetry factor, in place of the spectrum.
+    #[inline]
+    #[must_use]
+    pub fn with_asym_formula(mut self, formula: Formula) -> Self {
+        self.asym_formula = Some(formula);
+        self
+    }
+
+    /// Refractive index at a given wavelength.
+    #[inline]
+    #[must_use]
+    pub fn refractive_index_at(&self, w: f64) -> f64 {
+        self.cauchy
+            .as_ref()
+            .map_or_else(|| self.ref_index.y(w), |cauchy| cauchy.ref_index(w))
+    }
+
     /// Generate an optical environment for a given wavelength.
     #[inline]
     #[must_use]
     pub fn sample_environment(&self, w: f64) -> Local {
-        let ref_index = self.ref_index.y(w);
+        let ref_index = self.refractive_index_at(w);
 
         let scat = self.scat_coeff.y(w);
 
@@ -61,10 +98,38 @@ impl Material {
             .shift_coeff
             .as_ref()
             .map_or(0.0, |shift_coeff_formula| shift_coeff_formula.y(w));
-        let g = self.asym_fact.y(w);
+        let g = self.g_at(w);
 
         Local::new(ref_index, scat, abs, shift, g)
     }
+
+    /// Asymmetry factor at a given wavelength.
+    /// Wavelengths outside of the range of the asymmetry spectrum scatter isotropically.
+    #[inline]
+    #[must_use]
+    pub fn g_at(&self, w: f64) -> f64 {
+        if let Some(ref formula) = self.asym_formula {
+            return formula.y(w);
+        }
+
+        self.asym_fact.value_at(w).unwrap_or(0.0)
+    }
+
+    /// Calculate the total extinction coefficient [1/m] at a given wavelength.
+    #[inline]
+    #[must_use]
+    pub fn extinction(&self, w: f64) -> f64 {
+        self.sample_environment(w).inter_coeff()
+    }
+
+    /// Calculate the Beer-Lambert transmittance over a path of the given length at a given wavelength.
+    #[inline]
+    #[must_use]
+    pub fn transmittance_over(&self, path_length: f64, w: f64) -> f64 {
+        debug_assert!(path_length >= 0.0);
+
+        (-self.extinction(w) * path_length).exp()
+    }
 }
 
 impl Display for Material {
@@ -72,6 +137,9 @@ impl Display for Material {
     fn fmt(&self, fmt: &mut Formatter) -> Result<(), Error> {
         writeln!(fmt, "...")?;
         fmt_report!(fmt, self.ref_index, "refractive index");
+        if let Some(ref cauchy) = self.cauchy {
+            fmt_report!(fmt, cauchy, "cauchy dispersion");
+        }
         fmt_report!(fmt, self.scat_coeff, "scattering coefficient (m^-1)");
 
         let abs_coeff = if let Some(ref abs_coeff) = self.abs_coeff {
@@ -88,7 +156,103 @@ impl Display for Material {
         };
         fmt_report!(fmt, shift_coeff, "shift coefficient (m^-1)");
 
-        fmt_report!(fmt, self.asym_fact, "asymmetry factor");
+        if let Some(ref asym_formula) = self.asym_formula {
+            fmt_report!(fmt, asym_formula, "asymmetry factor");
+        } else {
+            fmt_report!(fmt, self.asym_fact, "asymmetry factor");
+        }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        geom::Ray,
+        math::{Dir3, Point3},
+        phys::Photon,
+        sim::scatter,
+    };
+
+    #[test]
+    fn test_transmittance_homogeneous() {
+        let mat = Material::new(
+            Formula::Constant { c: 1.0 },
+            Formula::Constant { c: 2.0 },
+            Some(Formula::Constant { c: 0.5 }),
+            None,
+            Spectrum::new_constant(0.0),
+        );
+
+        assert!((mat.extinction(500.0e-9) - 2.5).abs() < 1.0e-12);
+        for length in [0.0, 0.1, 1.0, 3.0].iter() {
+            let analytic = (-2.5 * length).exp();
+            assert!((mat.transmittance_over(*length, 500.0e-9) - analytic).abs() < 1.0e-12);
+        }
+    }
+
+    #[test]
+    fn test_wavelength_dependent_asymmetry() {
+        let mat = Material::new(
+            Formula::Constant { c: 1.0 },
+            Formula::Constant { c: 1.0 },
+            None,
+            None,
+            Spectrum::new_linear(400.0e-9, 800.0e-9, 0.9, 0.1),
+        );
+        assert!((mat.g_at(400.0e-9) - 0.9).abs() < 1.0e-9);
+        assert!((mat.g_at(800.0e-9) - 0.1).abs() < 1.0e-9);
+
+        // The mean scattering cosine of Henyey-Greenstein scattering is the asymmetry factor.
+        let mut rng = rand::thread_rng();
+        let num_samples = 100_000;
+        let mean_cos = |w: f64, rng: &mut rand::rngs::ThreadRng| {
+            let env = mat.sample_environment(w);
+            (0..num_samples)
+                .map(|_| {
+                    let ray = Ray::new(Point3::new(0.0, 0.0, 0.0), Dir3::new(0.0, 0.0, 1.0));
+                    let mut phot = Photon::new(ray, w, 1.0);
+                    scatter(rng, &mut phot, &env);
+                    phot.ray().dir().z()
+                })
+                .sum::<f64>()
+                / f64::from(num_samples)
+        };
+
+        let forward = mean_cos(400.0e-9, &mut rng);
+        let broad = mean_cos(800.0e-9, &mut rng);
+        assert!((forward - 0.9).abs() < 0.01);
+        assert!((broad - 0.1).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_cauchy_glass() {
+        // BK7 glass, with A = 1.5046 and B = 0.00420 um^2.
+        let mat = Material::new(
+            Formula::Constant { c: 1.0 },
+            Formula::Constant { c: 0.0 },
+            None,
+            None,
+            Spectrum::new_constant(0.0),
+        )
+        .with_cauchy(Cauchy::new(
+            [1.5046, 0.00420e-12, 0.0],
+            [350.0e-9, 1000.0e-9],
+        )
+        .unwrap());
+
+        // Hydrogen F and C lines.
+        assert!((mat.refractive_index_at(486.1e-9) - 1.5224).abs() < 1.0e-4);
+        assert!((mat.refractive_index_at(656.3e-9) - 1.5144).abs() < 1.0e-4);
+        assert!((mat.sample_environment(486.1e-9).ref_index() - 1.5224).abs() < 1.0e-4);
+        assert!(!mat.cauchy().as_ref().unwrap().clamped());
+
+        // Wavelengths beyond the valid range are clamped to its limits.
+        assert!(
+            (mat.refractive_index_at(2000.0e-9) - mat.refractive_index_at(1000.0e-9)).abs()
+                < 1.0e-12
+        );
+        assert!(mat.cauchy().as_ref().unwrap().clamped());
+    }
+}
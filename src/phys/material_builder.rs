@@ -1,36 +1,123 @@
 //! Material builder.
 
-use crate::{fmt_report, math::FormulaBuilder, ord::Build, phys::Material};
+use crate::{
+    data::Table,
+    err, fmt_report,
+    fs::File,
+    math::FormulaBuilder,
+    ord::Build,
+    phys::{AsymmetryBuilder, CauchyBuilder, Material, SpectrumBuilder},
+};
 use arctk_attr::file;
-use std::fmt::{Display, Error, Formatter};
+use std::{
+    fmt::{Display, Error, Formatter},
+    path::Path,
+};
 
 /// Loadable material.
 #[file]
 pub struct MaterialBuilder {
     /// Refractive index.
     ref_index: FormulaBuilder,
+    /// Optional Cauchy dispersion relation, overriding the refractive index.
+    cauchy: Option<CauchyBuilder>,
     /// Scattering coefficient [1/m].
     scat_coeff: FormulaBuilder,
     /// Absorption coefficient [1/m].
     abs_coeff: Option<FormulaBuilder>,
     /// Shifting coefficient [1/m].
     shift_coeff: Option<FormulaBuilder>,
-    /// Asymmetry factor.
-    asym_fact: FormulaBuilder,
+    /// Asymmetry factor, either a single value or a spectrum.
+    asym_fact: AsymmetryBuilder,
+}
+
+impl MaterialBuilder {
+    /// Construct a spectral material from a table of measured optical properties.
+    /// The CSV file must contain a `wavelength` column, and may contain `mu_a`, `mu_s`, `g` and `n` columns.
+    /// Properties are linearly interpolated between the tabulated wavelengths.
+    /// Missing columns default to no absorption, no scattering, isotropic scattering, and a refractive index of unity.
+    /// # Errors
+    /// if the file can not be read, lacks a wavelength column, or has fewer than two rows.
+    #[inline]
+    pub fn from_csv(path: &Path) -> Result<Self, err::Error> {
+        let table = Table::<f64>::load(path)?;
+        let column = |name: &str| {
+            table
+                .headings()
+                .iter()
+                .position(|heading| heading.trim().eq_ignore_ascii_case(name))
+        };
+
+        let wavelength = column("wavelength").ok_or_else(|| {
+            err::Error::Text(format!(
+                "Material table '{}' is missing a wavelength column.",
+                path.display()
+            ))
+        })?;
+        if table.rows().len() < 2 {
+            return Err(err::Error::Text(format!(
+                "Material table '{}' must contain at least two wavelengths.",
+                path.display()
+            )));
+        }
+
+        let xs: Vec<_> = table.rows().iter().map(|row| row[wavelength]).collect();
+        let spline = |name: &str| {
+            column(name).map(|index| {
+                let ys = table.rows().iter().map(|row| row[index]).collect();
+                FormulaBuilder::LinearSplineAuto(xs.clone(), ys)
+            })
+        };
+
+        Ok(Self {
+            ref_index: spline("n").unwrap_or(FormulaBuilder::Constant(1.0)),
+            cauchy: None,
+            scat_coeff: spline("mu_s").unwrap_or(FormulaBuilder::Constant(0.0)),
+            abs_coeff: spline("mu_a"),
+            shift_coeff: None,
+            asym_fact: column("g").map_or(AsymmetryBuilder::Scalar(0.0), |index| {
+                let gs = table.rows().iter().map(|row| row[index]).collect();
+                AsymmetryBuilder::Spectrum(SpectrumBuilder::Data(xs.clone(), gs))
+            }),
+        })
+    }
+
+    /// Check that the material can be built.
+    /// # Errors
+    /// if the Cauchy dispersion relation is invalid.
+    #[inline]
+    pub fn validate(&self) -> Result<(), err::Error> {
+        if let Some(ref cauchy) = self.cauchy {
+            cauchy.build()?;
+        }
+
+        Ok(())
+    }
 }
 
 impl Build for MaterialBuilder {
     type Inst = Material;
 
+    #[allow(clippy::expect_used)]
     #[inline]
     fn build(self) -> Self::Inst {
         let ref_index = self.ref_index.build();
         let scat_coeff = self.scat_coeff.build();
         let abs_coeff = self.abs_coeff.map(Build::build);
         let shift_coeff = self.shift_coeff.map(Build::build);
-        let asym_fact = self.asym_fact.build();
+        let asym_fact = self
+            .asym_fact
+            .build()
+            .expect("Failed to build asymmetry spectrum.");
 
-        Self::Inst::new(ref_index, scat_coeff, abs_coeff, shift_coeff, asym_fact)
+        let mut mat = Self::Inst::new(ref_index, scat_coeff, abs_coeff, shift_coeff, asym_fact);
+        if let Some(formula) = self.asym_fact.formula() {
+            mat = mat.with_asym_formula(formula);
+        }
+        match self.cauchy {
+            Some(cauchy) => mat.with_cauchy(cauchy.build().expect("Invalid Cauchy dispersion.")),
+            None => mat,
+        }
     }
 }
 
@@ -39,6 +126,9 @@ impl Display for MaterialBuilder {
     fn fmt(&self, fmt: &mut Formatter) -> Result<(), Error> {
         writeln!(fmt, "...")?;
         fmt_report!(fmt, self.ref_index, "refractive index");
+        if let Some(ref cauchy) = self.cauchy {
+            fmt_report!(fmt, cauchy, "cauchy dispersion");
+        }
         fmt_report!(fmt, self.scat_coeff, "scattering coefficient (m^-1)");
 
         let abs_coeff = if let Some(ref abs_coeff) = self.shift_coeff {
@@ -59,3 +149,48 @@ impl Display for MaterialBuilder {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_from_csv() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "wavelength, mu_a, mu_s, n").unwrap();
+        writeln!(file, "400e-9, 10.0, 100.0, 1.40").unwrap();
+        writeln!(file, "500e-9, 5.0, 80.0, 1.38").unwrap();
+        writeln!(file, "600e-9, 2.0, 60.0, 1.36").unwrap();
+        file.flush().unwrap();
+
+        let mat = MaterialBuilder::from_csv(file.path()).unwrap().build();
+
+        let env = mat.sample_environment(500.0e-9);
+        assert_approx_eq!(env.abs_coeff(), 5.0);
+        assert_approx_eq!(env.scat_coeff(), 80.0);
+        assert_approx_eq!(env.ref_index(), 1.38);
+
+        // The asymmetry column is absent, so scattering is isotropic.
+        assert_approx_eq!(env.asym(), 0.0);
+
+        let env = mat.sample_environment(600.0e-9);
+        assert_approx_eq!(env.abs_coeff(), 2.0);
+        assert_approx_eq!(env.scat_coeff(), 60.0);
+
+        // Properties are interpolated between the tabulated wavelengths.
+        assert_approx_eq!(mat.sample_environment(450.0e-9).abs_coeff(), 7.5);
+    }
+
+    #[test]
+    fn test_reversed_cauchy_range_rejected() {
+        let mat: MaterialBuilder = crate::fs::from_json_str(
+            "{ ref_index: { Constant: 1.0 }, scat_coeff: { Constant: 0.0 }, asym_fact: 0.0, \
+             cauchy: { a: 1.5, b: 0.0, range: [1000.0e-9, 350.0e-9] } }",
+        )
+        .unwrap();
+        assert!(mat.validate().is_err());
+    }
+}
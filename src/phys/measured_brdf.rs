@@ -0,0 +1,419 @@
+//! Measured bidirectional reflectance distribution function.
+
+use crate::{
+    access,
+    data::Table,
+    err::Error,
+    fmt_report,
+    geom::Ray,
+    math::{bilinear, Dir3, Vec3},
+};
+use ndarray::{Array2, Array3, Axis};
+use rand::Rng;
+use std::{
+    f64::consts::{FRAC_PI_2, PI},
+    fmt::Display,
+};
+
+/// Tolerance used when checking the angular coverage of a tabulated BRDF.
+const COVERAGE_TOL: f64 = 1.0e-6;
+
+/// Tolerance used when checking that a tabulated BRDF does not reflect more energy than it receives.
+const ALBEDO_TOL: f64 = 1.0e-6;
+
+/// # Measured BRDF
+///
+/// A tabulated, isotropic, bidirectional reflectance distribution function.
+/// Values are stored on a uniform grid of incident polar angle (0 to π/2),
+/// outgoing polar angle (0 to π/2) and relative azimuth (0 to π).
+/// The relative azimuth is measured from the specular plane of incidence,
+/// such that an azimuth of zero lies in the direction of mirror reflection.
+/// The function is assumed to be symmetric about the plane of incidence.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MeasuredBrdf {
+    /// BRDF values [sr^-1], one outgoing polar by relative azimuth grid per incident node.
+    slices: Vec<Array2<f64>>,
+    /// Cumulative outgoing cell weights for each incident node.
+    cdfs: Vec<Vec<f64>>,
+    /// Directional-hemispherical reflectance for each incident node.
+    albedos: Vec<f64>,
+}
+
+impl MeasuredBrdf {
+    access!(albedos: Vec<f64>);
+
+    /// Construct a new instance from a uniform grid of BRDF values.
+    /// The grid is indexed by incident polar, outgoing polar and relative azimuth node.
+    /// # Errors
+    /// if there are fewer than two nodes along an axis, a value is negative or not finite,
+    /// or the albedo at any incident angle exceeds unity.
+    #[inline]
+    pub fn new(values: &Array3<f64>) -> Result<Self, Error> {
+        let shape = values.shape();
+        if shape.iter().any(|n| *n < 2) {
+            return Err(Error::Text(format!(
+                "Measured BRDF requires at least two nodes along each axis, found {:?}.",
+                shape
+            )));
+        }
+        if values.iter().any(|v| !v.is_finite() || *v < 0.0) {
+            return Err("Measured BRDF values must be finite and non-negative.".into());
+        }
+
+        let slices: Vec<_> = values.axis_iter(Axis(0)).map(|s| s.to_owned()).collect();
+
+        let mut cdfs = Vec::with_capacity(slices.len());
+        let mut albedos = Vec::with_capacity(slices.len());
+        for (i, slice) in slices.iter().enumerate() {
+            let cdf = Self::cell_cdf(slice);
+            let albedo = *cdf.last().unwrap();
+            if albedo > 1.0 + ALBEDO_TOL {
+                return Err(Error::Text(format!(
+                    "Measured BRDF albedo exceeds one at incident polar node {}: {}",
+                    i, albedo
+                )));
+            }
+            albedos.push(albedo);
+            cdfs.push(cdf);
+        }
+
+        Ok(Self {
+            slices,
+            cdfs,
+            albedos,
+        })
+    }
+
+    /// Construct a new instance from a table of `theta_in`, `theta_out`, `phi`, `value` rows.
+    /// Angles are given in degrees, and must form a complete uniform grid spanning
+    /// the hemisphere of incident and outgoing directions.
+    /// # Errors
+    /// if the table does not have four columns, the angles do not form a complete uniform grid,
+    /// an entry is duplicated, or the values can not form a valid BRDF.
+    #[inline]
+    pub fn from_table(table: &Table<f64>) -> Result<Self, Error> {
+        let rows = table.rows();
+        if rows.iter().any(|row| row.len() != 4) {
+            return Err(
+                "Measured BRDF table must have exactly four columns: theta_in, theta_out, phi, value."
+                    .into(),
+            );
+        }
+
+        let axes = [
+            Self::axis_nodes(rows, 0, 90.0, "theta_in")?,
+            Self::axis_nodes(rows, 1, 90.0, "theta_out")?,
+            Self::axis_nodes(rows, 2, 180.0, "phi")?,
+        ];
+        let shape = [axes[0], axes[1], axes[2]];
+        if rows.len() != shape.iter().product::<usize>() {
+            return Err(Error::Text(format!(
+                "Measured BRDF table has {} rows but a {:?} grid requires {}.",
+                rows.len(),
+                shape,
+                shape.iter().product::<usize>()
+            )));
+        }
+
+        let spans = [90.0, 90.0, 180.0];
+        let mut values = Array3::from_elem(shape, f64::NAN);
+        for row in rows {
+            let mut index = [0; 3];
+            for (axis, ((res, span), i)) in shape.iter().zip(&spans).zip(&mut index).enumerate() {
+                let step = span / (res - 1) as f64;
+                *i = (row[axis] / step).round() as usize;
+            }
+            if !values[index].is_nan() {
+                return Err(Error::Text(format!(
+                    "Measured BRDF table contains a duplicate entry at ({}, {}, {}).",
+                    row[0], row[1], row[2]
+                )));
+            }
+            values[index] = row[3];
+        }
+
+        Self::new(&values)
+    }
+
+    /// Determine the number of uniformly spaced nodes in a column of the table,
+    /// checking that they span zero to the given maximum angle in degrees.
+    #[inline]
+    fn axis_nodes(rows: &[Vec<f64>], col: usize, max: f64, name: &str) -> Result<usize, Error> {
+        let mut nodes: Vec<f64> = rows.iter().map(|row| row[col]).collect();
+        nodes.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        nodes.dedup_by(|a, b| (*a - *b).abs() < COVERAGE_TOL);
+
+        if nodes.len() < 2
+            || nodes[0].abs() > COVERAGE_TOL
+            || (nodes[nodes.len() - 1] - max).abs() > COVERAGE_TOL
+        {
+            return Err(Error::Text(format!(
+                "Measured BRDF column '{}' must span 0 to {} degrees.",
+                name, max
+            )));
+        }
+
+        let step = max / (nodes.len() - 1) as f64;
+        for (i, node) in nodes.iter().enumerate() {
+            if (node - (i as f64 * step)).abs() > COVERAGE_TOL {
+                return Err(Error::Text(format!(
+                    "Measured BRDF column '{}' must be uniformly spaced.",
+                    name
+                )));
+            }
+        }
+
+        Ok(nodes.len())
+    }
+
+    /// Calculate the cumulative projected-solid-angle weights of the outgoing cells of a slice.
+    /// Cells are ordered by outgoing polar index, then by relative azimuth index.
+    #[inline]
+    fn cell_cdf(slice: &Array2<f64>) -> Vec<f64> {
+        let (num_theta, num_phi) = slice.dim();
+        let d_theta = FRAC_PI_2 / (num_theta - 1) as f64;
+        let d_phi = PI / (num_phi - 1) as f64;
+
+        let mut total = 0.0;
+        let mut cdf = Vec::with_capacity((num_theta - 1) * (num_phi - 1));
+        for i in 0..(num_theta - 1) {
+            let low = (i as f64 * d_theta).sin().powi(2);
+            let high = ((i + 1) as f64 * d_theta).sin().powi(2);
+            // Integral of cos(theta) sin(theta) over the cell, for both sides of the plane of incidence.
+            let projected = (high - low) * d_phi;
+
+            for j in 0..(num_phi - 1) {
+                let u = (i as f64 + 0.5) / (num_theta - 1) as f64;
+                let v = (j as f64 + 0.5) / (num_phi - 1) as f64;
+                total += bilinear(slice, u, v) * projected;
+                cdf.push(total);
+            }
+        }
+
+        cdf
+    }
+
+    /// Determine the incident node closest to a given incident polar angle.
+    #[inline]
+    #[must_use]
+    fn incident_node(&self, theta_in: f64) -> usize {
+        let res = self.slices.len() - 1;
+        ((theta_in / FRAC_PI_2).clamp(0.0, 1.0) * res as f64).round() as usize
+    }
+
+    /// Evaluate the BRDF [sr^-1] for a set of incident polar, outgoing polar and relative azimuth angles.
+    #[inline]
+    #[must_use]
+    pub fn eval(&self, theta_in: f64, theta_out: f64, phi: f64) -> f64 {
+        let res = (self.slices.len() - 1) as f64;
+        let x = (theta_in / FRAC_PI_2).clamp(0.0, 1.0) * res;
+        let i = (x.floor() as usize).min(self.slices.len() - 2);
+        let frac = x - i as f64;
+
+        let u = theta_out / FRAC_PI_2;
+        let v = phi.abs() / PI;
+        let low = bilinear(&self.slices[i], u, v);
+        let high = bilinear(&self.slices[i + 1], u, v);
+
+        (high - low).mul_add(frac, low)
+    }
+
+    /// Directional-hemispherical reflectance for a given incident polar angle.
+    #[inline]
+    #[must_use]
+    pub fn albedo(&self, theta_in: f64) -> f64 {
+        self.albedos[self.incident_node(theta_in)]
+    }
+
+    /// Sample a reflected ray for a ray incident on a surface with the given normal.
+    /// The probability of reflection is given by the albedo of the table,
+    /// and `None` is returned if the photon is absorbed.
+    #[inline]
+    pub fn sample<R: Rng>(&self, rng: &mut R, incident: &Ray, norm: &Dir3) -> Option<Ray> {
+        let theta_in = (-incident.dir().dot(norm)).clamp(-1.0, 1.0).acos();
+        if rng.gen_range(0.0..1.0) >= self.albedo(theta_in).min(1.0) {
+            return None;
+        }
+
+        Some(self.sample_dir(rng, incident, norm))
+    }
+
+    /// Sample a reflected ray for a ray incident on a surface with the given normal,
+    /// irrespective of the albedo of the table.
+    #[inline]
+    pub fn sample_dir<R: Rng>(&self, rng: &mut R, incident: &Ray, norm: &Dir3) -> Ray {
+        let dir = *incident.dir();
+        let cos_in = -dir.dot(norm);
+        let theta_in = cos_in.clamp(-1.0, 1.0).acos();
+        let node = self.incident_node(theta_in);
+
+        // Pick an outgoing cell in proportion to its weight.
+        let cdf = &self.cdfs[node];
+        let total = *cdf.last().unwrap();
+        let target = rng.gen_range(0.0..1.0) * total;
+        let cell = cdf.partition_point(|c| *c <= target).min(cdf.len() - 1);
+
+        let (num_theta, num_phi) = self.slices[node].dim();
+        let (i, j) = (cell / (num_phi - 1), cell % (num_phi - 1));
+        let d_theta = FRAC_PI_2 / (num_theta - 1) as f64;
+        let d_phi = PI / (num_phi - 1) as f64;
+
+        // Sample within the cell according to the projected solid angle.
+        let low = (i as f64 * d_theta).sin().powi(2);
+        let high = ((i + 1) as f64 * d_theta).sin().powi(2);
+        let theta_out = rng.gen_range(low..=high).sqrt().asin();
+        let mut phi = (j as f64 + rng.gen_range(0.0..1.0)) * d_phi;
+        if rng.gen_bool(0.5) {
+            phi = -phi;
+        }
+
+        // Construct the tangent frame aligned with the specular plane of incidence.
+        let mirror = dir + (*norm * (2.0 * cos_in));
+        let in_plane = mirror - (*norm * mirror.dot_dir3(norm));
+        let tangent = if in_plane.mag() > 1.0e-9 {
+            Dir3::from(in_plane)
+        } else if norm.z().abs() <= 0.9 {
+            Dir3::from(norm.cross(&Vec3::z_axis()))
+        } else {
+            Dir3::from(norm.cross(&Vec3::x_axis()))
+        };
+        let bitangent = Dir3::from(norm.cross(&tangent));
+
+        let out = (*norm * theta_out.cos())
+            + (tangent * (theta_out.sin() * phi.cos()))
+            + (bitangent * (theta_out.sin() * phi.sin()));
+
+        Ray::new(*incident.pos(), Dir3::from(out))
+    }
+}
+
+impl Display for MeasuredBrdf {
+    #[inline]
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (num_theta, num_phi) = self.slices[0].dim();
+        writeln!(fmt, "...")?;
+        fmt_report!(fmt, self.slices.len(), "incident polar nodes");
+        fmt_report!(fmt, num_theta, "outgoing polar nodes");
+        fmt_report!(fmt, num_phi, "relative azimuth nodes");
+        fmt_report!(fmt, self.albedos[0], "normal albedo");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Point3;
+
+    /// A table representing a uniform Lambertian surface of unit albedo.
+    fn lambertian_table() -> Table<f64> {
+        let mut rows = Vec::new();
+        for ti in 0..=9 {
+            for to in 0..=9 {
+                for p in 0..=18 {
+                    rows.push(vec![
+                        ti as f64 * 10.0,
+                        to as f64 * 10.0,
+                        p as f64 * 10.0,
+                        1.0 / PI,
+                    ]);
+                }
+            }
+        }
+        Table::new(
+            vec![
+                "theta_in".to_string(),
+                "theta_out".to_string(),
+                "phi".to_string(),
+                "value".to_string(),
+            ],
+            rows,
+        )
+    }
+
+    #[test]
+    fn test_from_table_lambertian_albedo() {
+        let brdf = MeasuredBrdf::from_table(&lambertian_table()).unwrap();
+        for theta_in in [0.0, 0.3, 1.2].iter() {
+            assert!((brdf.albedo(*theta_in) - 1.0).abs() < 1.0e-9);
+        }
+        assert!((brdf.eval(0.4, 0.7, 2.0) - 1.0 / PI).abs() < 1.0e-12);
+    }
+
+    #[test]
+    fn test_from_table_incomplete_coverage() {
+        let mut table = lambertian_table();
+        let rows: Vec<_> = table
+            .rows()
+            .iter()
+            .filter(|row| row[1] < 85.0)
+            .cloned()
+            .collect();
+        table = Table::new(vec!["a".into(), "b".into(), "c".into(), "d".into()], rows);
+        assert!(MeasuredBrdf::from_table(&table).is_err());
+    }
+
+    #[test]
+    fn test_from_table_albedo_above_one() {
+        let rows: Vec<_> = lambertian_table()
+            .rows()
+            .iter()
+            .map(|row| vec![row[0], row[1], row[2], 1.5 * row[3]])
+            .collect();
+        let table = Table::new(vec!["a".into(), "b".into(), "c".into(), "d".into()], rows);
+        let err = MeasuredBrdf::from_table(&table).unwrap_err();
+        assert!(format!("{:?}", err).contains("albedo"));
+    }
+
+    #[test]
+    fn test_from_table_missing_entry() {
+        let rows: Vec<_> = lambertian_table().rows().iter().skip(1).cloned().collect();
+        let table = Table::new(vec!["a".into(), "b".into(), "c".into(), "d".into()], rows);
+        assert!(MeasuredBrdf::from_table(&table).is_err());
+    }
+
+    #[test]
+    fn test_mirror_table_reflects_specularly() {
+        // Peak at the mirror direction for each incident angle.
+        let mut values = Array3::zeros([19, 19, 37]);
+        for i in 0..19 {
+            values[[i, i, 0]] = 100.0;
+        }
+        let brdf = MeasuredBrdf::new(&values).unwrap();
+
+        let mut rng = rand::thread_rng();
+        let norm = Dir3::new(0.0, 0.0, 1.0);
+        let incident = Ray::new(Point3::new(0.0, 0.0, 0.0), Dir3::new(1.0, 0.0, -1.0));
+        let mirror = Dir3::new(1.0, 0.0, 1.0);
+
+        for _ in 0..10_000 {
+            if let Some(ray) = brdf.sample(&mut rng, &incident, &norm) {
+                assert!(ray.dir().dot(&mirror) > 0.99);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lambertian_table_cosine_distribution() {
+        let brdf = MeasuredBrdf::from_table(&lambertian_table()).unwrap();
+
+        let mut rng = rand::thread_rng();
+        let norm = Dir3::new(0.0, 0.0, 1.0);
+        let incident = Ray::new(Point3::new(0.0, 0.0, 0.0), Dir3::new(1.0, 1.0, -1.0));
+
+        let n_phot = 100_000;
+        let mut total_cos = 0.0;
+        let mut total_perp = 0.0;
+        for _ in 0..n_phot {
+            let ray = brdf.sample(&mut rng, &incident, &norm).unwrap();
+            assert!(ray.dir().dot(&norm) >= 0.0);
+            total_cos += ray.dir().dot(&norm);
+            total_perp += ray.dir().x() - ray.dir().y();
+        }
+
+        // A cosine-weighted hemisphere has a mean cosine of 2/3.
+        assert!((total_cos / n_phot as f64 - 2.0 / 3.0).abs() < 0.01);
+        // And is symmetric about the plane of incidence.
+        assert!((total_perp / n_phot as f64).abs() < 0.01);
+    }
+}
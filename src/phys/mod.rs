@@ -1,14 +1,21 @@
 //! Physics module.
 
+pub mod cauchy;
 pub mod crossing;
 pub mod light;
 pub mod local;
 pub mod material;
+pub mod measured_brdf;
 pub mod photon;
+pub mod raman_spectrum;
 pub mod reflectance;
+pub mod spectral_unit;
 pub mod spectrum;
+pub mod translucent;
 
 // Builders
+pub mod asymmetry_builder;
+pub mod cauchy_builder;
 pub mod light_linker_builder;
 pub mod material_builder;
 pub mod spectrum_builder;
@@ -20,7 +27,8 @@ pub mod light_linker;
 pub mod light_linker_builder_loader;
 
 pub use self::{
-    crossing::*, light::*, light_linker::*, light_linker_builder::*,
-    light_linker_builder_loader::*, local::*, material::*, material_builder::*, photon::*,
-    reflectance::*, spectrum::*, spectrum_builder::*,
+    asymmetry_builder::*, cauchy::*, cauchy_builder::*, crossing::*, light::*, light_linker::*,
+    light_linker_builder::*, light_linker_builder_loader::*, local::*, material::*,
+    material_builder::*, measured_brdf::*, photon::*, raman_spectrum::*, reflectance::*,
+    spectral_unit::*, spectrum::*, spectrum_builder::*, translucent::*,
 };
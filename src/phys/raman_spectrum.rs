@@ -0,0 +1,175 @@
+//! Raman shift spectrum.
+
+use crate::{access, data::Table, err::Error, fmt_report};
+use rand::Rng;
+use std::fmt::{Display, Formatter};
+
+/// Number of inverse metres in an inverse centimetre.
+const PER_CM: f64 = 100.0;
+
+/// Discrete spectrum of Raman shifts.
+/// Positive shifts are Stokes lines, lengthening the wavelength,
+/// and negative shifts are anti-Stokes lines, shortening it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RamanSpectrum {
+    /// Wavenumber shifts [cm^-1].
+    shifts: Vec<f64>,
+    /// Cumulative probability of each shift.
+    cdf: Vec<f64>,
+}
+
+impl RamanSpectrum {
+    access!(shifts: Vec<f64>);
+
+    /// Construct a new instance from shifts [cm^-1] and their relative probabilities.
+    /// # Errors
+    /// if the lists differ in length, are empty, or the probabilities are negative or sum to zero.
+    #[inline]
+    pub fn new(shifts: Vec<f64>, probs: &[f64]) -> Result<Self, Error> {
+        if shifts.is_empty() || shifts.len() != probs.len() {
+            return Err(
+                "Raman spectrum requires one probability for each of at least one shift.".into(),
+            );
+        }
+        if probs.iter().any(|p| *p < 0.0) {
+            return Err("Raman spectrum probabilities must be non-negative.".into());
+        }
+
+        let total: f64 = probs.iter().sum();
+        if total <= 0.0 {
+            return Err("Raman spectrum probabilities must not sum to zero.".into());
+        }
+
+        let mut sum = 0.0;
+        let cdf = probs
+            .iter()
+            .map(|p| {
+                sum += p;
+                sum / total
+            })
+            .collect();
+
+        Ok(Self { shifts, cdf })
+    }
+
+    /// Construct a new instance from a table of `shift`, `probability` rows.
+    /// # Errors
+    /// if the table does not have two columns, or the probabilities are invalid.
+    #[inline]
+    pub fn from_table(table: &Table<f64>) -> Result<Self, Error> {
+        let rows = table.rows();
+        if rows.iter().any(|row| row.len() != 2) {
+            return Err(
+                "Raman spectrum table must have exactly two columns: shift, probability.".into(),
+            );
+        }
+
+        let shifts = rows.iter().map(|row| row[0]).collect();
+        let probs: Vec<_> = rows.iter().map(|row| row[1]).collect();
+        Self::new(shifts, &probs)
+    }
+
+    /// Sample a wavenumber shift [cm^-1].
+    #[inline]
+    #[must_use]
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> f64 {
+        let r = rng.gen::<f64>();
+        let index = self
+            .cdf
+            .iter()
+            .position(|c| r <= *c)
+            .unwrap_or(self.cdf.len() - 1);
+
+        self.shifts[index]
+    }
+
+    /// Sample the wavelength [m] of a Raman shifted photon.
+    /// Only the lines which leave the wavenumber positive are drawn from, in proportion to their probabilities.
+    /// Returns `None` if no line can shift the wavelength.
+    #[inline]
+    #[must_use]
+    pub fn sample_shifted<R: Rng>(&self, rng: &mut R, wavelength: f64) -> Option<f64> {
+        debug_assert!(wavelength > 0.0);
+
+        let limit = wavelength.recip() / PER_CM;
+        if self.shifts.iter().all(|shift| *shift < limit) {
+            return Self::shift_wavelength(wavelength, self.sample(rng));
+        }
+
+        // Probability of each line which can shift the wavelength.
+        let probs = || {
+            self.shifts
+                .iter()
+                .zip(&self.cdf)
+                .scan(0.0, |prev, (shift, cdf)| {
+                    let prob = cdf - *prev;
+                    *prev = *cdf;
+                    Some((*shift, prob))
+                })
+                .filter(move |&(shift, prob)| shift < limit && prob > 0.0)
+        };
+
+        let total: f64 = probs().map(|(_shift, prob)| prob).sum();
+        let mut r = rng.gen::<f64>() * total;
+        let mut last = None;
+        for (shift, prob) in probs() {
+            last = Some(shift);
+            if r <= prob {
+                break;
+            }
+            r -= prob;
+        }
+
+        Self::shift_wavelength(wavelength, last?)
+    }
+
+    /// Calculate the wavelength [m] after applying a wavenumber shift [cm^-1].
+    /// Returns `None` if the shift would leave the wavenumber non-positive.
+    #[inline]
+    #[must_use]
+    pub fn shift_wavelength(wavelength: f64, shift: f64) -> Option<f64> {
+        debug_assert!(wavelength > 0.0);
+
+        let wavenumber = wavelength.recip() - (shift * PER_CM);
+        (wavenumber > 0.0).then(|| wavenumber.recip())
+    }
+}
+
+impl Display for RamanSpectrum {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        writeln!(fmt, "...")?;
+        fmt_report!(fmt, self.shifts.len(), "lines");
+        let min = self.shifts.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = self
+            .shifts
+            .iter()
+            .copied()
+            .fold(f64::NEG_INFINITY, f64::max);
+        fmt_report!(fmt, &format!("{} to {}", min, max), "shifts (cm^-1)");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shifts_keep_wavelength_positive() {
+        let mut rng = rand::thread_rng();
+
+        // At 500 nm the wavenumber is 20,000 cm^-1, so the larger Stokes line is unphysical.
+        let wavelength = 500.0e-9;
+        let spec = RamanSpectrum::new(vec![30_000.0, -1000.0], &[100.0, 1.0]).unwrap();
+        let anti_stokes = 1.0 / ((1.0 / wavelength) + 1.0e5);
+        for _ in 0..100 {
+            let shifted = spec.sample_shifted(&mut rng, wavelength).unwrap();
+            assert!((shifted - anti_stokes).abs() < 1.0e-15);
+        }
+
+        let spec = RamanSpectrum::new(vec![25_000.0, 30_000.0], &[1.0, 1.0]).unwrap();
+        assert_eq!(spec.sample_shifted(&mut rng, wavelength), None);
+        assert_eq!(RamanSpectrum::shift_wavelength(wavelength, 25_000.0), None);
+    }
+}
@@ -1,8 +1,10 @@
 use crate::{
     core::Real,
+    err::Error,
     fmt_report,
     geom::{Hit, Ray},
-    phys::Spectrum,
+    math::Dir3,
+    phys::{MeasuredBrdf, SpectralUnit, Spectrum},
     sim::Attribute,
 };
 use rand::Rng;
@@ -11,35 +13,25 @@ use std::{f64::consts::PI, fmt::Display};
 use super::Photon;
 
 /// A small utility function that checks that the provided spectrum is valid as a
-/// reflectance spectrum. This means that it should have values that are between 0.0
+/// reflectance spectrum. This means that it should be dimensionless, with values that are between 0.0
 /// and 1.0.
 pub fn reflectance_spectrum_valid(spec: &Spectrum) -> bool {
+    if spec.unit() != SpectralUnit::Dimensionless {
+        return false;
+    }
+
     match *spec {
-        Spectrum::Constant(ref val) | Spectrum::Tophat(_, _, ref val) => {
+        Spectrum::Constant(ref val, _) | Spectrum::Tophat(_, _, ref val, _) => {
             if *val >= 0.0 && *val <= 1.0 {
                 true
             } else {
                 false
             }
         }
-        Spectrum::Data(_, _) => {
-            let max = spec.max_val();
-            let min = spec.min_val();
-
-            if min.is_some() && max.is_some() {
-                if *max.unwrap() <= 1.0 && *max.unwrap() >= 0.0 {
-                    if *max.unwrap() <= 1.0 && *max.unwrap() >= 0.0 {
-                        true
-                    } else {
-                        false
-                    }
-                } else {
-                    false
-                }
-            } else {
-                false
-            }
-        }
+        Spectrum::Data(..) => match (spec.min_val(), spec.max_val()) {
+            (Some(min), Some(max)) => *min >= 0.0 && *max <= 1.0,
+            _ => false,
+        },
     }
 }
 
@@ -76,6 +68,42 @@ pub enum Reflectance {
         specular_refspec: Spectrum,
         specularity: Real,
     },
+    /// Phong Reflectance
+    ///
+    /// Provides a glossy reflectance, where reflected photons are distributed in a
+    /// `cos^n` lobe about the mirror direction, with `n` given by the `exponent`.
+    /// Large exponents approach specular reflection, whilst small exponents broaden
+    /// the lobe towards diffuse reflection.
+    /// The `refspec` is the reflectance spectrum, as for the other models.
+    Phong { refspec: Spectrum, exponent: Real },
+    /// Oren-Nayar Reflectance
+    ///
+    /// Provides a rough diffuse reflectance, for matte surfaces made up of
+    /// V-shaped Lambertian facets whose slopes are normally distributed with a
+    /// standard deviation of `roughness` radians.
+    /// Compared to Lambertian reflectance, light is preferentially scattered back
+    /// towards its source. With zero roughness, the model is purely Lambertian.
+    /// The `refspec` is the reflectance spectrum, as for the other models.
+    OrenNayar { refspec: Spectrum, roughness: Real },
+    /// Cook-Torrance Reflectance
+    ///
+    /// Provides a glossy microfacet reflectance, with a GGX distribution of facet
+    /// normals of width `roughness`, Smith masking and shadowing, and Schlick's
+    /// approximation to the Fresnel reflectance, `fresnel_f0`, at normal incidence.
+    /// Small roughnesses approach specular reflection.
+    /// The `refspec` is the reflectance spectrum, as for the other models.
+    CookTorrance {
+        refspec: Spectrum,
+        roughness: Real,
+        fresnel_f0: Real,
+    },
+    /// Measured Reflectance
+    ///
+    /// A reflectance model backed by a tabulated, measured BRDF.
+    /// Outgoing directions are importance sampled from the table for the
+    /// angle of incidence, and photons are killed with a probability of one
+    /// minus the directional-hemispherical reflectance of the table.
+    Measured { brdf: MeasuredBrdf },
 }
 
 impl Reflectance {
@@ -125,63 +153,194 @@ impl Reflectance {
         }
     }
 
+    /// Produces a new composite Reflectance instance, as `new_composite`, but returns an
+    /// error if the weighted sum of the diffuse and specular reflectances exceeds 1.0
+    /// at any wavelength, as this would create energy.
+    pub fn new_composite_checked(
+        diffuse_refspec: Spectrum,
+        specular_refspec: Spectrum,
+        specularity: Real,
+    ) -> Result<Self, Error> {
+        let model = Self::Composite {
+            diffuse_refspec,
+            specular_refspec,
+            specularity,
+        };
+
+        let max = model.max_total_reflectance();
+        if max > 1.0 {
+            return Err(Error::Text(format!(
+                "Composite reflectance exceeds unity: maximum total reflectance of {}.",
+                max
+            )));
+        }
+
+        Ok(model)
+    }
+
+    /// Determines the greatest fraction of incident photons that the model can reflect at any wavelength.
+    /// The spectra are sampled on the merged set of wavelengths at which they change gradient,
+    /// so the result is exact for piecewise-linear spectra.
+    /// Wavelengths outside of a spectrum's range are treated as non-reflecting.
+    pub fn max_total_reflectance(&self) -> Real {
+        match *self {
+            Self::Lambertian { ref refspec }
+            | Self::Specular { ref refspec }
+            | Self::Phong { ref refspec, .. }
+            | Self::OrenNayar { ref refspec, .. }
+            | Self::CookTorrance { ref refspec, .. } => refspec.max_val().copied().unwrap_or(0.0),
+            Self::Composite {
+                ref diffuse_refspec,
+                ref specular_refspec,
+                specularity,
+            } => {
+                let mut lams = diffuse_refspec.breakpoints();
+                lams.extend(specular_refspec.breakpoints());
+                if lams.is_empty() {
+                    // Both spectra are constant.
+                    lams.push(0.0);
+                }
+
+                lams.iter()
+                    .map(|lam| {
+                        ((1.0 - specularity) * diffuse_refspec.value_at(*lam).unwrap_or(0.0))
+                            + (specularity * specular_refspec.value_at(*lam).unwrap_or(0.0))
+                    })
+                    .fold(0.0, Real::max)
+            }
+            Self::Measured { ref brdf } => brdf.albedos().iter().copied().fold(0.0, Real::max),
+        }
+    }
+
+    /// Produces a new Phong reflectance instance.
+    /// Photons are reflected into a lobe about the mirror direction, whose tightness
+    /// increases with the `exponent`.
+    pub fn new_phong(refspec: Spectrum, exponent: Real) -> Self {
+        // Check that we have sensible reflectances --- they range from 0.0 - 1.0.
+        assert!(reflectance_spectrum_valid(&refspec));
+        assert!(exponent >= 0.0);
+
+        Self::Phong { refspec, exponent }
+    }
+
+    /// Produces a new Oren-Nayar reflectance instance.
+    /// Photons are distributed as for Lambertian reflectance, but weighted
+    /// towards back-scattering as the `roughness` increases.
+    pub fn new_oren_nayar(refspec: Spectrum, roughness: Real) -> Self {
+        // Check that we have sensible reflectances --- they range from 0.0 - 1.0.
+        assert!(reflectance_spectrum_valid(&refspec));
+        assert!(roughness >= 0.0);
+
+        Self::OrenNayar { refspec, roughness }
+    }
+
+    /// Produces a new Cook-Torrance reflectance instance.
+    /// Photons are reflected about microfacet normals sampled from a GGX distribution,
+    /// whose width is given by the `roughness`.
+    pub fn new_cook_torrance(refspec: Spectrum, roughness: Real, fresnel_f0: Real) -> Self {
+        // Check that we have sensible reflectances --- they range from 0.0 - 1.0.
+        assert!(reflectance_spectrum_valid(&refspec));
+        assert!(roughness > 0.0);
+        assert!((0.0..=1.0).contains(&fresnel_f0));
+
+        Self::CookTorrance {
+            refspec,
+            roughness,
+            fresnel_f0,
+        }
+    }
+
+    /// Produces a new Reflectance instance from a measured BRDF.
+    /// This is wavelength independent; the reflected fraction at a given angle
+    /// of incidence is determined by the albedo of the tabulated data.
+    pub fn new_measured(brdf: MeasuredBrdf) -> Self {
+        Self::Measured { brdf }
+    }
+
     /// Provided an incident photon, this will reflect the its ray according to the
     /// reflectance model that is used. Note that the returned ray can be an
     /// option. In the case that `None` is returned, this is indicative that the
     /// photon should not be reflected, and should be destroyed.
+    /// Otherwise, the ray is returned alongside the factor by which the surviving photon's weight should be multiplied.
+    /// This is one, unless the model reflects more than the incident weight into the sampled direction,
+    /// as the Oren-Nayar model may, in which case the photon always survives and carries the excess.
+    /// The returned ray is offset from the surface, so it can not immediately re-hit it.
     #[inline]
     pub fn reflect<R: Rng>(
         &self,
         rng: &mut R,
         incident_photon: &Photon,
         hit: &Hit<Attribute>,
-    ) -> Option<Ray> {
+    ) -> Option<(Ray, Real)> {
+        let (ray, weight) = self.reflect_weighted(rng, incident_photon, hit)?;
+
+        // This random draw determines if the photon should reflect, based on the value of the albedo.
+        if weight >= 1.0 {
+            Some((ray, weight))
+        } else if rng.gen_range(0.0..1.0) < weight {
+            Some((ray, 1.0))
+        } else {
+            None
+        }
+    }
+
+    /// Provided an incident photon, this will reflect its ray according to the
+    /// reflectance model that is used, without stochastically killing it.
+    /// Instead, the reflectance at the photon's wavelength is returned alongside the ray,
+    /// as the factor by which the photon weight should be multiplied.
+    /// In the case that `None` is returned, the reflectance is undefined or zero,
+    /// and the photon should be destroyed.
+    /// The returned ray is offset from the surface, so it can not immediately re-hit it.
+    #[inline]
+    pub fn reflect_weighted<R: Rng>(
+        &self,
+        rng: &mut R,
+        incident_photon: &Photon,
+        hit: &Hit<Attribute>,
+    ) -> Option<(Ray, Real)> {
+        let (mut ray, weight) = self.sample_ray(rng, incident_photon, hit)?;
+        if weight <= 0.0 {
+            return None;
+        }
+
+        ray.offset_from_surface(hit.side().norm());
+        Some((ray, weight))
+    }
+
+    /// Sample the reflected ray, positioned on the surface, and the reflectance at the photon's wavelength.
+    #[inline]
+    fn sample_ray<R: Rng>(
+        &self,
+        rng: &mut R,
+        incident_photon: &Photon,
+        hit: &Hit<Attribute>,
+    ) -> Option<(Ray, Real)> {
         match *self {
             Self::Lambertian { ref refspec } => {
-                // This random draw determines if the photon should reflect, based on the value of the albedo.
-                match refspec.value_at(incident_photon.wavelength()) {
-                    None => None,
-                    Some(ref_prob) => {
-                        let should_reflect = rng.gen_range(0.0..1.0) < ref_prob;
-
-                        if should_reflect {
-                            let theta = rng.gen_range(0.0..2.0 * PI);
-                            // We sample the phi angle using PDF = sin(theta)
-                            let phi = (rng.gen_range(0.0..1.0) as Real).asin();
-
-                            let mut reflected_ray = Ray::new(
-                                incident_photon.ray().pos().clone(),
-                                hit.side().norm().clone(),
-                            );
-                            reflected_ray.rotate(phi, theta);
-                            Some(reflected_ray)
-                        } else {
-                            None
-                        }
-                    }
-                }
+                let ref_prob = refspec.value_at(incident_photon.wavelength())?;
+
+                // Cosine-weighted sampling of the polar angle from the normal, PDF = 2 sin(theta) cos(theta),
+                // and uniform sampling of the azimuthal angle.
+                let theta = (rng.gen_range(0.0..1.0) as Real).sqrt().acos();
+                let phi = rng.gen_range(0.0..2.0 * PI);
+
+                let mut reflected_ray = Ray::new(
+                    incident_photon.ray().pos().clone(),
+                    hit.side().norm().clone(),
+                );
+                reflected_ray.rotate(theta, phi);
+                Some((reflected_ray, ref_prob))
             }
             Self::Specular { ref refspec } => {
-                // This random draw determines if the photon should reflect, based on the value of the albedo.
-                match refspec.value_at(incident_photon.wavelength()) {
-                    None => None,
-                    Some(ref_prob) => {
-                        let should_reflect = rng.gen_range(0.0..1.0) < ref_prob;
-
-                        if should_reflect {
-                            // Implementation for this heavily borrowed from: https://www.cs.uaf.edu/2006/fall/cs381/lecture/10_03_specular.html
-                            let reflect = *incident_photon.ray().dir()
-                                + 2.0
-                                    * hit.side().norm().dot(&-*incident_photon.ray().dir())
-                                    * hit.side().norm();
-                            let reflected_ray =
-                                Ray::new(incident_photon.ray().pos().clone(), reflect.into());
-                            Some(reflected_ray)
-                        } else {
-                            None
-                        }
-                    }
-                }
+                let ref_prob = refspec.value_at(incident_photon.wavelength())?;
+
+                // Implementation for this heavily borrowed from: https://www.cs.uaf.edu/2006/fall/cs381/lecture/10_03_specular.html
+                let reflect = *incident_photon.ray().dir()
+                    + 2.0
+                        * hit.side().norm().dot(&-*incident_photon.ray().dir())
+                        * hit.side().norm();
+                let reflected_ray = Ray::new(incident_photon.ray().pos().clone(), reflect.into());
+                Some((reflected_ray, ref_prob))
             }
             Self::Composite {
                 ref diffuse_refspec,
@@ -194,10 +353,132 @@ impl Reflectance {
 
                 // Then we just delegate handling of the reflection to the respective model.
                 if is_specular {
-                    Self::new_specular(specular_refspec.clone()).reflect(rng, incident_photon, hit)
+                    Self::new_specular(specular_refspec.clone()).sample_ray(
+                        rng,
+                        incident_photon,
+                        hit,
+                    )
+                } else {
+                    Self::new_lambertian(diffuse_refspec.clone()).sample_ray(
+                        rng,
+                        incident_photon,
+                        hit,
+                    )
+                }
+            }
+            Self::Phong {
+                ref refspec,
+                ref exponent,
+            } => {
+                let ref_prob = refspec.value_at(incident_photon.wavelength())?;
+
+                let norm = hit.side().norm();
+                let mirror = *incident_photon.ray().dir()
+                    + 2.0 * norm.dot(&-*incident_photon.ray().dir()) * norm;
+
+                // Sample the angle from the mirror direction using PDF ~ cos^n,
+                // resampling any directions which fall beneath the surface.
+                loop {
+                    let alpha = (rng.gen_range(0.0..1.0) as Real)
+                        .powf(1.0 / (exponent + 1.0))
+                        .acos();
+                    let theta = rng.gen_range(0.0..2.0 * PI);
+
+                    let mut reflected_ray =
+                        Ray::new(incident_photon.ray().pos().clone(), mirror.into());
+                    reflected_ray.rotate(alpha, theta);
+                    if reflected_ray.dir().dot(norm) > 0.0 {
+                        break Some((reflected_ray, ref_prob));
+                    }
+                }
+            }
+            Self::OrenNayar {
+                ref refspec,
+                roughness,
+            } => {
+                let (reflected_ray, ref_prob) =
+                    Self::new_lambertian(refspec.clone()).sample_ray(rng, incident_photon, hit)?;
+
+                // Weight the diffuse reflection by the Oren-Nayar A + B term.
+                let sigma_sq = roughness * roughness;
+                let a = 1.0 - (0.5 * sigma_sq / (sigma_sq + 0.33));
+                let b = 0.45 * sigma_sq / (sigma_sq + 0.09);
+
+                let norm = hit.side().norm();
+                let incoming = -*incident_photon.ray().dir();
+                let outgoing = *reflected_ray.dir();
+                let cos_in = incoming.dot(norm).clamp(-1.0, 1.0);
+                let cos_out = outgoing.dot(norm).clamp(-1.0, 1.0);
+                let theta_in = cos_in.acos();
+                let theta_out = cos_out.acos();
+
+                // Cosine of the azimuthal angle between the incoming and outgoing directions.
+                let tan_in = incoming + (*norm * -cos_in);
+                let tan_out = outgoing + (*norm * -cos_out);
+                let tan_mags = tan_in.mag() * tan_out.mag();
+                let cos_phi = if tan_mags > 0.0 {
+                    tan_in.dot(&tan_out) / tan_mags
                 } else {
-                    Self::new_lambertian(diffuse_refspec.clone()).reflect(rng, incident_photon, hit)
+                    0.0
+                };
+
+                let alpha = theta_in.max(theta_out);
+                let beta = theta_in.min(theta_out);
+                let factor = b.mul_add(cos_phi.max(0.0) * alpha.sin() * beta.tan(), a);
+
+                Some((reflected_ray, ref_prob * factor))
+            }
+            Self::CookTorrance {
+                ref refspec,
+                roughness,
+                fresnel_f0,
+            } => {
+                let ref_prob = refspec.value_at(incident_photon.wavelength())?;
+
+                // Importance sample the half-vector from the GGX distribution.
+                let norm = hit.side().norm();
+                let xi = rng.gen_range(0.0..1.0) as Real;
+                let theta_h = (roughness * (xi / (1.0 - xi)).sqrt()).atan();
+                let mut half = Ray::new(incident_photon.ray().pos().clone(), norm.clone());
+                half.rotate(theta_h, rng.gen_range(0.0..2.0 * PI));
+                let half = *half.dir();
+
+                // Reflect about the half-vector, killing photons reflected beneath the surface.
+                let incoming = -*incident_photon.ray().dir();
+                let cos_in_half = incoming.dot(&half);
+                let outgoing =
+                    Dir3::from(*incident_photon.ray().dir() + (half * (2.0 * cos_in_half)));
+                let cos_in = incoming.dot(norm);
+                let cos_out = outgoing.dot(norm);
+                if cos_in_half <= 0.0 || cos_in <= 0.0 || cos_out <= 0.0 {
+                    return None;
                 }
+
+                // Schlick Fresnel and Smith masking-shadowing terms.
+                let fresnel = (1.0 - fresnel_f0).mul_add((1.0 - cos_in_half).powi(5), fresnel_f0);
+                let alpha_sq = roughness * roughness;
+                let smith = |cos: Real| {
+                    2.0 * cos / (cos + (1.0 - alpha_sq).mul_add(cos * cos, alpha_sq).sqrt())
+                };
+                let geom = smith(cos_in) * smith(cos_out);
+
+                // Ratio of the BRDF, projected onto the outgoing direction, to the sampling density.
+                let weight = fresnel * geom * cos_in_half / (cos_in * half.dot(norm));
+
+                Some((
+                    Ray::new(incident_photon.ray().pos().clone(), outgoing),
+                    ref_prob * weight,
+                ))
+            }
+            Self::Measured { ref brdf } => {
+                let norm = hit.side().norm();
+                let theta_in = (-incident_photon.ray().dir().dot(norm))
+                    .clamp(-1.0, 1.0)
+                    .acos();
+                Some((
+                    brdf.sample_dir(rng, incident_photon.ray(), norm),
+                    brdf.albedo(theta_in).min(1.0),
+                ))
             }
         }
     }
@@ -228,17 +509,51 @@ impl Display for Reflectance {
                 fmt_report!(fmt, specularity, "specularity");
                 Ok(())
             }
+            Self::Phong {
+                ref refspec,
+                ref exponent,
+            } => {
+                writeln!(fmt, "Phong: ")?;
+                fmt_report!(fmt, refspec, "reflectance spectrum");
+                fmt_report!(fmt, exponent, "exponent");
+                Ok(())
+            }
+            Self::OrenNayar {
+                ref refspec,
+                ref roughness,
+            } => {
+                writeln!(fmt, "Oren-Nayar: ")?;
+                fmt_report!(fmt, refspec, "reflectance spectrum");
+                fmt_report!(fmt, roughness, "roughness (rad)");
+                Ok(())
+            }
+            Self::CookTorrance {
+                ref refspec,
+                ref roughness,
+                ref fresnel_f0,
+            } => {
+                writeln!(fmt, "Cook-Torrance: ")?;
+                fmt_report!(fmt, refspec, "reflectance spectrum");
+                fmt_report!(fmt, roughness, "roughness");
+                fmt_report!(fmt, fresnel_f0, "normal incidence fresnel reflectance");
+                Ok(())
+            }
+            Self::Measured { ref brdf } => {
+                writeln!(fmt, "Measured: ")?;
+                fmt_report!(fmt, brdf, "brdf");
+                Ok(())
+            }
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Reflectance;
+    use super::{reflectance_spectrum_valid, Reflectance};
     use crate::{
         core::Real,
         data::Histogram,
-        geom::{Hit, Ray, Side},
+        geom::{Hit, Ray, Side, Trace, Triangle},
         math::{Dir2, Dir3, Point3},
         phys::{Photon, Spectrum},
         sim::Attribute,
@@ -246,7 +561,13 @@ mod tests {
     use assert_approx_eq::assert_approx_eq;
     use rand::Rng;
     use statrs::statistics::Statistics;
-    use std::f64::consts::PI;
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    /// Fraction of Lambertian reflections, with a cos(theta) fall off in radiance,
+    /// leaving at a polar angle from the normal between `lower` and `upper`.
+    fn lambertian_fraction(lower: Real, upper: Real) -> Real {
+        upper.sin().powi(2) - lower.sin().powi(2)
+    }
 
     #[test]
     fn test_lambertian_reflectance_perfect_reflector() {
@@ -271,7 +592,7 @@ mod tests {
         let mut theta_dot_neg: usize = 0;
         for _ in 0..n_phot {
             match reflect.reflect(&mut rng, &incoming_photon, &hit) {
-                Some(ray) => {
+                Some((ray, _)) => {
                     // Check that the outgoing ray is within the same hemisphere as the surface normal.
                     // In the case of Lambertian scattering, this is a requirement.
                     // The easy check for this is to check that norm · ray is positive.
@@ -301,11 +622,10 @@ mod tests {
         assert_eq!(n_killed, 0);
 
         // Check that the phi distribution conforms to a cos(theta) fall off with angle.
-        let norm_fac = phi_hist.iter().map(|(_b, c)| c).take(3).mean();
+        let width = phi_hist.binner().bin_width();
         for (bin, count) in phi_hist.iter() {
-            // Assuming a generous threshold due to the relatively low number of photons and the nature of random draws.
-            // I don't want to be triggering off false positives left, right and centre.
-            assert_approx_eq!(bin.cos(), count / norm_fac as Real, 0.1);
+            let expected = n_phot as Real * lambertian_fraction(bin, bin + width);
+            assert_approx_eq!(count, expected, n_phot as Real * 0.003);
         }
 
         // Now check that the theta distribution is uniform.
@@ -347,7 +667,7 @@ mod tests {
         let mut theta_dot_neg: usize = 0;
         for _ in 0..n_phot {
             match reflect.reflect(&mut rng, &incoming_photon, &hit) {
-                Some(ray) => {
+                Some((ray, _)) => {
                     // Check that the outgoing ray is within the same hemisphere as the surface normal.
                     // In the case of Lambertian scattering, this is a requirement.
                     // The easy check for this is to check that norm · ray is positive.
@@ -381,11 +701,10 @@ mod tests {
         );
 
         // Check that the phi distribution conforms to a cos(theta) fall off with angle.
-        let norm_fac = phi_hist.iter().map(|(_b, c)| c).take(3).mean();
+        let width = phi_hist.binner().bin_width();
         for (bin, count) in phi_hist.iter() {
-            // Assuming a generous threshold due to the relatively low number of photons and the nature of random draws.
-            // I don't want to be triggering off false positives left, right and centre.
-            assert_approx_eq!(bin.cos(), count / norm_fac as Real, 0.15);
+            let expected = (n_phot - n_killed) as Real * lambertian_fraction(bin, bin + width);
+            assert_approx_eq!(count, expected, n_phot as Real * 0.003);
         }
 
         // Now check that the theta distribution is uniform.
@@ -424,7 +743,7 @@ mod tests {
         let reflected_ray_test = Ray::new(Point3::new(1.0, 0.0, 1.0), Dir3::new(1.0, 0.0, 1.0));
 
         match reflect.reflect(&mut rng, &incoming_photon, &hit) {
-            Some(ray) => {
+            Some((ray, _)) => {
                 // Use assert_approx_eq due to numerical noise.
                 assert_approx_eq!(ray.dir().dot(reflected_ray_test.dir()), 1.0);
             }
@@ -453,7 +772,7 @@ mod tests {
         let mut n_killed_photons: usize = 0;
         for _ in 0..n_photon {
             match reflect.reflect(&mut rng, &incoming_photon, &hit) {
-                Some(ray) => {
+                Some((ray, _)) => {
                     // Use assert_approx_eq due to numerical noise.
                     assert_approx_eq!(ray.dir().dot(reflected_ray_test.dir()), 1.0);
                 }
@@ -496,7 +815,7 @@ mod tests {
         let mut theta_dot_neg: usize = 0;
         for _ in 0..n_phot {
             match reflect.reflect(&mut rng, &incoming_photon, &hit) {
-                Some(ray) => {
+                Some((ray, _)) => {
                     // Check that the outgoing ray is within the same hemisphere as the surface normal.
                     // In the case of Lambertian scattering, this is a requirement.
                     // The easy check for this is to check that norm · ray is positive.
@@ -537,15 +856,17 @@ mod tests {
         // the the entire n_photon / 2 allocation over all bins in the histogram.
         // However in the phi component there is a cos(phi) dependence, which we
         // model by borrowing the method from out lambertian reflectance tests above.
-        let diff_norm_fac_phi = phi_hist.iter().map(|(_b, c)| c).take(3).mean();
-        let diffuse_component_phi = |bin: Real, norm_fac: Real| bin.cos() * norm_fac;
+        let width = phi_hist.binner().bin_width();
+        let diffuse_component_phi = |bin: Real, nphot: usize, ratio: Real| {
+            nphot as Real * ratio * lambertian_fraction(bin, bin + width)
+        };
         let diffuse_component_theta = |nbin: usize, nphot: usize, ratio: Real, albedo: Real| {
             (albedo * nphot as Real * ratio) / nbin as Real
         };
 
         for (ibin, (bin, count)) in phi_hist.iter().enumerate() {
             // The bins are at 1 degree increments. As we are testing relative to the x-axis, the reflection should be at 90 degrees, and hence should be in the 90th bin.
-            let model_count = diffuse_component_phi(bin, diff_norm_fac_phi)
+            let model_count = diffuse_component_phi(bin, n_phot, 0.5)
                 + specular_component(ibin, 45, n_phot, 0.5, 1.0);
             // We are checking that we agree to about the 1% level.
             assert_approx_eq!(model_count, count, n_phot as Real * 0.01);
@@ -599,4 +920,284 @@ mod tests {
             }
         }
     }
+
+    /// The angular spread of Phong reflected rays about the mirror direction
+    /// should narrow as the exponent increases.
+    #[test]
+    fn test_phong_lobe_narrows() {
+        let incoming_ray = Ray::new(Point3::new(1., 0., 1.0), Dir3::new(1.0, 0.0, -1.0));
+        let mut rng = rand::thread_rng();
+
+        let norm = Dir3::new(0.0, 0.0, 1.0);
+        let mirror = Dir3::new(1.0, 0.0, 1.0);
+        let incoming_photon = Photon::new(incoming_ray, 550.0, 1.0);
+
+        let mean_spread = |exponent: Real, rng: &mut rand::rngs::ThreadRng| {
+            let reflect = Reflectance::new_phong(Spectrum::new_tophat(300.0, 900.0, 1.0), exponent);
+            let attrib = Attribute::Reflector(reflect.clone());
+            let hit = Hit::new(&attrib, 2.0_f64.sqrt(), Side::Outside(norm));
+
+            (0..10_000)
+                .map(|_| {
+                    let (ray, _) = reflect.reflect(rng, &incoming_photon, &hit).unwrap();
+                    // Survivors must remain above the surface.
+                    assert!(ray.dir().dot(&norm) > 0.0);
+                    ray.dir().dot(&mirror).min(1.0).acos()
+                })
+                .mean()
+        };
+
+        let broad = mean_spread(1.0, &mut rng);
+        let glossy = mean_spread(20.0, &mut rng);
+        let tight = mean_spread(1000.0, &mut rng);
+
+        assert!(broad > glossy);
+        assert!(glossy > tight);
+        assert!(tight < 0.05);
+    }
+
+    #[test]
+    fn test_composite_energy_check() {
+        // A specularity outside of [0, 1] can push the weighted sum above unity.
+        let over = Reflectance::new_composite_checked(
+            Spectrum::new_linear(400.0, 700.0, 0.2, 0.9),
+            Spectrum::new_tophat(400.0, 700.0, 1.0),
+            1.2,
+        );
+        assert!(over.is_err());
+
+        let valid = Reflectance::new_composite_checked(
+            Spectrum::new_linear(400.0, 700.0, 0.2, 0.9),
+            Spectrum::new_constant(1.0),
+            0.5,
+        )
+        .unwrap();
+        assert_approx_eq!(valid.max_total_reflectance(), 0.95);
+    }
+
+    /// Reflected rays should leave the surface, rather than immediately re-hitting it due to rounding.
+    #[test]
+    fn test_reflected_ray_leaves_surface() {
+        let mut rng = rand::thread_rng();
+        let tri = Triangle::new([
+            Point3::new(1.0e4, 1.0e4, 1.0),
+            Point3::new(1.0e4 + 3.0, 1.0e4, 1.7),
+            Point3::new(1.0e4, 1.0e4 + 3.0, 1.3),
+        ]);
+        let reflect = Reflectance::new_lambertian(Spectrum::new_tophat(300.0, 900.0, 1.0));
+        let attrib = Attribute::Reflector(reflect.clone());
+
+        for _ in 0..10_000 {
+            let mut ray = Ray::new(
+                Point3::new(1.0e4 + 0.5, 1.0e4 + 0.5, 10.0),
+                Dir3::new(rng.gen_range(-0.02..0.02), rng.gen_range(-0.02..0.02), -1.0),
+            );
+            let (dist, side) = tri.dist_side(&ray).unwrap();
+            ray.travel(dist);
+
+            let hit = Hit::new(&attrib, dist, side);
+            let phot = Photon::new(ray, 550.0, 1.0);
+            let (reflected, _) = reflect.reflect(&mut rng, &phot, &hit).unwrap();
+            assert!(tri.dist(&reflected).is_none());
+        }
+    }
+
+    #[test]
+    fn test_reflect_weighted_returns_reflectance() {
+        let mut rng = rand::thread_rng();
+        let norm = Dir3::new(0.0, 0.0, 1.0);
+        let refspec = Spectrum::new_linear(400.0e-9, 800.0e-9, 0.2, 0.6);
+
+        for reflect in &[
+            Reflectance::new_lambertian(refspec.clone()),
+            Reflectance::new_specular(refspec.clone()),
+            Reflectance::new_phong(refspec.clone(), 10.0),
+        ] {
+            let attrib = Attribute::Reflector(reflect.clone());
+            let hit = Hit::new(&attrib, 1.0, Side::Outside(norm));
+
+            for wavelength in &[450.0e-9, 550.0e-9, 700.0e-9] {
+                let phot = Photon::new(
+                    Ray::new(Point3::new(0.0, 0.0, 1.0), Dir3::new(1.0, 0.0, -1.0)),
+                    *wavelength,
+                    1.0,
+                );
+
+                // Photons are never killed, only down-weighted.
+                for _ in 0..100 {
+                    let (ray, weight) = reflect.reflect_weighted(&mut rng, &phot, &hit).unwrap();
+                    assert!(ray.dir().dot(&norm) > 0.0);
+                    assert_approx_eq!(weight, refspec.value_at(*wavelength).unwrap());
+                }
+            }
+
+            // Wavelengths outside of the spectrum can not be reflected.
+            let phot = Photon::new(
+                Ray::new(Point3::new(0.0, 0.0, 1.0), Dir3::new(1.0, 0.0, -1.0)),
+                1000.0e-9,
+                1.0,
+            );
+            assert!(reflect.reflect_weighted(&mut rng, &phot, &hit).is_none());
+        }
+    }
+
+    #[test]
+    fn test_oren_nayar_back_scattering() {
+        let mut rng = rand::thread_rng();
+        let norm = Dir3::new(0.0, 0.0, 1.0);
+        let incoming_photon = Photon::new(
+            Ray::new(Point3::new(0.0, 0.0, 0.0), Dir3::new(1.0, 0.0, -1.0)),
+            550.0,
+            1.0,
+        );
+
+        // Fraction of the reflected weight returning towards the side of the source.
+        let n_phot = 100_000;
+        let mut back_fraction = |roughness: Real| {
+            let reflect =
+                Reflectance::new_oren_nayar(Spectrum::new_tophat(300.0, 900.0, 1.0), roughness);
+            let attrib = Attribute::Reflector(reflect.clone());
+            let hit = Hit::new(&attrib, 1.0, Side::Outside(norm));
+
+            let mut n_reflected = 0;
+            let mut total = 0.0;
+            let mut back = 0.0;
+            for _ in 0..n_phot {
+                if let Some((ray, weight)) = reflect.reflect(&mut rng, &incoming_photon, &hit) {
+                    assert!(ray.dir().dot(&norm) > 0.0);
+                    assert!(weight >= 1.0);
+                    n_reflected += 1;
+                    total += weight;
+                    if ray.dir().x() < 0.0 {
+                        back += weight;
+                    }
+                }
+            }
+            (n_reflected, back / total)
+        };
+
+        // Smooth surfaces reduce to Lambertian reflectance.
+        let (n_reflected, smooth) = back_fraction(0.0);
+        assert_eq!(n_reflected, n_phot);
+        assert_approx_eq!(smooth, 0.5, 0.01);
+
+        // Rough surfaces preferentially scatter back towards the source.
+        let (_, rough) = back_fraction(1.0);
+        assert!(rough > smooth + 0.02);
+    }
+
+    #[test]
+    fn test_oren_nayar_carries_excess_weight() {
+        let mut rng = rand::thread_rng();
+        let norm = Dir3::new(0.0, 0.0, 1.0);
+        let incoming_photon = Photon::new(
+            Ray::new(Point3::new(0.0, 0.0, 0.0), Dir3::new(1.0, 0.0, -1.0)),
+            550.0,
+            1.0,
+        );
+        let reflect = Reflectance::new_oren_nayar(Spectrum::new_tophat(300.0, 900.0, 1.0), 1.0);
+        let attrib = Attribute::Reflector(reflect.clone());
+        let hit = Hit::new(&attrib, 1.0, Side::Outside(norm));
+
+        // The mean weight leaving the surface matches the mean reflectance, including that in excess of one.
+        let n_phot = 200_000;
+        let mut expected = 0.0;
+        let mut carried = 0.0;
+        let mut max_weight: Real = 0.0;
+        for _ in 0..n_phot {
+            let (_, weight) = reflect
+                .reflect_weighted(&mut rng, &incoming_photon, &hit)
+                .unwrap();
+            expected += weight;
+            max_weight = max_weight.max(weight);
+            if let Some((_, weight)) = reflect.reflect(&mut rng, &incoming_photon, &hit) {
+                carried += weight;
+            }
+        }
+        assert!(max_weight > 1.0);
+        assert_approx_eq!(carried / expected, 1.0, 0.02);
+    }
+
+    #[test]
+    fn test_cook_torrance_energy_conservation() {
+        let mut rng = rand::thread_rng();
+        let norm = Dir3::new(0.0, 0.0, 1.0);
+        let n_phot = 50_000;
+
+        for roughness in &[0.05, 0.2, 0.5, 1.0] {
+            // A perfect reflector, so that only the microfacet geometry loses energy.
+            let reflect = Reflectance::new_cook_torrance(
+                Spectrum::new_tophat(300.0, 900.0, 1.0),
+                *roughness,
+                1.0,
+            );
+            let attrib = Attribute::Reflector(reflect.clone());
+            let hit = Hit::new(&attrib, 1.0, Side::Outside(norm));
+
+            for incidence in &[0.0, 30.0, 60.0] {
+                let theta = (*incidence as Real).to_radians();
+                let incoming_photon = Photon::new(
+                    Ray::new(
+                        Point3::new(0.0, 0.0, 0.0),
+                        Dir3::new(theta.sin(), 0.0, -theta.cos()),
+                    ),
+                    550.0,
+                    1.0,
+                );
+
+                // Monte Carlo estimate of the reflected energy integrated over the hemisphere.
+                let mut total = 0.0;
+                for _ in 0..n_phot {
+                    if let Some((ray, weight)) =
+                        reflect.reflect_weighted(&mut rng, &incoming_photon, &hit)
+                    {
+                        assert!(ray.dir().dot(&norm) > 0.0);
+                        total += weight;
+                    }
+                }
+                let albedo = total / n_phot as Real;
+
+                assert!(albedo > 0.0);
+                assert!(albedo < 1.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_reflectance_spectrum_valid_data() {
+        let valid = Spectrum::new_data(vec![400.0, 500.0, 600.0], vec![0.0, 0.5, 1.0]);
+        assert!(reflectance_spectrum_valid(&valid));
+
+        let negative = Spectrum::new_data(vec![400.0, 500.0, 600.0], vec![0.5, -0.1, 0.5]);
+        assert!(!reflectance_spectrum_valid(&negative));
+
+        let excessive = Spectrum::new_data(vec![400.0, 500.0, 600.0], vec![0.5, 1.1, 0.5]);
+        assert!(!reflectance_spectrum_valid(&excessive));
+    }
+
+    #[test]
+    fn test_lambertian_cosine_falloff() {
+        let incoming_ray = Ray::new(Point3::new(1., 1., 0.0), Dir3::new(-1.0, -1.0, 0.0));
+        let mut rng = rand::thread_rng();
+
+        let norm = Dir3::new(0.0, 0.0, 1.0);
+        let reflect = Reflectance::new_lambertian(Spectrum::new_tophat(300.0, 900.0, 1.0));
+        let attrib = Attribute::Reflector(reflect.clone());
+        let hit = Hit::new(&attrib, 1.0, Side::Outside(norm));
+        let incoming_photon = Photon::new(incoming_ray, 550.0, 1.0);
+
+        let mut theta_hist = Histogram::new(0.0, FRAC_PI_2, 9);
+        let n_phot = 1_000_000;
+        for _ in 0..n_phot {
+            let (ray, _) = reflect.reflect(&mut rng, &incoming_photon, &hit).unwrap();
+            theta_hist.collect(ray.dir().dot(&norm).acos());
+        }
+
+        // Each band of polar angle should agree with the analytic fraction at the 2.5% level.
+        let width = theta_hist.binner().bin_width();
+        for (bin, count) in theta_hist.iter() {
+            let expected = n_phot as Real * lambertian_fraction(bin, bin + width);
+            assert_approx_eq!(count / expected, 1.0, 0.025);
+        }
+    }
 }
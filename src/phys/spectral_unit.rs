@@ -0,0 +1,63 @@
+//! Spectral density units.
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// Unit of the values of a spectrum, with respect to wavelength.
+/// Spectral densities are given per unit wavelength, whereas dimensionless spectra,
+/// such as reflectances and probabilities, are not.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpectralUnit {
+    /// Dimensionless ratio.
+    Dimensionless,
+    /// Density per metre of wavelength.
+    PerMetre,
+    /// Density per nanometre of wavelength.
+    PerNanometre,
+}
+
+impl SpectralUnit {
+    /// Number of metres of wavelength spanned by the unit of density,
+    /// such that a value in this unit is multiplied by the factor to give a density per metre.
+    /// Dimensionless spectra are not densities, and have no factor.
+    #[inline]
+    #[must_use]
+    pub const fn per_metre_factor(self) -> Option<f64> {
+        match self {
+            Self::Dimensionless => None,
+            Self::PerMetre => Some(1.0),
+            Self::PerNanometre => Some(1.0e9),
+        }
+    }
+
+    /// Factor by which a value in this unit is multiplied to express it in another unit.
+    /// Returns `None` if only one of the units is a spectral density.
+    #[inline]
+    #[must_use]
+    pub fn conversion_to(self, other: Self) -> Option<f64> {
+        match (self.per_metre_factor(), other.per_metre_factor()) {
+            (None, None) => Some(1.0),
+            (Some(a), Some(b)) => Some(a / b),
+            _ => None,
+        }
+    }
+}
+
+impl Default for SpectralUnit {
+    #[inline]
+    fn default() -> Self {
+        Self::Dimensionless
+    }
+}
+
+impl Display for SpectralUnit {
+    #[inline]
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match *self {
+            Self::Dimensionless => "dimensionless",
+            Self::PerMetre => "per m",
+            Self::PerNanometre => "per nm",
+        };
+        write!(fmt, "{}", kind)
+    }
+}
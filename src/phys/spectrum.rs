@@ -1,54 +1,116 @@
-use crate::{data::Table, err::Error, fmt_report, fs::File};
-use std::{fmt::Display, path::Path};
-
+use crate::{data::Table, err::Error, fmt_report, fs::File, phys::SpectralUnit};
+use std::{
+    fmt::Display,
+    ops::{Add, Mul},
+    path::Path,
+};
+
+/// Relative offset of the samples taken either side of the edges of a spectrum, when tabulating a sum.
+const EDGE_OFFSET: f64 = 1.0e-9;
+
+/// Value as a function of wavelength [m].
+/// Each spectrum records the unit of its values, so that spectral densities
+/// given per metre or per nanometre of wavelength can be integrated and combined consistently.
 #[derive(Debug, Clone, PartialEq)]
 pub enum Spectrum {
-    Constant(f64),
-    Tophat(f64, f64, f64),
-    /// A collection of wavelengths (in nm), assumed to be sorted shortest to longest.
+    Constant(f64, SpectralUnit),
+    Tophat(f64, f64, f64, SpectralUnit),
+    /// A collection of wavelengths, assumed to be sorted shortest to longest.
     /// The independent value that is being represented as a function of wavelength.
-    Data(Vec<f64>, Vec<f64>),
+    Data(Vec<f64>, Vec<f64>, SpectralUnit),
 }
 
 impl Spectrum {
     /// Returns an instance which represents a uniform value between two wavelengths.
     pub fn new_constant(value: f64) -> Spectrum {
-        Spectrum::Constant(value)
+        Spectrum::Constant(value, SpectralUnit::Dimensionless)
     }
 
     /// Returns an instance which represents a uniform value between two wavelengths.
     pub fn new_tophat(lower: f64, upper: f64, value: f64) -> Spectrum {
-        Spectrum::Tophat(lower, upper, value)
+        Spectrum::Tophat(lower, upper, value, SpectralUnit::Dimensionless)
     }
 
     /// Returns a new instance which represents a linear function between two wavelengths.
     pub fn new_linear(lower: f64, upper: f64, lower_value: f64, upper_value: f64) -> Spectrum {
-        Spectrum::Data(vec![lower, upper], vec![lower_value, upper_value])
+        Spectrum::Data(
+            vec![lower, upper],
+            vec![lower_value, upper_value],
+            SpectralUnit::Dimensionless,
+        )
+    }
+
+    /// Returns a new instance which represents tabulated values at each of the given wavelengths.
+    pub fn new_data(lams: Vec<f64>, vals: Vec<f64>) -> Spectrum {
+        debug_assert!(lams.len() == vals.len());
+        Spectrum::Data(lams, vals, SpectralUnit::Dimensionless)
+    }
+
+    /// Sets the unit of the values, without altering them.
+    pub fn with_unit(mut self, unit: SpectralUnit) -> Spectrum {
+        match self {
+            Self::Constant(_, ref mut u)
+            | Self::Tophat(_, _, _, ref mut u)
+            | Self::Data(_, _, ref mut u) => *u = unit,
+        }
+        self
+    }
+
+    /// Returns the unit of the values.
+    pub fn unit(&self) -> SpectralUnit {
+        match *self {
+            Self::Constant(_, unit) | Self::Tophat(_, _, _, unit) | Self::Data(_, _, unit) => unit,
+        }
+    }
+
+    /// Returns the same spectrum, with its values converted to another unit.
+    /// # Errors
+    /// if only one of the units is a spectral density.
+    pub fn in_unit(&self, unit: SpectralUnit) -> Result<Spectrum, Error> {
+        let factor = self.unit().conversion_to(unit).ok_or_else(|| {
+            Error::Text(format!(
+                "Can not convert a spectrum from {} to {}.",
+                self.unit(),
+                unit
+            ))
+        })?;
+
+        Ok(match *self {
+            Self::Constant(value, _) => Self::Constant(value * factor, unit),
+            Self::Tophat(lower, upper, value, _) => {
+                Self::Tophat(lower, upper, value * factor, unit)
+            }
+            Self::Data(ref lams, ref vals, _) => Self::Data(
+                lams.clone(),
+                vals.iter().map(|v| v * factor).collect(),
+                unit,
+            ),
+        })
     }
 
     /// Loads the wavelengths and independent values from a file, handling errors while it does it.
-    /// This function makes the assumption that wavelength (in nm) is the first column, and the
-    /// independent variable is the second column.
+    /// This function makes the assumption that wavelength [m] is the first column, and the
+    /// independent variable is the second column. The values are taken to be dimensionless.
     pub fn data_from_file(input_file: &Path) -> Result<Spectrum, Error> {
         let tab: Table<f64> = Table::load(input_file)?;
         let lams = tab.rows().iter().map(|r| r[0]).collect();
         let vals = tab.rows().iter().map(|r| r[1]).collect();
-        let spec = Spectrum::Data(lams, vals);
+        let spec = Spectrum::new_data(lams, vals);
         Ok(spec)
     }
 
     /// Performs a linear interpolation of the loaded data to return a value.
     pub fn value_at(&self, lam: f64) -> Option<f64> {
         match *self {
-            Self::Constant(val) => Some(val),
-            Self::Tophat(lower, upper, val) => {
+            Self::Constant(val, _) => Some(val),
+            Self::Tophat(lower, upper, val, _) => {
                 if lower <= lam && lam <= upper {
                     Some(val)
                 } else {
                     None
                 }
             }
-            Self::Data(ref lams, ref vals) => {
+            Self::Data(ref lams, ref vals, _) => {
                 // First check that the wavelength falls within the region of wavelength spectrum that we cover.
                 // If not, we are done. Just return a None.
                 if lams.iter().count() > 0
@@ -84,57 +146,275 @@ impl Spectrum {
         }
     }
 
+    /// Returns the wavelengths at which the spectrum may change gradient, in ascending order.
+    /// As the spectrum is piecewise linear, its extrema lie at these points.
+    pub fn breakpoints(&self) -> Vec<f64> {
+        match *self {
+            Self::Constant(..) => vec![],
+            Self::Tophat(lower, upper, _, _) => vec![lower, upper],
+            Self::Data(ref lams, _, _) => lams.clone(),
+        }
+    }
+
+    /// Integrates the spectrum with respect to wavelength [m] between the given limits.
+    /// The spectrum is taken to be zero wherever it is undefined.
+    /// Spectral densities are converted to per metre, so that the result is independent of the unit they are given in,
+    /// whereas dimensionless spectra integrate to a value multiplied by metres.
+    pub fn integrate(&self, min: f64, max: f64) -> f64 {
+        debug_assert!(min <= max);
+
+        let mut lams = vec![min];
+        lams.extend(
+            self.breakpoints()
+                .into_iter()
+                .filter(|lam| min < *lam && *lam < max),
+        );
+        lams.push(max);
+
+        // The spectrum is linear between breakpoints, so the midpoint rule is exact.
+        let total: f64 = lams
+            .iter()
+            .zip(lams.iter().skip(1))
+            .map(|(a, b)| (b - a) * self.value_at(0.5 * (a + b)).unwrap_or(0.0))
+            .sum();
+
+        total * self.unit().per_metre_factor().unwrap_or(1.0)
+    }
+
+    /// Combines two spectra, value by value.
+    /// If `union` is set, the spectra are combined over the range of wavelengths where either is defined,
+    /// taking undefined values as zero, otherwise only over the range where both are defined.
+    /// The values of the other spectrum are given in the unit of the result before being combined.
+    /// Non-constant results are tabulated at the breakpoints of both spectra.
+    fn combine<F: Fn(f64, f64) -> f64>(
+        &self,
+        other: &Spectrum,
+        unit: SpectralUnit,
+        factor: f64,
+        union: bool,
+        func: F,
+    ) -> Spectrum {
+        if let (Self::Constant(a, _), Self::Constant(b, _)) = (self, other) {
+            return Self::Constant(func(*a, *b * factor), unit);
+        }
+
+        let bound = |a: Option<&f64>, b: Option<&f64>, pick: fn(f64, f64) -> f64| match (a, b) {
+            (Some(a), Some(b)) => pick(*a, *b),
+            (Some(a), None) | (None, Some(a)) => *a,
+            (None, None) => unreachable!(),
+        };
+        let (min, max) = if union {
+            (
+                bound(self.min_lam(), other.min_lam(), f64::min),
+                bound(self.max_lam(), other.max_lam(), f64::max),
+            )
+        } else {
+            (
+                bound(self.min_lam(), other.min_lam(), f64::max),
+                bound(self.max_lam(), other.max_lam(), f64::min),
+            )
+        };
+
+        let mut lams = self.breakpoints();
+        lams.extend(other.breakpoints());
+        if union {
+            // Sample just outside the edges of each spectrum, so that the steps at its edges are kept.
+            for spec in &[self, other] {
+                if let (Some(lower), Some(upper)) = (spec.min_lam(), spec.max_lam()) {
+                    lams.push(lower * (1.0 - EDGE_OFFSET));
+                    lams.push(upper * (1.0 + EDGE_OFFSET));
+                }
+            }
+        }
+        lams.retain(|lam| min <= *lam && *lam <= max);
+        lams.sort_by(|a, b| a.total_cmp(b));
+        lams.dedup();
+
+        let vals = lams
+            .iter()
+            .map(|lam| {
+                func(
+                    self.value_at(*lam).unwrap_or(0.0),
+                    other.value_at(*lam).unwrap_or(0.0) * factor,
+                )
+            })
+            .collect();
+
+        Self::Data(lams, vals, unit)
+    }
+
+    /// Determines whether two spectra of the same unit agree to within a tolerance.
+    /// Constant and tophat spectra are compared structurally, otherwise the spectra are
+    /// compared on the merged set of their breakpoints, and must share the same coverage.
+    pub fn approx_eq(&self, other: &Spectrum, tol: f64) -> bool {
+        debug_assert!(tol >= 0.0);
+
+        if self.unit() != other.unit() {
+            return false;
+        }
+
+        match (self, other) {
+            (Self::Constant(a, _), Self::Constant(b, _)) => (a - b).abs() <= tol,
+            (Self::Tophat(a_lower, a_upper, a, _), Self::Tophat(b_lower, b_upper, b, _)) => {
+                (a_lower - b_lower).abs() <= tol
+                    && (a_upper - b_upper).abs() <= tol
+                    && (a - b).abs() <= tol
+            }
+            _ => {
+                let mut lams = self.breakpoints();
+                lams.extend(other.breakpoints());
+
+                lams.iter()
+                    .all(|lam| match (self.value_at(*lam), other.value_at(*lam)) {
+                        (Some(a), Some(b)) => (a - b).abs() <= tol,
+                        (None, None) => true,
+                        _ => false,
+                    })
+            }
+        }
+    }
+
+    /// Convolves the spectrum with an instrument response kernel, sampling both on a common grid of the given spacing.
+    /// The spacing, and the kernel offsets, share the wavelength unit of the spectrum.
+    /// The kernel wavelengths are offsets from the line centre, and the kernel is normalised to unit area,
+    /// so that the convolution broadens the spectrum without changing its integral.
+    /// The spectrum is zero-padded: it is taken to be zero wherever it is undefined,
+    /// and the result covers its extent widened by that of the kernel.
+    /// A constant spectrum is unchanged by convolution.
+    pub fn convolve(&self, kernel: &Spectrum, step: f64) -> Result<Spectrum, Error> {
+        if step <= 0.0 {
+            return Err("Convolution grid spacing must be positive.".into());
+        }
+        let (kern_min, kern_max) = match (kernel.min_lam(), kernel.max_lam()) {
+            (Some(min), Some(max)) => (*min, *max),
+            _ => return Err("Convolution kernel must have a finite wavelength extent.".into()),
+        };
+
+        let offsets: Vec<f64> = (0..=((kern_max - kern_min) / step).ceil() as usize)
+            .map(|j| step.mul_add(j as f64, kern_min))
+            .collect();
+        let mut weights: Vec<f64> = offsets
+            .iter()
+            .map(|k| kernel.value_at(*k).unwrap_or(0.0))
+            .collect();
+        let total: f64 = weights.iter().sum();
+        if total <= 0.0 {
+            return Err("Convolution kernel must have a positive integral.".into());
+        }
+        weights.iter_mut().for_each(|w| *w /= total);
+
+        let (min, max) = match (self.min_lam(), self.max_lam()) {
+            (Some(min), Some(max)) => (*min + kern_min, *max + kern_max),
+            _ => return Ok(self.clone()),
+        };
+
+        let lams: Vec<f64> = (0..=((max - min) / step).ceil() as usize)
+            .map(|i| step.mul_add(i as f64, min))
+            .collect();
+        let vals = lams
+            .iter()
+            .map(|lam| {
+                offsets
+                    .iter()
+                    .zip(&weights)
+                    .map(|(k, w)| w * self.value_at(lam - k).unwrap_or(0.0))
+                    .sum::<f64>()
+            })
+            .collect();
+
+        Ok(Spectrum::Data(lams, vals, self.unit()))
+    }
+
     pub fn min_lam(&self) -> Option<&f64> {
         match *self {
-            Self::Constant(_) => None,
-            Self::Tophat(ref lower, _, _) => Some(&lower),
-            Self::Data(ref lams, _) => lams.iter().min_by(|a, b| a.total_cmp(b)),
+            Self::Constant(..) => None,
+            Self::Tophat(ref lower, _, _, _) => Some(&lower),
+            Self::Data(ref lams, _, _) => lams.iter().min_by(|a, b| a.total_cmp(b)),
         }
     }
 
     pub fn max_lam(&self) -> Option<&f64> {
         match *self {
-            Self::Constant(_) => None,
-            Self::Tophat(_, ref upper, _) => Some(&upper),
-            Self::Data(ref lams, _) => lams.iter().max_by(|a, b| a.total_cmp(b)),
+            Self::Constant(..) => None,
+            Self::Tophat(_, ref upper, _, _) => Some(&upper),
+            Self::Data(ref lams, _, _) => lams.iter().max_by(|a, b| a.total_cmp(b)),
         }
     }
 
     pub fn min_val(&self) -> Option<&f64> {
         match *self {
-            Self::Constant(ref value) => Some(&value),
-            Self::Tophat(_, _, ref value) => Some(&value),
-            Self::Data(_, ref vals) => vals.iter().min_by(|a, b| a.total_cmp(b)),
+            Self::Constant(ref value, _) => Some(&value),
+            Self::Tophat(_, _, ref value, _) => Some(&value),
+            Self::Data(_, ref vals, _) => vals.iter().min_by(|a, b| a.total_cmp(b)),
         }
     }
 
     pub fn max_val(&self) -> Option<&f64> {
         match *self {
-            Self::Constant(ref value) => Some(&value),
-            Self::Tophat(_, _, ref value) => Some(&value),
-            Self::Data(_, ref vals) => vals.iter().max_by(|a, b| a.total_cmp(b)),
+            Self::Constant(ref value, _) => Some(&value),
+            Self::Tophat(_, _, ref value, _) => Some(&value),
+            Self::Data(_, ref vals, _) => vals.iter().max_by(|a, b| a.total_cmp(b)),
         }
     }
 }
 
+impl Add for &Spectrum {
+    type Output = Spectrum;
+
+    /// Sum two spectra of compatible units, giving the result in the unit of the left-hand side.
+    /// The sum covers the wavelengths where either spectrum is defined, each being zero where it is not.
+    /// # Panics
+    /// if only one of the spectra is a spectral density.
+    #[inline]
+    fn add(self, rhs: Self) -> Spectrum {
+        let factor = rhs
+            .unit()
+            .conversion_to(self.unit())
+            .expect("Can not add a dimensionless spectrum to a spectral density.");
+
+        self.combine(rhs, self.unit(), factor, true, |a, b| a + b)
+    }
+}
+
+impl Mul for &Spectrum {
+    type Output = Spectrum;
+
+    /// Multiply two spectra, at least one of which must be dimensionless,
+    /// such as a spectral density attenuated by a reflectance.
+    /// The product covers only the wavelengths where both spectra are defined.
+    /// # Panics
+    /// if both spectra are spectral densities.
+    #[inline]
+    fn mul(self, rhs: Self) -> Spectrum {
+        let unit = match (self.unit(), rhs.unit()) {
+            (SpectralUnit::Dimensionless, unit) | (unit, SpectralUnit::Dimensionless) => unit,
+            _ => panic!("Can not multiply two spectral densities."),
+        };
+
+        self.combine(rhs, unit, 1.0, false, |a, b| a * b)
+    }
+}
+
 impl Display for Spectrum {
     #[inline]
     fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(fmt, "Spectrum: ")?;
 
         match *self {
-            Self::Constant(ref value) => {
+            Self::Constant(ref value, ref unit) => {
                 writeln!(fmt, "Constant: ")?;
                 fmt_report!(fmt, *value, "value");
+                fmt_report!(fmt, unit, "unit");
                 Ok(())
             }
-            Self::Tophat(ref lower, ref upper, ref value) => {
+            Self::Tophat(ref lower, ref upper, ref value, ref unit) => {
                 writeln!(fmt, "Tophat: ")?;
                 fmt_report!(fmt, format!("{}..{}", lower, upper), "wavelength range");
                 fmt_report!(fmt, value, "value");
+                fmt_report!(fmt, unit, "unit");
                 Ok(())
             }
-            Self::Data(ref lam, _) => {
+            Self::Data(ref lam, _, ref unit) => {
                 writeln!(fmt, "Data: ")?;
                 fmt_report!(fmt, lam.iter().count(), "no. points");
                 if lam.iter().count() > 1 {
@@ -148,6 +428,7 @@ impl Display for Spectrum {
                         "wavelength range"
                     );
                 }
+                fmt_report!(fmt, unit, "unit");
                 Ok(())
             }
         }
@@ -159,6 +440,8 @@ mod tests {
     use std::io::Write;
 
     use super::Spectrum;
+    use crate::phys::SpectralUnit;
+    use assert_approx_eq::assert_approx_eq;
     use tempfile::NamedTempFile;
 
     /// Test that the constant spectrum produces the correct results.
@@ -205,7 +488,7 @@ mod tests {
     #[test]
     fn test_linear_func_interp_11pts() {
         let pts = vec![0.0, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0];
-        let spec = Spectrum::Data(pts.clone(), pts);
+        let spec = Spectrum::new_data(pts.clone(), pts);
         assert_eq!(spec.value_at(0.25), Some(0.25));
         assert_eq!(spec.value_at(0.5), Some(0.5));
         assert_eq!(spec.value_at(0.75), Some(0.75));
@@ -286,4 +569,134 @@ mod tests {
         assert_eq!(spec.value_at(0.0), Some(0.0));
         assert_eq!(spec.value_at(1.0), Some(1.0));
     }
+
+    #[test]
+    fn test_approx_eq() {
+        let spec = Spectrum::new_data(vec![400.0, 500.0, 700.0], vec![0.1, 0.5, 0.3]);
+        let close = Spectrum::new_data(
+            vec![400.0, 500.0, 600.0, 700.0],
+            vec![0.1005, 0.5005, 0.4005, 0.3005],
+        );
+        let far = Spectrum::new_data(vec![400.0, 500.0, 700.0], vec![0.1, 0.6, 0.3]);
+
+        assert!(spec.approx_eq(&close, 1.0e-3));
+        assert!(!spec.approx_eq(&close, 1.0e-4));
+        assert!(!spec.approx_eq(&far, 1.0e-3));
+
+        assert!(Spectrum::new_constant(0.5).approx_eq(&Spectrum::new_constant(0.5001), 1.0e-3));
+        assert!(!Spectrum::new_tophat(400.0, 700.0, 0.5)
+            .approx_eq(&Spectrum::new_tophat(450.0, 700.0, 0.5), 1.0e-3));
+    }
+
+    /// Convolving a narrow line with a Gaussian response recovers the shape of the response.
+    #[test]
+    fn test_convolve_narrow_tophat() {
+        let sigma: f64 = 10.0e-9;
+        let offsets: Vec<f64> = (-50..=50).map(|x| f64::from(x) * 1.0e-9).collect();
+        let gauss = offsets
+            .iter()
+            .map(|x| (-x * x / (2.0 * sigma * sigma)).exp())
+            .collect();
+        let kernel = Spectrum::new_data(offsets, gauss);
+
+        let line = Spectrum::new_tophat(499.5e-9, 500.5e-9, 1.0);
+        let broad = line.convolve(&kernel, 0.1e-9).unwrap();
+
+        let peak = broad.value_at(500.0e-9).unwrap();
+        for x in &[-25.0e-9, -10.0e-9, 5.0e-9, 20.0e-9] {
+            let expected = (-x * x / (2.0 * sigma * sigma)).exp();
+            assert!((broad.value_at(500.0e-9 + x).unwrap() / peak - expected).abs() < 0.01);
+        }
+        assert!(broad.value_at(445.0e-9).is_none());
+
+        assert_eq!(
+            Spectrum::new_constant(0.5)
+                .convolve(&kernel, 0.1e-9)
+                .unwrap(),
+            Spectrum::new_constant(0.5)
+        );
+        assert!(line.convolve(&Spectrum::new_constant(1.0), 0.1e-9).is_err());
+    }
+
+    /// The same physical spectrum, given per nanometre or per metre, integrates to the same total.
+    #[test]
+    fn test_integrate_units() {
+        let lams = vec![400.0e-9, 500.0e-9, 550.0e-9, 700.0e-9];
+        let per_nm = Spectrum::new_data(lams.clone(), vec![0.0, 2.0, 3.0, 1.0])
+            .with_unit(SpectralUnit::PerNanometre);
+        let per_m = Spectrum::new_data(lams, vec![0.0, 2.0e9, 3.0e9, 1.0e9])
+            .with_unit(SpectralUnit::PerMetre);
+
+        // Trapezoidal areas of 100, 125 and 300 nm.
+        assert_approx_eq!(per_nm.integrate(300.0e-9, 800.0e-9), 525.0);
+        assert_approx_eq!(per_m.integrate(300.0e-9, 800.0e-9), 525.0);
+        assert_approx_eq!(per_nm.integrate(500.0e-9, 550.0e-9), 125.0);
+        assert_approx_eq!(per_m.integrate(450.0e-9, 475.0e-9), 31.25);
+
+        assert!(per_nm
+            .in_unit(SpectralUnit::PerMetre)
+            .unwrap()
+            .approx_eq(&per_m, 1.0e-3));
+        assert!(per_nm.in_unit(SpectralUnit::Dimensionless).is_err());
+
+        let flat =
+            Spectrum::new_tophat(400.0e-9, 500.0e-9, 2.0).with_unit(SpectralUnit::PerNanometre);
+        assert_approx_eq!(flat.integrate(0.0, 1.0e-6), 200.0);
+    }
+
+    /// Sums convert between density units, and products with dimensionless spectra keep the density unit.
+    #[test]
+    fn test_unit_arithmetic() {
+        let lams = vec![400.0e-9, 500.0e-9, 600.0e-9];
+        let per_nm = Spectrum::new_data(lams.clone(), vec![1.0, 2.0, 1.0])
+            .with_unit(SpectralUnit::PerNanometre);
+        let per_m = Spectrum::new_constant(1.0e9).with_unit(SpectralUnit::PerMetre);
+
+        let sum = &per_nm + &per_m;
+        assert_eq!(sum.unit(), SpectralUnit::PerNanometre);
+        assert_approx_eq!(sum.value_at(450.0e-9).unwrap(), 2.5);
+        assert_approx_eq!(
+            sum.integrate(400.0e-9, 600.0e-9),
+            per_nm.integrate(400.0e-9, 600.0e-9) + 200.0
+        );
+
+        let refl = Spectrum::new_tophat(450.0e-9, 550.0e-9, 0.5);
+        let product = &per_nm * &refl;
+        assert_eq!(product.unit(), SpectralUnit::PerNanometre);
+        assert_eq!(product.min_lam(), Some(&450.0e-9));
+        assert_eq!(product.max_lam(), Some(&550.0e-9));
+        assert_approx_eq!(product.value_at(500.0e-9).unwrap(), 1.0);
+
+        let ratio = &Spectrum::new_constant(0.5) * &Spectrum::new_constant(0.5);
+        assert_eq!(ratio, Spectrum::new_constant(0.25));
+    }
+
+    /// Sums cover the wavelengths where either spectrum is defined, products only where both are.
+    #[test]
+    fn test_sum_range_union() {
+        let blue = Spectrum::new_tophat(400.0e-9, 500.0e-9, 1.0);
+        let red = Spectrum::new_tophat(600.0e-9, 700.0e-9, 2.0);
+
+        let sum = &blue + &red;
+        assert_eq!(sum.min_lam(), Some(&400.0e-9));
+        assert_eq!(sum.max_lam(), Some(&700.0e-9));
+        assert_approx_eq!(sum.value_at(450.0e-9).unwrap(), 1.0);
+        assert_approx_eq!(sum.value_at(550.0e-9).unwrap(), 0.0);
+        assert_approx_eq!(sum.value_at(650.0e-9).unwrap(), 2.0);
+        assert_approx_eq!(
+            sum.integrate(400.0e-9, 700.0e-9),
+            blue.integrate(400.0e-9, 700.0e-9) + red.integrate(400.0e-9, 700.0e-9),
+            1.0e-12
+        );
+
+        let lams = vec![400.0e-9, 600.0e-9];
+        let ramp = Spectrum::new_data(lams, vec![1.0, 3.0]);
+        let sum = &ramp + &Spectrum::new_tophat(500.0e-9, 700.0e-9, 1.0);
+        assert_approx_eq!(sum.value_at(450.0e-9).unwrap(), 1.5);
+        assert_approx_eq!(sum.value_at(550.0e-9).unwrap(), 3.5);
+        assert_approx_eq!(sum.value_at(650.0e-9).unwrap(), 1.0);
+
+        let product = &blue * &red;
+        assert_eq!(product.value_at(450.0e-9), None);
+    }
 }
@@ -1,4 +1,8 @@
-use crate::{err::Error, fmt_report, phys::Spectrum};
+use crate::{
+    err::Error,
+    fmt_report,
+    phys::{SpectralUnit, Spectrum},
+};
 use serde::{Deserialize, Serialize};
 use std::{fmt::Display, path::Path};
 
@@ -8,6 +12,10 @@ pub enum SpectrumBuilder {
     Spectrum(String),
     Tophat(f64, f64, f64),
     Linear(f64, f64, f64, f64),
+    /// Tabulated wavelengths, in ascending order, and the values at each.
+    Data(Vec<f64>, Vec<f64>),
+    /// Spectral density of the given unit; other spectra are dimensionless.
+    Density(SpectralUnit, Box<SpectrumBuilder>),
 }
 
 impl SpectrumBuilder {
@@ -19,6 +27,17 @@ impl SpectrumBuilder {
             Self::Linear(lower, upper, lower_value, upper_value) => {
                 Ok(Spectrum::new_linear(lower, upper, lower_value, upper_value))
             }
+            Self::Data(ref lams, ref vals) => {
+                if lams.len() != vals.len() {
+                    return Err(Error::Text(format!(
+                        "Spectrum data has {} wavelengths but {} values.",
+                        lams.len(),
+                        vals.len()
+                    )));
+                }
+                Ok(Spectrum::new_data(lams.clone(), vals.clone()))
+            }
+            Self::Density(unit, ref spec) => Ok(spec.build()?.with_unit(unit)),
         }
     }
 }
@@ -53,6 +72,17 @@ impl Display for SpectrumBuilder {
                 );
                 Ok(())
             }
+            Self::Data(ref lams, _) => {
+                writeln!(fmt, "Data: ")?;
+                fmt_report!(fmt, lams.len(), "points");
+                Ok(())
+            }
+            Self::Density(ref unit, ref spec) => {
+                writeln!(fmt, "Density: ")?;
+                fmt_report!(fmt, unit, "unit");
+                fmt_report!(fmt, spec, "spectrum");
+                Ok(())
+            }
         }
     }
 }
@@ -0,0 +1,152 @@
+//! Thin translucent surface model.
+
+use crate::{
+    access, clone, fmt_report,
+    geom::{Hit, Ray},
+    phys::{reflectance_spectrum_valid, Photon, Reflectance, Spectrum},
+    sim::Attribute,
+};
+use rand::Rng;
+use std::{
+    f64::consts::PI,
+    fmt::{Display, Formatter},
+};
+
+/// Thin translucent sheet, such as paper, a leaf, or a diffusing film.
+/// Each photon is either reflected according to a sub-model, or transmitted through the sheet.
+/// No second medium is involved; transmitted photons continue in the medium they arrived in.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Translucent {
+    /// Model applied to reflected photons.
+    reflectance: Reflectance,
+    /// Probability of a photon being reflected, rather than transmitted, at each wavelength.
+    ref_prob: Spectrum,
+    /// Fraction of transmitted photons spread diffusely over the far hemisphere, rather than continuing undeviated.
+    diffusion: f64,
+}
+
+impl Translucent {
+    access!(reflectance: Reflectance);
+    access!(ref_prob: Spectrum);
+    clone!(diffusion: f64);
+
+    /// Construct a new instance.
+    /// Wavelengths outside of the reflection probability spectrum are transmitted.
+    #[inline]
+    #[must_use]
+    pub fn new(reflectance: Reflectance, ref_prob: Spectrum, diffusion: f64) -> Self {
+        assert!(reflectance_spectrum_valid(&ref_prob));
+        debug_assert!((0.0..=1.0).contains(&diffusion));
+
+        Self {
+            reflectance,
+            ref_prob,
+            diffusion,
+        }
+    }
+
+    /// Determine the ray leaving the sheet, either reflected or transmitted.
+    /// In the case that `None` is returned, the photon was absorbed by the reflectance sub-model, and should be destroyed.
+    /// Otherwise, the ray is returned alongside the factor by which the photon's weight should be multiplied,
+    /// as given by `Reflectance::reflect` for reflected photons, and one for transmitted photons.
+    /// The returned ray is offset from the surface, so it can not immediately re-hit it.
+    #[inline]
+    pub fn interact<R: Rng>(
+        &self,
+        rng: &mut R,
+        phot: &Photon,
+        hit: &Hit<Attribute>,
+    ) -> Option<(Ray, f64)> {
+        let ref_prob = self.ref_prob.value_at(phot.wavelength()).unwrap_or(0.0);
+        if rng.gen::<f64>() < ref_prob {
+            return self.reflectance.reflect(rng, phot, hit);
+        }
+
+        let norm = hit.side().norm();
+        let mut ray = if rng.gen::<f64>() < self.diffusion {
+            // Cosine-weighted about the far side normal.
+            let mut ray = Ray::new(*phot.ray().pos(), -*norm);
+            ray.rotate(
+                rng.gen::<f64>().sqrt().acos(),
+                rng.gen_range(0.0..(2.0 * PI)),
+            );
+            ray
+        } else {
+            phot.ray().clone()
+        };
+        ray.offset_from_surface(norm);
+
+        Some((ray, 1.0))
+    }
+}
+
+impl Display for Translucent {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        writeln!(fmt, "...")?;
+        fmt_report!(fmt, self.reflectance, "reflectance");
+        fmt_report!(fmt, self.ref_prob, "reflection probability");
+        fmt_report!(fmt, self.diffusion, "transmitted diffusion");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        geom::Side,
+        math::{Dir3, Point3},
+    };
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn test_reflect_transmit_ratio() {
+        let mut rng = rand::thread_rng();
+        let ref_prob = Spectrum::new_linear(400.0e-9, 800.0e-9, 0.2, 0.6);
+        let sheet = Translucent::new(
+            Reflectance::new_specular(Spectrum::new_constant(1.0)),
+            ref_prob.clone(),
+            0.5,
+        );
+
+        let norm = Dir3::new(0.0, 0.0, 1.0);
+        let mirror = Dir3::new(1.0, 0.0, 1.0);
+        let attrib = Attribute::Mirror(0.0);
+        let hit = Hit::new(&attrib, 1.0, Side::Outside(norm));
+
+        let num_phot = 100_000;
+        for wavelength in &[450.0e-9, 700.0e-9] {
+            let phot = Photon::new(
+                Ray::new(Point3::new(0.0, 0.0, 0.0), Dir3::new(1.0, 0.0, -1.0)),
+                *wavelength,
+                1.0,
+            );
+
+            let mut reflected = 0;
+            let mut undeviated = 0;
+            for _ in 0..num_phot {
+                let (ray, _) = sheet.interact(&mut rng, &phot, &hit).unwrap();
+                if ray.dir().dot(&mirror) > 1.0 - 1.0e-9 {
+                    reflected += 1;
+                    continue;
+                }
+
+                // Transmitted photons continue into the far hemisphere.
+                assert!(ray.dir().dot(&norm) < 0.0);
+                if ray.dir() == phot.ray().dir() {
+                    undeviated += 1;
+                }
+            }
+
+            let expected = ref_prob.value_at(*wavelength).unwrap();
+            let transmitted = num_phot - reflected;
+            assert_approx_eq!(f64::from(reflected) / f64::from(num_phot), expected, 0.01);
+            assert_approx_eq!(
+                f64::from(undeviated) / f64::from(transmitted),
+                1.0 - sheet.diffusion(),
+                0.015
+            );
+        }
+    }
+}
@@ -0,0 +1,157 @@
+//! Variance guided adaptive pixel sampling.
+
+use crate::{
+    clone, fmt_report,
+    ord::{X, Y},
+    sim::PixelVariance,
+};
+use arctk_attr::file;
+use std::fmt::{Display, Formatter};
+
+/// Adaptive sampling settings.
+/// Pixels are sampled in passes, with each pass refining only the pixels
+/// whose standard error of the mean remains above the tolerance.
+#[file]
+#[derive(Clone)]
+pub struct Adaptive {
+    /// Number of samples initially taken in every pixel.
+    initial: usize,
+    /// Number of samples added to each noisy pixel per pass.
+    batch: usize,
+    /// Maximum number of samples in any single pixel.
+    max_samples: usize,
+    /// Target standard error of the mean of each pixel.
+    tol: f64,
+}
+
+impl Adaptive {
+    clone!(initial: usize);
+    clone!(batch: usize);
+    clone!(max_samples: usize);
+    clone!(tol: f64);
+
+    /// Construct a new instance.
+    #[inline]
+    #[must_use]
+    pub fn new(initial: usize, batch: usize, max_samples: usize, tol: f64) -> Self {
+        debug_assert!(initial > 1);
+        debug_assert!(batch > 0);
+        debug_assert!(max_samples >= initial);
+        debug_assert!(tol > 0.0);
+
+        Self {
+            initial,
+            batch,
+            max_samples,
+            tol,
+        }
+    }
+
+    /// Check whether a pixel, with the given number of samples and sample variance, requires further samples.
+    #[inline]
+    #[must_use]
+    fn is_noisy(&self, n: f64, var: f64) -> bool {
+        let n_samples = n as usize;
+        n_samples < self.initial || (n_samples < self.max_samples && (var / n).sqrt() > self.tol)
+    }
+
+    /// Check whether every pixel of an image has converged, or reached the sample budget.
+    #[inline]
+    #[must_use]
+    pub fn converged(&self, stats: &PixelVariance) -> bool {
+        let counts = stats.counts();
+        let var = stats.variance();
+        counts
+            .iter()
+            .zip(var.iter())
+            .all(|(n, var)| !self.is_noisy(*n, *var))
+    }
+
+    /// Sample each pixel of an image until its estimated error falls below the tolerance,
+    /// or it reaches the sample budget.
+    /// The returned statistics hold the mean and number of samples of each pixel.
+    #[inline]
+    pub fn sample<F: FnMut([usize; 2]) -> f64>(
+        &self,
+        res: [usize; 2],
+        mut sample: F,
+    ) -> PixelVariance {
+        let mut stats = PixelVariance::new(res);
+        let pixels: Vec<[usize; 2]> = (0..res[X] * res[Y])
+            .map(|n| [n / res[Y], n % res[Y]])
+            .collect();
+
+        for pixel in &pixels {
+            for _ in 0..self.initial {
+                stats.collect(*pixel, sample(*pixel));
+            }
+        }
+
+        loop {
+            let counts = stats.counts();
+            let var = stats.variance();
+            let noisy: Vec<_> = pixels
+                .iter()
+                .filter(|pixel| self.is_noisy(counts[**pixel], var[**pixel]))
+                .copied()
+                .collect();
+
+            if noisy.is_empty() {
+                break;
+            }
+
+            for pixel in noisy {
+                let remaining = self.max_samples - counts[pixel] as usize;
+                for _ in 0..self.batch.min(remaining) {
+                    stats.collect(pixel, sample(pixel));
+                }
+            }
+        }
+
+        stats
+    }
+}
+
+impl Display for Adaptive {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        writeln!(fmt, "...")?;
+        fmt_report!(fmt, self.initial, "initial samples");
+        fmt_report!(fmt, self.batch, "samples per pass");
+        fmt_report!(fmt, self.max_samples, "maximum samples");
+        fmt_report!(fmt, self.tol, "error tolerance");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Adaptive;
+    use rand::Rng;
+
+    #[test]
+    fn test_noisy_region_receives_more_samples() {
+        let mut rng = rand::thread_rng();
+        let adaptive = Adaptive::new(8, 8, 512, 0.01);
+
+        // The left half of the image is noisy, the right half smooth.
+        let stats = adaptive.sample(
+            [4, 2],
+            |[x, _y]| {
+                if x < 2 {
+                    rng.gen_range(0.0..1.0)
+                } else {
+                    0.5
+                }
+            },
+        );
+
+        let counts = stats.counts();
+        for y in 0..2 {
+            assert_eq!(counts[[2, y]], 8.0);
+            assert_eq!(counts[[3, y]], 8.0);
+            assert!(counts[[0, y]] > 8.0);
+            assert!(counts[[1, y]] > 8.0);
+        }
+    }
+}
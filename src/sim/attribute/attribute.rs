@@ -1,6 +1,12 @@
 //! Optical attributes.
 
-use crate::{fmt_report, geom::Orient, phys::Material, phys::Reflectance, tools::Binner};
+use crate::{
+    fmt_report,
+    geom::Orient,
+    img::Texture,
+    phys::{Material, Reflectance, Translucent},
+    sim::CcdChannels,
+};
 use std::fmt::{Display, Error, Formatter};
 
 /// Surface attributes.
@@ -14,13 +20,26 @@ pub enum Attribute<'a> {
     Spectrometer(usize),
     /// Imager detector id, width, orientation.
     Imager(usize, f64, Orient),
-    /// CCD detector id, width, orientation, binner.
-    Ccd(usize, f64, Orient, Binner),
+    /// CCD detector id, width, orientation, channels.
+    Ccd(usize, f64, Orient, CcdChannels),
     /// A purely reflecting material, with a provided reflectance model.
     Reflector(Reflectance),
+    /// A reflecting material whose albedo is scaled across the surface by a texture,
+    /// sampled at the texture coordinates of the hit.
+    TexturedReflector(Reflectance, Texture),
+    /// A thin translucent sheet, which either reflects or transmits photons.
+    Translucent(Translucent),
     /// A photon collector, which collects the photon that interact with the linked entities.
     /// These photons can be optionally killed, or left to keep propogating.
     PhotonCollector(usize),
+    /// Hyperspectral volume id.
+    /// Photons are recorded into the wavelength channel of the volume at the point of the hit.
+    Hyperspectral(usize),
+    /// Radiance detector id.
+    /// Photons are recorded into the angular bin of their direction of travel.
+    Radiance(usize),
+    /// A chain of attributes, applied in order until the photon is killed.
+    AttributeChain(Vec<Attribute<'a>>),
 }
 
 impl Display for Attribute<'_> {
@@ -43,12 +62,12 @@ impl Display for Attribute<'_> {
                 fmt_report!(fmt, orient, "orientation");
                 Ok(())
             }
-            Self::Ccd(ref id, width, ref orient, ref binner) => {
+            Self::Ccd(ref id, width, ref orient, ref channels) => {
                 writeln!(fmt, "Ccd: ...")?;
                 fmt_report!(fmt, id, "name");
                 fmt_report!(fmt, width, "width (m)");
                 fmt_report!(fmt, orient, "orientation");
-                fmt_report!(fmt, binner, "binner (m)");
+                fmt_report!(fmt, channels, "channels");
                 Ok(())
             }
             Self::Reflector(ref reflectance) => {
@@ -56,11 +75,35 @@ impl Display for Attribute<'_> {
                 fmt_report!(fmt, reflectance, "reflectance");
                 Ok(())
             }
+            Self::TexturedReflector(ref reflectance, ref texture) => {
+                writeln!(fmt, "Textured Reflector: ...")?;
+                fmt_report!(fmt, reflectance, "reflectance");
+                fmt_report!(fmt, texture, "texture");
+                Ok(())
+            }
+            Self::Translucent(ref sheet) => {
+                writeln!(fmt, "Translucent: ...")?;
+                fmt_report!(fmt, sheet, "sheet");
+                Ok(())
+            }
             Self::PhotonCollector(ref id) => {
                 writeln!(fmt, "Photon Collector: ...")?;
                 fmt_report!(fmt, id, "name");
                 Ok(())
             }
+            Self::Hyperspectral(id) => {
+                write!(fmt, "Hyperspectral: {}", id)
+            }
+            Self::Radiance(id) => {
+                write!(fmt, "Radiance: {}", id)
+            }
+            Self::AttributeChain(ref attrs) => {
+                writeln!(fmt, "Attribute Chain: ...")?;
+                for attr in attrs {
+                    fmt_report!(fmt, attr, "attribute");
+                }
+                Ok(())
+            }
         }
     }
 }
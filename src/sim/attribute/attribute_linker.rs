@@ -4,11 +4,11 @@ use crate::{
     err::Error,
     fmt_report,
     geom::Orient,
+    img::Texture,
     ord::{Link, Name, Set},
     phys::Material,
-    phys::Reflectance,
-    sim::attribute::Attribute,
-    tools::Binner,
+    phys::{Reflectance, Translucent},
+    sim::{attribute::Attribute, CcdChannels},
 };
 use std::fmt::{Display, Formatter};
 
@@ -22,10 +22,20 @@ pub enum AttributeLinker {
     Spectrometer(usize),
     /// Imager id, width, orientation.
     Imager(usize, f64, Orient),
-    /// CCD detector id, width, orientation, binner.
-    Ccd(usize, f64, Orient, Binner),
+    /// CCD detector id, width, orientation, channels.
+    Ccd(usize, f64, Orient, CcdChannels),
     /// A purely reflecting material, with a provided reflectance model.
     Reflector(Reflectance),
+    /// A reflecting material whose albedo is scaled across the surface by a texture.
+    TexturedReflector(Reflectance, Texture),
+    /// A thin translucent sheet, which either reflects or transmits photons.
+    Translucent(Translucent),
+    /// A chain of attributes, applied in order until the photon is killed.
+    AttributeChain(Vec<Self>),
+    /// Hyperspectral volume id.
+    Hyperspectral(usize),
+    /// Radiance detector id.
+    Radiance(usize),
     /// A photon collector, which collects the photon that interact with the linked entities.
     /// These photons can be optionally killed, or left to keep propogating.
     PhotonCollector(usize),
@@ -43,9 +53,14 @@ impl<'a> Link<'a, Material> for AttributeLinker {
             | Self::Imager(..)
             | Self::Ccd(..)
             | Self::Reflector(..)
-            | Self::PhotonCollector(..) => {
+            | Self::PhotonCollector(..)
+            | Self::TexturedReflector(..)
+            | Self::Translucent(..)
+            | Self::Hyperspectral(..)
+            | Self::Radiance(..) => {
                 vec![]
             }
+            Self::AttributeChain(ref attrs) => attrs.requires(),
         }
     }
 
@@ -53,18 +68,27 @@ impl<'a> Link<'a, Material> for AttributeLinker {
     fn link(self, mats: &'a Set<Material>) -> Result<Self::Inst, Error> {
         Ok(match self {
             Self::Interface(ref inside, ref outside) => Self::Inst::Interface(
-                mats.get(inside).unwrap_or_else(|| {
-                    panic!("Failed to link attribute-interface key: {}", inside)
-                }),
-                mats.get(outside).unwrap_or_else(|| {
-                    panic!("Failed to link attribute-interface key: {}", outside)
-                }),
+                mats.get(inside).ok_or_else(|| {
+                    Error::Text(format!("Failed to link material key: {}", inside))
+                        .with_context("interface.inside")
+                })?,
+                mats.get(outside).ok_or_else(|| {
+                    Error::Text(format!("Failed to link material key: {}", outside))
+                        .with_context("interface.outside")
+                })?,
             ),
             Self::Mirror(r) => Self::Inst::Mirror(r),
             Self::Spectrometer(id) => Self::Inst::Spectrometer(id),
             Self::Imager(id, width, orient) => Self::Inst::Imager(id, width, orient),
-            Self::Ccd(id, width, orient, binner) => Self::Inst::Ccd(id, width, orient, binner),
+            Self::Ccd(id, width, orient, channels) => Self::Inst::Ccd(id, width, orient, channels),
             Self::Reflector(reflectance) => Self::Inst::Reflector(reflectance),
+            Self::TexturedReflector(reflectance, texture) => {
+                Self::Inst::TexturedReflector(reflectance, texture)
+            }
+            Self::Translucent(sheet) => Self::Inst::Translucent(sheet),
+            Self::AttributeChain(attrs) => Self::Inst::AttributeChain(attrs.link(mats)?),
+            Self::Hyperspectral(id) => Self::Inst::Hyperspectral(id),
+            Self::Radiance(id) => Self::Inst::Radiance(id),
             Self::PhotonCollector(id) => Self::Inst::PhotonCollector(id),
         })
     }
@@ -90,12 +114,12 @@ impl Display for AttributeLinker {
                 fmt_report!(fmt, orient, "orientation");
                 Ok(())
             }
-            Self::Ccd(ref id, width, ref orient, ref binner) => {
+            Self::Ccd(ref id, width, ref orient, ref channels) => {
                 writeln!(fmt, "Ccd: ...")?;
                 fmt_report!(fmt, id, "name");
                 fmt_report!(fmt, width, "width (m)");
                 fmt_report!(fmt, orient, "orientation");
-                fmt_report!(fmt, binner, "binner");
+                fmt_report!(fmt, channels, "channels");
                 Ok(())
             }
             Self::Reflector(ref reflectance) => {
@@ -103,6 +127,30 @@ impl Display for AttributeLinker {
                 fmt_report!(fmt, reflectance, "reflectance");
                 Ok(())
             }
+            Self::TexturedReflector(ref reflectance, ref texture) => {
+                writeln!(fmt, "Textured Reflector: ...")?;
+                fmt_report!(fmt, reflectance, "reflectance");
+                fmt_report!(fmt, texture, "texture");
+                Ok(())
+            }
+            Self::Translucent(ref sheet) => {
+                writeln!(fmt, "Translucent: ...")?;
+                fmt_report!(fmt, sheet, "sheet");
+                Ok(())
+            }
+            Self::AttributeChain(ref attrs) => {
+                writeln!(fmt, "Attribute Chain: ...")?;
+                for attr in attrs {
+                    fmt_report!(fmt, attr, "attribute");
+                }
+                Ok(())
+            }
+            Self::Hyperspectral(id) => {
+                write!(fmt, "Hyperspectral: {}", id)
+            }
+            Self::Radiance(id) => {
+                write!(fmt, "Radiance: {}", id)
+            }
             Self::PhotonCollector(ref id) => {
                 writeln!(fmt, "Photon Collector: ...")?;
                 fmt_report!(fmt, id, "name");
@@ -111,3 +159,36 @@ impl Display for AttributeLinker {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ord::Map;
+
+    #[test]
+    fn test_missing_material_path() {
+        let mats: Set<Material> = Set::new(Map::new());
+        let attrs = vec![
+            AttributeLinker::Mirror(0.5),
+            AttributeLinker::Interface(Name::new("glass"), Name::new("air")),
+        ];
+
+        let err = attrs.link(&mats).unwrap_err();
+        let msg = format!("{:?}", err);
+        assert!(msg.contains("[1].interface.inside"));
+        assert!(msg.contains("glass"));
+    }
+
+    #[test]
+    fn test_missing_material_set_path() {
+        let mats: Set<Material> = Set::new(Map::new());
+        let mut map = Map::new();
+        map.insert(
+            Name::new("lens"),
+            AttributeLinker::Interface(Name::new("glass"), Name::new("air")),
+        );
+
+        let err = Set::new(map).link(&mats).unwrap_err();
+        assert!(format!("{:?}", err).contains("lens.interface.inside"));
+    }
+}
@@ -4,10 +4,11 @@ use crate::{
     err::Error,
     fmt_report,
     geom::Orient,
+    img::Texture,
     ord::{Link, Name, Set},
-    phys::Reflectance,
-    sim::attribute::AttributeLinker,
-    tools::{Binner, Range},
+    phys::{Reflectance, Translucent},
+    sim::{attribute::AttributeLinker, CcdChannels},
+    tools::Range,
 };
 use std::fmt::{Display, Formatter};
 
@@ -22,10 +23,20 @@ pub enum AttributeLinkerLinker {
     Spectrometer(Name, [f64; 2], usize),
     /// Imager id, horizontal size, orientation.
     Imager(usize, f64, Orient),
-    /// CCD detector id, width, orientation, binner.
-    Ccd(usize, f64, Orient, Binner),
+    /// CCD detector id, width, orientation, channels.
+    Ccd(usize, f64, Orient, CcdChannels),
     /// A purely reflecting material, with a provided reflectance model.
     Reflector(Reflectance),
+    /// A reflecting material whose albedo is scaled across the surface by a texture.
+    TexturedReflector(Reflectance, Texture),
+    /// A thin translucent sheet, which either reflects or transmits photons.
+    Translucent(Translucent),
+    /// A chain of attributes, applied in order until the photon is killed.
+    AttributeChain(Vec<Self>),
+    /// Hyperspectral volume id.
+    Hyperspectral(usize),
+    /// Radiance detector id.
+    Radiance(usize),
     /// A photon collector, which collects the photon that interact with the linked entities.
     /// These photons can be optionally killed, or left to keep propogating.
     PhotonCollector(usize),
@@ -49,8 +60,15 @@ impl<'a> Link<'a, usize> for AttributeLinkerLinker {
                     .unwrap_or_else(|| panic!("Failed to link attribute-spectrometer key: {}", id)),
             ),
             Self::Imager(id, width, orient) => Self::Inst::Imager(id, width, orient),
-            Self::Ccd(id, width, orient, binner) => Self::Inst::Ccd(id, width, orient, binner),
+            Self::Ccd(id, width, orient, channels) => Self::Inst::Ccd(id, width, orient, channels),
             Self::Reflector(reflectance) => Self::Inst::Reflector(reflectance),
+            Self::TexturedReflector(reflectance, texture) => {
+                Self::Inst::TexturedReflector(reflectance, texture)
+            }
+            Self::Translucent(sheet) => Self::Inst::Translucent(sheet),
+            Self::AttributeChain(attrs) => Self::Inst::AttributeChain(attrs.link(reg)?),
+            Self::Hyperspectral(id) => Self::Inst::Hyperspectral(id),
+            Self::Radiance(id) => Self::Inst::Radiance(id),
             Self::PhotonCollector(id) => Self::Inst::PhotonCollector(id),
         })
     }
@@ -82,12 +100,12 @@ impl Display for AttributeLinkerLinker {
                 fmt_report!(fmt, orient, "orientation");
                 Ok(())
             }
-            Self::Ccd(ref id, width, ref orient, ref binner) => {
+            Self::Ccd(ref id, width, ref orient, ref channels) => {
                 writeln!(fmt, "Ccd: ...")?;
                 fmt_report!(fmt, id, "name");
                 fmt_report!(fmt, width, "width (m)");
                 fmt_report!(fmt, orient, "orientation");
-                fmt_report!(fmt, binner, "binner");
+                fmt_report!(fmt, channels, "channels");
                 Ok(())
             }
             Self::Reflector(ref reflectance) => {
@@ -95,6 +113,30 @@ impl Display for AttributeLinkerLinker {
                 fmt_report!(fmt, reflectance, "reflectance");
                 Ok(())
             }
+            Self::TexturedReflector(ref reflectance, ref texture) => {
+                writeln!(fmt, "Textured Reflector: ...")?;
+                fmt_report!(fmt, reflectance, "reflectance");
+                fmt_report!(fmt, texture, "texture");
+                Ok(())
+            }
+            Self::Translucent(ref sheet) => {
+                writeln!(fmt, "Translucent: ...")?;
+                fmt_report!(fmt, sheet, "sheet");
+                Ok(())
+            }
+            Self::AttributeChain(ref attrs) => {
+                writeln!(fmt, "Attribute Chain: ...")?;
+                for attr in attrs {
+                    fmt_report!(fmt, attr, "attribute");
+                }
+                Ok(())
+            }
+            Self::Hyperspectral(id) => {
+                write!(fmt, "Hyperspectral: {}", id)
+            }
+            Self::Radiance(id) => {
+                write!(fmt, "Radiance: {}", id)
+            }
             Self::PhotonCollector(ref id) => {
                 writeln!(fmt, "Photon Collector: ...")?;
                 fmt_report!(fmt, id, "name");
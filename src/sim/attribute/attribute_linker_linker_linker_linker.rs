@@ -4,11 +4,12 @@ use crate::{
     err::Error,
     fmt_report,
     geom::{Orient, Ray},
+    img::Texture,
     math::{Dir3, Point3, Vec3},
     ord::{Link, Name, Set, X, Y},
-    phys::Reflectance,
-    sim::attribute::AttributeLinkerLinkerLinker,
-    tools::{Binner, Range},
+    phys::{Reflectance, Translucent},
+    sim::{attribute::AttributeLinkerLinkerLinker, CcdChannels},
+    tools::Range,
 };
 use std::fmt::{Display, Formatter};
 
@@ -23,11 +24,21 @@ pub enum AttributeLinkerLinkerLinkerLinker {
     Spectrometer(Name, [f64; 2], usize),
     /// Imager id, resolution, horizontal width (m), center, forward direction.
     Imager(Name, [usize; 2], f64, Point3, Vec3),
-    /// Imager id, resolution, horizontal width (m), center, forward direction, wavelength binner (m).
-    Ccd(Name, [usize; 2], f64, Point3, Vec3, Binner),
+    /// Imager id, resolution, horizontal width (m), center, forward direction, channels.
+    Ccd(Name, [usize; 2], f64, Point3, Vec3, CcdChannels),
     /// A purely reflecting material, with a provided reflectance model.
     /// The first coefficient is diffuse albedo, the second is specular.
     Reflector(Reflectance),
+    /// A reflecting material whose albedo is scaled across the surface by a texture.
+    TexturedReflector(Reflectance, Texture),
+    /// A thin translucent sheet, which either reflects or transmits photons.
+    Translucent(Translucent),
+    /// A chain of attributes, applied in order until the photon is killed.
+    AttributeChain(Vec<Self>),
+    /// Hyperspectral volume id.
+    Hyperspectral(usize),
+    /// Radiance detector id.
+    Radiance(usize),
     /// A photon collector, which collects the photon that interact with the linked entities.
     /// These photons can be optionally killed, or left to keep propogating.
     PhotonCollector(usize),
@@ -52,14 +63,21 @@ impl<'a> Link<'a, usize> for AttributeLinkerLinkerLinkerLinker {
             Self::Imager(id, resolution, width, center, forward) => {
                 Self::Inst::Imager(id, resolution, width, center, forward)
             }
-            Self::Ccd(id, _resolution, width, center, forward, binner) => Self::Inst::Ccd(
+            Self::Ccd(id, _resolution, width, center, forward, channels) => Self::Inst::Ccd(
                 *reg.get(&id)
                     .unwrap_or_else(|| panic!("Failed to link attribute-ccd key: {}", id)),
                 width,
                 Orient::new(Ray::new(center, Dir3::from(forward))),
-                binner,
+                channels,
             ),
             Self::Reflector(reflect) => Self::Inst::Reflector(reflect),
+            Self::TexturedReflector(reflectance, texture) => {
+                Self::Inst::TexturedReflector(reflectance, texture)
+            }
+            Self::Translucent(sheet) => Self::Inst::Translucent(sheet),
+            Self::AttributeChain(attrs) => Self::Inst::AttributeChain(attrs.link(reg)?),
+            Self::Hyperspectral(id) => Self::Inst::Hyperspectral(id),
+            Self::Radiance(id) => Self::Inst::Radiance(id),
             Self::PhotonCollector(id) => Self::Inst::PhotonCollector(id),
         })
     }
@@ -93,14 +111,14 @@ impl Display for AttributeLinkerLinkerLinkerLinker {
                 fmt_report!(fmt, forward, "forward");
                 Ok(())
             }
-            Self::Ccd(ref id, res, width, center, forward, ref binner) => {
+            Self::Ccd(ref id, res, width, center, forward, ref channels) => {
                 writeln!(fmt, "Ccd: ...")?;
                 fmt_report!(fmt, id, "name");
                 fmt_report!(fmt, &format!("[{} x {}]", res[X], res[Y]), "resolution");
                 fmt_report!(fmt, width, "width (m)");
                 fmt_report!(fmt, center, "center (m)");
                 fmt_report!(fmt, forward, "forward");
-                fmt_report!(fmt, binner, "binner");
+                fmt_report!(fmt, channels, "channels");
                 Ok(())
             }
             Self::Reflector(ref reflectance) => {
@@ -108,6 +126,30 @@ impl Display for AttributeLinkerLinkerLinkerLinker {
                 fmt_report!(fmt, reflectance, "reflectance");
                 Ok(())
             }
+            Self::TexturedReflector(ref reflectance, ref texture) => {
+                writeln!(fmt, "Textured Reflector: ...")?;
+                fmt_report!(fmt, reflectance, "reflectance");
+                fmt_report!(fmt, texture, "texture");
+                Ok(())
+            }
+            Self::Translucent(ref sheet) => {
+                writeln!(fmt, "Translucent: ...")?;
+                fmt_report!(fmt, sheet, "sheet");
+                Ok(())
+            }
+            Self::AttributeChain(ref attrs) => {
+                writeln!(fmt, "Attribute Chain: ...")?;
+                for attr in attrs {
+                    fmt_report!(fmt, attr, "attribute");
+                }
+                Ok(())
+            }
+            Self::Hyperspectral(id) => {
+                write!(fmt, "Hyperspectral: {}", id)
+            }
+            Self::Radiance(id) => {
+                write!(fmt, "Radiance: {}", id)
+            }
             Self::PhotonCollector(ref id) => {
                 writeln!(fmt, "Photon Collector: ...")?;
                 fmt_report!(fmt, id, "id");
@@ -1,19 +1,29 @@
 //! Attribute first-stage imager linker.
 
 use crate::{
+    data::Table,
     err::Error,
     fmt_report,
+    fs::{File, Load},
+    geom::GridBuilder,
+    img::Texture,
     math::{Point3, Vec3},
     ord::{Link, Name, Set, X, Y},
-    phys::{Reflectance, SpectrumBuilder},
-    sim::attribute::AttributeLinkerLinkerLinkerLinker,
-    tools::{Binner, Range},
+    phys::{reflectance_spectrum_valid, MeasuredBrdf, Reflectance, SpectrumBuilder, Translucent},
+    sim::{attribute::AttributeLinkerLinkerLinkerLinker, CcdBuilder, PhotonCollectorBuilder},
+    tools::Range,
 };
 use arctk_attr::file;
-use std::fmt::{Display, Formatter};
+use ndarray::Array2;
+use std::{
+    fmt::{Display, Formatter},
+    path::{Path, PathBuf},
+};
 
 /// Surface attribute setup.
 /// Handles detector linking.
+/// Photon collectors, hyperspectral volumes and radiance detectors are linked together,
+/// by a set mapping each name to its index within its own register.
 #[file]
 pub enum AttributeLinkerLinkerLinkerLinkerLinker {
     /// Material interface, inside material name, outside material name.
@@ -22,20 +32,54 @@ pub enum AttributeLinkerLinkerLinkerLinkerLinker {
     Mirror(f64),
     /// Spectrometer id, range, resolution.
     Spectrometer(Name, [f64; 2], usize),
+    /// Photon-counting spectrometer id, range, resolution.
+    /// Poisson counting noise is applied to the counts once the simulation is complete.
+    CountingSpectrometer(Name, [f64; 2], usize),
     /// Imager id, resolution, horizontal width (m), center, forward direction.
     Imager(Name, [usize; 2], f64, Point3, Vec3),
-    /// Imager id, resolution, horizontal width (m), center, forward direction, wavelength binner (m).
-    Ccd(Name, [usize; 2], f64, Point3, Vec3, Binner),
+    /// Imager id, resolution, horizontal width (m), center, forward direction, channels.
+    Ccd(Name, [usize; 2], f64, Point3, Vec3, CcdBuilder),
     /// A purely reflecting material, with a provided reflectance model.
     /// The first coefficient is diffuse albedo, the second is specular.
+    /// Alternatively, an optional trailing path to a measured BRDF table may be given in place of both.
     Reflector(
         Option<SpectrumBuilder>,
         Option<SpectrumBuilder>,
         Option<f64>,
+        #[serde(default)] Option<PathBuf>,
+    ),
+    /// A reflecting material whose albedo is scaled across the surface by a texture.
+    /// The reflectance is given as for a reflector, followed by the texel values,
+    /// listed as columns along the first texture coordinate, and the value used where the surface has no texture coordinates.
+    TexturedReflector(
+        Option<SpectrumBuilder>,
+        Option<SpectrumBuilder>,
+        Option<f64>,
+        Vec<Vec<f64>>,
+        f64,
     ),
+    /// A thin translucent sheet, which either reflects or transmits photons.
+    /// The reflectance is given as for a reflector, followed by the probability of reflection at each wavelength,
+    /// and the fraction of transmitted photons which are diffused.
+    Translucent(
+        Option<SpectrumBuilder>,
+        Option<SpectrumBuilder>,
+        Option<f64>,
+        SpectrumBuilder,
+        f64,
+    ),
+    /// A chain of attributes, applied in order until the photon is killed.
+    AttributeChain(Vec<Self>),
+    /// Hyperspectral volume id, spatial grid, wavelength range [m], number of wavelength channels.
+    /// Photons are recorded into the wavelength channel of the volume at the point of the hit, and killed.
+    Hyperspectral(Name, GridBuilder, [f64; 2], usize),
+    /// Radiance detector id, number of azimuthal and polar angle bins.
+    /// Photons are recorded into the angular bin of their direction of travel, and killed.
+    Radiance(Name, [usize; 2]),
     /// A photon collector, which collects the photon that interact with the linked entities.
-    /// These photons can be optionally killed, or left to keep propogating.
-    PhotonCollector(Name, bool),
+    /// These photons can be optionally killed, or left to keep propogating,
+    /// and may be summarised by histograms of their weight and wavelength.
+    PhotonCollector(Name, PhotonCollectorBuilder),
 }
 
 impl<'a> Link<'a, usize> for AttributeLinkerLinkerLinkerLinkerLinker {
@@ -51,39 +95,61 @@ impl<'a> Link<'a, usize> for AttributeLinkerLinkerLinkerLinkerLinker {
         Ok(match self {
             Self::Interface(inside, outside) => Self::Inst::Interface(inside, outside),
             Self::Mirror(r) => Self::Inst::Mirror(r),
-            Self::Spectrometer(name, range, resolution) => {
+            Self::Spectrometer(name, range, resolution)
+            | Self::CountingSpectrometer(name, range, resolution) => {
                 Self::Inst::Spectrometer(name, range, resolution)
             }
             Self::Imager(id, resolution, width, center, forward) => {
                 Self::Inst::Imager(id, resolution, width, center, forward)
             }
-            Self::Ccd(id, _resolution, width, center, forward, binner) => {
-                Self::Inst::Ccd(id, _resolution, width, center, forward, binner)
+            Self::Ccd(id, _resolution, width, center, forward, channels) => {
+                Self::Inst::Ccd(id, _resolution, width, center, forward, channels.build()?)
             }
-            Self::Reflector(diff_ref, spec_ref, specularity) => {
-                let ref_model = if diff_ref.is_some() {
-                    if spec_ref.is_some() {
-                        // Check that the specularity of the reflector is defined.
-                        assert!(specularity.is_some());
-                        Reflectance::Composite {
-                            diffuse_refspec: diff_ref.unwrap().build()?,
-                            specular_refspec: spec_ref.unwrap().build()?,
-                            specularity: specularity.unwrap(),
-                        }
-                    } else {
-                        Reflectance::Lambertian {
-                            refspec: diff_ref.unwrap().build()?,
-                        }
-                    }
-                } else {
-                    Reflectance::Specular {
-                        refspec: spec_ref.unwrap().build()?,
-                    }
-                };
+            Self::Reflector(diff_ref, spec_ref, specularity, brdf) => {
+                Self::Inst::Reflector(build_reflectance(diff_ref, spec_ref, specularity, brdf)?)
+            }
+            Self::TexturedReflector(diff_ref, spec_ref, specularity, texels, fallback) => {
+                Self::Inst::TexturedReflector(
+                    build_reflectance(diff_ref, spec_ref, specularity, None)?,
+                    build_texture(texels, fallback)?,
+                )
+            }
+            Self::Translucent(diff_ref, spec_ref, specularity, ref_prob, diffusion) => {
+                let ref_prob = ref_prob.build()?;
+                if !reflectance_spectrum_valid(&ref_prob) {
+                    return Err(
+                        "The reflection probability of a translucent sheet must be dimensionless, and between zero and one."
+                            .into(),
+                    );
+                }
+                if !(0.0..=1.0).contains(&diffusion) {
+                    return Err(Error::Text(format!(
+                        "The diffusion of a translucent sheet must be between zero and one: {}",
+                        diffusion
+                    )));
+                }
 
-                Self::Inst::Reflector(ref_model)
+                Self::Inst::Translucent(Translucent::new(
+                    build_reflectance(diff_ref, spec_ref, specularity, None)?,
+                    ref_prob,
+                    diffusion,
+                ))
+            }
+            Self::AttributeChain(attrs) => Self::Inst::AttributeChain(attrs.link(reg)?),
+            Self::Hyperspectral(ref id, ..) => {
+                Self::Inst::Hyperspectral(*reg.get(id).ok_or_else(|| {
+                    Error::Text(format!(
+                        "Failed to link attribute-hyperspectral key: {}",
+                        id
+                    ))
+                })?)
             }
-            Self::PhotonCollector(ref id, _kill_photons) => {
+            Self::Radiance(ref id, _bins) => {
+                Self::Inst::Radiance(*reg.get(id).ok_or_else(|| {
+                    Error::Text(format!("Failed to link attribute-radiance key: {}", id))
+                })?)
+            }
+            Self::PhotonCollector(ref id, _) => {
                 Self::Inst::PhotonCollector(*reg.get(&id).unwrap_or_else(|| {
                     panic!("Failed to link attribute-photon collector key : {}", id)
                 }))
@@ -92,6 +158,88 @@ impl<'a> Link<'a, usize> for AttributeLinkerLinkerLinkerLinkerLinker {
     }
 }
 
+impl Load for AttributeLinkerLinkerLinkerLinkerLinker {
+    type Inst = Self;
+
+    #[inline]
+    fn load(self, in_dir: &Path) -> Result<Self::Inst, Error> {
+        // Measured BRDF tables are found relative to the input directory.
+        Ok(match self {
+            Self::Reflector(diff_ref, spec_ref, specularity, brdf) => Self::Reflector(
+                diff_ref,
+                spec_ref,
+                specularity,
+                brdf.map(|path| in_dir.join(path)),
+            ),
+            Self::AttributeChain(attrs) => Self::AttributeChain(
+                attrs
+                    .into_iter()
+                    .map(|attr| attr.load(in_dir))
+                    .collect::<Result<_, _>>()?,
+            ),
+            attr => attr,
+        })
+    }
+}
+
+/// Build a reflectance model from optional diffuse and specular reflectance spectra,
+/// or from the path to a measured BRDF table.
+/// A composite model is built when both spectra are given, which requires the specularity.
+/// # Errors
+/// if neither spectrum nor a table is given, both spectra and a table are given,
+/// the specularity of a composite model is missing, or a spectrum or table can not be built.
+#[inline]
+fn build_reflectance(
+    diff_ref: Option<SpectrumBuilder>,
+    spec_ref: Option<SpectrumBuilder>,
+    specularity: Option<f64>,
+    brdf: Option<PathBuf>,
+) -> Result<Reflectance, Error> {
+    if let Some(path) = brdf {
+        if diff_ref.is_some() || spec_ref.is_some() {
+            return Err(
+                "A measured reflector can not also be given diffuse or specular reflectance."
+                    .into(),
+            );
+        }
+        let table = Table::load(&path)?;
+        return Ok(Reflectance::new_measured(MeasuredBrdf::from_table(&table)?));
+    }
+
+    Ok(match (diff_ref, spec_ref) {
+        (Some(diff_ref), Some(spec_ref)) => Reflectance::Composite {
+            diffuse_refspec: diff_ref.build()?,
+            specular_refspec: spec_ref.build()?,
+            specularity: specularity
+                .ok_or("The specularity of a composite reflector must be given.")?,
+        },
+        (Some(diff_ref), None) => Reflectance::Lambertian {
+            refspec: diff_ref.build()?,
+        },
+        (None, Some(spec_ref)) => Reflectance::Specular {
+            refspec: spec_ref.build()?,
+        },
+        (None, None) => {
+            return Err("A reflector requires a diffuse or specular reflectance.".into())
+        }
+    })
+}
+
+/// Build a texture from columns of texel values.
+/// # Errors
+/// if there are no texels, or the columns differ in length.
+#[inline]
+fn build_texture(texels: Vec<Vec<f64>>, fallback: f64) -> Result<Texture, Error> {
+    let res = [texels.len(), texels.first().map_or(0, Vec::len)];
+    if res[X] == 0 || res[Y] == 0 || texels.iter().any(|col| col.len() != res[Y]) {
+        return Err("Texture texels must be a non-empty list of columns of equal length.".into());
+    }
+
+    let texels = Array2::from_shape_vec(res, texels.into_iter().flatten().collect())
+        .map_err(|err| Error::Text(err.to_string()))?;
+    Ok(Texture::new(texels, fallback))
+}
+
 impl Display for AttributeLinkerLinkerLinkerLinkerLinker {
     #[inline]
     fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
@@ -111,6 +259,15 @@ impl Display for AttributeLinkerLinkerLinkerLinkerLinker {
                     bins
                 )
             }
+            Self::CountingSpectrometer(ref id, [min, max], bins) => {
+                write!(
+                    fmt,
+                    "Counting spectrometer: {} {} ({})",
+                    id,
+                    Range::new(min, max),
+                    bins
+                )
+            }
             Self::Imager(ref id, res, width, center, forward) => {
                 writeln!(fmt, "Imager: ...")?;
                 fmt_report!(fmt, id, "name");
@@ -120,17 +277,17 @@ impl Display for AttributeLinkerLinkerLinkerLinkerLinker {
                 fmt_report!(fmt, forward, "forward");
                 Ok(())
             }
-            Self::Ccd(ref id, res, width, center, forward, ref binner) => {
+            Self::Ccd(ref id, res, width, center, forward, ref channels) => {
                 writeln!(fmt, "Ccd: ...")?;
                 fmt_report!(fmt, id, "name");
                 fmt_report!(fmt, &format!("[{} x {}]", res[X], res[Y]), "resolution");
                 fmt_report!(fmt, width, "width (m)");
                 fmt_report!(fmt, center, "center (m)");
                 fmt_report!(fmt, forward, "forward");
-                fmt_report!(fmt, binner, "binner");
+                fmt_report!(fmt, channels, "channels");
                 Ok(())
             }
-            Self::Reflector(ref diff_ref, ref spec_ref, ref specularity) => {
+            Self::Reflector(ref diff_ref, ref spec_ref, ref specularity, ref brdf) => {
                 writeln!(fmt, "Reflector: ...")?;
                 fmt_report!(
                     fmt,
@@ -159,14 +316,224 @@ impl Display for AttributeLinkerLinkerLinkerLinkerLinker {
                     },
                     "specularity"
                 );
+                if let Some(ref brdf) = *brdf {
+                    fmt_report!(fmt, brdf.display(), "measured brdf");
+                }
                 Ok(())
             }
-            Self::PhotonCollector(ref id, ref kill_phot) => {
+            Self::TexturedReflector(_, _, _, ref texels, fallback) => {
+                writeln!(fmt, "Textured Reflector: ...")?;
+                fmt_report!(
+                    fmt,
+                    &format!(
+                        "[{} x {}]",
+                        texels.len(),
+                        texels.first().map_or(0, Vec::len)
+                    ),
+                    "texture resolution"
+                );
+                fmt_report!(fmt, fallback, "texture fallback");
+                Ok(())
+            }
+            Self::Translucent(_, _, _, ref ref_prob, diffusion) => {
+                writeln!(fmt, "Translucent: ...")?;
+                fmt_report!(fmt, ref_prob, "reflection probability");
+                fmt_report!(fmt, diffusion, "diffusion");
+                Ok(())
+            }
+            Self::AttributeChain(ref attrs) => {
+                writeln!(fmt, "Attribute Chain: ...")?;
+                for attr in attrs {
+                    fmt_report!(fmt, attr, "attribute");
+                }
+                Ok(())
+            }
+            Self::Hyperspectral(ref id, ref grid, [min, max], bins) => {
+                writeln!(fmt, "Hyperspectral: ...")?;
+                fmt_report!(fmt, id, "name");
+                fmt_report!(fmt, grid, "grid");
+                fmt_report!(fmt, Range::new(min, max), "wavelength range (m)");
+                fmt_report!(fmt, bins, "channels");
+                Ok(())
+            }
+            Self::Radiance(ref id, bins) => {
+                writeln!(fmt, "Radiance: ...")?;
+                fmt_report!(fmt, id, "name");
+                fmt_report!(fmt, &format!("[{} x {}]", bins[X], bins[Y]), "angular bins");
+                Ok(())
+            }
+            Self::PhotonCollector(ref id, ref phot_col) => {
                 writeln!(fmt, "Photon Collector: ...")?;
                 fmt_report!(fmt, id, "name");
-                fmt_report!(fmt, kill_phot, "kill photons?");
+                fmt_report!(fmt, phot_col, "collector");
                 Ok(())
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fs::from_json_str,
+        ord::{Map, Register},
+        phys::Material,
+        sim::Attribute,
+    };
+
+    /// Load an attribute from its configuration, and link it with empty registers and materials.
+    fn load_attribute(json: &str) -> Result<String, Error> {
+        link_attribute(from_json_str(json)?)
+    }
+
+    /// Link an attribute with empty registers and materials.
+    fn link_attribute(attr: AttributeLinkerLinkerLinkerLinkerLinker) -> Result<String, Error> {
+        let reg = Register::new(vec![]);
+        let mats: Set<Material> = Set::new(Map::new());
+
+        let attr = attr
+            .link(reg.set())?
+            .link(reg.set())?
+            .link(reg.set())?
+            .link(reg.set())?
+            .link(&mats)?;
+        Ok(format!("{:?}", attr))
+    }
+
+    #[test]
+    fn test_load_textured_reflector() {
+        let attr = load_attribute(
+            "{ TexturedReflector: [{ Constant: 0.5 }, null, null, [[0.0, 1.0], [0.5, 0.25]], 1.0] }",
+        )
+        .unwrap();
+        assert!(attr.starts_with("TexturedReflector(Lambertian"));
+
+        // Ragged texels are rejected.
+        assert!(load_attribute(
+            "{ TexturedReflector: [{ Constant: 0.5 }, null, null, [[0.0, 1.0], [0.5]], 1.0] }"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_load_measured_reflector() {
+        // A constant BRDF tabulated at the corners of the hemisphere.
+        let write_table = |path: &Path, value: f64| {
+            let mut csv = String::from("theta_in,theta_out,phi,value\n");
+            for theta_in in &[0.0, 90.0] {
+                for theta_out in &[0.0, 90.0] {
+                    for phi in &[0.0, 180.0] {
+                        csv.push_str(&format!("{},{},{},{}\n", theta_in, theta_out, phi, value));
+                    }
+                }
+            }
+            std::fs::write(path, csv).unwrap();
+        };
+        let dir = tempfile::tempdir().unwrap();
+        write_table(&dir.path().join("grey.csv"), 0.2);
+        write_table(&dir.path().join("bright.csv"), 0.5);
+
+        // Table paths are relative to the input directory.
+        let load = |json: &str| {
+            let attr: AttributeLinkerLinkerLinkerLinkerLinker = from_json_str(json).unwrap();
+            link_attribute(attr.load(dir.path()).unwrap())
+        };
+        let attr = load("{ Reflector: [null, null, null, 'grey.csv'] }").unwrap();
+        assert!(attr.starts_with("Reflector(Measured"));
+
+        // Tables reflecting more than they receive are rejected.
+        let err = load("{ Reflector: [null, null, null, 'bright.csv'] }").unwrap_err();
+        assert!(format!("{:?}", err).contains("albedo"));
+
+        // Missing tables are reported, and the measured model can not be mixed with spectra.
+        assert!(load("{ Reflector: [null, null, null, 'missing.csv'] }").is_err());
+        assert!(load("{ Reflector: [{ Constant: 0.5 }, null, null, 'grey.csv'] }").is_err());
+    }
+
+    #[test]
+    fn test_load_translucent() {
+        let attr = load_attribute(
+            "{ Translucent: [{ Constant: 0.8 }, null, null, { Constant: 0.4 }, 0.5] }",
+        )
+        .unwrap();
+        assert!(attr.starts_with("Translucent(Translucent"));
+
+        // Reflection probabilities above one are rejected.
+        assert!(load_attribute(
+            "{ Translucent: [{ Constant: 0.8 }, null, null, { Constant: 1.5 }, 0.5] }"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_load_attribute_chain() {
+        let attr = load_attribute(
+            "{ AttributeChain: [{ Mirror: 0.5 }, { Reflector: [{ Constant: 0.5 }, null, null] }] }",
+        )
+        .unwrap();
+        assert!(attr.starts_with("AttributeChain([Mirror(0.5), Reflector(Lambertian"));
+
+        // Errors within the chain are reported with the position of the failing attribute.
+        let err = load_attribute(
+            "{ AttributeChain: [{ Mirror: 0.5 }, { Reflector: [null, null, null] }] }",
+        )
+        .unwrap_err();
+        assert!(format!("{:?}", err).contains("[1]"));
+    }
+
+    #[test]
+    fn test_load_hyperspectral() {
+        let attr: AttributeLinkerLinkerLinkerLinkerLinker = from_json_str(
+            "{ Hyperspectral: ['cube', { boundary: { mins: [-1, -1, -1], maxs: [1, 1, 1] }, res: [2, 2, 2] }, \
+             [400e-9, 800e-9], 4] }",
+        )
+        .unwrap();
+        let reg = Register::new(vec![Name::new("cube")]);
+        let empty = Register::new(vec![]);
+        let mats: Set<Material> = Set::new(Map::new());
+
+        let attr = attr
+            .link(reg.set())
+            .unwrap()
+            .link(empty.set())
+            .unwrap()
+            .link(empty.set())
+            .unwrap()
+            .link(empty.set())
+            .unwrap()
+            .link(&mats)
+            .unwrap();
+        assert_eq!(attr, Attribute::Hyperspectral(0));
+
+        // Unregistered volumes are reported.
+        assert!(load_attribute(
+            "{ Hyperspectral: ['cube', { boundary: { mins: [-1, -1, -1], maxs: [1, 1, 1] }, res: [2, 2, 2] }, \
+             [400e-9, 800e-9], 4] }"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_load_radiance() {
+        let attr: AttributeLinkerLinkerLinkerLinkerLinker =
+            from_json_str("{ Radiance: ['sky', [8, 4]] }").unwrap();
+        let reg = Register::new(vec![Name::new("sky")]);
+        let empty = Register::new(vec![]);
+        let mats: Set<Material> = Set::new(Map::new());
+
+        let attr = attr
+            .link(reg.set())
+            .unwrap()
+            .link(empty.set())
+            .unwrap()
+            .link(empty.set())
+            .unwrap()
+            .link(empty.set())
+            .unwrap()
+            .link(&mats)
+            .unwrap();
+        assert_eq!(attr, Attribute::Radiance(0));
+        assert!(load_attribute("{ Radiance: ['sky', [8, 4]] }").is_err());
+    }
+}
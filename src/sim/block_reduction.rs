@@ -0,0 +1,159 @@
+//! Ordered reduction of per-block detector buffers.
+
+use crate::{
+    img::{Colour, Image},
+    ord::{X, Y},
+};
+use ndarray::Array3;
+use std::collections::BTreeMap;
+
+/// Index one past the last photon, and the image and CCD contributions, of a single block of photons.
+type BlockBuffers = (usize, Vec<Image>, Vec<Array3<f64>>);
+
+/// Accumulates the image and CCD contributions of blocks of photons in block order,
+/// regardless of the order in which the blocks complete.
+/// Floating-point summation is not associative, so this makes the accumulated pixels
+/// independent of which thread simulated each block.
+/// Blocks completing out of order are held until all preceding blocks have been added,
+/// up to a limited number, and the buffers of added blocks are kept for reuse.
+#[derive(Clone)]
+pub struct BlockReduction {
+    /// Accumulated images.
+    imgs: Vec<Image>,
+    /// Accumulated ccds.
+    ccds: Vec<Array3<f64>>,
+    /// Index of the first photon of the next block to be added.
+    next: usize,
+    /// Completed blocks awaiting their predecessors, keyed by the index of their first photon.
+    pending: BTreeMap<usize, BlockBuffers>,
+    /// Maximum number of blocks held awaiting their predecessors.
+    max_pending: usize,
+    /// Zeroed buffers of added blocks, ready for reuse.
+    spare: Vec<(Vec<Image>, Vec<Array3<f64>>)>,
+}
+
+impl BlockReduction {
+    /// Construct a new instance with zeroed buffers shaped like the given images and ccds,
+    /// holding at most the given number of blocks awaiting their predecessors.
+    #[inline]
+    #[must_use]
+    pub fn new(imgs: &[Image], ccds: &[Array3<f64>], max_pending: usize) -> Self {
+        debug_assert!(max_pending > 0);
+
+        let (imgs, ccds) = Self::blank(imgs, ccds);
+        Self {
+            imgs,
+            ccds,
+            next: 0,
+            pending: BTreeMap::new(),
+            max_pending,
+            spare: Vec::new(),
+        }
+    }
+
+    /// Create zeroed buffers shaped like the given images and ccds.
+    #[inline]
+    #[must_use]
+    pub fn blank(imgs: &[Image], ccds: &[Array3<f64>]) -> (Vec<Image>, Vec<Array3<f64>>) {
+        let zero = Colour::new(0.0, 0.0, 0.0, 0.0);
+        (
+            imgs.iter()
+                .map(|img| {
+                    let res = img.pixels().raw_dim();
+                    Image::new_blank([res[X], res[Y]], zero)
+                })
+                .collect(),
+            ccds.iter()
+                .map(|ccd| Array3::zeros(ccd.raw_dim()))
+                .collect(),
+        )
+    }
+
+    /// Take zeroed buffers for a block, reusing those of an added block where possible.
+    #[inline]
+    #[must_use]
+    pub fn take_buffers(&mut self) -> (Vec<Image>, Vec<Array3<f64>>) {
+        self.spare
+            .pop()
+            .unwrap_or_else(|| Self::blank(&self.imgs, &self.ccds))
+    }
+
+    /// Check whether the block of photons starting at the given index can be submitted.
+    /// The next block to be added is always accepted, others only while fewer than the maximum are held.
+    #[inline]
+    #[must_use]
+    pub fn accepts(&self, start: usize) -> bool {
+        start == self.next || self.pending.len() < self.max_pending
+    }
+
+    /// Submit the buffers of the block of photons `start..end`,
+    /// and add all blocks which are now contiguous with those already added.
+    #[inline]
+    pub fn submit(&mut self, start: usize, end: usize, imgs: Vec<Image>, ccds: Vec<Array3<f64>>) {
+        debug_assert!(start >= self.next);
+        debug_assert!(self.accepts(start));
+
+        let _ = self.pending.insert(start, (end, imgs, ccds));
+        while let Some((end, mut imgs, mut ccds)) = self.pending.remove(&self.next) {
+            for (a, b) in self.imgs.iter_mut().zip(&imgs) {
+                *a += b;
+            }
+            for (a, b) in self.ccds.iter_mut().zip(&ccds) {
+                *a += b;
+            }
+            self.next = end;
+
+            let zero = Colour::new(0.0, 0.0, 0.0, 0.0);
+            for img in &mut imgs {
+                img.pixels_mut().fill(zero);
+            }
+            for ccd in &mut ccds {
+                ccd.fill(0.0);
+            }
+            self.spare.push((imgs, ccds));
+        }
+    }
+
+    /// Retrieve the accumulated images and ccds.
+    #[inline]
+    #[must_use]
+    pub fn finish(self) -> (Vec<Image>, Vec<Array3<f64>>) {
+        debug_assert!(self.pending.is_empty());
+
+        (self.imgs, self.ccds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pending_blocks_bounded() {
+        let one = Image::new_blank([2, 2], Colour::new(1.0, 0.0, 0.0, 1.0));
+        let mut reduction = BlockReduction::new(&[one.clone()], &[], 2);
+
+        // Later blocks are held until the window is full.
+        for start in &[1, 2] {
+            assert!(reduction.accepts(*start));
+            let (mut imgs, ccds) = reduction.take_buffers();
+            imgs[0] += &one;
+            reduction.submit(*start, start + 1, imgs, ccds);
+        }
+        assert!(!reduction.accepts(3));
+
+        // The next block is always accepted, and releases those held.
+        assert!(reduction.accepts(0));
+        let (mut imgs, ccds) = reduction.take_buffers();
+        imgs[0] += &one;
+        reduction.submit(0, 1, imgs, ccds);
+        assert!(reduction.accepts(3));
+
+        // The buffers of added blocks are reused, zeroed.
+        let (imgs, _ccds) = reduction.take_buffers();
+        assert!(imgs[0].pixels().iter().all(|col| col.red == 0.0));
+
+        let (imgs, _ccds) = reduction.finish();
+        assert!(imgs[0].pixels().iter().all(|col| col.red == 3.0));
+    }
+}
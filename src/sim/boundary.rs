@@ -0,0 +1,413 @@
+//! Simulation domain boundary.
+
+use crate::{
+    access, fmt_report,
+    geom::{Cube, Ray},
+    math::{Dir3, Point3},
+    ord::{X, Y, Z},
+    phys::{Photon, Spectrum},
+};
+use rand::Rng;
+use std::{
+    f64::consts::PI,
+    fmt::{Display, Formatter},
+};
+
+/// Face of the boundary domain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BoundaryDirection {
+    /// Negative x face.
+    West,
+    /// Positive x face.
+    East,
+    /// Negative y face.
+    South,
+    /// Positive y face.
+    North,
+    /// Negative z face.
+    Bottom,
+    /// Positive z face.
+    Top,
+}
+
+impl BoundaryDirection {
+    /// All faces, in index order.
+    pub const ALL: [Self; 6] = [
+        Self::West,
+        Self::East,
+        Self::South,
+        Self::North,
+        Self::Bottom,
+        Self::Top,
+    ];
+
+    /// Index of the face.
+    #[inline]
+    #[must_use]
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Axis normal to the face.
+    #[inline]
+    #[must_use]
+    pub const fn axis(self) -> usize {
+        match self {
+            Self::West | Self::East => X,
+            Self::South | Self::North => Y,
+            Self::Bottom | Self::Top => Z,
+        }
+    }
+
+    /// Whether the face lies at the positive end of its axis.
+    #[inline]
+    #[must_use]
+    pub const fn is_positive(self) -> bool {
+        matches!(self, Self::East | Self::North | Self::Top)
+    }
+
+    /// Face on the other side of the domain.
+    #[inline]
+    #[must_use]
+    pub const fn opposite(self) -> Self {
+        match self {
+            Self::West => Self::East,
+            Self::East => Self::West,
+            Self::South => Self::North,
+            Self::North => Self::South,
+            Self::Bottom => Self::Top,
+            Self::Top => Self::Bottom,
+        }
+    }
+}
+
+/// Treatment of photons crossing a face of the boundary.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BoundaryCondition {
+    /// Remove the photon from the simulation.
+    Kill,
+    /// Remove the photon from the simulation, recording the energy it carries out of the domain.
+    Open,
+    /// Re-inject the photon through the opposite face, the given padding distance [m] inside the domain.
+    /// The padding should exceed the bump distance, and be large enough to avoid immediately re-colliding with thin faces.
+    Periodic(f64),
+    /// Semi-infinite background medium, diffusely reflecting photons back into the domain
+    /// with a probability given by the albedo at their wavelength, and absorbing the remainder.
+    Background {
+        /// Albedo spectrum.
+        albedo: Spectrum,
+    },
+}
+
+impl Display for BoundaryCondition {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match *self {
+            Self::Kill => write!(fmt, "Kill"),
+            Self::Open => write!(fmt, "Open"),
+            Self::Periodic(padding) => write!(fmt, "Periodic ({} m padding)", padding),
+            Self::Background { .. } => write!(fmt, "Background"),
+        }
+    }
+}
+
+/// Domain boundary, with a condition applied to photons leaving through each face.
+#[derive(Clone, PartialEq)]
+pub struct Boundary {
+    /// Bounding box of the domain.
+    bounds: Cube,
+    /// Conditions of each face, in `BoundaryDirection` index order.
+    conditions: [BoundaryCondition; 6],
+}
+
+impl Boundary {
+    access!(bounds: Cube);
+    access!(conditions: [BoundaryCondition; 6]);
+
+    /// Construct a new instance applying the same condition to every face.
+    #[inline]
+    #[must_use]
+    pub fn new(bounds: Cube, condition: BoundaryCondition) -> Self {
+        Self {
+            bounds,
+            conditions: [
+                condition.clone(),
+                condition.clone(),
+                condition.clone(),
+                condition.clone(),
+                condition.clone(),
+                condition,
+            ],
+        }
+    }
+
+    /// Construct a new fully periodic instance, with a padding [m] for each axis.
+    #[inline]
+    #[must_use]
+    pub fn new_periodic(bounds: Cube, padding: [f64; 3]) -> Self {
+        let mut boundary = Self::new(bounds, BoundaryCondition::Kill);
+        for dir in &BoundaryDirection::ALL {
+            boundary.conditions[dir.index()] = BoundaryCondition::Periodic(padding[dir.axis()]);
+        }
+        boundary
+    }
+
+    /// Set the condition of a single face.
+    #[inline]
+    #[must_use]
+    pub fn with_face(mut self, dir: BoundaryDirection, condition: BoundaryCondition) -> Self {
+        self.conditions[dir.index()] = condition;
+        self
+    }
+
+    /// Reference the condition of a face.
+    #[inline]
+    #[must_use]
+    pub fn condition(&self, dir: BoundaryDirection) -> &BoundaryCondition {
+        &self.conditions[dir.index()]
+    }
+
+    /// Determine the face through which a position has left the domain, if it has.
+    /// If the position is beyond more than one face, the face it is furthest beyond is returned.
+    #[inline]
+    #[must_use]
+    pub fn crossed_face(&self, pos: &Point3) -> Option<BoundaryDirection> {
+        let mut crossed = None;
+        let mut furthest = 0.0;
+        for dir in &BoundaryDirection::ALL {
+            let axis = dir.axis();
+            let excess = if dir.is_positive() {
+                pos[axis] - self.bounds.maxs()[axis]
+            } else {
+                self.bounds.mins()[axis] - pos[axis]
+            };
+            if excess > furthest {
+                furthest = excess;
+                crossed = Some(*dir);
+            }
+        }
+        crossed
+    }
+
+    /// Move a photon that has left through a face to the opposite face,
+    /// placing it inside the domain by the padding of that axis.
+    #[inline]
+    pub fn set_ray_to_opposite_boundary(
+        &self,
+        phot: &mut Photon,
+        dir: BoundaryDirection,
+        padding: f64,
+    ) {
+        let axis = dir.axis();
+        let pos = phot.ray_mut().pos_mut();
+        pos[axis] = if dir.is_positive() {
+            self.bounds.mins()[axis] + padding
+        } else {
+            self.bounds.maxs()[axis] - padding
+        };
+    }
+
+    /// Diffusely reflect a photon that has left through a face back into the domain.
+    /// The photon is mirrored back inside by the distance it overshot the face,
+    /// and given a cosine-weighted direction about the inward normal.
+    #[inline]
+    pub fn set_ray_diffuse_reflection<R: Rng>(
+        &self,
+        rng: &mut R,
+        phot: &mut Photon,
+        dir: BoundaryDirection,
+    ) {
+        let axis = dir.axis();
+        let (face, sign) = if dir.is_positive() {
+            (self.bounds.maxs()[axis], -1.0)
+        } else {
+            (self.bounds.mins()[axis], 1.0)
+        };
+
+        let mut pos = *phot.ray().pos();
+        pos[axis] = 2.0f64.mul_add(face, -pos[axis]);
+        let mut norm = [0.0; 3];
+        norm[axis] = sign;
+
+        // Cosine-weighted polar angle, and uniform azimuth.
+        let theta = rng.gen::<f64>().sqrt().acos();
+        let phi = rng.gen_range(0.0..(2.0 * PI));
+        let norm = Dir3::new(norm[X], norm[Y], norm[Z]);
+        let mut ray = Ray::new(pos, norm);
+        ray.rotate(theta, phi);
+        ray.offset_from_surface(&norm);
+        *phot.ray_mut() = ray;
+    }
+
+    /// Apply the boundary condition to a photon which may have left the domain.
+    /// Photons still inside the domain are left unchanged.
+    /// If the photon escapes through an open face, the face and the energy it carried out are returned.
+    #[inline]
+    pub fn apply<R: Rng>(
+        &self,
+        rng: &mut R,
+        phot: &mut Photon,
+    ) -> Option<(BoundaryDirection, f64)> {
+        let dir = self.crossed_face(phot.ray().pos())?;
+
+        match *self.condition(dir) {
+            BoundaryCondition::Kill => phot.kill(),
+            BoundaryCondition::Open => {
+                let energy = phot.power() * phot.weight();
+                phot.kill();
+                return Some((dir, energy));
+            }
+            BoundaryCondition::Periodic(padding) => {
+                self.set_ray_to_opposite_boundary(phot, dir, padding);
+            }
+            BoundaryCondition::Background { ref albedo } => {
+                let reflect = albedo
+                    .value_at(phot.wavelength())
+                    .map_or(false, |albedo| rng.gen::<f64>() < albedo);
+                if reflect {
+                    self.set_ray_diffuse_reflection(rng, phot, dir);
+                } else {
+                    phot.kill();
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl Display for Boundary {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        writeln!(fmt, "...")?;
+        fmt_report!(fmt, self.bounds, "bounds");
+        for dir in &BoundaryDirection::ALL {
+            fmt_report!(
+                fmt,
+                self.conditions[dir.index()],
+                &format!("{:?} condition", dir)
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Boundary, BoundaryCondition, BoundaryDirection};
+    use crate::{
+        geom::{Cube, Ray},
+        math::{Dir3, Point3},
+        phys::{Photon, Spectrum},
+    };
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn test_periodic_thin_axis_padding() {
+        let mut rng = rand::thread_rng();
+
+        // Thin in z, wide in x and y.
+        let bounds = Cube::new(Point3::new(0.0, 0.0, 0.0), Point3::new(10.0, 10.0, 0.1));
+        let boundary = Boundary::new_periodic(bounds, [0.5, 0.5, 0.01]);
+
+        // Leaving upwards through the thin axis re-enters at the bottom with the z padding.
+        let mut phot = Photon::new(
+            Ray::new(Point3::new(4.0, 6.0, 0.1001), Dir3::new(0.0, 0.0, 1.0)),
+            500.0e-9,
+            1.0,
+        );
+        boundary.apply(&mut rng, &mut phot);
+        assert_approx_eq!(phot.ray().pos().x(), 4.0);
+        assert_approx_eq!(phot.ray().pos().y(), 6.0);
+        assert_approx_eq!(phot.ray().pos().z(), 0.01);
+        assert!(boundary.bounds().contains(phot.ray().pos()));
+
+        // Leaving through the west face re-enters at the east face with the x padding.
+        let mut phot = Photon::new(
+            Ray::new(Point3::new(-0.001, 6.0, 0.05), Dir3::new(-1.0, 0.0, 0.0)),
+            500.0e-9,
+            1.0,
+        );
+        boundary.apply(&mut rng, &mut phot);
+        assert_approx_eq!(phot.ray().pos().x(), 9.5);
+        assert_approx_eq!(phot.ray().pos().z(), 0.05);
+
+        // Other faces are unaffected by a kill face.
+        let boundary = boundary.with_face(BoundaryDirection::Top, BoundaryCondition::Kill);
+        let mut phot = Photon::new(
+            Ray::new(Point3::new(4.0, 6.0, 0.1001), Dir3::new(0.0, 0.0, 1.0)),
+            500.0e-9,
+            1.0,
+        );
+        boundary.apply(&mut rng, &mut phot);
+        assert_eq!(phot.weight(), 0.0);
+    }
+
+    #[test]
+    fn test_open_escape_by_face() {
+        let bounds = Cube::new(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0));
+        let boundary = Boundary::new(bounds, BoundaryCondition::Open);
+        let mut rng = rand::thread_rng();
+
+        let mut escaped = [0.0; 6];
+        for (n, dir) in BoundaryDirection::ALL.iter().enumerate() {
+            let mut pos = Point3::new(0.0, 0.0, 0.0);
+            pos[dir.axis()] = if dir.is_positive() { 1.001 } else { -1.001 };
+
+            // Fire n + 1 photons, each of weight one half, through each face.
+            for _ in 0..=n {
+                let mut phot = Photon::new(Ray::new(pos, Dir3::new(0.0, 0.0, 1.0)), 500.0e-9, 2.0);
+                *phot.weight_mut() = 0.5;
+
+                let (face, energy) = boundary.apply(&mut rng, &mut phot).unwrap();
+                assert_eq!(face, *dir);
+                assert_eq!(phot.weight(), 0.0);
+                escaped[face.index()] += energy;
+            }
+        }
+
+        for (n, total) in escaped.iter().enumerate() {
+            assert_approx_eq!(*total, (n + 1) as f64);
+        }
+
+        // Photons inside the domain do not escape.
+        let mut phot = Photon::new(
+            Ray::new(Point3::new(0.0, 0.0, 0.0), Dir3::new(0.0, 0.0, 1.0)),
+            500.0e-9,
+            1.0,
+        );
+        assert!(boundary.apply(&mut rng, &mut phot).is_none());
+        assert_eq!(phot.weight(), 1.0);
+    }
+
+    #[test]
+    fn test_background_albedo() {
+        let bounds = Cube::new(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0));
+        let albedo = Spectrum::new_linear(400.0e-9, 800.0e-9, 0.2, 0.6);
+        let boundary = Boundary::new(bounds, BoundaryCondition::Background { albedo });
+        let mut rng = rand::thread_rng();
+
+        let num_phot = 20_000;
+        for dir in &BoundaryDirection::ALL {
+            let mut pos = Point3::new(0.5, 0.5, 0.5);
+            pos[dir.axis()] = if dir.is_positive() { 1.001 } else { -0.001 };
+
+            let mut reflected = 0;
+            for _ in 0..num_phot {
+                let mut phot = Photon::new(Ray::new(pos, Dir3::new(0.0, 0.0, 1.0)), 600.0e-9, 1.0);
+                assert!(boundary.apply(&mut rng, &mut phot).is_none());
+
+                if phot.weight() > 0.0 {
+                    reflected += 1;
+
+                    // Reflected photons re-enter the domain, heading inwards.
+                    assert!(boundary.bounds().contains(phot.ray().pos()));
+                    let sign = if dir.is_positive() { -1.0 } else { 1.0 };
+                    assert!(sign * phot.ray().dir().data()[dir.axis()] > 0.0);
+                }
+            }
+
+            assert_approx_eq!(reflected as f64 / num_phot as f64, 0.4, 0.02);
+        }
+    }
+}
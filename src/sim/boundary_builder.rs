@@ -0,0 +1,196 @@
+//! Domain boundary builder.
+
+use crate::{
+    err::Error,
+    fmt_report,
+    geom::Cube,
+    phys::{reflectance_spectrum_valid, SpectrumBuilder},
+    sim::{Boundary, BoundaryCondition, BoundaryDirection},
+};
+use arctk_attr::file;
+use std::fmt::{Display, Formatter};
+
+/// Loadable boundary condition.
+#[file]
+pub enum BoundaryConditionBuilder {
+    /// Remove the photon from the simulation.
+    Kill,
+    /// Remove the photon from the simulation, recording the energy it carries out of the domain.
+    Open,
+    /// Re-inject the photon through the opposite face, the given padding distance [m] inside the domain.
+    Periodic(f64),
+    /// Reflect the photon diffusely back into the domain with the given albedo, absorbing it otherwise.
+    Background(SpectrumBuilder),
+}
+
+impl BoundaryConditionBuilder {
+    /// Build the condition.
+    /// # Errors
+    /// if a periodic padding is negative,
+    /// or a background albedo can not be built or is not between zero and one.
+    #[inline]
+    pub fn build(&self) -> Result<BoundaryCondition, Error> {
+        Ok(match *self {
+            Self::Kill => BoundaryCondition::Kill,
+            Self::Open => BoundaryCondition::Open,
+            Self::Periodic(padding) => {
+                if padding.is_nan() || padding < 0.0 {
+                    return Err(Error::Text(format!(
+                        "Periodic boundary padding must not be negative: {}",
+                        padding
+                    )));
+                }
+                BoundaryCondition::Periodic(padding)
+            }
+            Self::Background(ref albedo) => {
+                let albedo = albedo.build()?;
+                if !reflectance_spectrum_valid(&albedo) {
+                    return Err(
+                        "The albedo of a background boundary must be dimensionless, and between zero and one."
+                            .into(),
+                    );
+                }
+                BoundaryCondition::Background { albedo }
+            }
+        })
+    }
+}
+
+impl Display for BoundaryConditionBuilder {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match *self {
+            Self::Kill => write!(fmt, "Kill"),
+            Self::Open => write!(fmt, "Open"),
+            Self::Periodic(padding) => write!(fmt, "Periodic ({} m padding)", padding),
+            Self::Background(ref albedo) => write!(fmt, "Background (albedo: {})", albedo),
+        }
+    }
+}
+
+/// Loadable domain boundary, sharing the bounds of the measurement grid.
+/// A single condition is applied to every face, unless a face is given its own.
+#[file]
+pub struct BoundaryBuilder {
+    /// Condition of faces not given their own.
+    condition: BoundaryConditionBuilder,
+    /// Optional condition of the negative x face.
+    west: Option<BoundaryConditionBuilder>,
+    /// Optional condition of the positive x face.
+    east: Option<BoundaryConditionBuilder>,
+    /// Optional condition of the negative y face.
+    south: Option<BoundaryConditionBuilder>,
+    /// Optional condition of the positive y face.
+    north: Option<BoundaryConditionBuilder>,
+    /// Optional condition of the negative z face.
+    bottom: Option<BoundaryConditionBuilder>,
+    /// Optional condition of the positive z face.
+    top: Option<BoundaryConditionBuilder>,
+}
+
+impl BoundaryBuilder {
+    /// Reference the conditions given to individual faces, in `BoundaryDirection` index order.
+    #[inline]
+    #[must_use]
+    fn faces(&self) -> [&Option<BoundaryConditionBuilder>; 6] {
+        [
+            &self.west,
+            &self.east,
+            &self.south,
+            &self.north,
+            &self.bottom,
+            &self.top,
+        ]
+    }
+
+    /// Build the boundary around the given bounds.
+    /// # Errors
+    /// if a condition can not be built.
+    #[inline]
+    pub fn build(&self, bounds: Cube) -> Result<Boundary, Error> {
+        let mut boundary = Boundary::new(
+            bounds,
+            self.condition
+                .build()
+                .map_err(|err| err.with_context("condition"))?,
+        );
+        for (dir, face) in BoundaryDirection::ALL.iter().zip(self.faces().iter()) {
+            if let Some(ref condition) = **face {
+                let condition = condition
+                    .build()
+                    .map_err(|err| err.with_context(&format!("{:?}", dir).to_lowercase()))?;
+                boundary = boundary.with_face(*dir, condition);
+            }
+        }
+
+        Ok(boundary)
+    }
+}
+
+impl Display for BoundaryBuilder {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        writeln!(fmt, "...")?;
+        fmt_report!(fmt, self.condition, "condition");
+        for (dir, face) in BoundaryDirection::ALL.iter().zip(self.faces().iter()) {
+            if let Some(ref condition) = **face {
+                fmt_report!(fmt, condition, &format!("{:?} condition", dir));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fs::from_json_str, math::Point3};
+
+    #[test]
+    fn test_face_conditions() {
+        let builder: BoundaryBuilder =
+            from_json_str("{ condition: 'Open', top: 'Kill', west: { Periodic: 0.01 } }").unwrap();
+        let bounds = Cube::new(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0));
+        let boundary = builder.build(bounds).unwrap();
+
+        assert_eq!(
+            *boundary.condition(BoundaryDirection::Top),
+            BoundaryCondition::Kill
+        );
+        assert_eq!(
+            *boundary.condition(BoundaryDirection::West),
+            BoundaryCondition::Periodic(0.01)
+        );
+        assert_eq!(
+            *boundary.condition(BoundaryDirection::Bottom),
+            BoundaryCondition::Open
+        );
+
+        let builder: BoundaryBuilder =
+            from_json_str("{ condition: 'Open', east: { Periodic: -1.0 } }").unwrap();
+        let bounds = Cube::new(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0));
+        let err = builder.build(bounds).unwrap_err();
+        assert!(format!("{:?}", err).contains("east"));
+    }
+
+    #[test]
+    fn test_background_condition() {
+        let builder: BoundaryBuilder =
+            from_json_str("{ condition: 'Kill', bottom: { Background: { Constant: 0.3 } } }")
+                .unwrap();
+        let bounds = Cube::new(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0));
+        let boundary = builder.build(bounds).unwrap();
+        match *boundary.condition(BoundaryDirection::Bottom) {
+            BoundaryCondition::Background { ref albedo } => {
+                assert_eq!(albedo.value_at(500.0e-9), Some(0.3));
+            }
+            ref other => panic!("Unexpected condition: {}", other),
+        }
+
+        let builder: BoundaryBuilder =
+            from_json_str("{ condition: { Background: { Constant: 1.5 } } }").unwrap();
+        let bounds = Cube::new(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0));
+        let err = builder.build(bounds).unwrap_err();
+        assert!(format!("{:?}", err).contains("condition"));
+    }
+}
@@ -0,0 +1,68 @@
+//! CCD channel builder.
+
+use crate::{err::Error, fmt_report, phys::SpectrumBuilder, sim::CcdChannels, tools::Binner};
+use arctk_attr::file;
+use std::fmt::{Display, Formatter};
+
+/// CCD channel setup.
+/// Channels are given either as wavelength bins, or as a set of spectral responses such as those of a colour sensor.
+#[file]
+pub struct CcdBuilder {
+    /// Optional wavelength binner [m].
+    binner: Option<Binner>,
+    /// Optional spectral response of each channel.
+    responses: Option<Vec<SpectrumBuilder>>,
+}
+
+impl CcdBuilder {
+    /// Number of channels described.
+    #[inline]
+    #[must_use]
+    pub fn num_channels(&self) -> usize {
+        match (&self.responses, &self.binner) {
+            (Some(responses), _) => responses.len(),
+            (None, Some(binner)) => binner.bins(),
+            (None, None) => 0,
+        }
+    }
+
+    /// Build the channels.
+    /// Responses take precedence over a binner.
+    /// # Errors
+    /// if neither a binner nor any responses are given, or a response spectrum can not be built.
+    #[inline]
+    pub fn build(&self) -> Result<CcdChannels, Error> {
+        if let Some(ref responses) = self.responses {
+            if responses.is_empty() {
+                return Err("CCD responses must describe at least one channel.".into());
+            }
+            return Ok(CcdChannels::Response(
+                responses
+                    .iter()
+                    .map(SpectrumBuilder::build)
+                    .collect::<Result<_, _>>()?,
+            ));
+        }
+
+        self.binner
+            .clone()
+            .map(CcdChannels::Binned)
+            .ok_or_else(|| "CCD requires either a binner or channel responses.".into())
+    }
+}
+
+impl Display for CcdBuilder {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        writeln!(fmt, "...")?;
+        if let Some(ref binner) = self.binner {
+            fmt_report!(fmt, binner, "binner (m)");
+        }
+        if let Some(ref responses) = self.responses {
+            for (index, response) in responses.iter().enumerate() {
+                fmt_report!(fmt, response, &format!("channel {} response", index));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,102 @@
+//! CCD detector channels.
+
+use crate::{fmt_report, phys::Spectrum, tools::Binner};
+use ndarray::ArrayViewMut1;
+use std::fmt::{Display, Formatter};
+
+/// Assignment of detected photons to the channels of a CCD pixel.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CcdChannels {
+    /// Contiguous wavelength bins [m], each photon contributing to the bin containing its wavelength.
+    Binned(Binner),
+    /// Channel spectral responses, each photon contributing to every channel weighted by its response at the photon wavelength.
+    Response(Vec<Spectrum>),
+}
+
+impl CcdChannels {
+    /// Number of channels.
+    #[inline]
+    #[must_use]
+    pub fn num_channels(&self) -> usize {
+        match *self {
+            Self::Binned(ref binner) => binner.bins(),
+            Self::Response(ref responses) => responses.len(),
+        }
+    }
+
+    /// Record the energy of a photon of the given wavelength into the channels of a pixel.
+    #[inline]
+    pub fn collect(&self, pixel: &mut ArrayViewMut1<f64>, wavelength: f64, energy: f64) {
+        debug_assert!(pixel.len() == self.num_channels());
+
+        match *self {
+            Self::Binned(ref binner) => {
+                if let Some(bin) = binner.try_bin(wavelength) {
+                    pixel[bin] += energy;
+                }
+            }
+            Self::Response(ref responses) => {
+                for (channel, response) in pixel.iter_mut().zip(responses) {
+                    if let Some(sensitivity) = response.value_at(wavelength) {
+                        *channel += sensitivity * energy;
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Display for CcdChannels {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match *self {
+            Self::Binned(ref binner) => write!(fmt, "Binned: {}", binner),
+            Self::Response(ref responses) => {
+                writeln!(fmt, "Response: ...")?;
+                for (index, response) in responses.iter().enumerate() {
+                    fmt_report!(fmt, response, &format!("channel {}", index));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::Array1;
+
+    #[test]
+    fn test_tophat_responses() {
+        let channels = CcdChannels::Response(vec![
+            Spectrum::new_tophat(600.0e-9, 700.0e-9, 0.8),
+            Spectrum::new_tophat(500.0e-9, 600.0e-9, 0.5),
+            Spectrum::new_tophat(400.0e-9, 500.0e-9, 0.25),
+        ]);
+        assert_eq!(channels.num_channels(), 3);
+
+        for (index, (wavelength, sensitivity)) in
+            [(650.0e-9, 0.8), (550.0e-9, 0.5), (450.0e-9, 0.25)]
+                .iter()
+                .enumerate()
+        {
+            let mut pixel = Array1::zeros(3);
+            channels.collect(&mut pixel.view_mut(), *wavelength, 2.0);
+
+            for channel in 0..3 {
+                let expected = if channel == index {
+                    2.0 * sensitivity
+                } else {
+                    0.0
+                };
+                assert_eq!(pixel[channel], expected);
+            }
+        }
+
+        // Photons outside every response are not recorded.
+        let mut pixel = Array1::zeros(3);
+        channels.collect(&mut pixel.view_mut(), 800.0e-9, 2.0);
+        assert_eq!(pixel.sum(), 0.0);
+    }
+}
@@ -0,0 +1,84 @@
+//! Detection events and callbacks.
+
+use crate::{
+    math::{Dir3, Point3},
+    phys::Photon,
+};
+use std::{
+    fmt::{Debug, Formatter},
+    sync::Arc,
+};
+
+/// Kind of detector at which a photon was detected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetectorKind {
+    /// Spectrometer.
+    Spectrometer,
+    /// Photon collector.
+    PhotonCollector,
+    /// Charge-coupled device.
+    Ccd,
+}
+
+/// Key properties of a detected photon.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Detection {
+    /// Kind of detector.
+    pub kind: DetectorKind,
+    /// Index of the detector within its register.
+    pub id: usize,
+    /// Position of detection.
+    pub pos: Point3,
+    /// Direction of travel.
+    pub dir: Dir3,
+    /// Wavelength [m].
+    pub wavelength: f64,
+    /// Statistical weight.
+    pub weight: f64,
+    /// Power [J/s].
+    pub power: f64,
+}
+
+impl Detection {
+    /// Construct a new instance describing a photon detected by the given detector.
+    #[inline]
+    #[must_use]
+    pub fn new(kind: DetectorKind, id: usize, phot: &Photon) -> Self {
+        Self {
+            kind,
+            id,
+            pos: *phot.ray().pos(),
+            dir: *phot.ray().dir(),
+            wavelength: phot.wavelength(),
+            weight: phot.weight(),
+            power: phot.power(),
+        }
+    }
+}
+
+/// Shareable function invoked on each detection, such as to stream results live.
+/// It is called concurrently from every simulation thread.
+#[derive(Clone)]
+pub struct DetectionCallback(Arc<dyn Fn(&Detection) + Send + Sync>);
+
+impl DetectionCallback {
+    /// Construct a new instance.
+    #[inline]
+    #[must_use]
+    pub fn new<F: Fn(&Detection) + Send + Sync + 'static>(callback: F) -> Self {
+        Self(Arc::new(callback))
+    }
+
+    /// Invoke the callback for a detection.
+    #[inline]
+    pub fn call(&self, detection: &Detection) {
+        (self.0)(detection);
+    }
+}
+
+impl Debug for DetectionCallback {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> std::fmt::Result {
+        write!(fmt, "DetectionCallback")
+    }
+}
@@ -3,11 +3,11 @@
 use crate::{
     math::{Formula, Point3},
     ord::{X, Y},
-    phys::Photon,
+    phys::{Photon, RamanSpectrum},
     sim::{engines, Frame, Input, Output},
 };
 use ndarray::Array3;
-use rand::rngs::ThreadRng;
+use rand::Rng;
 use std::fmt::{Display, Error, Formatter};
 
 /// Engine selection.
@@ -15,8 +15,8 @@ use std::fmt::{Display, Error, Formatter};
 pub enum Engine {
     /// Standard sampling engine.
     Standard,
-    /// Raman engine.
-    Raman(Point3),
+    /// Raman engine, with an optional spectrum of Raman shifts.
+    Raman(Point3, Option<RamanSpectrum>),
     /// Photography engine.
     Photo(Vec<Frame>, [usize; 2]),
     /// Fluorescence engine.
@@ -26,10 +26,12 @@ pub enum Engine {
 impl Engine {
     /// Run the engine for a single photon.
     #[inline]
-    pub fn run(&self, input: &Input, data: &mut Output, rng: &mut ThreadRng, phot: Photon) {
+    pub fn run<R: Rng>(&self, input: &Input, data: &mut Output, rng: &mut R, phot: Photon) {
         match *self {
             Self::Standard => engines::standard(input, data, rng, phot),
-            Self::Raman(ref p) => engines::raman(p, input, data, rng, phot),
+            Self::Raman(ref p, ref spec) => {
+                engines::raman(p, spec.as_ref(), input, data, rng, phot)
+            }
             Self::Photo(ref frames, _res) => engines::photo(frames, input, data, rng, phot),
             Self::Fluorescence(ref shift_map, ref conc_spec) => {
                 engines::fluorescence(shift_map, conc_spec, input, data, rng, phot);
@@ -43,7 +45,7 @@ impl Display for Engine {
     fn fmt(&self, fmt: &mut Formatter) -> Result<(), Error> {
         match *self {
             Self::Standard => write!(fmt, "Standard"),
-            Self::Raman(ref _p) => write!(fmt, "Raman"),
+            Self::Raman(..) => write!(fmt, "Raman"),
             Self::Photo(ref frames, ref res) => write!(
                 fmt,
                 "Photography ({} * [{}x{}])",
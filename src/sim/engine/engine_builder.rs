@@ -3,6 +3,7 @@
 use crate::{
     math::{FormulaBuilder, Point3},
     ord::Build,
+    phys::RamanSpectrum,
     sim::{Engine, FilmBuilder},
 };
 use ndarray::Array3;
@@ -12,8 +13,8 @@ use std::fmt::{Display, Error, Formatter};
 pub enum EngineBuilder {
     /// Standard sampling engine.
     Standard,
-    /// Raman engine.
-    Raman(Point3),
+    /// Raman engine, with an optional spectrum of Raman shifts.
+    Raman(Point3, Option<RamanSpectrum>),
     /// Photography engine.
     Photo(FilmBuilder),
     /// Fluorescence engine.
@@ -27,7 +28,7 @@ impl Build for EngineBuilder {
     fn build(self) -> Self::Inst {
         match self {
             Self::Standard => Self::Inst::Standard,
-            Self::Raman(p) => Self::Inst::Raman(p),
+            Self::Raman(p, spec) => Self::Inst::Raman(p, spec),
             Self::Photo(film) => {
                 let res = film.res();
                 Self::Inst::Photo(film.build(), res)
@@ -44,7 +45,7 @@ impl Display for EngineBuilder {
     fn fmt(&self, fmt: &mut Formatter) -> Result<(), Error> {
         match *self {
             Self::Standard => write!(fmt, "Standard"),
-            Self::Raman(ref _p) => write!(fmt, "Raman"),
+            Self::Raman(..) => write!(fmt, "Raman"),
             Self::Photo(ref _film) => write!(fmt, "Photography"),
             Self::Fluorescence(..) => write!(fmt, "Fluorescence"),
         }
@@ -3,8 +3,8 @@
 use crate::{
     err::Error,
     fs::{File, Load, Redirect},
-    math::{FormulaBuilder, Point3},
-    sim::{EngineBuilder, FilmBuilder},
+    math::FormulaBuilder,
+    sim::{EngineBuilder, FilmBuilder, RamanLoader},
 };
 use arctk_attr::file;
 use ndarray::Array3;
@@ -19,7 +19,7 @@ pub enum EngineBuilderLoader {
     /// Standard sampling engine.
     Standard,
     /// Raman engine.
-    Raman(Point3),
+    Raman(RamanLoader),
     /// Photography engine.
     Photo(FilmBuilder),
     /// Fluorescence engine.
@@ -33,7 +33,10 @@ impl Load for EngineBuilderLoader {
     fn load(self, in_dir: &Path) -> Result<Self::Inst, Error> {
         Ok(match self {
             Self::Standard => Self::Inst::Standard,
-            Self::Raman(p) => Self::Inst::Raman(p),
+            Self::Raman(raman) => {
+                let (p, spec) = raman.load(in_dir)?;
+                Self::Inst::Raman(p, spec)
+            }
             Self::Photo(frames) => Self::Inst::Photo(frames),
             Self::Fluorescence(shift_map, conc_spec) => Self::Inst::Fluorescence(
                 Array3::new_from_file(&in_dir.join(shift_map))?,
@@ -48,7 +51,7 @@ impl Display for EngineBuilderLoader {
     fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
         match *self {
             Self::Standard => write!(fmt, "Standard"),
-            Self::Raman(ref _p) => write!(fmt, "Raman"),
+            Self::Raman(..) => write!(fmt, "Raman"),
             Self::Photo(ref _frames) => write!(fmt, "Photography"),
             Self::Fluorescence(..) => write!(fmt, "Fluorescence"),
         }
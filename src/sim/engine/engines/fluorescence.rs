@@ -4,28 +4,29 @@ use crate::{
     geom::Trace,
     math::Formula,
     phys::{Local, Photon},
-    sim::{scatter::scatter, surface::surface, travel::travel, Event, Input, Output},
+    sim::{scatter::scatter, surface::surface, travel::travel, Event, EventKind, Input, Output},
 };
 use ndarray::Array3;
-use rand::{rngs::ThreadRng, Rng};
+use rand::Rng;
 
 /// Lifetime of a single photon capable of participating in fluorescence.
 #[allow(clippy::expect_used)]
 #[inline]
-pub fn fluorescence(
+pub fn fluorescence<R: Rng>(
     flu_concs: &Array3<f64>,
     flu_spec: &Formula,
     input: &Input,
     mut data: &mut Output,
-    mut rng: &mut ThreadRng,
+    mut rng: &mut R,
     mut phot: Photon,
 ) {
     // Check photon is within the grid.
     if let Some(index) = input.grid.gen_index(phot.ray().pos()) {
-        data.emission[index] += phot.power() * phot.weight();
+        data.emission.add(index, phot.power() * phot.weight());
     } else {
         panic!("Photon was not emitted within the grid.");
     }
+    data.log_event(EventKind::Emission, &phot);
 
     // Common constants.
     let bump_dist = input.sett.bump_dist();
@@ -73,21 +74,34 @@ pub fn fluorescence(
             .dist(phot.ray())
             .expect("Could not determine voxel distance.");
         let scat_dist = -(rng.gen::<f64>()).ln() / env.inter_coeff();
-        let surf_hit = input
-            .tree
-            .scan(phot.ray().clone(), bump_dist, voxel_dist.min(scat_dist));
+        let surf_hit = input.tree.scan_with(
+            phot.ray().clone(),
+            bump_dist,
+            voxel_dist.min(scat_dist),
+            data.traversal.as_mut(),
+        );
 
         // Event handling.
         match Event::new(voxel_dist, scat_dist, surf_hit, bump_dist) {
-            Event::Voxel(dist) => travel(&mut data, &mut phot, &env, index, dist + bump_dist),
+            Event::Voxel(dist) => {
+                travel(&mut data, &mut phot, &env, index, dist + bump_dist);
+                data.log_event(EventKind::Voxel, &phot);
+                if let Some(boundary) = input.boundary {
+                    if let Some((face, energy)) = boundary.apply(&mut rng, &mut phot) {
+                        data.escaped[face.index()] += energy;
+                    }
+                }
+            }
             Event::Scattering(dist) => {
                 travel(&mut data, &mut phot, &env, index, dist);
                 scatter(&mut rng, &mut phot, &env);
+                data.log_event(EventKind::Scattering, &phot);
             }
             Event::Surface(hit) => {
                 travel(&mut data, &mut phot, &env, index, hit.dist());
                 surface(&mut rng, &hit, &mut phot, &mut local, &mut data);
                 travel(&mut data, &mut phot, &env, index, bump_dist);
+                data.log_event(EventKind::Surface, &phot);
             }
         }
 
@@ -95,4 +109,6 @@ pub fn fluorescence(
             break;
         }
     }
+
+    data.log_event(EventKind::Termination, &phot);
 }
@@ -5,28 +5,29 @@ use crate::{
     img::Colour,
     phys::Photon,
     sim::{
-        peel_off::peel_off, scatter::scatter, surface::surface, travel::travel, Event, Frame,
-        Input, Output,
+        peel_off::peel_off_multi, scatter::scatter, surface::surface, travel::travel, Event,
+        EventKind, Frame, Input, Output,
     },
 };
-use rand::{rngs::ThreadRng, Rng};
+use rand::Rng;
 
 /// Photograph the life of a single photon.
 #[allow(clippy::expect_used)]
 #[inline]
-pub fn photo(
+pub fn photo<R: Rng>(
     frames: &[Frame],
     input: &Input,
     mut data: &mut Output,
-    mut rng: &mut ThreadRng,
+    mut rng: &mut R,
     mut phot: Photon,
 ) {
     // Check photon is within the grid.
     if let Some(index) = input.grid.gen_index(phot.ray().pos()) {
-        data.emission[index] += phot.power() * phot.weight();
+        data.emission.add(index, phot.power() * phot.weight());
     } else {
         panic!("Photon was not emitted within the grid.");
     }
+    data.log_event(EventKind::Emission, &phot);
 
     // Common constants.
     let bump_dist = input.sett.bump_dist();
@@ -39,6 +40,7 @@ pub fn photo(
     let phot_col = wavelength_to_rbg(phot.wavelength());
     let mat = input.light.mat();
     let mut env = mat.sample_environment(phot.wavelength());
+    let mut samples = vec![Vec::new(); frames.len()];
 
     // Main event loop.
     let mut num_loops = 0;
@@ -64,37 +66,63 @@ pub fn photo(
             .dist(phot.ray())
             .expect("Could not determine voxel distance.");
         let scat_dist = -(rng.gen::<f64>()).ln() / env.inter_coeff();
-        let surf_hit = input
-            .tree
-            .scan(phot.ray().clone(), bump_dist, voxel_dist.min(scat_dist));
+        let surf_hit = input.tree.scan_with(
+            phot.ray().clone(),
+            bump_dist,
+            voxel_dist.min(scat_dist),
+            data.traversal.as_mut(),
+        );
 
         // Event handling.
         match Event::new(voxel_dist, scat_dist, surf_hit, bump_dist) {
-            Event::Voxel(dist) => travel(&mut data, &mut phot, &env, index, dist + bump_dist),
+            Event::Voxel(dist) => {
+                travel(&mut data, &mut phot, &env, index, dist + bump_dist);
+                data.log_event(EventKind::Voxel, &phot);
+                if let Some(boundary) = input.boundary {
+                    if let Some((face, energy)) = boundary.apply(&mut rng, &mut phot) {
+                        data.escaped[face.index()] += energy;
+                    }
+                }
+            }
             Event::Scattering(dist) => {
                 travel(&mut data, &mut phot, &env, index, dist);
 
-                // Capture.
-                for (frame, photo) in frames.iter().zip(data.photos.iter_mut()) {
-                    if let Some([x, y]) = frame.transform(phot.ray().pos()) {
-                        if let Some(weight) = peel_off(input, phot.clone(), &env, *frame.pos()) {
-                            photo.pixels_mut()[[x, y]] +=
-                                Colour::new(
-                                    phot_col[0] as f32,
-                                    phot_col[1] as f32,
-                                    phot_col[2] as f32,
-                                    1.0,
-                                ) * (phot.power() * phot.weight() * weight) as f32;
+                // Capture, peeling off towards every frame the scattering point is visible in at once.
+                let captured: Vec<_> = frames
+                    .iter()
+                    .enumerate()
+                    .map(|(n, frame)| (n, frame.splat(phot.ray().pos())))
+                    .filter(|(_, pixels)| !pixels.is_empty())
+                    .collect();
+                let targets: Vec<_> = captured.iter().map(|(n, _)| *frames[*n].pos()).collect();
+                let weights = peel_off_multi(input, &phot, &env, &targets);
+
+                for ((n, pixels), weight) in captured.into_iter().zip(weights) {
+                    if let Some(weight) = weight {
+                        let value = phot.power() * phot.weight() * weight;
+                        for (pixel, frac) in pixels {
+                            data.photos[n].pixels_mut()[pixel] += Colour::new(
+                                phot_col[0] as f32,
+                                phot_col[1] as f32,
+                                phot_col[2] as f32,
+                                1.0,
+                            ) * (value * frac) as f32;
+
+                            if let Some(&Some(_)) = data.photo_vars.get(n) {
+                                samples[n].push((pixel, value * frac));
+                            }
                         }
-                    };
+                    }
                 }
 
                 scatter(&mut rng, &mut phot, &env);
+                data.log_event(EventKind::Scattering, &phot);
             }
             Event::Surface(hit) => {
                 travel(&mut data, &mut phot, &env, index, hit.dist());
                 surface(&mut rng, &hit, &mut phot, &mut env, &mut data);
                 travel(&mut data, &mut phot, &env, index, bump_dist);
+                data.log_event(EventKind::Surface, &phot);
             }
         }
 
@@ -102,6 +130,15 @@ pub fn photo(
             break;
         }
     }
+
+    // The photon is a single sample of each tracked image, which is zero wherever it made no contribution.
+    for (var, samples) in data.photo_vars.iter_mut().zip(samples.iter_mut()) {
+        if let Some(ref mut var) = *var {
+            var.collect_image(samples);
+        }
+    }
+
+    data.log_event(EventKind::Termination, &phot);
 }
 
 /// Generate the RGB components of a given wavelength.
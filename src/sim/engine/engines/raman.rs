@@ -3,27 +3,35 @@
 use crate::{
     geom::Trace,
     math::Point3,
-    phys::Photon,
-    sim::{scatter::shift_scatter, surface::surface, travel::travel, Event, Input, Output},
+    phys::{Photon, RamanSpectrum},
+    sim::{
+        scatter::{raman_scatter, shift_scatter},
+        surface::surface,
+        travel::travel,
+        Event, EventKind, Input, Output,
+    },
 };
-use rand::{rngs::ThreadRng, Rng};
+use rand::Rng;
 
 /// Simulate the life of a single photon which has the potential to generate a Raman photon.
+/// Without a Raman spectrum, shifted photons are removed from the simulation.
 #[allow(clippy::expect_used)]
 #[inline]
-pub fn raman(
+pub fn raman<R: Rng>(
     _detector_pos: &Point3,
+    spec: Option<&RamanSpectrum>,
     input: &Input,
     mut data: &mut Output,
-    mut rng: &mut ThreadRng,
+    mut rng: &mut R,
     mut phot: Photon,
 ) {
     // Check photon is within the grid.
     if let Some(index) = input.grid.gen_index(phot.ray().pos()) {
-        data.emission[index] += phot.power() * phot.weight();
+        data.emission.add(index, phot.power() * phot.weight());
     } else {
         panic!("Photon was not emitted within the grid.");
     }
+    data.log_event(EventKind::Emission, &phot);
 
     // Common constants.
     let bump_dist = input.sett.bump_dist();
@@ -61,13 +69,24 @@ pub fn raman(
             .dist(phot.ray())
             .expect("Could not determine voxel distance.");
         let scat_dist = -(rng.gen::<f64>()).ln() / env.inter_coeff();
-        let surf_hit = input
-            .tree
-            .scan(phot.ray().clone(), bump_dist, voxel_dist.min(scat_dist));
+        let surf_hit = input.tree.scan_with(
+            phot.ray().clone(),
+            bump_dist,
+            voxel_dist.min(scat_dist),
+            data.traversal.as_mut(),
+        );
 
         // Event handling.
         match Event::new(voxel_dist, scat_dist, surf_hit, bump_dist) {
-            Event::Voxel(dist) => travel(&mut data, &mut phot, &env, index, dist + bump_dist),
+            Event::Voxel(dist) => {
+                travel(&mut data, &mut phot, &env, index, dist + bump_dist);
+                data.log_event(EventKind::Voxel, &phot);
+                if let Some(boundary) = input.boundary {
+                    if let Some((face, energy)) = boundary.apply(&mut rng, &mut phot) {
+                        data.escaped[face.index()] += energy;
+                    }
+                }
+            }
             Event::Scattering(dist) => {
                 travel(&mut data, &mut phot, &env, index, dist);
 
@@ -78,12 +97,17 @@ pub fn raman(
                 //     detected_weight += weight;
                 // }
 
-                shift_scatter(&mut rng, &mut phot, &env);
+                match spec {
+                    Some(spec) => raman_scatter(&mut rng, &mut phot, &env, spec),
+                    None => shift_scatter(&mut rng, &mut phot, &env),
+                }
+                data.log_event(EventKind::Scattering, &phot);
             }
             Event::Surface(hit) => {
                 travel(&mut data, &mut phot, &env, index, hit.dist());
                 surface(&mut rng, &hit, &mut phot, &mut env, &mut data);
                 travel(&mut data, &mut phot, &env, index, bump_dist);
+                data.log_event(EventKind::Surface, &phot);
             }
         }
 
@@ -91,6 +115,8 @@ pub fn raman(
             break;
         }
     }
+
+    data.log_event(EventKind::Termination, &phot);
 }
 
 // //! Raman specialised photon-lifetime engine function.
@@ -178,7 +204,7 @@ pub fn raman(
 //                 if let Some(attr) = uni.attrs.map().get(*hit.tag()) {
 //                     match *attr {
 //                         Attributes::Spectrometer => {
-//                             data.spec.collect_weight(phot.wavelength(), phot.weight()*phot.power());
+//                             data.spec.collect_weighted(phot.wavelength(), phot.weight()*phot.power());
 //                         }
 //                         Attributes::Mirror => {
 //                             *phot.ray_mut().dir_mut() =
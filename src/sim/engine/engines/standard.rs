@@ -2,22 +2,62 @@
 
 use crate::{
     geom::Trace,
-    phys::Photon,
-    sim::{scatter::scatter, surface::surface, travel::travel, Event, Input, Output},
+    phys::{Local, Photon},
+    sim::{
+        scatter::{forced_scatter_dist, interaction_prob, scatter},
+        surface::surface,
+        travel::travel,
+        Event, EventKind, Input, Output,
+    },
 };
-use rand::{rngs::ThreadRng, Rng};
+use rand::Rng;
 
 /// Simulate the life of a single photon.
 #[allow(clippy::expect_used)]
 #[inline]
-pub fn standard(input: &Input, mut data: &mut Output, mut rng: &mut ThreadRng, mut phot: Photon) {
+pub fn standard<R: Rng>(input: &Input, data: &mut Output, rng: &mut R, phot: Photon) {
     // Check photon is within the grid.
     if let Some(index) = input.grid.gen_index(phot.ray().pos()) {
-        data.emission[index] += phot.power() * phot.weight();
+        data.emission.add(index, phot.power() * phot.weight());
     } else {
         panic!("Photon was not emitted within the grid.");
     }
+    data.log_event(EventKind::Emission, &phot);
 
+    // Initialisation.
+    let mat = input.light.mat();
+    let env = mat.sample_environment(phot.wavelength());
+    data.split_budget = input.sett.max_splits().unwrap_or(0);
+    if let Some(ref mut tallies) = data.tallies {
+        tallies.enter(mat);
+    }
+
+    // Simulate the photon, followed by any packets split from it at interfaces.
+    let force = input.sett.forced_scattering().unwrap_or(false);
+    trace(input, data, rng, phot, env, force, 0.0);
+    while let Some((phot, env, free_dist)) = data.spawned.pop() {
+        if let Some(ref mut tallies) = data.tallies {
+            tallies.resume();
+        }
+        trace(input, data, rng, phot, env, false, free_dist);
+    }
+}
+
+/// Simulate a single packet from its current position until it leaves the grid or is terminated.
+/// If forced, the packet is split into a part which scatters at least once before leaving its initial medium,
+/// and a spawned part which leaves it unscattered.
+/// The packet does not interact with the medium until it has travelled the given free distance [m].
+#[allow(clippy::expect_used)]
+#[inline]
+fn trace<R: Rng>(
+    input: &Input,
+    mut data: &mut Output,
+    mut rng: &mut R,
+    mut phot: Photon,
+    mut env: Local,
+    mut force: bool,
+    mut free_dist: f64,
+) {
     // Common constants.
     let bump_dist = input.sett.bump_dist();
     let loop_limit = input.sett.loop_limit();
@@ -25,11 +65,8 @@ pub fn standard(input: &Input, mut data: &mut Output, mut rng: &mut ThreadRng, m
     let roulette_barrels = input.sett.roulette_barrels() as f64;
     let roulette_survive_prob = 1.0 / roulette_barrels;
 
-    // Initialisation.
-    let mat = input.light.mat();
-    let mut env = mat.sample_environment(phot.wavelength());
-
     // Main event loop.
+    let mut compensate = force;
     let mut num_loops = 0;
     while let Some((index, voxel)) = input.grid.gen_index_voxel(phot.ray().pos()) {
         // Loop limit check.
@@ -52,22 +89,67 @@ pub fn standard(input: &Input, mut data: &mut Output, mut rng: &mut ThreadRng, m
         let voxel_dist = voxel
             .dist(phot.ray())
             .expect("Could not determine voxel distance.");
-        let scat_dist = -(rng.gen::<f64>()).ln() / env.inter_coeff();
-        let surf_hit = input
-            .tree
-            .scan(phot.ray().clone(), bump_dist, voxel_dist.min(scat_dist));
+        let scat_dist = if force && env.inter_coeff() > 0.0 {
+            // Path length remaining before leaving the medium, at the next surface or the grid boundary.
+            let exit_dist = input.grid.boundary().dist(phot.ray()).unwrap_or(voxel_dist);
+            let exit_dist = input
+                .tree
+                .scan(phot.ray().clone(), bump_dist, exit_dist)
+                .map_or(exit_dist, |hit| hit.dist());
+
+            // Split off the part of the packet which crosses the medium without interacting, for an unbiased estimate.
+            if compensate {
+                let prob = interaction_prob(&env, exit_dist);
+                let mut unscattered = phot.clone();
+                *unscattered.weight_mut() *= 1.0 - prob;
+                data.spawned.push((unscattered, env.clone(), exit_dist));
+                if let Some(ref mut tallies) = data.tallies {
+                    tallies.spawn();
+                }
+
+                *phot.weight_mut() *= prob;
+                compensate = false;
+            }
+            forced_scatter_dist(&mut rng, &env, exit_dist)
+        } else {
+            free_dist - (rng.gen::<f64>()).ln() / env.inter_coeff()
+        };
+        let surf_hit = input.tree.scan_with(
+            phot.ray().clone(),
+            bump_dist,
+            voxel_dist.min(scat_dist),
+            data.traversal.as_mut(),
+        );
 
         // Event handling.
         match Event::new(voxel_dist, scat_dist, surf_hit, bump_dist) {
-            Event::Voxel(dist) => travel(&mut data, &mut phot, &env, index, dist + bump_dist),
+            Event::Voxel(dist) => {
+                travel(&mut data, &mut phot, &env, index, dist + bump_dist);
+                free_dist = (free_dist - dist - bump_dist).max(0.0);
+                data.log_event(EventKind::Voxel, &phot);
+                if let Some(boundary) = input.boundary {
+                    if let Some((face, energy)) = boundary.apply(&mut rng, &mut phot) {
+                        data.escaped[face.index()] += energy;
+                    }
+                }
+            }
             Event::Scattering(dist) => {
                 travel(&mut data, &mut phot, &env, index, dist);
+                if let Some(ref mut tallies) = data.tallies {
+                    tallies.record(phot.weight() * (1.0 - env.albedo()));
+                }
                 scatter(&mut rng, &mut phot, &env);
+                data.log_event(EventKind::Scattering, &phot);
+                force = false;
+                free_dist = 0.0;
             }
             Event::Surface(hit) => {
                 travel(&mut data, &mut phot, &env, index, hit.dist());
                 surface(&mut rng, &hit, &mut phot, &mut env, &mut data);
                 travel(&mut data, &mut phot, &env, index, bump_dist);
+                data.log_event(EventKind::Surface, &phot);
+                force = false;
+                free_dist = 0.0;
             }
         }
 
@@ -75,4 +157,79 @@ pub fn standard(input: &Input, mut data: &mut Output, mut rng: &mut ThreadRng, m
             break;
         }
     }
+
+    data.log_event(EventKind::Termination, &phot);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        geom::{Cube, Grid, Ray},
+        math::{Dir3, Point3},
+        ord::{Map, Name, Register, Set},
+        sim::{
+            fixture::{self, WAVELENGTH},
+            Attribute, Boundary, BoundaryCondition, BoundaryDirection,
+        },
+    };
+
+    #[test]
+    fn test_forced_scattering_conserves_energy() {
+        // A single mirror well away from the domain.
+        let norm = Dir3::new(0.0, 0.0, 1.0);
+        let mirror = Attribute::Mirror(0.5);
+        let surfs = fixture::far_mirror(&mirror);
+        let tree = fixture::single_cell_tree(&surfs);
+
+        // An optically thin, purely scattering slab.
+        let name = Name::new("slab");
+        let mats = fixture::material_set(&name, fixture::material(0.1, None, 0.0));
+        let mat = mats.get(&name).unwrap();
+
+        let reg = Register::new(vec![]);
+        let attrs = Set::new(Map::new());
+        let ray = Ray::new(Point3::new(0.0, 0.0, 1.0e-9), norm);
+        let bounds = Cube::new(
+            Point3::new(-100.0, -100.0, 0.0),
+            Point3::new(100.0, 100.0, 1.0),
+        );
+        let grid = Grid::new(bounds.clone(), [1, 1, 1]);
+        let boundary = Boundary::new(bounds.clone(), BoundaryCondition::Open);
+
+        // Fraction of the emitted energy escaping through each face.
+        let num_phot = 10_000;
+        let run = |forced| {
+            let light = fixture::beam(1.0, ray.clone(), mat);
+            let sett = fixture::settings(&format!("num_phot: 1, forced_scattering: {}", forced));
+            let input = Input::new(&reg, &mats, &attrs, light, &tree, &grid, &sett)
+                .with_boundary(&boundary);
+            let mut data = fixture::blank_output(bounds.clone(), [1, 1, 1], &reg);
+
+            let mut rng = rand::thread_rng();
+            for _ in 0..num_phot {
+                standard(
+                    &input,
+                    &mut data,
+                    &mut rng,
+                    Photon::new(ray.clone(), WAVELENGTH, 1.0),
+                );
+            }
+            data.escaped
+                .iter()
+                .map(|energy| energy / f64::from(num_phot))
+                .collect::<Vec<_>>()
+        };
+
+        // Without absorption all of the energy escapes, including the part which crosses the slab unscattered.
+        let unforced = run(false);
+        let forced = run(true);
+        assert!((forced.iter().sum::<f64>() - 1.0).abs() < 1.0e-9);
+        assert!((unforced.iter().sum::<f64>() - 1.0).abs() < 1.0e-9);
+
+        let top = BoundaryDirection::Top.index();
+        let std_err = (unforced[top] * (1.0 - unforced[top]) / f64::from(num_phot)).sqrt();
+        assert!(forced[top] > (-0.1_f64).exp());
+        assert!((forced[top] - unforced[top]).abs() < 4.0 * std_err);
+    }
 }
@@ -6,8 +6,11 @@
 pub mod engine;
 pub mod engine_builder;
 pub mod engine_builder_loader;
+pub mod raman_loader;
 
 // Provide our pre-built engines.
 pub mod engines;
 
-pub use self::{engine::*, engine_builder::*, engine_builder_loader::*, engines::*};
+pub use self::{
+    engine::*, engine_builder::*, engine_builder_loader::*, engines::*, raman_loader::*,
+};
@@ -0,0 +1,59 @@
+//! Loadable Raman engine settings.
+
+use crate::{
+    err::Error,
+    fs::{File, Load},
+    math::Point3,
+    phys::RamanSpectrum,
+};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Loadable Raman engine settings.
+/// May be given as the detector position alone, removing shifted photons,
+/// or together with a path to a table of Raman shifts [cm^-1] and probabilities.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum RamanLoader {
+    /// Detector position.
+    Position(Point3),
+    /// Detector position, and path to the table of Raman shifts.
+    Spectrum(Point3, PathBuf),
+}
+
+impl Load for RamanLoader {
+    type Inst = (Point3, Option<RamanSpectrum>);
+
+    #[inline]
+    fn load(self, in_dir: &Path) -> Result<Self::Inst, Error> {
+        Ok(match self {
+            Self::Position(p) => (p, None),
+            Self::Spectrum(p, path) => (p, Some(RamanSpectrum::new_from_file(&in_dir.join(path))?)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fs::from_json_str, sim::EngineBuilderLoader};
+
+    #[test]
+    fn test_position_only_form() {
+        let loader: EngineBuilderLoader = from_json_str("{ Raman: [0.0, 1.0, 2.0] }").unwrap();
+        match loader {
+            EngineBuilderLoader::Raman(RamanLoader::Position(p)) => assert_eq!(p.y(), 1.0),
+            _ => panic!("Expected a Raman engine without a spectrum."),
+        }
+
+        let loader: EngineBuilderLoader =
+            from_json_str("{ Raman: [[0.0, 1.0, 2.0], 'shifts.csv'] }").unwrap();
+        match loader {
+            EngineBuilderLoader::Raman(RamanLoader::Spectrum(p, path)) => {
+                assert_eq!(p.z(), 2.0);
+                assert_eq!(path, PathBuf::from("shifts.csv"));
+            }
+            _ => panic!("Expected a Raman engine with a spectrum."),
+        }
+    }
+}
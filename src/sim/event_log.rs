@@ -0,0 +1,225 @@
+//! Photon event trace log.
+
+use crate::{
+    access, clone,
+    err::Error,
+    fmt_report,
+    fs::Save,
+    math::{Dir3, Point3},
+    phys::Photon,
+};
+use std::{
+    fmt::{Display, Formatter},
+    fs::File,
+    io::Write,
+    ops::AddAssign,
+    path::Path,
+};
+
+/// Kinds of traced photon event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventKind {
+    /// Photon emission.
+    Emission,
+    /// Voxel boundary crossing.
+    Voxel,
+    /// Scattering event.
+    Scattering,
+    /// Surface interaction.
+    Surface,
+    /// End of the photon's life.
+    Termination,
+}
+
+impl Display for EventKind {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        let kind = match *self {
+            Self::Emission => "emission",
+            Self::Voxel => "voxel",
+            Self::Scattering => "scattering",
+            Self::Surface => "surface",
+            Self::Termination => "termination",
+        };
+        write!(fmt, "{}", kind)
+    }
+}
+
+/// Snapshot of a photon's state at an event.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EventRecord {
+    /// Photon id.
+    phot_id: usize,
+    /// Kind of event.
+    kind: EventKind,
+    /// Position [m].
+    pos: Point3,
+    /// Direction.
+    dir: Dir3,
+    /// Wavelength [m].
+    wavelength: f64,
+    /// Statistical weight.
+    weight: f64,
+}
+
+impl EventRecord {
+    clone!(phot_id: usize);
+    clone!(kind: EventKind);
+    access!(pos: Point3);
+    access!(dir: Dir3);
+    clone!(wavelength: f64);
+    clone!(weight: f64);
+
+    /// Construct a new instance from the current state of a photon.
+    #[inline]
+    #[must_use]
+    pub fn new(phot_id: usize, kind: EventKind, phot: &Photon) -> Self {
+        Self {
+            phot_id,
+            kind,
+            pos: *phot.ray().pos(),
+            dir: *phot.ray().dir(),
+            wavelength: phot.wavelength(),
+            weight: phot.weight(),
+        }
+    }
+}
+
+/// Trace log of the events of a sampled subset of photons.
+/// Every photon whose id is a multiple of the interval is traced.
+#[derive(Clone, Debug)]
+pub struct EventLog {
+    /// Photon sampling interval.
+    interval: usize,
+    /// Id of the photon currently being traced.
+    current: Option<usize>,
+    /// Recorded events.
+    records: Vec<EventRecord>,
+}
+
+impl EventLog {
+    clone!(interval: usize);
+    access!(records: Vec<EventRecord>);
+
+    /// Construct a new instance.
+    #[inline]
+    #[must_use]
+    pub fn new(interval: usize) -> Self {
+        debug_assert!(interval > 0);
+
+        Self {
+            interval,
+            current: None,
+            records: Vec::new(),
+        }
+    }
+
+    /// Begin the life of a new photon, tracing it if it falls within the sampled subset.
+    #[inline]
+    pub fn begin(&mut self, phot_id: usize) {
+        self.current = if phot_id % self.interval == 0 {
+            Some(phot_id)
+        } else {
+            None
+        };
+    }
+
+    /// Record an event of the current photon, if it is being traced.
+    #[inline]
+    pub fn record(&mut self, kind: EventKind, phot: &Photon) {
+        if let Some(phot_id) = self.current {
+            self.records.push(EventRecord::new(phot_id, kind, phot));
+        }
+    }
+}
+
+impl AddAssign<&Self> for EventLog {
+    #[inline]
+    fn add_assign(&mut self, rhs: &Self) {
+        self.records.extend_from_slice(&rhs.records);
+    }
+}
+
+impl Save for EventLog {
+    /// Records are written in photon order, preserving the event sequence of each photon.
+    #[inline]
+    fn save_data(&self, path: &Path) -> Result<(), Error> {
+        let mut records: Vec<_> = self.records.iter().collect();
+        records.sort_by_key(|rec| rec.phot_id);
+
+        let mut file = File::create(path)?;
+        writeln!(
+            file,
+            "photon,event,pos_x,pos_y,pos_z,dir_x,dir_y,dir_z,wavelength,weight"
+        )?;
+        for rec in records {
+            writeln!(
+                file,
+                "{},{},{},{},{},{},{},{},{},{}",
+                rec.phot_id,
+                rec.kind,
+                rec.pos.x(),
+                rec.pos.y(),
+                rec.pos.z(),
+                rec.dir.x(),
+                rec.dir.y(),
+                rec.dir.z(),
+                rec.wavelength,
+                rec.weight,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for EventLog {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        writeln!(fmt, "...")?;
+        fmt_report!(fmt, self.interval, "photon sampling interval");
+        fmt_report!(fmt, self.records.len(), "recorded events");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geom::Ray;
+
+    #[test]
+    fn test_trace_single_photon() {
+        let mut log = EventLog::new(2);
+        let mut phot = Photon::new(
+            Ray::new(Point3::new(0.0, 0.0, 0.0), Dir3::new(1.0, 0.0, 0.0)),
+            500.0e-9,
+            1.0,
+        );
+
+        // Photon 0 is traced.
+        log.begin(0);
+        log.record(EventKind::Emission, &phot);
+        phot.ray_mut().travel(1.0);
+        log.record(EventKind::Scattering, &phot);
+        *phot.weight_mut() = 0.5;
+        log.record(EventKind::Termination, &phot);
+
+        // Photon 1 is not.
+        log.begin(1);
+        log.record(EventKind::Emission, &phot);
+
+        let kinds: Vec<_> = log.records().iter().map(EventRecord::kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                EventKind::Emission,
+                EventKind::Scattering,
+                EventKind::Termination
+            ]
+        );
+        assert!(log.records().iter().all(|rec| rec.phot_id() == 0));
+        assert_eq!(log.records()[1].pos().x(), 1.0);
+        assert_eq!(log.records()[2].weight(), 0.5);
+    }
+}
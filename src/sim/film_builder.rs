@@ -2,10 +2,11 @@
 
 use crate::{
     clone,
-    geom::Track,
-    math::{Mat4, Vec3},
+    geom::{Orient, Track, Trajectory},
+    img::SplatKernel,
+    math::{Mat4, Point3, Vec3},
     ord::{Build, X, Y},
-    sim::Frame,
+    sim::{Adaptive, Frame},
 };
 use arctk_attr::file;
 
@@ -28,6 +29,14 @@ pub struct FilmBuilder {
     res: [usize; 2],
     /// Frames.
     frames: i32,
+    /// Optionally track the per-pixel variance of each frame.
+    variance: Option<bool>,
+    /// Optional anti-aliasing kernel used to spread samples over neighbouring pixels.
+    kernel: Option<SplatKernel>,
+    /// Optional light source keyframes (position, target), interpolated across the frames.
+    source: Option<Vec<[Point3; 2]>>,
+    /// Optional adaptive sampling, in which each photon is a sample of every pixel, and each further pass of the photons a batch.
+    adaptive: Option<Adaptive>,
 }
 
 impl FilmBuilder {
@@ -44,6 +53,14 @@ impl Build for FilmBuilder {
         let aspect_ratio = self.res[X] as f64 / self.res[Y] as f64;
         let fovy = self.fov.to_radians() / aspect_ratio;
 
+        let source = self.source.as_ref().map(|keys| {
+            Trajectory::new(
+                keys.iter()
+                    .map(|[pos, tar]| Orient::new_tar(*pos, tar))
+                    .collect(),
+            )
+        });
+
         let mut frames = Vec::with_capacity(self.frames as usize);
         for n in 0..self.frames {
             let pos = self.pos.sample(n, self.frames);
@@ -52,7 +69,20 @@ impl Build for FilmBuilder {
             let view = Mat4::look_at_rh(&pos, &tar, &Vec3::z_axis());
             let proj = Mat4::new_perspective(aspect_ratio, fovy, NEAR_CLIP, FAR_CLIP);
 
-            frames.push(Frame::new(pos, view, proj, self.res));
+            let mut frame = Frame::new(pos, view, proj, self.res);
+            if self.variance.unwrap_or(false) {
+                frame = frame.with_variance();
+            }
+            if let Some(kernel) = self.kernel {
+                frame = frame.with_kernel(kernel);
+            }
+            if let Some(ref adaptive) = self.adaptive {
+                frame = frame.with_adaptive(adaptive.clone());
+            }
+            if let Some(ref source) = source {
+                frame = frame.with_source(source.sample_frame(n as usize, self.frames as usize));
+            }
+            frames.push(frame);
         }
 
         frames
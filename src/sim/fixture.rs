@@ -0,0 +1,103 @@
+//! Shared fixtures of the simulation tests.
+
+use crate::{
+    fs::from_json_str,
+    geom::{Cube, Emitter, Mesh, Ray, SmoothTriangle, Surface, Tree, TreeSettings, Triangle},
+    math::{Dir3, Formula, Point3, Probability},
+    ord::{Map, Name, Register, Set},
+    phys::{Light, Material, Spectrum},
+    sim::{Attribute, Output, Settings},
+};
+
+/// Wavelength [m] of the fixture lights.
+pub const WAVELENGTH: f64 = 500.0e-9;
+
+/// Surfaces of a single mirror with the given attribute, well away from any simulated domain.
+#[must_use]
+pub fn far_mirror<'a>(attr: &'a Attribute<'a>) -> Set<Surface<'a, Attribute<'a>>> {
+    let norm = Dir3::new(0.0, 0.0, 1.0);
+    let mut surfs_map = Map::new();
+    surfs_map.insert(
+        Name::new("mirror"),
+        Surface::new(
+            Mesh::new(vec![SmoothTriangle::new(
+                Triangle::new([
+                    Point3::new(10.0, 10.0, 10.0),
+                    Point3::new(11.0, 10.0, 10.0),
+                    Point3::new(10.0, 11.0, 10.0),
+                ]),
+                [norm, norm, norm],
+            )]),
+            attr,
+        ),
+    );
+    Set::new(surfs_map)
+}
+
+/// Hit-scan tree holding the surfaces in a single cell.
+#[must_use]
+pub fn single_cell_tree<'a>(surfs: &'a Set<Surface<'a, Attribute<'a>>>) -> Tree<'a, Attribute<'a>> {
+    Tree::new(&TreeSettings::new(1, 1, 1.0e-6), surfs)
+}
+
+/// Index-matched material with the given scattering and optional absorption coefficients [1/m], and asymmetry factor.
+#[must_use]
+pub fn material(scat_coeff: f64, abs_coeff: Option<f64>, asym: f64) -> Material {
+    Material::new(
+        Formula::Constant { c: 1.0 },
+        Formula::Constant { c: scat_coeff },
+        abs_coeff.map(|c| Formula::Constant { c }),
+        None,
+        Spectrum::new_constant(asym),
+    )
+}
+
+/// Set of a single material under the given name.
+#[must_use]
+pub fn material_set(name: &Name, mat: Material) -> Set<Material> {
+    let mut mats_map = Map::new();
+    mats_map.insert(name.clone(), mat);
+    Set::new(mats_map)
+}
+
+/// Monochromatic beam emitted along the given ray into the given material.
+#[must_use]
+pub fn beam<'a>(power: f64, ray: Ray, mat: &'a Material) -> Light<'a> {
+    Light::new(
+        power,
+        Emitter::new_beam(ray),
+        Probability::Point { c: WAVELENGTH },
+        mat,
+    )
+}
+
+/// Settings with a single photon per block, and no roulette,
+/// given the remaining fields, including `num_phot`, in JSON5.
+#[must_use]
+pub fn settings(fields: &str) -> Settings {
+    from_json_str(&format!(
+        "{{ block_size: 1, bump_dist: 1.0e-9, loop_limit: 1000000, min_weight: 0.0, roulette_barrels: 2, {} }}",
+        fields
+    ))
+    .unwrap()
+}
+
+/// Output without detectors, dividing the given bounds into voxels at the given resolution.
+#[must_use]
+pub fn blank_output(bounds: Cube, res: [usize; 3], reg: &Register) -> Output<'_> {
+    Output::new(
+        bounds,
+        res,
+        reg,
+        reg,
+        reg,
+        reg,
+        reg,
+        reg,
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+    )
+}
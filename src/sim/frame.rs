@@ -1,12 +1,17 @@
 //! Simulation input.
 
 use crate::{
-    access,
+    access, clone,
+    geom::Orient,
+    img::SplatKernel,
     math::{Mat4, Point3},
     ord::{X, Y},
+    sim::Adaptive,
 };
 
 /// Real-space to frame-space transformer.
+/// A frame may cover a rectangular tile of the full image, in which case pixels are indexed relative to the tile.
+#[derive(Clone)]
 pub struct Frame {
     /// Position.
     pos: Point3,
@@ -16,11 +21,29 @@ pub struct Frame {
     proj: Mat4,
     /// Image resolution.
     res: [usize; 2],
+    /// Pixel offset of the tile within the full image.
+    offset: [usize; 2],
+    /// Tile resolution.
+    tile_res: [usize; 2],
+    /// Whether the per-pixel variance of the image should be tracked.
+    track_variance: bool,
+    /// Optional kernel used to spread each sample over neighbouring pixels.
+    kernel: Option<SplatKernel>,
+    /// Optional orientation of the light source while this frame is captured.
+    source: Option<Orient>,
+    /// Optional adaptive sampling, refining the image until every pixel has converged.
+    adaptive: Option<Adaptive>,
 }
 
 impl Frame {
     access!(pos: Point3);
     access!(res: [usize; 2]);
+    access!(offset: [usize; 2]);
+    access!(tile_res: [usize; 2]);
+    clone!(track_variance: bool);
+    clone!(kernel: Option<SplatKernel>);
+    access!(source: Option<Orient>);
+    access!(adaptive: Option<Adaptive>);
 
     /// Construct a new instance.
     #[inline]
@@ -34,13 +57,76 @@ impl Frame {
             view,
             proj,
             res,
+            offset: [0, 0],
+            tile_res: res,
+            track_variance: false,
+            kernel: None,
+            source: None,
+            adaptive: None,
         }
     }
 
-    /// Transform a position in real-space to a pixel element.
+    /// Enable tracking of the per-pixel variance of the image.
     #[inline]
     #[must_use]
-    pub fn transform(&self, pos: &Point3) -> Option<[usize; 2]> {
+    pub fn with_variance(mut self) -> Self {
+        self.track_variance = true;
+        self
+    }
+
+    /// Spread each sample over neighbouring pixels using the given kernel.
+    #[inline]
+    #[must_use]
+    pub fn with_kernel(mut self, kernel: SplatKernel) -> Self {
+        self.kernel = Some(kernel);
+        self
+    }
+
+    /// Capture the frame with the light source moved to the given orientation.
+    /// Such frames are captured in separate runs, each with the lights steered to match using `Light::steered`.
+    #[inline]
+    #[must_use]
+    pub fn with_source(mut self, source: Orient) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// Refine the image with further passes of the photons, until every pixel has converged.
+    /// Each photon is one sample of every pixel, so the variance of the image is tracked.
+    #[inline]
+    #[must_use]
+    pub fn with_adaptive(mut self, adaptive: Adaptive) -> Self {
+        self.track_variance = true;
+        self.adaptive = Some(adaptive);
+        self
+    }
+
+    /// Split the frame into tiles of at most the given resolution, which together cover the full image.
+    /// Tiles at the upper edges are truncated to fit the image.
+    #[inline]
+    #[must_use]
+    pub fn tiles(&self, size: [usize; 2]) -> Vec<Self> {
+        debug_assert!(size[X] > 0);
+        debug_assert!(size[Y] > 0);
+
+        let mut tiles = Vec::new();
+        for x in (0..self.res[X]).step_by(size[X]) {
+            for y in (0..self.res[Y]).step_by(size[Y]) {
+                tiles.push(Self {
+                    offset: [x, y],
+                    tile_res: [size[X].min(self.res[X] - x), size[Y].min(self.res[Y] - y)],
+                    ..self.clone()
+                });
+            }
+        }
+
+        tiles
+    }
+
+    /// Project a position in real-space to a continuous pixel position within the full image.
+    #[inline]
+    #[must_use]
+    fn project(&self, pos: &Point3) -> Option<[f64; 2]> {
         let p = self.proj * self.view * pos.to_homogeneous();
 
         if !(-1.0..1.0).contains(&p.x()) || !(-1.0..1.0).contains(&p.y()) {
@@ -48,8 +134,97 @@ impl Frame {
         }
 
         Some([
-            ((p.x() + 1.0) * 0.5 * self.res[X] as f64) as usize,
-            ((p.y() + 1.0) * 0.5 * self.res[Y] as f64) as usize,
+            (p.x() + 1.0) * 0.5 * self.res[X] as f64,
+            (p.y() + 1.0) * 0.5 * self.res[Y] as f64,
         ])
     }
+
+    /// Convert a pixel of the full image to a pixel of this tile.
+    #[inline]
+    #[must_use]
+    fn localise(&self, pixel: [usize; 2]) -> Option<[usize; 2]> {
+        // Pixels outside of this tile belong to another.
+        let x = pixel[X].checked_sub(self.offset[X])?;
+        let y = pixel[Y].checked_sub(self.offset[Y])?;
+        (x < self.tile_res[X] && y < self.tile_res[Y]).then(|| [x, y])
+    }
+
+    /// Transform a position in real-space to a pixel element.
+    #[inline]
+    #[must_use]
+    pub fn transform(&self, pos: &Point3) -> Option<[usize; 2]> {
+        let p = self.project(pos)?;
+        self.localise([p[X] as usize, p[Y] as usize])
+    }
+
+    /// Transform a position in real-space to the weighted pixel elements it contributes to.
+    /// Without a kernel, the whole weight falls in the pixel given by `transform`.
+    #[inline]
+    #[must_use]
+    pub fn splat(&self, pos: &Point3) -> Vec<([usize; 2], f64)> {
+        match self.kernel {
+            None => self
+                .transform(pos)
+                .map(|pixel| (pixel, 1.0))
+                .into_iter()
+                .collect(),
+            Some(kernel) => self.project(pos).map_or_else(Vec::new, |p| {
+                kernel
+                    .splat(p, self.res)
+                    .into_iter()
+                    .filter_map(|(pixel, w)| self.localise(pixel).map(|pixel| (pixel, w)))
+                    .collect()
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        img::{Colour, Image},
+        math::{Dir3, Vec3},
+    };
+
+    /// Splat a grid of coloured points into the image of a frame.
+    fn render(frame: &Frame) -> Image {
+        let mut img = Image::new_blank(*frame.tile_res(), Colour::new(0.0, 0.0, 0.0, 0.0));
+        for i in 0..41 {
+            for j in 0..41 {
+                let pos = Point3::new(f64::from(i) * 0.05 - 1.0, f64::from(j) * 0.05 - 1.0, 0.0);
+                if let Some(pixel) = frame.transform(&pos) {
+                    img.pixels_mut()[pixel] += Colour::new(i as f32, j as f32, 1.0, 1.0);
+                }
+            }
+        }
+        img
+    }
+
+    #[test]
+    fn test_tiled_render_matches_full() {
+        let pos = Point3::new(0.0, 0.0, 5.0);
+        let view = Mat4::look_at_rh(
+            &pos,
+            &Point3::new(0.0, 0.0, 0.0),
+            &Dir3::from(Vec3::new(0.0, 1.0, 0.0)),
+        );
+        let proj = Mat4::new_perspective(1.5, 0.5, 1.0e-3, 1.0e3);
+        let frame = Frame::new(pos, view, proj, [30, 20]);
+
+        let full = render(&frame);
+
+        let tiles = frame.tiles([8, 8]);
+        assert_eq!(tiles.len(), 12);
+        assert_eq!(*tiles[11].offset(), [24, 16]);
+        assert_eq!(*tiles[11].tile_res(), [6, 4]);
+
+        let mut stitched = Image::new_blank([30, 20], Colour::new(0.0, 0.0, 0.0, 0.0));
+        for tile in &tiles {
+            stitched.paste(*tile.offset(), &render(tile)).unwrap();
+        }
+
+        assert!(full.pixels().iter().any(|col| col.alpha > 0.0));
+        assert_eq!(full.pixels(), stitched.pixels());
+    }
 }
@@ -0,0 +1,160 @@
+//! Hyperspectral volume output.
+
+use crate::{
+    access, clone,
+    err::{check_shape, Error},
+    fmt_report,
+    fs::Save,
+    geom::Grid,
+    math::Point3,
+    ord::{X, Y, Z},
+    tools::Binner,
+};
+use ndarray::{Array4, ArrayView3, Axis};
+use std::{
+    fmt::{Display, Formatter},
+    ops::AddAssign,
+    path::Path,
+};
+
+/// Spatially and spectrally resolved volume.
+///
+/// The final axis of the data indexes the wavelength channels of the binner,
+/// such that each spectral band is a separate volume slice.
+/// Weight recorded outside of the binner range is accumulated as overflow.
+#[derive(Clone)]
+pub struct Hyperspectral {
+    /// Spatial grid.
+    grid: Grid,
+    /// Wavelength channel binner [m].
+    binner: Binner,
+    /// Accumulated data, indexed by voxel then channel.
+    data: Array4<f64>,
+    /// Weight recorded outside of the wavelength range.
+    overflow: f64,
+}
+
+impl Hyperspectral {
+    access!(grid: Grid);
+    access!(binner: Binner);
+    access!(data: Array4<f64>);
+    clone!(overflow: f64);
+
+    /// Construct a new instance.
+    #[inline]
+    #[must_use]
+    pub fn new(grid: Grid, binner: Binner) -> Self {
+        let res = *grid.res();
+        let data = Array4::zeros([res[X], res[Y], res[Z], binner.bins()]);
+
+        Self {
+            grid,
+            binner,
+            data,
+            overflow: 0.0,
+        }
+    }
+
+    /// Record a weight at a position and wavelength.
+    /// Weight outside of the wavelength range is added to the overflow,
+    /// and weight outside of the grid is ignored.
+    #[inline]
+    pub fn collect(&mut self, pos: &Point3, wavelength: f64, weight: f64) {
+        if let Some([xi, yi, zi]) = self.grid.gen_index(pos) {
+            match self.binner.try_bin(wavelength) {
+                Some(channel) => self.data[[xi, yi, zi, channel]] += weight,
+                None => self.overflow += weight,
+            }
+        }
+    }
+
+    /// View the volume of a single wavelength channel.
+    #[inline]
+    #[must_use]
+    pub fn channel(&self, index: usize) -> ArrayView3<f64> {
+        self.data.index_axis(Axis(3), index)
+    }
+
+    /// Add the data of another hyperspectral volume to this one.
+    /// # Errors
+    /// if the volumes do not share the same spatial and spectral resolution.
+    #[inline]
+    pub fn merge(&mut self, rhs: &Self) -> Result<(), Error> {
+        check_shape("hyperspectral volume", self.data.shape(), rhs.data.shape())?;
+
+        *self += rhs;
+        Ok(())
+    }
+}
+
+impl AddAssign<&Self> for Hyperspectral {
+    #[inline]
+    fn add_assign(&mut self, rhs: &Self) {
+        self.data += &rhs.data;
+        self.overflow += rhs.overflow;
+    }
+}
+
+impl Save for Hyperspectral {
+    /// Each channel is saved separately, with the channel index appended to the file stem.
+    #[inline]
+    fn save_data(&self, path: &Path) -> Result<(), Error> {
+        let stem = path
+            .file_stem()
+            .ok_or("Missing hyperspectral file name.")?
+            .to_string_lossy();
+        let ext = path
+            .extension()
+            .map_or_else(|| "nc".to_owned(), |ext| ext.to_string_lossy().into_owned());
+
+        for index in 0..self.binner.bins() {
+            self.channel(index)
+                .to_owned()
+                .save(&path.with_file_name(format!("{}_{:03}.{}", stem, index, ext)))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Hyperspectral {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        writeln!(fmt, "...")?;
+        fmt_report!(fmt, self.grid, "grid");
+        fmt_report!(fmt, self.binner, "wavelength binner (m)");
+        fmt_report!(fmt, self.overflow, "overflow");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Hyperspectral;
+    use crate::{
+        geom::{Cube, Grid},
+        math::Point3,
+        tools::{Binner, Range},
+    };
+
+    #[test]
+    fn test_spectral_slices() {
+        let grid = Grid::new(
+            Cube::new(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0)),
+            [2, 2, 2],
+        );
+        let binner = Binner::new(Range::new(400.0e-9, 700.0e-9), 3);
+        let mut hyper = Hyperspectral::new(grid, binner);
+
+        let pos = Point3::new(0.25, 0.25, 0.25);
+        hyper.collect(&pos, 450.0e-9, 1.0);
+        hyper.collect(&pos, 650.0e-9, 2.0);
+        hyper.collect(&pos, 800.0e-9, 4.0);
+
+        assert_eq!(hyper.channel(0)[[0, 0, 0]], 1.0);
+        assert_eq!(hyper.channel(1).sum(), 0.0);
+        assert_eq!(hyper.channel(2)[[0, 0, 0]], 2.0);
+        assert_eq!(hyper.data().sum(), 3.0);
+        assert_eq!(hyper.overflow(), 4.0);
+    }
+}
@@ -1,13 +1,17 @@
 //! Simulation input.
 
 use crate::{
+    data::Histogram,
+    err::Error,
     fmt_report,
     geom::{Grid, Tree},
+    math::Probability,
     ord::{Register, Set},
-    phys::{Light, Material},
-    sim::{Attribute, Settings},
+    phys::{Light, Material, Photon},
+    sim::{Attribute, Boundary, Settings},
 };
-use std::fmt::{Display, Error, Formatter};
+use rand::Rng;
+use std::fmt::{Display, Formatter};
 
 /// MCRT simulation resources conglomerate.
 #[derive(Clone)]
@@ -26,6 +30,12 @@ pub struct Input<'a> {
     pub grid: &'a Grid,
     /// General settings.
     pub sett: &'a Settings,
+    /// Optional domain boundary, applied to photons leaving the measurement grid.
+    pub boundary: Option<&'a Boundary>,
+    /// Lights sharing the emitted photons in proportion to their power, in place of the emission light.
+    pub lights: Vec<Light<'a>>,
+    /// Distribution of light indices, weighted by power.
+    light_select: Option<Probability>,
 }
 
 impl<'a> Input<'a> {
@@ -49,21 +59,243 @@ impl<'a> Input<'a> {
             tree,
             grid,
             sett,
+            boundary: None,
+            lights: Vec::new(),
+            light_select: None,
         }
     }
+
+    /// Apply a boundary to photons leaving the measurement grid.
+    /// Without a boundary, photons leaving the grid are removed from the simulation.
+    /// The boundary should share the bounds of the grid.
+    #[inline]
+    #[must_use]
+    pub fn with_boundary(mut self, boundary: &'a Boundary) -> Self {
+        self.boundary = Some(boundary);
+        self
+    }
+
+    /// Emit photons from several lights, in place of the emission light.
+    /// Each photon originates from a light chosen with probability proportional to its power.
+    /// # Errors
+    /// if no lights are given.
+    #[inline]
+    pub fn with_lights(mut self, lights: Vec<Light<'a>>) -> Result<Self, Error> {
+        if lights.is_empty() {
+            return Err("At least one light must be given.".into());
+        }
+
+        let mut powers = Histogram::new(0.0, lights.len() as f64, lights.len());
+        for (index, light) in lights.iter().enumerate() {
+            powers.collect_weighted(index as f64 + 0.5, light.power());
+        }
+
+        self.light_select = Some(Probability::from_histogram(&powers)?);
+        self.lights = lights;
+        Ok(self)
+    }
+
+    /// Total power of the emitting lights [J/s].
+    /// As lights are selected in proportion to their power, every photon carries an equal share of the total,
+    /// keeping the estimate of each light's contribution unbiased.
+    #[inline]
+    #[must_use]
+    pub fn total_power(&self) -> f64 {
+        if self.lights.is_empty() {
+            self.light.power()
+        } else {
+            self.lights.iter().map(Light::power).sum()
+        }
+    }
+
+    /// Select the index of the light from which the next photon is emitted.
+    /// Always zero when a single emission light is used.
+    #[inline]
+    #[must_use]
+    pub fn select_light<R: Rng>(&self, rng: &mut R) -> usize {
+        self.light_select.as_ref().map_or(0, |select| {
+            (select.sample(rng) as usize).min(self.lights.len() - 1)
+        })
+    }
+
+    /// Emit the photons of a block of photon indices up front, each paired with the index of the light it was emitted from.
+    /// Draws are made in the same order as emitting each photon in turn with `select_light` and `Light::emit_nth`,
+    /// so a batch is distributed identically to serial emission.
+    #[inline]
+    #[must_use]
+    pub fn emit_batch<R: Rng>(
+        &self,
+        rng: &mut R,
+        power: f64,
+        start: usize,
+        end: usize,
+    ) -> Vec<(usize, Photon)> {
+        (start..end)
+            .map(|phot_id| {
+                let index = self.select_light(rng);
+                let light = self.lights.get(index).unwrap_or(&self.light);
+                (index, light.emit_nth(rng, power, phot_id))
+            })
+            .collect()
+    }
+
+    /// Create a copy of the input for each of the emitting lights, in light index order.
+    #[inline]
+    #[must_use]
+    pub fn sources(&self) -> Vec<Self> {
+        if self.lights.is_empty() {
+            return vec![self.clone()];
+        }
+
+        let base = Self {
+            lights: Vec::new(),
+            light_select: None,
+            ..self.clone()
+        };
+        self.lights
+            .iter()
+            .map(|light| Self {
+                light: light.clone(),
+                ..base.clone()
+            })
+            .collect()
+    }
 }
 
 impl Display for Input<'_> {
     #[inline]
-    fn fmt(&self, fmt: &mut Formatter) -> Result<(), Error> {
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
         writeln!(fmt, "...")?;
         fmt_report!(fmt, self.spec_reg, "spectrometer register");
         fmt_report!(fmt, self.attrs, "materials");
         fmt_report!(fmt, self.attrs, "attributes");
-        fmt_report!(fmt, self.light, "light");
+        if self.lights.is_empty() {
+            fmt_report!(fmt, self.light, "light");
+        } else {
+            fmt_report!(fmt, self.lights.len(), "lights");
+            fmt_report!(fmt, self.total_power(), "total power (J/s)");
+        }
         fmt_report!(fmt, self.tree, "hit-scan tree");
         fmt_report!(fmt, self.grid, "measurement grid");
         fmt_report!(fmt, self.sett, "settings");
+        if let Some(boundary) = self.boundary {
+            fmt_report!(fmt, boundary, "boundary");
+        }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        geom::{Cube, Emitter, Ray},
+        math::{substream, Dir3, Point3},
+        ord::Map,
+        sim::fixture,
+    };
+
+    #[test]
+    fn test_select_light_by_power() {
+        let mirror = Attribute::Mirror(0.5);
+        let surfs = fixture::far_mirror(&mirror);
+        let tree = fixture::single_cell_tree(&surfs);
+
+        let reg = Register::new(vec![]);
+        let mats = Set::new(Map::new());
+        let attrs = Set::new(Map::new());
+        let mat = fixture::material(1.0, None, 0.0);
+        let ray = Ray::new(Point3::new(0.0, 0.0, 0.0), Dir3::new(1.0, 0.0, 0.0));
+        let light = |power| fixture::beam(power, ray.clone(), &mat);
+        let grid = Grid::new(
+            Cube::new(Point3::new(-5.0, -5.0, -5.0), Point3::new(5.0, 5.0, 5.0)),
+            [1, 1, 1],
+        );
+        let sett = fixture::settings("num_phot: 1");
+        let input = Input::new(&reg, &mats, &attrs, light(1.0), &tree, &grid, &sett)
+            .with_lights(vec![light(3.0), light(1.0)])
+            .unwrap();
+        assert_eq!(input.total_power(), 4.0);
+        assert_eq!(input.sources().len(), 2);
+
+        let mut rng = rand::thread_rng();
+        let num_phot = 100_000;
+        let strong = (0..num_phot)
+            .filter(|_| input.select_light(&mut rng) == 0)
+            .count();
+        let frac = strong as f64 / num_phot as f64;
+        assert!((frac - 0.75).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_emit_batch_matches_serial() {
+        let mirror = Attribute::Mirror(0.5);
+        let surfs = fixture::far_mirror(&mirror);
+        let tree = fixture::single_cell_tree(&surfs);
+
+        let reg = Register::new(vec![]);
+        let mats = Set::new(Map::new());
+        let attrs = Set::new(Map::new());
+        let mat = fixture::material(1.0, None, 0.0);
+        let beam = fixture::beam(
+            3.0,
+            Ray::new(Point3::new(0.0, 0.0, 0.0), Dir3::new(1.0, 0.0, 0.0)),
+            &mat,
+        );
+        let points = Light::new(
+            1.0,
+            Emitter::new_points(vec![Point3::new(1.0, 0.0, 0.0), Point3::new(0.0, 1.0, 0.0)]),
+            Probability::new_uniform(400.0e-9, 700.0e-9),
+            &mat,
+        );
+        let grid = Grid::new(
+            Cube::new(Point3::new(-5.0, -5.0, -5.0), Point3::new(5.0, 5.0, 5.0)),
+            [1, 1, 1],
+        );
+        let sett = fixture::settings("num_phot: 1");
+        let input = Input::new(&reg, &mats, &attrs, beam.clone(), &tree, &grid, &sett)
+            .with_lights(vec![beam, points])
+            .unwrap();
+        let sources = input.sources();
+
+        // From the same stream, a batch reproduces serial emission exactly.
+        let mut batch_rng = substream(7, 0);
+        let mut serial_rng = substream(7, 0);
+        let batch = input.emit_batch(&mut batch_rng, 1.0, 100, 1100);
+        for (phot_id, (index, phot)) in (100..1100).zip(batch) {
+            let serial_index = input.select_light(&mut serial_rng);
+            let serial = sources[serial_index]
+                .light
+                .emit_nth(&mut serial_rng, 1.0, phot_id);
+
+            assert_eq!(index, serial_index);
+            assert_eq!(phot.ray(), serial.ray());
+            assert_eq!(phot.wavelength(), serial.wavelength());
+            assert_eq!(phot.weight(), serial.weight());
+        }
+
+        // Independently drawn batches follow the same distributions as the lights.
+        let mut rng = rand::thread_rng();
+        let num_phot = 100_000;
+        let batch = input.emit_batch(&mut rng, 1.0, 0, num_phot);
+        let points: Vec<_> = batch.iter().filter(|(index, _)| *index == 1).collect();
+        let frac = points.len() as f64 / num_phot as f64;
+        assert!((frac - 0.25).abs() < 0.01);
+
+        let ave_wavelength = points
+            .iter()
+            .map(|(_, phot)| phot.wavelength())
+            .sum::<f64>()
+            / points.len() as f64;
+        assert!((ave_wavelength - 550.0e-9).abs() < 2.0e-9);
+        let on_x = points
+            .iter()
+            .filter(|(_, phot)| phot.ray().pos().x() > 0.5)
+            .count();
+        assert!((on_x as f64 / points.len() as f64 - 0.5).abs() < 0.02);
+        assert!(batch
+            .iter()
+            .filter(|(index, _)| *index == 0)
+            .all(|(_, phot)| phot.wavelength() == 500.0e-9));
+    }
+}
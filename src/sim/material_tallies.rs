@@ -0,0 +1,166 @@
+//! Per-material event tallies.
+
+use crate::{
+    access, fmt_report,
+    ord::{Name, Set},
+    phys::Material,
+    sim::MaterialTally,
+};
+use std::{
+    fmt::{Display, Formatter},
+    ops::AddAssign,
+};
+
+/// Interaction event tallies of each material of a simulation.
+/// Events are attributed to the material the photon is currently travelling through,
+/// which is followed across refracting interfaces.
+#[derive(Clone)]
+pub struct MaterialTallies<'a> {
+    /// Material names.
+    names: Vec<Name>,
+    /// Materials, used to identify the material entered at an interface.
+    mats: Vec<&'a Material>,
+    /// Tally of each material.
+    tallies: Vec<MaterialTally>,
+    /// Index of the material currently occupied by the photon.
+    current: Option<usize>,
+    /// Materials occupied by packets awaiting simulation.
+    pending: Vec<Option<usize>>,
+}
+
+impl<'a> MaterialTallies<'a> {
+    access!(names: Vec<Name>);
+    access!(tallies: Vec<MaterialTally>);
+
+    /// Construct a new instance with an empty tally for each material.
+    #[inline]
+    #[must_use]
+    pub fn new(mats: &'a Set<Material>) -> Self {
+        Self {
+            names: mats.names_list(),
+            mats: mats.values().collect(),
+            tallies: vec![MaterialTally::default(); mats.len()],
+            current: None,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Get the tally of a named material.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, name: &Name) -> Option<&MaterialTally> {
+        self.names
+            .iter()
+            .position(|n| n == name)
+            .map(|index| &self.tallies[index])
+    }
+
+    /// Attribute further events to the given material.
+    /// Materials not part of the set go untallied.
+    #[inline]
+    pub fn enter(&mut self, mat: &Material) {
+        self.current = self.mats.iter().position(|m| std::ptr::eq(*m, mat));
+    }
+
+    /// Store the current material of a packet spawned for later simulation.
+    #[inline]
+    pub fn spawn(&mut self) {
+        self.pending.push(self.current);
+    }
+
+    /// Restore the material of the most recently spawned packet, as it begins simulation.
+    #[inline]
+    pub fn resume(&mut self) {
+        self.current = self.pending.pop().flatten();
+    }
+
+    /// Record an interaction event within the current material, at which the given photon weight was absorbed.
+    #[inline]
+    pub fn record(&mut self, absorbed_weight: f64) {
+        if let Some(index) = self.current {
+            self.tallies[index].record(absorbed_weight);
+        }
+    }
+}
+
+impl AddAssign<&Self> for MaterialTallies<'_> {
+    #[inline]
+    fn add_assign(&mut self, rhs: &Self) {
+        debug_assert!(self.names == rhs.names);
+
+        for (a, b) in self.tallies.iter_mut().zip(&rhs.tallies) {
+            *a += b;
+        }
+    }
+}
+
+impl Display for MaterialTallies<'_> {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        writeln!(fmt, "...")?;
+        for (name, tally) in self.names.iter().zip(&self.tallies) {
+            fmt_report!(fmt, tally, name.as_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        geom::{Cube, Grid, Ray},
+        math::{Dir3, Point3},
+        ord::{Map, Register},
+        phys::Photon,
+        sim::{
+            fixture::{self, WAVELENGTH},
+            standard, Attribute, Boundary, BoundaryCondition, Input,
+        },
+    };
+
+    #[test]
+    fn test_absorbed_weight_matches_energy_lost() {
+        // A single mirror well away from the domain.
+        let mirror = Attribute::Mirror(0.5);
+        let surfs = fixture::far_mirror(&mirror);
+        let tree = fixture::single_cell_tree(&surfs);
+
+        // A single absorbing and scattering material filling the domain.
+        let name = Name::new("tissue");
+        let mats = fixture::material_set(&name, fixture::material(2.0, Some(1.0), 0.5));
+        let mat = mats.get(&name).unwrap();
+
+        let reg = Register::new(vec![]);
+        let attrs = Set::new(Map::new());
+        let ray = Ray::new(Point3::new(0.0, 0.0, 0.0), Dir3::new(1.0, 0.0, 0.0));
+        let light = fixture::beam(1.0, ray.clone(), mat);
+        let bounds = Cube::new(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0));
+        let grid = Grid::new(bounds.clone(), [1, 1, 1]);
+        let boundary = Boundary::new(bounds.clone(), BoundaryCondition::Open);
+        let sett = fixture::settings("num_phot: 1, material_tallies: true");
+        let input =
+            Input::new(&reg, &mats, &attrs, light, &tree, &grid, &sett).with_boundary(&boundary);
+
+        let mut data = fixture::blank_output(bounds, [1, 1, 1], &reg);
+        data.tallies = Some(MaterialTallies::new(&mats));
+
+        // Without roulette, all weight not absorbed escapes through the open boundary.
+        let num_phot = 1000;
+        let mut rng = rand::thread_rng();
+        for _ in 0..num_phot {
+            standard(
+                &input,
+                &mut data,
+                &mut rng,
+                Photon::new(ray.clone(), WAVELENGTH, 1.0),
+            );
+        }
+
+        let tally = data.tallies.as_ref().unwrap().get(&name).unwrap();
+        let escaped: f64 = data.escaped.iter().sum();
+        assert!(tally.scatterings() > 0);
+        assert_eq!(tally.absorptions(), tally.scatterings());
+        assert!((tally.absorbed_weight() + escaped - num_phot as f64).abs() < 1.0e-6);
+    }
+}
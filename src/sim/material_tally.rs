@@ -0,0 +1,56 @@
+//! Material event tally.
+
+use crate::clone;
+use std::{
+    fmt::{Display, Formatter},
+    ops::AddAssign,
+};
+
+/// Count of the interaction events occurring within a single material.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MaterialTally {
+    /// Number of scattering events.
+    scatterings: u64,
+    /// Number of events at which part of the photon weight was absorbed.
+    absorptions: u64,
+    /// Total photon weight absorbed.
+    absorbed_weight: f64,
+}
+
+impl MaterialTally {
+    clone!(scatterings: u64);
+    clone!(absorptions: u64);
+    clone!(absorbed_weight: f64);
+
+    /// Record an interaction event, at which the given photon weight was absorbed.
+    #[inline]
+    pub fn record(&mut self, absorbed_weight: f64) {
+        debug_assert!(absorbed_weight >= 0.0);
+
+        self.scatterings += 1;
+        if absorbed_weight > 0.0 {
+            self.absorptions += 1;
+            self.absorbed_weight += absorbed_weight;
+        }
+    }
+}
+
+impl AddAssign<&Self> for MaterialTally {
+    #[inline]
+    fn add_assign(&mut self, rhs: &Self) {
+        self.scatterings += rhs.scatterings;
+        self.absorptions += rhs.absorptions;
+        self.absorbed_weight += rhs.absorbed_weight;
+    }
+}
+
+impl Display for MaterialTally {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        write!(
+            fmt,
+            "{} scatterings, {} absorptions, {:.6e} weight absorbed",
+            self.scatterings, self.absorptions, self.absorbed_weight
+        )
+    }
+}
@@ -2,23 +2,50 @@
 //!
 //! Contains much of the high-level simulation constructs the implement the Monte Carlo Radiative Transfer simulation.
 
+pub mod adaptive;
 pub mod attribute;
+pub mod block_reduction;
+pub mod boundary;
+pub mod boundary_builder;
+pub mod ccd_builder;
+pub mod ccd_channels;
+pub mod detection;
 pub mod engine;
 pub mod event;
+pub mod event_log;
 pub mod film_builder;
+#[cfg(test)]
+pub mod fixture;
 pub mod frame;
+pub mod hyperspectral;
 pub mod input;
+pub mod material_tallies;
+pub mod material_tally;
 pub mod output;
+pub mod output_registry;
+pub mod output_volume;
 pub mod param;
 pub mod peel_off;
 pub mod photon_collector;
+pub mod photon_collector_builder;
+pub mod photon_stream;
+pub mod pixel_variance;
+pub mod plane_stack;
+pub mod plane_stack_builder;
+pub mod radiance;
 pub mod run;
 pub mod scatter;
 pub mod settings;
+pub mod slab_diffusion;
 pub mod surface;
 pub mod travel;
 
 pub use self::{
-    attribute::*, engine::*, event::*, film_builder::*, frame::*, input::*, output::*, param::*,
-    peel_off::*, photon_collector::*, run::*, scatter::*, settings::*, surface::*, travel::*,
+    adaptive::*, attribute::*, block_reduction::*, boundary::*, boundary_builder::*,
+    ccd_builder::*, ccd_channels::*, detection::*, engine::*, event::*, event_log::*,
+    film_builder::*, frame::*, hyperspectral::*, input::*, material_tallies::*, material_tally::*,
+    output::*, output_registry::*, output_volume::*, param::*, peel_off::*, photon_collector::*,
+    photon_collector_builder::*, photon_stream::*, pixel_variance::*, plane_stack::*,
+    plane_stack_builder::*, radiance::*, run::*, scatter::*, settings::*, slab_diffusion::*,
+    surface::*, travel::*,
 };
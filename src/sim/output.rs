@@ -3,22 +3,30 @@
 use crate::{
     access, clone,
     data::Histogram,
-    err::Error,
+    err::{check_shape, Error},
     fmt_report,
     fs::Save,
-    geom::Cube,
+    geom::{Cube, TraversalStats},
     img::Image,
-    ord::{Register, X, Y, Z},
+    ord::{Name, Register, X, Y, Z},
+    phys::{EnergyCheck, Local, Photon},
     util::fmt::DataCube,
 };
 use ndarray::Array3;
+use rand::Rng;
 use std::{
     fmt::{Display, Formatter},
+    fs::File,
+    io::Write,
     ops::AddAssign,
     path::Path,
 };
 
-use super::PhotonCollector;
+use super::{
+    BoundaryDirection, Detection, DetectionCallback, DetectorKind, EventKind, EventLog,
+    Hyperspectral, MaterialTallies, OutputItem, OutputRegistry, OutputVolume, PhotonCollector,
+    PixelVariance, PlaneStack, Radiance,
+};
 
 /// MCRT output data.
 #[derive(Clone)]
@@ -29,15 +37,15 @@ pub struct Output<'a> {
     cell_vol: f64,
 
     /// Emission power.
-    pub emission: Array3<f64>,
+    pub emission: OutputVolume,
     /// Photo-energy.
-    pub energy: Array3<f64>,
+    pub energy: OutputVolume,
     /// Absorptions.
-    pub absorptions: Array3<f64>,
+    pub absorptions: OutputVolume,
     /// Wavelength shifts.
-    pub shifts: Array3<f64>,
-    /// Flux - the energy density travelling through each voxel.
-    pub flux: Array3<f64>,
+    pub shifts: OutputVolume,
+    /// Flux - the weighted path length travelled through each voxel [m].
+    pub flux: OutputVolume,
 
     /// Spectrometer name register.
     spec_reg: &'a Register,
@@ -47,8 +55,15 @@ pub struct Output<'a> {
     ccd_reg: &'a Register,
     /// Photon collectors.
     phot_col_reg: &'a Register,
+    /// Hyperspectral volume name register.
+    hyper_reg: &'a Register,
+    /// Radiance detector name register.
+    rad_reg: &'a Register,
     /// Spectrometer data.
     pub specs: Vec<Histogram>,
+    /// Whether each spectrometer applies Poisson counting noise on finalisation.
+    /// Spectrometers without an entry are noise-free.
+    pub spec_noise: Vec<bool>,
     /// Image data.
     pub imgs: Vec<Image>,
     /// Ccd data.
@@ -56,8 +71,33 @@ pub struct Output<'a> {
 
     /// Photo data.
     pub photos: Vec<Image>,
+    /// Optional per-pixel variance of each photo.
+    pub photo_vars: Vec<Option<PixelVariance>>,
     /// Photon collectors.
     pub phot_cols: Vec<PhotonCollector>,
+    /// Hyperspectral volumes.
+    pub hypers: Vec<Hyperspectral>,
+    /// Angular radiance detectors.
+    pub radiances: Vec<Radiance>,
+    /// Stacks of output planes, recording the photon paths crossing them.
+    pub plane_stacks: Vec<PlaneStack>,
+    /// Energy escaping through each open face of the boundary, in `BoundaryDirection` index order.
+    pub escaped: [f64; 6],
+    /// Optional photon event trace log.
+    pub log: Option<EventLog>,
+    /// Optional interface energy conservation diagnostics.
+    pub energy_check: Option<EnergyCheck>,
+    /// Optional hit-scan tree traversal diagnostics.
+    pub traversal: Option<TraversalStats>,
+    /// Optional per-material interaction event tallies.
+    pub tallies: Option<MaterialTallies<'a>>,
+    /// Optional callback invoked on each detection by a spectrometer, photon collector or ccd.
+    pub on_detect: Option<DetectionCallback>,
+    /// Number of further splits the photon currently being simulated may make at interfaces.
+    pub split_budget: usize,
+    /// Packets spawned by splitting the photon currently being simulated, awaiting simulation,
+    /// with the distance [m] each travels before it may interact.
+    pub spawned: Vec<(Photon, Local, f64)>,
 }
 
 impl<'a> Output<'a> {
@@ -78,6 +118,8 @@ impl<'a> Output<'a> {
         img_reg: &'a Register,
         ccd_reg: &'a Register,
         phot_col_reg: &'a Register,
+        hyper_reg: &'a Register,
+        rad_reg: &'a Register,
         specs: Vec<Histogram>,
         imgs: Vec<Image>,
         ccds: Vec<Array3<f64>>,
@@ -93,24 +135,250 @@ impl<'a> Output<'a> {
         Self {
             boundary,
             cell_vol,
-            emission: Array3::zeros(res),
-            energy: Array3::zeros(res),
-            absorptions: Array3::zeros(res),
-            shifts: Array3::zeros(res),
-            flux: Array3::zeros(res),
+            emission: OutputVolume::new(res),
+            energy: OutputVolume::new(res),
+            absorptions: OutputVolume::new(res),
+            shifts: OutputVolume::new(res),
+            flux: OutputVolume::new(res),
             spec_reg,
             img_reg,
             ccd_reg,
             phot_col_reg: phot_col_reg,
+            hyper_reg,
+            rad_reg,
             specs,
+            spec_noise: Vec::new(),
             imgs,
             ccds,
             photos,
+            photo_vars: Vec::new(),
             phot_cols,
+            hypers: Vec::new(),
+            radiances: Vec::new(),
+            plane_stacks: Vec::new(),
+            escaped: [0.0; 6],
+            log: None,
+            energy_check: None,
+            traversal: None,
+            tallies: None,
+            on_detect: None,
+            split_budget: 0,
+            spawned: Vec::new(),
+        }
+    }
+
+    /// Switch the volume data to compensated summation for all further accumulation.
+    #[inline]
+    pub fn compensate(&mut self) {
+        self.emission.compensate();
+        self.energy.compensate();
+        self.absorptions.compensate();
+        self.shifts.compensate();
+        self.flux.compensate();
+    }
+
+    /// Invoke the detection callback, if any, for a photon detected by the given detector.
+    #[inline]
+    pub fn detect(&self, kind: DetectorKind, id: usize, phot: &Photon) {
+        if let Some(ref callback) = self.on_detect {
+            callback.call(&Detection::new(kind, id, phot));
+        }
+    }
+
+    /// Convert the accumulated path lengths into the fluence rate [W/m^2] within each voxel,
+    /// given the total power [W] of the source and the number of photons it emitted.
+    #[inline]
+    #[must_use]
+    pub fn fluence_rate(&self, source_power: f64, num_phot: usize) -> Array3<f64> {
+        debug_assert!(source_power > 0.0);
+        debug_assert!(num_phot > 0);
+
+        self.flux.total() * (source_power / (num_phot as f64 * self.cell_vol))
+    }
+
+    /// Apply Poisson counting noise to the accumulated counts of each noisy spectrometer.
+    /// Should be called once, after all photons have been simulated and the outputs merged.
+    #[inline]
+    pub fn apply_spectrometer_noise<R: Rng>(&mut self, rng: &mut R) {
+        for (spec, noisy) in self.specs.iter_mut().zip(&self.spec_noise) {
+            if *noisy {
+                spec.apply_poisson_noise(rng);
+            }
+        }
+    }
+
+    /// Collect the data into a registry of named outputs, named as the files written by `save`.
+    /// Volumes are registered as densities.
+    /// # Errors
+    /// if two outputs would share a name.
+    #[inline]
+    pub fn registry(&self) -> Result<OutputRegistry, Error> {
+        let mut reg = OutputRegistry::new();
+
+        for (name, vol) in &[
+            ("emission_density", &self.emission),
+            ("energy_density", &self.energy),
+            ("absorption_density", &self.absorptions),
+            ("shift_density", &self.shifts),
+        ] {
+            reg.register(
+                Name::new(name),
+                OutputItem::Volume(vol.total() / self.cell_vol),
+            )?;
+        }
+
+        for (name, index) in self.spec_reg.set().map().iter() {
+            reg.register(
+                Name::new(&format!("spectrometer_{}", name)),
+                OutputItem::Spectrometer(self.specs[*index].clone()),
+            )?;
+        }
+
+        for (name, index) in self.img_reg.set().map().iter() {
+            reg.register(
+                Name::new(&format!("img_{}", name)),
+                OutputItem::Imager(self.imgs[*index].clone()),
+            )?;
+        }
+
+        for (name, index) in self.ccd_reg.set().map().iter() {
+            reg.register(
+                Name::new(&format!("ccd_{}", name)),
+                OutputItem::Ccd(self.ccds[*index].clone()),
+            )?;
+        }
+
+        for (n, photo) in self.photos.iter().enumerate() {
+            reg.register(
+                Name::new(&format!("photo_{:03}", n)),
+                OutputItem::Imager(photo.clone()),
+            )?;
+        }
+
+        for (name, index) in self.phot_col_reg.set().map().iter() {
+            reg.register(
+                Name::new(&format!("photon_collector_{}", name)),
+                OutputItem::PhotonCollector(self.phot_cols[*index].clone()),
+            )?;
+        }
+
+        for (name, index) in self.hyper_reg.set().map().iter() {
+            reg.register(
+                Name::new(&format!("hyperspectral_{}", name)),
+                OutputItem::Hyperspectral(self.hypers[*index].clone()),
+            )?;
+        }
+
+        for (name, index) in self.rad_reg.set().map().iter() {
+            reg.register(
+                Name::new(&format!("radiance_{}", name)),
+                OutputItem::Radiance(self.radiances[*index].clone()),
+            )?;
+        }
+
+        for (n, stack) in self.plane_stacks.iter().enumerate() {
+            reg.register(
+                Name::new(&format!("plane_stack_{:03}", n)),
+                OutputItem::PlaneStack(stack.clone()),
+            )?;
+        }
+
+        Ok(reg)
+    }
+
+    /// Record an event of the current photon in the trace log, if tracing is enabled.
+    #[inline]
+    pub fn log_event(&mut self, kind: EventKind, phot: &Photon) {
+        if let Some(ref mut log) = self.log {
+            log.record(kind, phot);
         }
     }
 }
 
+impl Output<'_> {
+    /// Add the data of another output to this one.
+    /// # Errors
+    /// if the volumes, spectrometers, images or ccds of the outputs have different shapes.
+    #[inline]
+    pub fn merge(&mut self, rhs: &Self) -> Result<(), Error> {
+        check_shape(
+            "emission volume",
+            self.emission.shape(),
+            rhs.emission.shape(),
+        )?;
+        check_shape("energy volume", self.energy.shape(), rhs.energy.shape())?;
+        check_shape(
+            "absorption volume",
+            self.absorptions.shape(),
+            rhs.absorptions.shape(),
+        )?;
+        check_shape("shift volume", self.shifts.shape(), rhs.shifts.shape())?;
+        check_shape("flux volume", self.flux.shape(), rhs.flux.shape())?;
+
+        for (a, b) in self.specs.iter().zip(&rhs.specs) {
+            check_shape("spectrometer", a.counts().shape(), b.counts().shape())?;
+        }
+        for (a, b) in self.imgs.iter().zip(&rhs.imgs) {
+            check_shape("image", a.pixels().shape(), b.pixels().shape())?;
+        }
+        for (a, b) in self.ccds.iter().zip(&rhs.ccds) {
+            check_shape("ccd", a.shape(), b.shape())?;
+        }
+        for (a, b) in self.photos.iter().zip(&rhs.photos) {
+            check_shape("photo", a.pixels().shape(), b.pixels().shape())?;
+        }
+        for (a, b) in self.hypers.iter().zip(&rhs.hypers) {
+            check_shape("hyperspectral", a.data().shape(), b.data().shape())?;
+        }
+        for (a, b) in self.radiances.iter().zip(&rhs.radiances) {
+            check_shape("radiance", a.data().shape(), b.data().shape())?;
+        }
+        for (a, b) in self.plane_stacks.iter().zip(&rhs.plane_stacks) {
+            a.check_compatible(b)?;
+        }
+
+        *self += rhs;
+        Ok(())
+    }
+
+    /// Add the data of another output to this one, taking ownership of it,
+    /// so that the photons buffered by its collectors are moved rather than copied.
+    #[inline]
+    pub fn combine(&mut self, mut rhs: Self) {
+        let phot_cols = std::mem::take(&mut rhs.phot_cols);
+        *self += &rhs;
+
+        for (a, b) in self.phot_cols.iter_mut().zip(phot_cols) {
+            a.merge(b);
+        }
+    }
+
+    /// Open the streams of the photon collectors which write their photons to file as they are collected,
+    /// placing each in the given directory.
+    /// Clones of this output, such as those of each thread, write to the same streams.
+    /// # Errors
+    /// if a stream file can not be created.
+    #[inline]
+    pub fn open_streams(&mut self, out_dir: &Path) -> Result<(), Error> {
+        for (name, index) in self.phot_col_reg.set().map().iter() {
+            self.phot_cols[*index]
+                .open_stream(&out_dir.join(&format!("photon_collector_{}.nc", name)))?;
+        }
+        Ok(())
+    }
+
+    /// Save the energy escaped through each face of the boundary as a csv file.
+    #[inline]
+    fn save_escaped(&self, path: &Path) -> Result<(), Error> {
+        let mut file = File::create(path)?;
+        writeln!(file, "face,energy")?;
+        for dir in &BoundaryDirection::ALL {
+            writeln!(file, "{:?},{}", dir, self.escaped[dir.index()])?;
+        }
+        Ok(())
+    }
+}
+
 impl AddAssign<&Self> for Output<'_> {
     #[inline]
     fn add_assign(&mut self, rhs: &Self) {
@@ -118,6 +386,7 @@ impl AddAssign<&Self> for Output<'_> {
         self.energy += &rhs.energy;
         self.absorptions += &rhs.absorptions;
         self.shifts += &rhs.shifts;
+        self.flux += &rhs.flux;
 
         for (a, b) in self.specs.iter_mut().zip(&rhs.specs) {
             *a += b;
@@ -135,9 +404,55 @@ impl AddAssign<&Self> for Output<'_> {
             *a += b;
         }
 
+        for (a, b) in self.photo_vars.iter_mut().zip(&rhs.photo_vars) {
+            if let (Some(a), Some(b)) = (a.as_mut(), b.as_ref()) {
+                *a += b;
+            }
+        }
+
         for (a, b) in self.phot_cols.iter_mut().zip(&rhs.phot_cols) {
             *a += b;
         }
+
+        for (a, b) in self.hypers.iter_mut().zip(&rhs.hypers) {
+            *a += b;
+        }
+
+        for (a, b) in self.radiances.iter_mut().zip(&rhs.radiances) {
+            *a += b;
+        }
+
+        for (a, b) in self.plane_stacks.iter_mut().zip(&rhs.plane_stacks) {
+            *a += b;
+        }
+
+        for (a, b) in self.escaped.iter_mut().zip(&rhs.escaped) {
+            *a += b;
+        }
+
+        add_optional(&mut self.log, &rhs.log);
+
+        add_optional(&mut self.energy_check, &rhs.energy_check);
+
+        add_optional(&mut self.traversal, &rhs.traversal);
+
+        add_optional(&mut self.tallies, &rhs.tallies);
+    }
+}
+
+/// Add an optional accumulator of another output to that of this one.
+/// Accumulators are only enabled within the simulating threads,
+/// so are adopted by outputs, such as the base output, which have none of their own.
+#[inline]
+fn add_optional<T>(lhs: &mut Option<T>, rhs: &Option<T>)
+where
+    T: Clone + for<'b> AddAssign<&'b T>,
+{
+    if let Some(ref b) = *rhs {
+        match *lhs {
+            Some(ref mut a) => *a += b,
+            None => *lhs = Some(b.clone()),
+        }
     }
 }
 
@@ -145,16 +460,16 @@ impl Save for Output<'_> {
     #[inline]
     fn save_data(&self, out_dir: &Path) -> Result<(), Error> {
         let path = out_dir.join("emission_density.nc");
-        (&self.emission / self.cell_vol).save(&path)?;
+        (self.emission.total() / self.cell_vol).save(&path)?;
 
         let path = out_dir.join("energy_density.nc");
-        (&self.energy / self.cell_vol).save(&path)?;
+        (self.energy.total() / self.cell_vol).save(&path)?;
 
         let path = out_dir.join("absorption_density.nc");
-        (&self.absorptions / self.cell_vol).save(&path)?;
+        (self.absorptions.total() / self.cell_vol).save(&path)?;
 
         let path = out_dir.join("shift_density.nc");
-        (&self.shifts / self.cell_vol).save(&path)?;
+        (self.shifts.total() / self.cell_vol).save(&path)?;
 
         for (name, index) in self.spec_reg.set().map().iter() {
             self.specs[*index].save(&out_dir.join(&format!("spectrometer_{}.csv", name)))?;
@@ -172,11 +487,46 @@ impl Save for Output<'_> {
             photo.save(&out_dir.join(&format!("photo_{:03}.png", n)))?;
         }
 
+        for (n, var) in self.photo_vars.iter().enumerate() {
+            if let Some(ref var) = *var {
+                var.variance()
+                    .save(&out_dir.join(&format!("photo_variance_{:03}.nc", n)))?;
+            }
+        }
+
         for (name, index) in self.phot_col_reg.set().map().iter() {
-            self.phot_cols[*index]
-                .save(&out_dir.join(&format!("photon_collector_{}.csv", name)))?;
+            let phot_col = &self.phot_cols[*index];
+            if phot_col.stream.is_some() {
+                phot_col.save(&out_dir.join(&format!("photon_collector_{}.nc", name)))?;
+            } else {
+                phot_col.save(&out_dir.join(&format!("photon_collector_{}.csv", name)))?;
+            }
+            if let Some(ref hist) = phot_col.weight_hist {
+                hist.save(&out_dir.join(&format!("photon_collector_{}_weights.csv", name)))?;
+            }
+            if let Some(ref hist) = phot_col.wavelength_hist {
+                hist.save(&out_dir.join(&format!("photon_collector_{}_wavelengths.csv", name)))?;
+            }
+        }
+
+        for (name, index) in self.hyper_reg.set().map().iter() {
+            self.hypers[*index].save(&out_dir.join(&format!("hyperspectral_{}.nc", name)))?;
+        }
+
+        for (name, index) in self.rad_reg.set().map().iter() {
+            self.radiances[*index].save(&out_dir.join(&format!("radiance_{}.nc", name)))?;
+        }
+
+        for (n, stack) in self.plane_stacks.iter().enumerate() {
+            stack.save(&out_dir.join(&format!("plane_stack_{:03}.nc", n)))?;
+        }
+
+        if let Some(ref log) = self.log {
+            log.save(&out_dir.join("event_log.csv"))?;
         }
 
+        self.save_escaped(&out_dir.join("escaped.csv"))?;
+
         Ok(())
     }
 }
@@ -188,14 +538,23 @@ impl Display for Output<'_> {
         fmt_report!(fmt, self.boundary, "boundary");
         fmt_report!(fmt, self.cell_vol, "cell volume (m^3)");
 
-        fmt_report!(fmt, DataCube::new(&self.emission), "emission data");
-        fmt_report!(fmt, DataCube::new(&self.energy), "energy data");
+        fmt_report!(fmt, DataCube::new(&self.emission.total()), "emission data");
+        fmt_report!(fmt, DataCube::new(&self.energy.total()), "energy data");
         fmt_report!(
             fmt,
-            DataCube::new(&self.absorptions),
+            DataCube::new(&self.absorptions.total()),
             "absorbed energy data"
         );
-        fmt_report!(fmt, DataCube::new(&self.shifts), "shifted energy data");
+        fmt_report!(
+            fmt,
+            DataCube::new(&self.shifts.total()),
+            "shifted energy data"
+        );
+        fmt_report!(
+            fmt,
+            DataCube::new(&self.flux.total()),
+            "path length data (m)"
+        );
 
         fmt_report!(fmt, self.spec_reg, "spectrometer register");
         fmt_report!(fmt, self.img_reg, "imager register");
@@ -207,6 +566,161 @@ impl Display for Output<'_> {
 
         fmt_report!(fmt, self.photos.len(), "photos");
         fmt_report!(fmt, self.phot_cols.len(), "photon collectors");
+        fmt_report!(fmt, self.hypers.len(), "hyperspectral volumes");
+        fmt_report!(fmt, self.radiances.len(), "radiance detectors");
+        fmt_report!(fmt, self.plane_stacks.len(), "plane stacks");
+        fmt_report!(
+            fmt,
+            self.escaped.iter().sum::<f64>(),
+            "energy escaped through open faces"
+        );
+        if let Some(ref check) = self.energy_check {
+            fmt_report!(fmt, check, "interface energy check");
+        }
+        if let Some(ref traversal) = self.traversal {
+            fmt_report!(fmt, traversal, "tree traversal");
+        }
+        if let Some(ref tallies) = self.tallies {
+            fmt_report!(fmt, tallies, "material event tallies");
+        }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        geom::{Grid, Ray},
+        math::{Dir3, Point3},
+        phys::Local,
+        sim::{fixture, travel, OutputType},
+        tools::{Binner, Range},
+    };
+    use rand::Rng;
+
+    #[test]
+    fn test_merge_shape_mismatch() {
+        let reg = Register::new(vec![]);
+        let boundary = Cube::new(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0));
+        let new_output = |res| fixture::blank_output(boundary.clone(), res, &reg);
+
+        let mut a = new_output([2, 2, 2]);
+        let b = new_output([4, 2, 2]);
+        let err = format!("{:?}", a.merge(&b).unwrap_err());
+        assert!(err.contains("[2, 2, 2]"));
+        assert!(err.contains("[4, 2, 2]"));
+
+        let mut c = new_output([2, 2, 2]);
+        c.energy.sum_mut().fill(1.0);
+        a.merge(&c).unwrap();
+        assert_eq!(a.energy.total().sum(), 8.0);
+    }
+
+    #[test]
+    fn test_registry_detector_names() {
+        let reg = Register::new(vec![]);
+        let names = Register::new(vec![Name::new("cube")]);
+        let boundary = Cube::new(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0));
+        let mut data = fixture::blank_output(boundary.clone(), [1, 1, 1], &reg);
+        data.hyper_reg = &names;
+        data.hypers = vec![Hyperspectral::new(
+            Grid::new(boundary, [1, 1, 1]),
+            Binner::new(Range::new(400.0e-9, 800.0e-9), 4),
+        )];
+        data.rad_reg = &names;
+        data.radiances = vec![Radiance::new(
+            Binner::new(Range::new(0.0, 1.0), 2),
+            Binner::new(Range::new(0.0, 1.0), 2),
+        )];
+
+        // Detectors are named after their register entries, as the files they are saved to.
+        let outputs = data.registry().unwrap();
+        let hyper = outputs.id(&Name::new("hyperspectral_cube")).unwrap();
+        assert!(outputs.get_hyperspectral(hyper).is_some());
+        let rad = outputs.id(&Name::new("radiance_cube")).unwrap();
+        assert!(outputs.get_radiance(rad).is_some());
+        assert_eq!(outputs.ids_of_type(OutputType::Hyperspectral), vec![hyper]);
+    }
+
+    #[test]
+    fn test_save_escaped() {
+        let reg = Register::new(vec![]);
+        let boundary = Cube::new(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0));
+        let mut data = fixture::blank_output(boundary, [1, 1, 1], &reg);
+        data.escaped[BoundaryDirection::Top.index()] = 2.5;
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        data.save_escaped(file.path()).unwrap();
+        let csv = std::fs::read_to_string(file.path()).unwrap();
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines.len(), 7);
+        assert_eq!(lines[0], "face,energy");
+        assert_eq!(lines[6], "Top,2.5");
+        assert_eq!(lines[1], "West,0");
+    }
+
+    #[test]
+    fn test_event_log_survives_accumulation() {
+        let reg = Register::new(vec![]);
+        let boundary = Cube::new(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0));
+        let new_output = || fixture::blank_output(boundary.clone(), [1, 1, 1], &reg);
+        let phot = Photon::new(
+            Ray::new(Point3::new(0.0, 0.0, 0.0), Dir3::new(1.0, 0.0, 0.0)),
+            500.0e-9,
+            1.0,
+        );
+
+        // Only the thread outputs trace events, the output they are accumulated into does not.
+        let mut thread = new_output();
+        let mut log = EventLog::new(1);
+        log.begin(0);
+        log.record(EventKind::Emission, &phot);
+        thread.log = Some(log);
+
+        let mut data = new_output();
+        data += &thread;
+        data += &thread;
+        assert_eq!(data.log.unwrap().records().len(), 2);
+    }
+
+    #[test]
+    fn test_fluence_rate_beer_lambert() {
+        let reg = Register::new(vec![]);
+        let boundary = Cube::new(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0));
+        let mut data = fixture::blank_output(boundary, [10, 1, 1], &reg);
+
+        // A collimated beam entering a purely absorbing slab.
+        let abs_coeff = 2.0;
+        let env = Local::new(1.0, 0.0, abs_coeff, 0.0, 0.0);
+        let num_phot = 50_000;
+        let power = 3.0;
+        let mut rng = rand::thread_rng();
+        for _ in 0..num_phot {
+            let mut phot = Photon::new(
+                Ray::new(Point3::new(0.0, 0.5, 0.5), Dir3::new(1.0, 0.0, 0.0)),
+                500.0e-9,
+                power / num_phot as f64,
+            );
+
+            let mut remaining = -rng.gen::<f64>().ln() / abs_coeff;
+            for i in 0..10 {
+                if remaining <= 0.0 {
+                    break;
+                }
+                let step = remaining.min(0.1);
+                travel(&mut data, &mut phot, &env, [i, 0, 0], step);
+                remaining -= step;
+            }
+        }
+
+        // Compare the mean fluence rate of each cell with the analytic profile.
+        let fluence = data.fluence_rate(power, num_phot);
+        for i in 0..5 {
+            let x = i as f64 * 0.1;
+            let analytic = power * ((-abs_coeff * x).exp() - (-abs_coeff * (x + 0.1)).exp())
+                / (abs_coeff * 0.1);
+            assert!(((fluence[[i, 0, 0]] - analytic) / analytic).abs() < 0.03);
+        }
+    }
+}
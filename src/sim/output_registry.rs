@@ -0,0 +1,320 @@
+//! Named output registry.
+
+use crate::{
+    data::Histogram,
+    err::Error,
+    fmt_report,
+    img::Image,
+    ord::{Map, Name},
+    sim::{Hyperspectral, PhotonCollector, PlaneStack, Radiance},
+};
+use ndarray::Array3;
+use std::fmt::{Display, Formatter};
+
+/// Kinds of simulation output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputType {
+    /// Spatially resolved volume data.
+    Volume,
+    /// Wavelength histogram.
+    Spectrometer,
+    /// Colour image.
+    Imager,
+    /// Spectrally resolved image.
+    Ccd,
+    /// Collected photon list.
+    PhotonCollector,
+    /// Spatially and spectrally resolved volume.
+    Hyperspectral,
+    /// Directionally resolved radiance.
+    Radiance,
+    /// Stack of parallel planes.
+    PlaneStack,
+}
+
+/// Output data item.
+#[derive(Clone)]
+pub enum OutputItem {
+    /// Volume data.
+    Volume(Array3<f64>),
+    /// Spectrometer data.
+    Spectrometer(Histogram),
+    /// Image data.
+    Imager(Image),
+    /// Ccd data.
+    Ccd(Array3<f64>),
+    /// Photon collector.
+    PhotonCollector(PhotonCollector),
+    /// Hyperspectral volume.
+    Hyperspectral(Hyperspectral),
+    /// Radiance detector.
+    Radiance(Radiance),
+    /// Plane stack.
+    PlaneStack(PlaneStack),
+}
+
+impl OutputItem {
+    /// Determine the kind of output.
+    #[inline]
+    #[must_use]
+    pub const fn kind(&self) -> OutputType {
+        match *self {
+            Self::Volume(..) => OutputType::Volume,
+            Self::Spectrometer(..) => OutputType::Spectrometer,
+            Self::Imager(..) => OutputType::Imager,
+            Self::Ccd(..) => OutputType::Ccd,
+            Self::PhotonCollector(..) => OutputType::PhotonCollector,
+            Self::Hyperspectral(..) => OutputType::Hyperspectral,
+            Self::Radiance(..) => OutputType::Radiance,
+            Self::PlaneStack(..) => OutputType::PlaneStack,
+        }
+    }
+}
+
+/// Registry of named outputs of mixed type.
+#[derive(Clone, Default)]
+pub struct OutputRegistry {
+    /// Output ids, keyed by name.
+    ids: Map<Name, usize>,
+    /// Registered outputs, in order of registration.
+    items: Vec<OutputItem>,
+}
+
+impl OutputRegistry {
+    /// Construct a new empty instance.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register an output under a name, returning its id.
+    /// # Errors
+    /// if an output has already been registered under the same name.
+    #[inline]
+    pub fn register(&mut self, name: Name, item: OutputItem) -> Result<usize, Error> {
+        if self.ids.contains_key(&name) {
+            return Err(Error::Text(format!(
+                "Duplicate output registration: {}",
+                name
+            )));
+        }
+
+        let id = self.items.len();
+        self.ids.insert(name, id);
+        self.items.push(item);
+        Ok(id)
+    }
+
+    /// Get the number of registered outputs.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Find if there are no registered outputs.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Find the id of a named output.
+    #[inline]
+    #[must_use]
+    pub fn id(&self, name: &Name) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    /// Reference an output by id.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, id: usize) -> Option<&OutputItem> {
+        self.items.get(id)
+    }
+
+    /// Mutably reference an output by id.
+    #[inline]
+    #[must_use]
+    pub fn get_mut(&mut self, id: usize) -> Option<&mut OutputItem> {
+        self.items.get_mut(id)
+    }
+
+    /// Ids of all outputs of a given kind, in order of registration.
+    #[inline]
+    #[must_use]
+    pub fn ids_of_type(&self, kind: OutputType) -> Vec<usize> {
+        self.items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.kind() == kind)
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    /// Reference a volume output by id.
+    #[inline]
+    #[must_use]
+    pub fn get_volume(&self, id: usize) -> Option<&Array3<f64>> {
+        match self.get(id) {
+            Some(OutputItem::Volume(ref vol)) => Some(vol),
+            _ => None,
+        }
+    }
+
+    /// Reference a spectrometer output by id.
+    #[inline]
+    #[must_use]
+    pub fn get_spectrometer(&self, id: usize) -> Option<&Histogram> {
+        match self.get(id) {
+            Some(OutputItem::Spectrometer(ref spec)) => Some(spec),
+            _ => None,
+        }
+    }
+
+    /// Reference an imager output by id.
+    #[inline]
+    #[must_use]
+    pub fn get_imager(&self, id: usize) -> Option<&Image> {
+        match self.get(id) {
+            Some(OutputItem::Imager(ref img)) => Some(img),
+            _ => None,
+        }
+    }
+
+    /// Reference a ccd output by id.
+    #[inline]
+    #[must_use]
+    pub fn get_ccd(&self, id: usize) -> Option<&Array3<f64>> {
+        match self.get(id) {
+            Some(OutputItem::Ccd(ref ccd)) => Some(ccd),
+            _ => None,
+        }
+    }
+
+    /// Reference a photon collector output by id.
+    #[inline]
+    #[must_use]
+    pub fn get_photon_collector(&self, id: usize) -> Option<&PhotonCollector> {
+        match self.get(id) {
+            Some(OutputItem::PhotonCollector(ref col)) => Some(col),
+            _ => None,
+        }
+    }
+
+    /// Reference a hyperspectral output by id.
+    #[inline]
+    #[must_use]
+    pub fn get_hyperspectral(&self, id: usize) -> Option<&Hyperspectral> {
+        match self.get(id) {
+            Some(OutputItem::Hyperspectral(ref hyper)) => Some(hyper),
+            _ => None,
+        }
+    }
+
+    /// Reference a radiance output by id.
+    #[inline]
+    #[must_use]
+    pub fn get_radiance(&self, id: usize) -> Option<&Radiance> {
+        match self.get(id) {
+            Some(OutputItem::Radiance(ref rad)) => Some(rad),
+            _ => None,
+        }
+    }
+
+    /// Reference a plane stack output by id.
+    #[inline]
+    #[must_use]
+    pub fn get_plane_stack(&self, id: usize) -> Option<&PlaneStack> {
+        match self.get(id) {
+            Some(OutputItem::PlaneStack(ref stack)) => Some(stack),
+            _ => None,
+        }
+    }
+}
+
+impl Display for OutputRegistry {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        writeln!(fmt, "...")?;
+        for (name, id) in &self.ids {
+            fmt_report!(
+                fmt,
+                &format!("{:?}", self.items[*id].kind()),
+                name.to_string()
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ids_of_type_volumes() {
+        let mut reg = OutputRegistry::new();
+        let energy = reg
+            .register(
+                Name::new("energy"),
+                OutputItem::Volume(Array3::zeros([2, 2, 2])),
+            )
+            .unwrap();
+        reg.register(
+            Name::new("spec"),
+            OutputItem::Spectrometer(Histogram::new(0.0, 1.0, 10)),
+        )
+        .unwrap();
+        let flux = reg
+            .register(
+                Name::new("flux"),
+                OutputItem::Volume(Array3::ones([3, 3, 3])),
+            )
+            .unwrap();
+        reg.register(
+            Name::new("collector"),
+            OutputItem::PhotonCollector(PhotonCollector::new()),
+        )
+        .unwrap();
+
+        assert_eq!(reg.len(), 4);
+        assert_eq!(reg.ids_of_type(OutputType::Volume), vec![energy, flux]);
+        assert_eq!(reg.ids_of_type(OutputType::Imager), Vec::<usize>::new());
+
+        let vols: Vec<_> = reg
+            .ids_of_type(OutputType::Volume)
+            .into_iter()
+            .map(|id| reg.get_volume(id).unwrap())
+            .collect();
+        assert_eq!(vols[0].shape(), &[2, 2, 2]);
+        assert_eq!(vols[1].sum(), 27.0);
+
+        assert_eq!(reg.id(&Name::new("spec")), Some(1));
+        assert!(reg.get_volume(1).is_none());
+        assert!(reg.get_spectrometer(1).is_some());
+    }
+
+    #[test]
+    fn test_register_duplicate_name() {
+        let mut reg = OutputRegistry::new();
+        reg.register(
+            Name::new("energy"),
+            OutputItem::Volume(Array3::zeros([2, 2, 2])),
+        )
+        .unwrap();
+
+        let err = reg
+            .register(
+                Name::new("energy"),
+                OutputItem::Spectrometer(Histogram::new(0.0, 1.0, 10)),
+            )
+            .unwrap_err();
+        assert!(format!("{:?}", err).contains("energy"));
+
+        // The original output is left in place.
+        assert_eq!(reg.len(), 1);
+        assert!(reg.get_volume(0).is_some());
+    }
+}
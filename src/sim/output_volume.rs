@@ -0,0 +1,150 @@
+//! Voxel accumulation volume.
+
+use crate::access;
+use ndarray::Array3;
+use std::ops::AddAssign;
+
+/// Volume of voxel accumulators.
+/// Optionally uses Neumaier compensated summation, recovering the low-order bits lost
+/// when many small contributions are added to a large total, at the cost of double the memory.
+#[derive(Clone)]
+pub struct OutputVolume {
+    /// Running sum of each voxel.
+    sum: Array3<f64>,
+    /// Optional running compensation of each voxel.
+    comp: Option<Array3<f64>>,
+}
+
+impl OutputVolume {
+    access!(sum, sum_mut: Array3<f64>);
+
+    /// Construct a new zeroed instance using naive summation.
+    #[inline]
+    #[must_use]
+    pub fn new(res: [usize; 3]) -> Self {
+        Self {
+            sum: Array3::zeros(res),
+            comp: None,
+        }
+    }
+
+    /// Construct a new zeroed instance using compensated summation.
+    #[inline]
+    #[must_use]
+    pub fn new_compensated(res: [usize; 3]) -> Self {
+        Self {
+            sum: Array3::zeros(res),
+            comp: Some(Array3::zeros(res)),
+        }
+    }
+
+    /// Switch to compensated summation for all further accumulation.
+    #[inline]
+    pub fn compensate(&mut self) {
+        if self.comp.is_none() {
+            self.comp = Some(Array3::zeros(self.sum.raw_dim()));
+        }
+    }
+
+    /// Check if compensated summation is in use.
+    #[inline]
+    #[must_use]
+    pub const fn is_compensated(&self) -> bool {
+        self.comp.is_some()
+    }
+
+    /// Shape of the volume.
+    #[inline]
+    #[must_use]
+    pub fn shape(&self) -> &[usize] {
+        self.sum.shape()
+    }
+
+    /// Add a value to a voxel.
+    #[inline]
+    pub fn add(&mut self, index: [usize; 3], val: f64) {
+        match self.comp {
+            Some(ref mut comp) => {
+                Self::neumaier(&mut self.sum[index], &mut comp[index], val);
+            }
+            None => self.sum[index] += val,
+        }
+    }
+
+    /// Accumulated total of each voxel, including any compensation.
+    #[inline]
+    #[must_use]
+    pub fn total(&self) -> Array3<f64> {
+        match self.comp {
+            Some(ref comp) => &self.sum + comp,
+            None => self.sum.clone(),
+        }
+    }
+
+    /// Add a value to a compensated sum.
+    #[inline]
+    fn neumaier(sum: &mut f64, comp: &mut f64, val: f64) {
+        let t = *sum + val;
+        if sum.abs() >= val.abs() {
+            *comp += (*sum - t) + val;
+        } else {
+            *comp += (val - t) + *sum;
+        }
+        *sum = t;
+    }
+}
+
+impl AddAssign<&Self> for OutputVolume {
+    #[inline]
+    fn add_assign(&mut self, rhs: &Self) {
+        if rhs.is_compensated() {
+            self.compensate();
+        }
+
+        match self.comp {
+            Some(ref mut comp) => {
+                for ((sum, comp), val) in
+                    self.sum.iter_mut().zip(comp.iter_mut()).zip(rhs.sum.iter())
+                {
+                    Self::neumaier(sum, comp, *val);
+                }
+                if let Some(ref rhs_comp) = rhs.comp {
+                    *comp += rhs_comp;
+                }
+            }
+            None => self.sum += &rhs.sum,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compensated_accumulation() {
+        let num = 1_000_000;
+        let weight = 1.0e-16;
+        let analytic = 1.0 + num as f64 * weight;
+
+        let mut naive = OutputVolume::new([1, 1, 1]);
+        let mut compensated = OutputVolume::new_compensated([1, 1, 1]);
+        for vol in [&mut naive, &mut compensated].iter_mut() {
+            vol.add([0, 0, 0], 1.0);
+            for _ in 0..num {
+                vol.add([0, 0, 0], weight);
+            }
+        }
+
+        let naive_err = (naive.total()[[0, 0, 0]] - analytic).abs();
+        let compensated_err = (compensated.total()[[0, 0, 0]] - analytic).abs();
+        assert!(compensated_err < 1.0e-15);
+        assert!(compensated_err < naive_err);
+
+        // Merging into a naive volume keeps the compensation.
+        let mut merged = OutputVolume::new([1, 1, 1]);
+        merged += &compensated;
+        assert!(merged.is_compensated());
+        assert!((merged.total()[[0, 0, 0]] - analytic).abs() < 1.0e-15);
+    }
+}
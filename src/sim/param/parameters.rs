@@ -5,7 +5,10 @@ use crate::{
     geom::{Grid, SurfaceLinker, TreeSettings},
     ord::Set,
     phys::{LightLinker, Material},
-    sim::{AttributeLinkerLinkerLinkerLinkerLinker, Engine, Settings},
+    sim::{
+        AttributeLinkerLinkerLinkerLinkerLinker, BoundaryBuilder, Engine, PlaneStackBuilder,
+        Settings,
+    },
 };
 use std::fmt::{Display, Error, Formatter};
 
@@ -17,6 +20,8 @@ pub struct Parameters {
     pub tree: TreeSettings,
     /// Measurement grid settings.
     pub grid: Grid,
+    /// Optional boundary of the measurement grid.
+    pub boundary: Option<BoundaryBuilder>,
     /// Surfaces.
     pub surfs: Set<SurfaceLinker>,
     /// Attributes.
@@ -27,6 +32,8 @@ pub struct Parameters {
     pub lights: Set<LightLinker>,
     /// Engine selection.
     pub engine: Engine,
+    /// Optional stacks of output planes.
+    pub planes: Option<Set<PlaneStackBuilder>>,
 }
 
 impl Parameters {
@@ -38,6 +45,7 @@ impl Parameters {
         sett: Settings,
         tree: TreeSettings,
         grid: Grid,
+        boundary: Option<BoundaryBuilder>,
         surfs: Set<SurfaceLinker>,
         attrs: Set<AttributeLinkerLinkerLinkerLinkerLinker>,
         mats: Set<Material>,
@@ -48,13 +56,23 @@ impl Parameters {
             sett,
             tree,
             grid,
+            boundary,
             surfs,
             attrs,
             mats,
             lights,
             engine,
+            planes: None,
         }
     }
+
+    /// Record the photon paths crossing the given stacks of output planes.
+    #[inline]
+    #[must_use]
+    pub fn with_planes(mut self, planes: Option<Set<PlaneStackBuilder>>) -> Self {
+        self.planes = planes;
+        self
+    }
 }
 
 impl Display for Parameters {
@@ -64,11 +82,17 @@ impl Display for Parameters {
         fmt_report!(fmt, self.sett, "settings");
         fmt_report!(fmt, self.tree, "tree settings");
         fmt_report!(fmt, self.grid, "grid settings");
+        if let Some(ref boundary) = self.boundary {
+            fmt_report!(fmt, boundary, "boundary");
+        }
         fmt_report!(fmt, self.surfs, "surfaces");
         fmt_report!(fmt, self.attrs, "attributes");
         fmt_report!(fmt, self.mats, "materials");
         fmt_report!(fmt, self.lights, "lights");
         fmt_report!(fmt, self.engine, "engine");
+        if let Some(ref planes) = self.planes {
+            fmt_report!(fmt, planes, "plane stacks");
+        }
         Ok(())
     }
 }
@@ -5,7 +5,10 @@ use crate::{
     geom::{GridBuilder, SurfaceLinker, TreeSettings},
     ord::{Build, Set},
     phys::{LightLinkerBuilder, MaterialBuilder},
-    sim::{AttributeLinkerLinkerLinkerLinkerLinker, EngineBuilder, Parameters, Settings},
+    sim::{
+        AttributeLinkerLinkerLinkerLinkerLinker, BoundaryBuilder, EngineBuilder, Parameters,
+        PlaneStackBuilder, Settings,
+    },
 };
 use std::fmt::{Display, Error, Formatter};
 
@@ -17,6 +20,8 @@ pub struct ParametersBuilder {
     tree: TreeSettings,
     /// Measurement grid settings.
     grid: GridBuilder,
+    /// Optional boundary of the measurement grid.
+    boundary: Option<BoundaryBuilder>,
     /// Surfaces.
     surfs: Set<SurfaceLinker>,
     /// Attributes.
@@ -27,6 +32,8 @@ pub struct ParametersBuilder {
     lights: Set<LightLinkerBuilder>,
     /// Engine selection.
     engine: EngineBuilder,
+    /// Optional stacks of output planes.
+    planes: Option<Set<PlaneStackBuilder>>,
 }
 
 impl ParametersBuilder {
@@ -38,6 +45,7 @@ impl ParametersBuilder {
         sett: Settings,
         tree: TreeSettings,
         grid: GridBuilder,
+        boundary: Option<BoundaryBuilder>,
         surfs: Set<SurfaceLinker>,
         attrs: Set<AttributeLinkerLinkerLinkerLinkerLinker>,
         mats: Set<MaterialBuilder>,
@@ -48,13 +56,23 @@ impl ParametersBuilder {
             sett,
             tree,
             grid,
+            boundary,
             surfs,
             attrs,
             mats,
             lights,
             engine,
+            planes: None,
         }
     }
+
+    /// Record the photon paths crossing the given stacks of output planes.
+    #[inline]
+    #[must_use]
+    pub fn with_planes(mut self, planes: Option<Set<PlaneStackBuilder>>) -> Self {
+        self.planes = planes;
+        self
+    }
 }
 
 impl Build for ParametersBuilder {
@@ -65,13 +83,17 @@ impl Build for ParametersBuilder {
         let sett = self.sett;
         let tree = self.tree;
         let grid = self.grid.build();
+        let boundary = self.boundary;
         let surfs = self.surfs;
         let attrs = self.attrs;
         let mats = self.mats.build();
         let light = self.lights.build();
         let engine = self.engine.build();
 
-        Self::Inst::new(sett, tree, grid, surfs, attrs, mats, light, engine)
+        Self::Inst::new(
+            sett, tree, grid, boundary, surfs, attrs, mats, light, engine,
+        )
+        .with_planes(self.planes)
     }
 }
 
@@ -82,11 +104,17 @@ impl Display for ParametersBuilder {
         fmt_report!(fmt, self.sett, "settings");
         fmt_report!(fmt, self.tree, "tree settings");
         fmt_report!(fmt, self.grid, "grid settings");
+        if let Some(ref boundary) = self.boundary {
+            fmt_report!(fmt, boundary, "boundary");
+        }
         fmt_report!(fmt, self.surfs, "surfaces");
         fmt_report!(fmt, self.attrs, "attributes");
         fmt_report!(fmt, self.mats, "materials");
         fmt_report!(fmt, self.lights, "lights");
         fmt_report!(fmt, self.engine, "engine");
+        if let Some(ref planes) = self.planes {
+            fmt_report!(fmt, planes, "plane stacks");
+        }
         Ok(())
     }
 }
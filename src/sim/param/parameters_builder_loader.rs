@@ -7,7 +7,8 @@ use crate::{
     ord::Set,
     phys::{LightLinkerBuilderLoader, MaterialBuilder},
     sim::{
-        AttributeLinkerLinkerLinkerLinkerLinker, EngineBuilderLoader, ParametersBuilder, Settings,
+        AttributeLinkerLinkerLinkerLinkerLinker, BoundaryBuilder, EngineBuilderLoader,
+        ParametersBuilder, PlaneStackBuilder, Settings,
     },
 };
 use arctk_attr::file;
@@ -22,6 +23,8 @@ pub struct ParametersBuilderLoader {
     tree: Redirect<TreeSettings>,
     /// Measurement grid settings.
     grid: Redirect<GridBuilder>,
+    /// Optional boundary of the measurement grid, applied to photons leaving it.
+    boundary: Option<Redirect<BoundaryBuilder>>,
     /// Surfaces.
     surfs: Redirect<Set<SurfaceLinkerLoader>>,
     /// Attributes.
@@ -32,6 +35,8 @@ pub struct ParametersBuilderLoader {
     lights: Redirect<Set<LightLinkerBuilderLoader>>,
     /// Engine selection.
     engine: EngineBuilderLoader,
+    /// Optional stacks of output planes, recording the photon paths crossing them.
+    planes: Option<Redirect<Set<PlaneStackBuilder>>>,
 }
 
 impl Load for ParametersBuilderLoader {
@@ -40,16 +45,30 @@ impl Load for ParametersBuilderLoader {
     #[inline]
     fn load(self, in_dir: &Path) -> Result<Self::Inst, Error> {
         let sett = self.sett.load(in_dir)?;
+        sett.validate()?;
         let tree = self.tree.load(in_dir)?;
         let grid = self.grid.load(in_dir)?;
+        let boundary = match self.boundary {
+            Some(boundary) => Some(boundary.load(in_dir)?),
+            None => None,
+        };
         let surfs = self.surfs.load(in_dir)?.load(in_dir)?;
-        let attrs = self.attrs.load(in_dir)?;
+        let attrs = self.attrs.load(in_dir)?.load(in_dir)?;
         let mats = self.mats.load(in_dir)?.load(in_dir)?;
+        for (name, mat) in mats.map() {
+            mat.validate()
+                .map_err(|err| err.with_context(&name.as_string()).with_context("mats"))?;
+        }
         let lights = self.lights.load(in_dir)?.load(in_dir)?;
         let engine = self.engine.load(in_dir)?;
+        let planes = match self.planes {
+            Some(planes) => Some(planes.load(in_dir)?),
+            None => None,
+        };
 
         Ok(Self::Inst::new(
-            sett, tree, grid, surfs, attrs, mats, lights, engine,
-        ))
+            sett, tree, grid, boundary, surfs, attrs, mats, lights, engine,
+        )
+        .with_planes(planes))
     }
 }
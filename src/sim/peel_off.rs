@@ -1,7 +1,7 @@
 //! Peel-off function.
 
 use crate::{
-    math::{Dir3, Point3},
+    math::{Dir3, Distribution, Point3},
     phys::{Local, Photon},
     sim::{Attribute, Input},
 };
@@ -15,23 +15,65 @@ const THRESHOLD: f64 = 1.0e-6;
 #[inline]
 #[must_use]
 pub fn peel_off(input: &Input, mut phot: Photon, env: &Local, pos: Point3) -> Option<f64> {
-    let g = env.asym();
-    let g_sq = g * g;
+    let phase = Distribution::HenyeyGreenstein { g: env.asym() };
+    let prob = phase_prob(&phase, &phot, &pos)?;
 
-    let dir = Dir3::from(pos - phot.ray().pos());
+    *phot.ray_mut().dir_mut() = Dir3::from(pos - phot.ray().pos());
+    transmit(input, phot, env.inter_coeff(), pos, prob)
+}
+
+/// Perform a peel-off event towards each of a set of detector positions.
+/// The scattering point and phase function setup are shared between detectors,
+/// and the result for each is identical to that of a single detector `peel_off`.
+#[inline]
+#[must_use]
+pub fn peel_off_multi(
+    input: &Input,
+    phot: &Photon,
+    env: &Local,
+    targets: &[Point3],
+) -> Vec<Option<f64>> {
+    let phase = Distribution::HenyeyGreenstein { g: env.asym() };
+    let inter_coeff = env.inter_coeff();
+
+    targets
+        .iter()
+        .map(|pos| {
+            let prob = phase_prob(&phase, phot, pos)?;
 
-    let cos_ang = phot.ray().dir().dot(&dir);
-    let mut prob = 0.5 * ((1.0 - g_sq) / (1.0 + g_sq - (2.0 * g * cos_ang)).powf(1.5));
+            let mut phot = phot.clone();
+            *phot.ray_mut().dir_mut() = Dir3::from(*pos - phot.ray().pos());
+            transmit(input, phot, inter_coeff, *pos, prob)
+        })
+        .collect()
+}
+
+/// Calculate the phase function probability of scattering towards a point.
+/// Returns `None` if the probability falls below the threshold.
+#[inline]
+fn phase_prob(phase: &Distribution, phot: &Photon, pos: &Point3) -> Option<f64> {
+    let dir = Dir3::from(*pos - phot.ray().pos());
+
+    let cos_ang = phot.ray().dir().dot(&dir).max(-1.0).min(1.0);
+    let prob = phase.pdf(cos_ang);
 
     if prob < THRESHOLD {
         return None;
     }
+    Some(prob)
+}
 
-    *phot.ray_mut().dir_mut() = dir;
-
+/// Calculate the weight transmitted along the flight of a photon, already directed at the given point.
+#[inline]
+fn transmit(
+    input: &Input,
+    mut phot: Photon,
+    mut inter_coeff: f64,
+    pos: Point3,
+    mut prob: f64,
+) -> Option<f64> {
     let loop_limit = input.sett.loop_limit();
     let bump_dist = input.sett.bump_dist();
-    let mut inter_coeff = env.inter_coeff();
 
     // Main trace loop.
     let mut num_loops = 0;
@@ -73,7 +115,12 @@ pub fn peel_off(input: &Input, mut phot: Photon, env: &Local, pos: Point3) -> Op
                 | Attribute::Imager(..)
                 | Attribute::Ccd(..)
                 | Attribute::Reflector(..)
-                | Attribute::PhotonCollector(..) => return None,
+                | Attribute::TexturedReflector(..)
+                | Attribute::Translucent(..)
+                | Attribute::PhotonCollector(..)
+                | Attribute::Hyperspectral(..)
+                | Attribute::Radiance(..)
+                | Attribute::AttributeChain(..) => return None,
             }
         } else {
             prob *= (-tar_dist * inter_coeff).exp();
@@ -87,3 +134,46 @@ pub fn peel_off(input: &Input, mut phot: Photon, env: &Local, pos: Point3) -> Op
 
     Some(prob)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        geom::{Cube, Grid, Ray},
+        ord::{Map, Register, Set},
+        sim::fixture::{self, WAVELENGTH},
+    };
+
+    #[test]
+    fn test_multi_matches_single() {
+        // A single mirror well away from the flight paths.
+        let mirror = Attribute::Mirror(0.5);
+        let surfs = fixture::far_mirror(&mirror);
+        let tree = fixture::single_cell_tree(&surfs);
+
+        let reg = Register::new(vec![]);
+        let mats = Set::new(Map::new());
+        let attrs = Set::new(Map::new());
+        let mat = fixture::material(1.0, Some(0.5), 0.8);
+        let ray = Ray::new(Point3::new(0.0, 0.0, 0.0), Dir3::new(1.0, 0.0, 0.0));
+        let light = fixture::beam(1.0, ray.clone(), &mat);
+        let grid = Grid::new(
+            Cube::new(Point3::new(-5.0, -5.0, -5.0), Point3::new(5.0, 5.0, 5.0)),
+            [1, 1, 1],
+        );
+        let sett = fixture::settings("num_phot: 1");
+        let input = Input::new(&reg, &mats, &attrs, light, &tree, &grid, &sett);
+
+        let phot = Photon::new(ray, WAVELENGTH, 1.0);
+        let env = mat.sample_environment(phot.wavelength());
+        let targets = [Point3::new(2.0, 1.0, 0.0), Point3::new(-1.0, 0.0, 3.0)];
+
+        let multi = peel_off_multi(&input, &phot, &env, &targets);
+        assert_eq!(multi.len(), 2);
+        for (weight, pos) in multi.iter().zip(targets.iter()) {
+            let single = peel_off(&input, phot.clone(), &env, *pos);
+            assert!(single.is_some());
+            assert_eq!(*weight, single);
+        }
+    }
+}
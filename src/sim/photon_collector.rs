@@ -1,5 +1,20 @@
-use crate::{err::Error, fmt_report, fs::Save, phys::Photon, tools::ProgressBar};
-use std::{fmt::Display, fs::File, io::Write, ops::AddAssign, path::Path};
+use crate::{
+    data::Histogram,
+    err::Error,
+    fmt_report,
+    fs::Save,
+    phys::Photon,
+    sim::PhotonStream,
+    tools::{Binner, ProgressBar},
+};
+use std::{
+    fmt::Display,
+    fs::File,
+    io::Write,
+    ops::AddAssign,
+    path::Path,
+    sync::{Arc, Mutex},
+};
 
 /*
 pub fn photon_collector_write_thread(path_str: String, rx: Receiver<Option<Photon>>) {
@@ -45,6 +60,16 @@ pub struct PhotonCollector {
     pub photons: Vec<Photon>,
     /// Whether the collector should kill the photon when it has been collected.
     pub kill_photon: bool,
+    /// Whether individual photons are discarded once summarised, rather than stored.
+    pub discard_photons: bool,
+    /// Optional histogram of the number of photons detected with each weight.
+    pub weight_hist: Option<Histogram>,
+    /// Optional histogram of the number of photons detected at each wavelength.
+    pub wavelength_hist: Option<Histogram>,
+    /// Optional number of photons buffered when streaming them to file, rather than storing them.
+    pub stream_buffer: Option<usize>,
+    /// Optional stream, shared between threads, to which collected photons are written instead of being stored.
+    pub stream: Option<Arc<Mutex<PhotonStream>>>,
 }
 
 impl PhotonCollector {
@@ -54,8 +79,43 @@ impl PhotonCollector {
         }
     }
 
+    /// Maintain summary histograms of detected weight and wavelength using the given binners.
+    /// If `discard_photons` is set, individual photons are not stored.
+    #[inline]
+    #[must_use]
+    pub fn with_summary(
+        mut self,
+        weight_binner: Binner,
+        wavelength_binner: Binner,
+        discard_photons: bool,
+    ) -> Self {
+        self.weight_hist = Some(Histogram::new_binner(weight_binner));
+        self.wavelength_hist = Some(Histogram::new_binner(wavelength_binner));
+        self.discard_photons = discard_photons;
+        self
+    }
+
+    /// Collect a photon, summarising it and storing it or writing it to the stream.
+    /// # Panics
+    /// if the photon can not be written to the stream.
+    #[allow(clippy::expect_used)]
     pub fn collect_photon(&mut self, phot: &mut Photon) {
-        self.photons.push(phot.clone());
+        if let Some(ref mut hist) = self.weight_hist {
+            hist.collect(phot.weight());
+        }
+        if let Some(ref mut hist) = self.wavelength_hist {
+            hist.collect(phot.wavelength());
+        }
+
+        if let Some(ref stream) = self.stream {
+            stream
+                .lock()
+                .expect("Could not lock photon stream.")
+                .collect_photon(phot)
+                .expect("Could not write to photon stream.");
+        } else if !self.discard_photons {
+            self.photons.push(phot.clone());
+        }
 
         if self.kill_photon {
             phot.kill();
@@ -65,11 +125,52 @@ impl PhotonCollector {
     pub fn nphoton(&self) -> usize {
         self.photons.iter().count()
     }
+
+    /// Combine another collector into this one, appending its photons without copying them.
+    /// Summary histograms are summed, so their contents do not depend on the order of merging.
+    #[inline]
+    pub fn merge(&mut self, mut other: Self) {
+        self.photons.append(&mut other.photons);
+        self.add_summaries(&other);
+    }
+
+    /// Stream collected photons to a NetCDF file at the given path, if a stream buffer size has been set.
+    /// # Errors
+    /// if the stream file can not be created.
+    #[inline]
+    pub fn open_stream(&mut self, path: &Path) -> Result<(), Error> {
+        if let Some(buffer_size) = self.stream_buffer {
+            self.stream = Some(Arc::new(Mutex::new(PhotonStream::new(
+                path,
+                buffer_size,
+                false,
+            )?)));
+        }
+        Ok(())
+    }
+
+    /// Sum the summary histograms of another collector into those of this one.
+    #[inline]
+    fn add_summaries(&mut self, rhs: &Self) {
+        if let (Some(ref mut lhs), Some(ref rhs)) = (&mut self.weight_hist, &rhs.weight_hist) {
+            *lhs += rhs;
+        }
+        if let (Some(ref mut lhs), Some(ref rhs)) =
+            (&mut self.wavelength_hist, &rhs.wavelength_hist)
+        {
+            *lhs += rhs;
+        }
+    }
 }
 
 impl Save for PhotonCollector {
     /// Loads the fields of the photon into a vec of vecs and outputs using a table to CSV.
     fn save_data(&self, path: &Path) -> Result<(), Error> {
+        // Streamed photons are already in their own file, bar those still buffered.
+        if let Some(ref stream) = self.stream {
+            return stream.lock()?.flush();
+        }
+
         if self.photons.iter().count() > 0 {
             let mut file = File::create(path)?;
 
@@ -120,7 +221,8 @@ impl Save for PhotonCollector {
 impl AddAssign<&Self> for PhotonCollector {
     #[inline]
     fn add_assign(&mut self, rhs: &Self) {
-        self.photons.extend(rhs.photons.clone());
+        self.photons.extend_from_slice(&rhs.photons);
+        self.add_summaries(rhs);
     }
 }
 
@@ -129,6 +231,12 @@ impl Display for PhotonCollector {
     fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(fmt, "PhotonCollector: ")?;
         fmt_report!(fmt, self.kill_photon, "kill on collect");
+        fmt_report!(fmt, self.discard_photons, "discard photons");
+        fmt_report!(fmt, self.weight_hist.is_some(), "weight summary");
+        fmt_report!(fmt, self.wavelength_hist.is_some(), "wavelength summary");
+        if let Some(ref stream) = self.stream {
+            fmt_report!(fmt, stream.lock().map_err(|_| std::fmt::Error)?, "stream");
+        }
         Ok(())
     }
 }
@@ -137,9 +245,12 @@ impl Display for PhotonCollector {
 mod tests {
     use super::PhotonCollector;
     use crate::{
+        data::Histogram,
+        fs::Save,
         geom::Ray,
         math::{Dir3, Point3},
         phys::Photon,
+        tools::{Binner, Range},
     };
 
     #[test]
@@ -201,4 +312,128 @@ mod tests {
         assert_eq!(col1.photons[0].wavelength(), col2.photons[0].wavelength());
         assert_eq!(col1.photons[0].power(), col2.photons[0].power());
     }
+
+    #[test]
+    fn test_summary_histograms() {
+        let weight_binner = Binner::new(Range::new(0.0, 1.0), 4);
+        let wavelength_binner = Binner::new(Range::new(400.0e-9, 700.0e-9), 6);
+
+        let mut full = PhotonCollector::new().with_summary(
+            weight_binner.clone(),
+            wavelength_binner.clone(),
+            false,
+        );
+        let mut summary = PhotonCollector::new().with_summary(
+            weight_binner.clone(),
+            wavelength_binner.clone(),
+            true,
+        );
+
+        let ray = Ray::new(Point3::new(0.0, 0.0, 0.0), Dir3::new(1.0, 0.0, 0.0));
+        for i in 0..20 {
+            let mut phot = Photon::new(ray.clone(), 410.0e-9 + (f64::from(i) * 14.0e-9), 1.0);
+            *phot.weight_mut() = 0.05 * f64::from(i);
+            full.collect_photon(&mut phot.clone());
+            summary.collect_photon(&mut phot);
+        }
+
+        // Only the summary is kept when discarding photons.
+        assert_eq!(summary.nphoton(), 0);
+        assert_eq!(full.nphoton(), 20);
+
+        let mut weight_hist = Histogram::new_binner(weight_binner);
+        let mut wavelength_hist = Histogram::new_binner(wavelength_binner);
+        for phot in &full.photons {
+            weight_hist.collect(phot.weight());
+            wavelength_hist.collect(phot.wavelength());
+        }
+
+        assert_eq!(
+            summary.weight_hist.as_ref().unwrap().counts(),
+            weight_hist.counts()
+        );
+        assert_eq!(
+            summary.wavelength_hist.as_ref().unwrap().counts(),
+            wavelength_hist.counts()
+        );
+    }
+
+    #[test]
+    fn test_merge_photon_collectors() {
+        let weight_binner = Binner::new(Range::new(0.0, 1.0), 5);
+        let wavelength_binner = Binner::new(Range::new(400.0e-9, 700.0e-9), 10);
+        let new_col = || {
+            PhotonCollector::new().with_summary(
+                weight_binner.clone(),
+                wavelength_binner.clone(),
+                false,
+            )
+        };
+
+        let mut single = new_col();
+        let mut parts = vec![new_col(), new_col(), new_col()];
+        let ray = Ray::new(Point3::new(0.0, 0.0, 0.0), Dir3::new(1.0, 0.0, 0.0));
+        for i in 0..30 {
+            let mut phot = Photon::new(ray.clone(), 405.0e-9 + (f64::from(i) * 9.5e-9), 1.0);
+            *phot.weight_mut() = 0.033 * f64::from(i);
+            single.collect_photon(&mut phot.clone());
+            parts[i as usize % 3].collect_photon(&mut phot);
+        }
+
+        // Merge in two different orders.
+        let mut forward = parts[0].clone();
+        forward.merge(parts[1].clone());
+        forward.merge(parts[2].clone());
+
+        let mut backward = parts[2].clone();
+        let mut rest = parts[1].clone();
+        rest.merge(parts[0].clone());
+        backward.merge(rest);
+
+        for merged in &[forward, backward] {
+            assert_eq!(merged.nphoton(), single.nphoton());
+            assert_eq!(
+                merged.weight_hist.as_ref().unwrap().counts(),
+                single.weight_hist.as_ref().unwrap().counts()
+            );
+            assert_eq!(
+                merged.wavelength_hist.as_ref().unwrap().counts(),
+                single.wavelength_hist.as_ref().unwrap().counts()
+            );
+
+            let mut wavelengths: Vec<_> = merged.photons.iter().map(Photon::wavelength).collect();
+            wavelengths.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            let expected: Vec<_> = single.photons.iter().map(Photon::wavelength).collect();
+            assert_eq!(wavelengths, expected);
+        }
+    }
+
+    #[test]
+    fn test_streamed_photons_shared_between_clones() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("photons.nc");
+        let mut col = PhotonCollector::new();
+        col.stream_buffer = Some(4);
+        col.open_stream(&path).unwrap();
+
+        // Clones, such as those of each thread, write to the same file.
+        let mut other = col.clone();
+        let ray = Ray::new(Point3::new(0.0, 0.0, 0.0), Dir3::new(1.0, 0.0, 0.0));
+        for _ in 0..5 {
+            col.collect_photon(&mut Photon::new(ray.clone(), 5.0e-7, 1.0));
+            other.collect_photon(&mut Photon::new(ray.clone(), 6.0e-7, 1.0));
+        }
+        assert_eq!(col.nphoton(), 0);
+
+        col.save(&path).unwrap();
+        let file = netcdf::open(&path).unwrap();
+        let wavelengths = file
+            .variable("wavelength")
+            .unwrap()
+            .values_arr::<f64, _>(..)
+            .unwrap();
+        assert_eq!(wavelengths.len(), 10);
+
+        let _ = dir.close();
+    }
 }
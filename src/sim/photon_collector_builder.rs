@@ -0,0 +1,168 @@
+//! Photon collector builder.
+
+use crate::{
+    err::Error,
+    fmt_report,
+    sim::PhotonCollector,
+    tools::{Binner, Range},
+};
+use arctk_attr::file;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// Loadable photon collector.
+/// May be given as a single flag, setting whether collected photons are killed, or as a set of options.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum PhotonCollectorBuilder {
+    /// Whether collected photons are killed.
+    Kill(bool),
+    /// Collection options.
+    Options(PhotonCollectorOptions),
+}
+
+/// Loadable photon collector options.
+#[file]
+pub struct PhotonCollectorOptions {
+    /// Optionally kill photons once they are collected.
+    kill: Option<bool>,
+    /// Optional summary histograms of the collected photons.
+    summary: Option<PhotonSummaryBuilder>,
+    /// Optionally stream collected photons to a NetCDF file, buffering the given number of photons,
+    /// rather than holding them all in memory.
+    stream: Option<usize>,
+}
+
+/// Loadable summary histograms of the weight and wavelength of collected photons.
+#[file]
+pub struct PhotonSummaryBuilder {
+    /// Range of weights.
+    weights: [f64; 2],
+    /// Number of weight bins.
+    weight_bins: usize,
+    /// Range of wavelengths [m].
+    wavelengths: [f64; 2],
+    /// Number of wavelength bins.
+    wavelength_bins: usize,
+    /// Optionally discard individual photons once summarised, rather than storing them.
+    discard_photons: Option<bool>,
+}
+
+/// Construct a binner over a range, checking that it can be binned.
+fn build_binner([min, max]: [f64; 2], bins: usize) -> Result<Binner, Error> {
+    let valid = min < max && bins > 0;
+    if !valid {
+        return Err(Error::Text(format!(
+            "Summary histograms must have a non-empty range and at least one bin: [{}, {}] with {} bins",
+            min, max, bins
+        )));
+    }
+
+    Ok(Binner::new(Range::new(min, max), bins))
+}
+
+impl PhotonCollectorBuilder {
+    /// Build the collector.
+    /// # Errors
+    /// if a summary histogram has an empty range, or no bins, or the stream buffer is empty.
+    #[inline]
+    pub fn build(&self) -> Result<PhotonCollector, Error> {
+        let mut phot_col = PhotonCollector::new();
+        match *self {
+            Self::Kill(kill) => phot_col.kill_photon = kill,
+            Self::Options(ref opts) => {
+                phot_col.kill_photon = opts.kill.unwrap_or(false);
+                if opts.stream == Some(0) {
+                    return Err("Photon stream buffers must hold at least one photon.".into());
+                }
+                phot_col.stream_buffer = opts.stream;
+                if let Some(ref summary) = opts.summary {
+                    phot_col = phot_col.with_summary(
+                        build_binner(summary.weights, summary.weight_bins)
+                            .map_err(|err| err.with_context("weights"))?,
+                        build_binner(summary.wavelengths, summary.wavelength_bins)
+                            .map_err(|err| err.with_context("wavelengths"))?,
+                        summary.discard_photons.unwrap_or(false),
+                    );
+                }
+            }
+        }
+
+        Ok(phot_col)
+    }
+}
+
+impl Display for PhotonCollectorBuilder {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match *self {
+            Self::Kill(kill) => {
+                writeln!(fmt, "...")?;
+                fmt_report!(fmt, kill, "kill photons?");
+            }
+            Self::Options(ref opts) => {
+                writeln!(fmt, "...")?;
+                fmt_report!(fmt, opts.kill.unwrap_or(false), "kill photons?");
+                if let Some(buffer_size) = opts.stream {
+                    fmt_report!(fmt, buffer_size, "stream buffer size");
+                }
+                if let Some(ref summary) = opts.summary {
+                    fmt_report!(
+                        fmt,
+                        &format!("[{}, {}]", summary.weights[0], summary.weights[1]),
+                        "weight range"
+                    );
+                    fmt_report!(fmt, summary.weight_bins, "weight bins");
+                    fmt_report!(
+                        fmt,
+                        &format!("[{}, {}]", summary.wavelengths[0], summary.wavelengths[1]),
+                        "wavelength range (m)"
+                    );
+                    fmt_report!(fmt, summary.wavelength_bins, "wavelength bins");
+                    fmt_report!(
+                        fmt,
+                        summary.discard_photons.unwrap_or(false),
+                        "discard photons?"
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::from_json_str;
+
+    #[test]
+    fn test_flag_and_options() {
+        let builder: PhotonCollectorBuilder = from_json_str("true").unwrap();
+        let phot_col = builder.build().unwrap();
+        assert!(phot_col.kill_photon);
+        assert!(phot_col.weight_hist.is_none());
+
+        let builder: PhotonCollectorBuilder = from_json_str(
+            "{ summary: { weights: [0.0, 1.0], weight_bins: 10, wavelengths: [400e-9, 800e-9], wavelength_bins: 4, discard_photons: true } }",
+        )
+        .unwrap();
+        let phot_col = builder.build().unwrap();
+        assert!(!phot_col.kill_photon);
+        assert!(phot_col.discard_photons);
+        assert_eq!(phot_col.weight_hist.unwrap().counts().len(), 10);
+        assert_eq!(phot_col.wavelength_hist.unwrap().counts().len(), 4);
+
+        let builder: PhotonCollectorBuilder = from_json_str(
+            "{ kill: true, summary: { weights: [1.0, 0.0], weight_bins: 10, wavelengths: [400e-9, 800e-9], wavelength_bins: 4 } }",
+        )
+        .unwrap();
+        let err = builder.build().unwrap_err();
+        assert!(format!("{:?}", err).contains("weights"));
+
+        let builder: PhotonCollectorBuilder = from_json_str("{ stream: 1024 }").unwrap();
+        let phot_col = builder.build().unwrap();
+        assert_eq!(phot_col.stream_buffer, Some(1024));
+        assert!(phot_col.stream.is_none());
+    }
+}
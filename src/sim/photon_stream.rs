@@ -0,0 +1,187 @@
+//! Streaming photon collector.
+
+use crate::{clone, err::Error, fmt_report, phys::Photon};
+use std::{
+    fmt::{Display, Formatter},
+    path::{Path, PathBuf},
+};
+
+/// Name of the unlimited photon record dimension.
+const RECORD_DIM: &str = "photon";
+
+/// Names of the per-photon record variables.
+const FIELDS: [&str; 8] = [
+    "pos_x",
+    "pos_y",
+    "pos_z",
+    "dir_x",
+    "dir_y",
+    "dir_z",
+    "wavelength",
+    "weight",
+];
+
+/// Photon collector which writes collected photons to a NetCDF file in chunks,
+/// such that memory use is bounded by the buffer size rather than the number of detections.
+/// Any buffered photons must be written with `flush` once collection is complete.
+pub struct PhotonStream {
+    /// Output file path.
+    path: PathBuf,
+    /// Number of photons held before the buffer is written.
+    buffer_size: usize,
+    /// Buffered photon records.
+    buffer: Vec<[f64; 8]>,
+    /// Number of photons already written to the file.
+    written: usize,
+    /// Whether the collector should kill the photon when it has been collected.
+    kill_photon: bool,
+}
+
+impl PhotonStream {
+    clone!(buffer_size: usize);
+    clone!(written: usize);
+    clone!(kill_photon: bool);
+
+    /// Construct a new instance, creating the output file.
+    /// # Errors
+    /// if the output file can not be created.
+    #[inline]
+    pub fn new(path: &Path, buffer_size: usize, kill_photon: bool) -> Result<Self, Error> {
+        debug_assert!(buffer_size > 0);
+
+        let mut file = netcdf::create(path)?;
+        file.add_unlimited_dimension(RECORD_DIM)?;
+        for name in FIELDS.iter() {
+            let _var = file.add_variable::<f64>(name, &[RECORD_DIM])?;
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            buffer_size,
+            buffer: Vec::with_capacity(buffer_size),
+            written: 0,
+            kill_photon,
+        })
+    }
+
+    /// Collect a photon, writing the buffer to file if it has filled.
+    /// # Errors
+    /// if the buffer could not be written.
+    #[inline]
+    pub fn collect_photon(&mut self, phot: &mut Photon) -> Result<(), Error> {
+        let pos = phot.ray().pos();
+        let dir = phot.ray().dir();
+        self.buffer.push([
+            pos.x(),
+            pos.y(),
+            pos.z(),
+            dir.x(),
+            dir.y(),
+            dir.z(),
+            phot.wavelength(),
+            phot.weight(),
+        ]);
+
+        if self.kill_photon {
+            phot.kill();
+        }
+
+        if self.buffer.len() >= self.buffer_size {
+            self.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// Total number of photons collected, including those still buffered.
+    #[inline]
+    #[must_use]
+    pub fn nphoton(&self) -> usize {
+        self.written + self.buffer.len()
+    }
+
+    /// Append any buffered photons to the output file.
+    /// # Errors
+    /// if the output file can not be opened or written to.
+    #[inline]
+    pub fn flush(&mut self) -> Result<(), Error> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+
+        let start = self.written;
+        let end = start + self.buffer.len();
+
+        let mut file = netcdf::append(&self.path)?;
+        for (index, name) in FIELDS.iter().enumerate() {
+            let values: Vec<f64> = self.buffer.iter().map(|record| record[index]).collect();
+            let mut var = file
+                .variable_mut(name)
+                .ok_or_else(|| Error::Text(format!("Missing variable '{}'.", name)))?;
+            var.put_values(&values, [start..end])?;
+        }
+
+        self.written = end;
+        self.buffer.clear();
+        Ok(())
+    }
+}
+
+impl Display for PhotonStream {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        writeln!(fmt, "...")?;
+        fmt_report!(fmt, self.path.display(), "path");
+        fmt_report!(fmt, self.buffer_size, "buffer size");
+        fmt_report!(fmt, self.nphoton(), "photons collected");
+        fmt_report!(fmt, self.kill_photon, "kill on collect");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PhotonStream;
+    use crate::{
+        geom::Ray,
+        math::{Dir3, Point3},
+        phys::Photon,
+    };
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_stream_exceeding_buffer() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("photons.nc");
+        let mut stream = PhotonStream::new(&path, 3, false).unwrap();
+
+        for i in 0..10 {
+            let mut phot = Photon::new(
+                Ray::new(
+                    Point3::new(f64::from(i), 0.0, 0.0),
+                    Dir3::new(0.0, 0.0, 1.0),
+                ),
+                5.0e-7,
+                1.0,
+            );
+            stream.collect_photon(&mut phot).unwrap();
+        }
+
+        // Three full buffers have been written, with one photon still pending.
+        assert_eq!(stream.written(), 9);
+        assert_eq!(stream.nphoton(), 10);
+        stream.flush().unwrap();
+        assert_eq!(stream.written(), 10);
+
+        let file = netcdf::open(&path).unwrap();
+        let pos_x = file
+            .variable("pos_x")
+            .unwrap()
+            .values_arr::<f64, _>(..)
+            .unwrap();
+        assert_eq!(pos_x.len(), 10);
+        assert_eq!(pos_x[[9]], 9.0);
+
+        let _ = dir.close();
+    }
+}
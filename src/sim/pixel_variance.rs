@@ -0,0 +1,223 @@
+//! Per-pixel running variance.
+
+use crate::img::{Colour, Image};
+use ndarray::{Array2, Zip};
+use std::ops::AddAssign;
+
+/// Running mean and variance of the samples recorded in each pixel of an image.
+/// Samples are accumulated with Welford's algorithm.
+/// Samples may also be taken of the whole image at once, such as one for each photon,
+/// in which case every pixel in which nothing was collected records a zero.
+#[derive(Clone)]
+pub struct PixelVariance {
+    /// Number of samples collected in each pixel.
+    counts: Array2<f64>,
+    /// Running mean of the samples collected in each pixel.
+    means: Array2<f64>,
+    /// Running sum of squared differences from the mean of the samples collected in each pixel.
+    m2s: Array2<f64>,
+    /// Number of samples of the whole image.
+    num_images: f64,
+}
+
+impl PixelVariance {
+    /// Construct a new instance.
+    #[inline]
+    #[must_use]
+    pub fn new(res: [usize; 2]) -> Self {
+        Self {
+            counts: Array2::zeros(res),
+            means: Array2::zeros(res),
+            m2s: Array2::zeros(res),
+            num_images: 0.0,
+        }
+    }
+
+    /// Record a sample value in a pixel.
+    #[inline]
+    pub fn collect(&mut self, pixel: [usize; 2], x: f64) {
+        self.counts[pixel] += 1.0;
+        let delta = x - self.means[pixel];
+        self.means[pixel] += delta / self.counts[pixel];
+        self.m2s[pixel] += delta * (x - self.means[pixel]);
+    }
+
+    /// Record a sample of the whole image, given the values of the pixels which are not zero.
+    /// Values given for the same pixel more than once are summed into a single sample.
+    /// The list of values is emptied.
+    #[inline]
+    pub fn collect_image(&mut self, values: &mut Vec<([usize; 2], f64)>) {
+        values.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut values = values.drain(..).peekable();
+        while let Some((pixel, mut x)) = values.next() {
+            while let Some(&(next, y)) = values.peek() {
+                if next != pixel {
+                    break;
+                }
+                x += y;
+                let _ = values.next();
+            }
+            self.collect(pixel, x);
+        }
+
+        self.num_images += 1.0;
+    }
+
+    /// Statistics of each pixel, including the zero samples of the whole image samples in which it was not collected.
+    #[inline]
+    #[must_use]
+    fn totals(&self) -> (Array2<f64>, Array2<f64>, Array2<f64>) {
+        let mut counts = self.counts.clone();
+        let mut means = self.means.clone();
+        let mut m2s = self.m2s.clone();
+        Zip::from(&mut counts)
+            .and(&mut means)
+            .and(&mut m2s)
+            .for_each(|n, mean, m2| {
+                let zeros = (self.num_images - *n).max(0.0);
+                if zeros > 0.0 {
+                    let total = *n + zeros;
+                    *m2 += *mean * *mean * *n * zeros / total;
+                    *mean *= *n / total;
+                    *n = total;
+                }
+            });
+
+        (counts, means, m2s)
+    }
+
+    /// Number of samples of each pixel.
+    #[inline]
+    #[must_use]
+    pub fn counts(&self) -> Array2<f64> {
+        self.totals().0
+    }
+
+    /// Mean of each pixel.
+    #[inline]
+    #[must_use]
+    pub fn means(&self) -> Array2<f64> {
+        self.totals().1
+    }
+
+    /// Sample variance of each pixel.
+    /// Pixels with fewer than two samples have zero variance.
+    #[inline]
+    #[must_use]
+    pub fn variance(&self) -> Array2<f64> {
+        let (counts, _means, m2s) = self.totals();
+        let mut var = Array2::zeros(counts.raw_dim());
+        Zip::from(&mut var)
+            .and(&counts)
+            .and(&m2s)
+            .for_each(|v, n, m2| {
+                if *n > 1.0 {
+                    *v = m2 / (n - 1.0);
+                }
+            });
+        var
+    }
+
+    /// Create a greyscale image of the variance, normalised to the largest pixel variance.
+    #[inline]
+    #[must_use]
+    pub fn image(&self) -> Image {
+        let var = self.variance();
+        let max = var.iter().copied().fold(0.0, f64::max);
+        let norm = if max > 0.0 { max } else { 1.0 };
+
+        Image::new(var.mapv(|v| {
+            let grey = (v / norm) as f32;
+            Colour::new(grey, grey, grey, 1.0)
+        }))
+    }
+}
+
+impl AddAssign<&Self> for PixelVariance {
+    /// Combine the statistics of two sets of samples, using the parallel form of Welford's algorithm.
+    #[inline]
+    fn add_assign(&mut self, rhs: &Self) {
+        debug_assert!(self.counts.shape() == rhs.counts.shape());
+
+        Zip::from(&mut self.counts)
+            .and(&mut self.means)
+            .and(&mut self.m2s)
+            .and(&rhs.counts)
+            .and(&rhs.means)
+            .and(&rhs.m2s)
+            .for_each(|n_a, mean_a, m2_a, n_b, mean_b, m2_b| {
+                let n = *n_a + n_b;
+                if n <= 0.0 {
+                    return;
+                }
+
+                let delta = mean_b - *mean_a;
+                *m2_a += m2_b + (delta * delta * *n_a * n_b / n);
+                *mean_a += delta * n_b / n;
+                *n_a = n;
+            });
+        self.num_images += rhs.num_images;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PixelVariance;
+    use assert_approx_eq::assert_approx_eq;
+    use rand::Rng;
+
+    #[test]
+    fn test_uniform_and_noisy_regions() {
+        let mut rng = rand::thread_rng();
+        let mut a = PixelVariance::new([2, 1]);
+        let mut b = PixelVariance::new([2, 1]);
+
+        // The first pixel is uniformly lit, the second is noisy.
+        for n in 0..1000 {
+            let var = if n % 2 == 0 { &mut a } else { &mut b };
+            var.collect([0, 0], 0.5);
+            var.collect([1, 0], rng.gen_range(0.0..1.0));
+        }
+
+        // Merged statistics match those of a single accumulation.
+        a += &b;
+        let var = a.variance();
+        assert_eq!(a.counts()[[0, 0]], 1000.0);
+        assert_approx_eq!(var[[0, 0]], 0.0, 1.0e-12);
+        assert_approx_eq!(var[[1, 0]], 1.0 / 12.0, 0.01);
+
+        let img = a.image();
+        assert!(img.pixels()[[0, 0]].red < img.pixels()[[1, 0]].red);
+    }
+
+    #[test]
+    fn test_image_samples_count_zeros() {
+        let mut a = PixelVariance::new([2, 1]);
+        let mut b = PixelVariance::new([2, 1]);
+
+        // The first pixel receives a unit value, split over two contributions, in every other image.
+        // The second pixel receives nothing.
+        for n in 0..1000 {
+            let var = if n < 300 { &mut a } else { &mut b };
+            let mut values = if n % 2 == 0 {
+                vec![([0, 0], 0.25), ([0, 0], 0.75)]
+            } else {
+                vec![]
+            };
+            var.collect_image(&mut values);
+            assert!(values.is_empty());
+        }
+
+        a += &b;
+        let counts = a.counts();
+        let means = a.means();
+        let var = a.variance();
+        assert_eq!(counts[[0, 0]], 1000.0);
+        assert_eq!(counts[[1, 0]], 1000.0);
+        assert_approx_eq!(means[[0, 0]], 0.5);
+        assert_approx_eq!(var[[0, 0]], 0.25 * 1000.0 / 999.0, 1.0e-12);
+        assert_approx_eq!(means[[1, 0]], 0.0);
+        assert_approx_eq!(var[[1, 0]], 0.0);
+    }
+}
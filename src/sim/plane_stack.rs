@@ -0,0 +1,239 @@
+//! Stacked output planes.
+
+use crate::{
+    access,
+    err::{check_shape, Error},
+    fmt_report,
+    fs::Save,
+    geom::OrientedPlane,
+    math::Point3,
+    ord::{X, Y},
+};
+use ndarray::Array2;
+use std::{
+    fmt::{Display, Formatter},
+    ops::AddAssign,
+    path::Path,
+};
+
+/// A stack of parallel output planes.
+///
+/// Each layer is a copy of the base plane shifted along its normal by a given offset.
+/// Photon path segments crossing a layer deposit their weight into that layer's pixel grid,
+/// and each layer accumulates independently.
+#[derive(Clone)]
+pub struct PlaneStack {
+    /// Base plane, at zero offset.
+    plane: OrientedPlane,
+    /// Offsets of each layer along the plane normal [m].
+    offsets: Vec<f64>,
+    /// Accumulated data of each layer.
+    pub layers: Vec<Array2<f64>>,
+}
+
+impl PlaneStack {
+    access!(plane: OrientedPlane);
+    access!(offsets: Vec<f64>);
+
+    /// Construct a new instance.
+    #[inline]
+    #[must_use]
+    pub fn new(plane: OrientedPlane, offsets: Vec<f64>, res: [usize; 2]) -> Self {
+        debug_assert!(!offsets.is_empty());
+        debug_assert!(res[X] > 0);
+        debug_assert!(res[Y] > 0);
+
+        let layers = vec![Array2::zeros(res); offsets.len()];
+
+        Self {
+            plane,
+            offsets,
+            layers,
+        }
+    }
+
+    /// Number of layers in the stack.
+    #[inline]
+    #[must_use]
+    pub fn num_layers(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// Signed distance of a point in front of the given layer.
+    #[inline]
+    #[must_use]
+    fn height(&self, index: usize, p: &Point3) -> f64 {
+        self.plane
+            .orient()
+            .forward()
+            .dot_vec(&(p - self.plane.orient().pos()))
+            - self.offsets[index]
+    }
+
+    /// Record the weight of a photon travelling from `start` to `end` in every layer the path crosses.
+    #[inline]
+    pub fn collect(&mut self, start: &Point3, end: &Point3, weight: f64) {
+        for index in 0..self.num_layers() {
+            let h0 = self.height(index, start);
+            let h1 = self.height(index, end);
+
+            if (h0 < 0.0) == (h1 < 0.0) || (h0 - h1).abs() <= 0.0 {
+                continue;
+            }
+
+            let t = h0 / (h0 - h1);
+            let crossing = *start + ((end - start) * t);
+
+            let res = self.layers[index].raw_dim();
+            if let Some([xi, yi]) = self.plane.pixel(&crossing, [res[X], res[Y]]) {
+                self.layers[index][[xi, yi]] += weight;
+            }
+        }
+    }
+}
+
+impl PlaneStack {
+    /// Check that the layers of another stack can be added to this one.
+    /// # Errors
+    /// if the stacks have a different number of layers, or layers of a different resolution.
+    #[inline]
+    pub fn check_compatible(&self, rhs: &Self) -> Result<(), Error> {
+        check_shape(
+            "plane stack layers",
+            &[self.num_layers()],
+            &[rhs.num_layers()],
+        )?;
+        for (a, b) in self.layers.iter().zip(&rhs.layers) {
+            check_shape("plane stack layer", a.shape(), b.shape())?;
+        }
+
+        Ok(())
+    }
+
+    /// Add the layers of another stack to this one.
+    /// # Errors
+    /// if the stacks have a different number of layers, or layers of a different resolution.
+    #[inline]
+    pub fn merge(&mut self, rhs: &Self) -> Result<(), Error> {
+        self.check_compatible(rhs)?;
+
+        *self += rhs;
+        Ok(())
+    }
+}
+
+impl AddAssign<&Self> for PlaneStack {
+    #[inline]
+    fn add_assign(&mut self, rhs: &Self) {
+        for (a, b) in self.layers.iter_mut().zip(&rhs.layers) {
+            *a += b;
+        }
+    }
+}
+
+impl Save for PlaneStack {
+    /// Each layer is saved separately, with the layer index appended to the file stem.
+    #[inline]
+    fn save_data(&self, path: &Path) -> Result<(), Error> {
+        let stem = path
+            .file_stem()
+            .ok_or("Missing plane stack file name.")?
+            .to_string_lossy();
+        let ext = path
+            .extension()
+            .map_or_else(|| "nc".to_owned(), |ext| ext.to_string_lossy().into_owned());
+
+        for (index, layer) in self.layers.iter().enumerate() {
+            layer.save(&path.with_file_name(format!("{}_{:03}.{}", stem, index, ext)))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for PlaneStack {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        writeln!(fmt, "...")?;
+        fmt_report!(fmt, self.plane, "base plane");
+        fmt_report!(fmt, format!("{:?}", self.offsets), "offsets (m)");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PlaneStack;
+    use crate::{
+        geom::{Orient, OrientedPlane, Ray},
+        math::{Dir3, Point3},
+    };
+
+    fn stack() -> PlaneStack {
+        let orient = Orient::new(Ray::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Dir3::new(0.0, 0.0, 1.0),
+        ));
+        PlaneStack::new(
+            OrientedPlane::new(orient, 2.0, 2.0),
+            vec![0.0, 1.0, 2.0],
+            [2, 2],
+        )
+    }
+
+    #[test]
+    fn test_layer_attribution() {
+        let mut stack = stack();
+
+        // Crosses the first layer only.
+        stack.collect(
+            &Point3::new(0.5, 0.5, -0.5),
+            &Point3::new(0.5, 0.5, 0.5),
+            1.0,
+        );
+        // Crosses the third layer only.
+        stack.collect(
+            &Point3::new(0.5, 0.5, 1.5),
+            &Point3::new(0.5, 0.5, 2.5),
+            2.0,
+        );
+        // Travelling backwards across the second layer.
+        stack.collect(
+            &Point3::new(0.5, 0.5, 1.2),
+            &Point3::new(0.5, 0.5, 0.8),
+            4.0,
+        );
+
+        assert_eq!(stack.layers[0].sum(), 1.0);
+        assert_eq!(stack.layers[1].sum(), 4.0);
+        assert_eq!(stack.layers[2].sum(), 2.0);
+    }
+
+    #[test]
+    fn test_multiple_crossings() {
+        let mut stack = stack();
+
+        stack.collect(
+            &Point3::new(0.5, 0.5, -1.0),
+            &Point3::new(0.5, 0.5, 3.0),
+            1.0,
+        );
+
+        for layer in &stack.layers {
+            assert_eq!(layer.sum(), 1.0);
+        }
+    }
+
+    #[test]
+    fn test_outside_plane_bounds() {
+        let mut stack = stack();
+
+        stack.collect(
+            &Point3::new(5.0, 0.0, -0.5),
+            &Point3::new(5.0, 0.0, 0.5),
+            1.0,
+        );
+
+        assert_eq!(stack.layers[0].sum(), 0.0);
+    }
+}
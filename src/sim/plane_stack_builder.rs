@@ -0,0 +1,103 @@
+//! Plane stack builder.
+
+use crate::{
+    err::Error,
+    fmt_report,
+    geom::{OrientBuilder, OrientedPlane},
+    ord::{Build, X, Y},
+    sim::PlaneStack,
+};
+use arctk_attr::file;
+use std::fmt::{Display, Formatter};
+
+/// Loadable stack of parallel output planes.
+#[file]
+#[derive(Clone)]
+pub struct PlaneStackBuilder {
+    /// Orientation of the base plane, whose normal is the forward direction.
+    orient: OrientBuilder,
+    /// Width and height of each plane [m].
+    size: [f64; 2],
+    /// Offsets of each layer along the plane normal [m].
+    offsets: Vec<f64>,
+    /// Pixel resolution of each layer.
+    res: [usize; 2],
+}
+
+impl PlaneStackBuilder {
+    /// Build the stack.
+    /// # Errors
+    /// if no layer offsets are given, or the planes have no area or no pixels.
+    #[inline]
+    pub fn build(&self) -> Result<PlaneStack, Error> {
+        if self.offsets.is_empty() {
+            return Err("Plane stacks must have at least one layer.".into());
+        }
+        if !(self.size[X] > 0.0 && self.size[Y] > 0.0) {
+            return Err(Error::Text(format!(
+                "Plane stack planes must have a positive width and height: [{}, {}]",
+                self.size[X], self.size[Y]
+            )));
+        }
+        if self.res[X] == 0 || self.res[Y] == 0 {
+            return Err("Plane stack layers must have at least one pixel along each axis.".into());
+        }
+
+        Ok(PlaneStack::new(
+            OrientedPlane::new(self.orient.clone().build(), self.size[X], self.size[Y]),
+            self.offsets.clone(),
+            self.res,
+        ))
+    }
+}
+
+impl Display for PlaneStackBuilder {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        writeln!(fmt, "...")?;
+        fmt_report!(fmt, self.orient, "base orientation");
+        fmt_report!(
+            fmt,
+            &format!("[{}, {}]", self.size[X], self.size[Y]),
+            "plane size (m)"
+        );
+        fmt_report!(fmt, &format!("{:?}", self.offsets), "offsets (m)");
+        fmt_report!(
+            fmt,
+            &format!("[{}, {}]", self.res[X], self.res[Y]),
+            "resolution"
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fs::from_json_str, math::Point3};
+
+    #[test]
+    fn test_build_layers() {
+        let builder: PlaneStackBuilder = from_json_str(
+            "{ orient: { pos: [0, 0, 0], yaw: 0, pitch: 90 }, size: [2, 2], offsets: [0, 1], res: [2, 2] }",
+        )
+        .unwrap();
+        let mut stack = builder.build().unwrap();
+        assert_eq!(stack.num_layers(), 2);
+
+        // Travelling up through the second layer only.
+        stack.collect(
+            &Point3::new(0.5, 0.5, 0.5),
+            &Point3::new(0.5, 0.5, 1.5),
+            1.0,
+        );
+        assert_eq!(stack.layers[0].sum(), 0.0);
+        assert_eq!(stack.layers[1].sum(), 1.0);
+
+        let builder: PlaneStackBuilder = from_json_str(
+            "{ orient: { pos: [0, 0, 0], yaw: 0, pitch: 90 }, size: [2, 2], offsets: [], res: [2, 2] }",
+        )
+        .unwrap();
+        assert!(builder.build().is_err());
+    }
+}
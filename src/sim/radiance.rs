@@ -0,0 +1,133 @@
+//! Angular radiance output.
+
+use crate::{
+    access, clone,
+    err::{check_shape, Error},
+    fmt_report,
+    fs::Save,
+    math::Dir3,
+    tools::Binner,
+};
+use ndarray::Array2;
+use std::{
+    f64::consts::PI,
+    fmt::{Display, Formatter},
+    ops::AddAssign,
+    path::Path,
+};
+
+/// Directionally resolved detector.
+///
+/// Photon directions are binned by azimuth, measured anticlockwise from the x-axis in the xy-plane,
+/// and by polar angle, measured from the z-axis.
+/// Weight travelling in directions outside of the binner ranges is accumulated as overflow.
+#[derive(Clone)]
+pub struct Radiance {
+    /// Azimuthal angle binner [rad].
+    azimuth: Binner,
+    /// Polar angle binner [rad].
+    polar: Binner,
+    /// Accumulated data, indexed by azimuth then polar bin.
+    data: Array2<f64>,
+    /// Weight recorded outside of the angular ranges.
+    overflow: f64,
+}
+
+impl Radiance {
+    access!(azimuth: Binner);
+    access!(polar: Binner);
+    access!(data: Array2<f64>);
+    clone!(overflow: f64);
+
+    /// Construct a new instance.
+    #[inline]
+    #[must_use]
+    pub fn new(azimuth: Binner, polar: Binner) -> Self {
+        let data = Array2::zeros([azimuth.bins(), polar.bins()]);
+
+        Self {
+            azimuth,
+            polar,
+            data,
+            overflow: 0.0,
+        }
+    }
+
+    /// Record a weight travelling in a given direction.
+    #[inline]
+    pub fn collect(&mut self, dir: &Dir3, weight: f64) {
+        let phi = dir.y().atan2(dir.x()).rem_euclid(2.0 * PI);
+        let theta = dir.z().clamp(-1.0, 1.0).acos();
+
+        match (self.azimuth.try_bin(phi), self.polar.try_bin(theta)) {
+            (Some(pi), Some(ti)) => self.data[[pi, ti]] += weight,
+            _ => self.overflow += weight,
+        }
+    }
+
+    /// Add the data of another radiance detector to this one.
+    /// # Errors
+    /// if the detectors do not share the same angular resolution.
+    #[inline]
+    pub fn merge(&mut self, rhs: &Self) -> Result<(), Error> {
+        check_shape("radiance", self.data.shape(), rhs.data.shape())?;
+
+        *self += rhs;
+        Ok(())
+    }
+}
+
+impl AddAssign<&Self> for Radiance {
+    #[inline]
+    fn add_assign(&mut self, rhs: &Self) {
+        self.data += &rhs.data;
+        self.overflow += rhs.overflow;
+    }
+}
+
+impl Save for Radiance {
+    #[inline]
+    fn save_data(&self, path: &Path) -> Result<(), Error> {
+        self.data.save(path)
+    }
+}
+
+impl Display for Radiance {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        writeln!(fmt, "...")?;
+        fmt_report!(fmt, self.azimuth, "azimuth binner (rad)");
+        fmt_report!(fmt, self.polar, "polar binner (rad)");
+        fmt_report!(fmt, self.overflow, "overflow");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Radiance;
+    use crate::{
+        math::Dir3,
+        tools::{Binner, Range},
+    };
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_collimated_beam_single_bin() {
+        let mut rad = Radiance::new(
+            Binner::new(Range::new(0.0, 2.0 * PI), 8),
+            Binner::new(Range::new(0.0, PI), 6),
+        );
+
+        // A beam travelling at 45 degrees to the z-axis, in the xz-plane.
+        let dir = Dir3::new(1.0, 0.0, 1.0);
+        for _ in 0..10 {
+            rad.collect(&dir, 0.5);
+        }
+
+        assert_eq!(rad.data().sum(), 5.0);
+        assert_eq!(rad.data()[[0, 1]], 5.0);
+        assert_eq!(rad.data().iter().filter(|w| **w > 0.0).count(), 1);
+        assert_eq!(rad.overflow(), 0.0);
+    }
+}
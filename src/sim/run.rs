@@ -1,23 +1,609 @@
 //! Simulation control functions.
 
 use crate::{
+    data::Histogram,
     err::Error,
-    sim::{Engine, Input, Output},
-    tools::ProgressBar,
+    fs::{File, Load, Save},
+    geom::{Grid, TraversalStats, Tree},
+    img::{Colour, Image},
+    math::substream,
+    ord::{Build, Link, Register, Set, X, Y},
+    phys::EnergyCheck,
+    sim::{
+        AttributeLinkerLinkerLinkerLinkerLinker as Attr, BlockReduction, Engine, EventLog, Frame,
+        Hyperspectral, Input, MaterialTallies, Output, OutputRegistry, Parameters,
+        ParametersBuilderLoader, PhotonCollector, PixelVariance, PlaneStack, PlaneStackBuilder,
+        Radiance, SlabDiffusion,
+    },
+    tools::{Binner, ProgressBar, Range},
 };
+use ndarray::Array3;
 use rand::thread_rng;
 use rayon::prelude::*;
-use std::sync::{Arc, Mutex};
+use std::{
+    f64::consts::PI,
+    fmt::Display,
+    path::Path,
+    sync::{Arc, Condvar, Mutex},
+};
+
+/// Load a parameters file, run the simulation of all lights, and return the collected output.
+/// Resource files referenced by the parameters are resolved relative to the parameters file.
+/// Nothing is written to disk.
+/// # Errors
+/// if the parameters can not be loaded, linked or run,
+/// or if two detectors share a name.
+#[inline]
+pub fn run_from_config(path: &Path) -> Result<OutputRegistry, Error> {
+    let in_dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let params = ParametersBuilderLoader::new_from_file(path)?
+        .load(in_dir)?
+        .build();
+
+    simulate(params, None, &mut |_, _| {}, |data| data.registry())
+}
+
+/// Register the detectors, link the parameters, grow the tree and run the simulation,
+/// then pass the collected output, with spectrometer noise applied, to `finish`.
+/// Each part of the setup is given to `report`, with a description, once it is formed.
+/// Given an output directory, photon collectors stream into it,
+/// and if the settings ask for it each light is run separately and saved into its own sub-directory.
+/// Otherwise photons are shared between the lights in proportion to their power.
+/// # Errors
+/// if the parameters can not be linked or run, two detectors share a name,
+/// or an output can not be written.
+#[inline]
+pub fn simulate<T, F>(
+    params: Parameters,
+    out_dir: Option<&Path>,
+    report: &mut dyn FnMut(&dyn Display, &str),
+    finish: F,
+) -> Result<T, Error>
+where
+    F: FnOnce(Output<'_>) -> Result<T, Error>,
+{
+    let Parameters {
+        sett,
+        tree: tree_sett,
+        grid,
+        boundary,
+        surfs,
+        attrs,
+        mats,
+        lights,
+        engine,
+        planes,
+    } = params;
+
+    report(&engine, "engine");
+    report(&sett, "settings");
+    report(&grid, "measurement grid");
+    let boundary = boundary
+        .as_ref()
+        .map(|boundary| boundary.build(grid.boundary().clone()))
+        .transpose()?;
+    if let Some(ref boundary) = boundary {
+        report(boundary, "boundary");
+    }
+    report(&mats, "materials");
+
+    let (spec_reg, img_reg, ccd_reg, phot_col_reg, hyper_reg, rad_reg) =
+        gen_detector_registers(&attrs)?;
+    report(&spec_reg, "spectrometer register");
+    report(&img_reg, "imager register");
+    report(&ccd_reg, "ccd register");
+    report(&phot_col_reg, "photon collector register");
+    report(&hyper_reg, "hyperspectral volume register");
+    report(&rad_reg, "radiance detector register");
+    let mut base_output = gen_base_output(
+        &engine,
+        &grid,
+        &spec_reg,
+        &img_reg,
+        &ccd_reg,
+        &phot_col_reg,
+        &hyper_reg,
+        &rad_reg,
+        &attrs,
+    )?;
+    if let Some(ref planes) = planes {
+        base_output.plane_stacks = gen_plane_stacks(planes)?;
+    }
+    if let Some(out_dir) = out_dir {
+        base_output.open_streams(out_dir)?;
+    }
+    let collectors = gen_collector_set(&phot_col_reg, &hyper_reg, &rad_reg)?;
+
+    let lights = lights.link(&mats)?;
+    report(&lights, "lights");
+    let attrs = attrs
+        .link(&collectors)?
+        .link(ccd_reg.set())?
+        .link(img_reg.set())?
+        .link(spec_reg.set())?
+        .link(&mats)?;
+    report(&attrs, "attributes");
+    let surfs = surfs.link(&attrs)?;
+    report(&surfs, "surfaces");
+    let diffusion = boundary
+        .as_ref()
+        .and_then(|boundary| SlabDiffusion::from_setup(&mats, &lights, &surfs, boundary));
+    if let Some(ref diffusion) = diffusion {
+        report(diffusion, "slab diffusion approximation");
+    }
+
+    let tree = Tree::new(&tree_sett, &surfs);
+    report(&tree, "hit-scan tree");
+
+    let mut data = match out_dir {
+        Some(out_dir) if sett.output_individual_lights().unwrap_or(false) => {
+            let nlights = lights.len();
+            lights.into_iter().enumerate().try_fold(
+                base_output.clone(),
+                |mut output, (light_idx, (light_id, light))| -> Result<_, Error> {
+                    report(
+                        &light,
+                        &format!("{} ({} / {})", light_id, light_idx + 1, nlights),
+                    );
+                    let mut input =
+                        Input::new(&spec_reg, &mats, &attrs, light, &tree, &grid, &sett);
+                    if let Some(ref boundary) = boundary {
+                        input = input.with_boundary(boundary);
+                    }
+
+                    let data = multi_thread(&engine, input, &base_output)?;
+
+                    let indiv_outpath = out_dir.join(&light_id.as_string());
+                    if !indiv_outpath.exists() {
+                        // Create the directory for the output if it does not already exist.
+                        std::fs::create_dir(&indiv_outpath)?;
+                    }
+                    data.save(&indiv_outpath)?;
+
+                    output += &data;
+                    Ok(output)
+                },
+            )?
+        }
+        _ => {
+            let lights: Vec<_> = lights.into_iter().map(|(_light_id, light)| light).collect();
+            let light = lights
+                .first()
+                .cloned()
+                .ok_or("At least one light must be given.")?;
+            let mut input = Input::new(&spec_reg, &mats, &attrs, light, &tree, &grid, &sett)
+                .with_lights(lights)?;
+            if let Some(ref boundary) = boundary {
+                input = input.with_boundary(boundary);
+            }
+            report(&input.total_power(), "total power (J/s)");
+
+            let mut output = base_output.clone();
+            output += &multi_thread(&engine, input, &base_output)?;
+            output
+        }
+    };
+
+    if let Some(mut rng) = sett.finalise_stream() {
+        data.apply_spectrometer_noise(&mut rng);
+    } else {
+        data.apply_spectrometer_noise(&mut thread_rng());
+    }
+    if let Some(diffusion) = diffusion {
+        report(&diffusion.compare(&data), "diffusion comparison");
+    }
+
+    finish(data)
+}
+
+/// Generate the spectrometer, imager, ccd, photon collector, hyperspectral volume and radiance detector registers.
+/// # Errors
+/// if two detectors of the same kind share a name.
+#[inline]
+pub fn gen_detector_registers(
+    attrs: &Set<Attr>,
+) -> Result<(Register, Register, Register, Register, Register, Register), Error> {
+    let mut spec_names = Vec::new();
+    let mut img_names = Vec::new();
+    let mut ccd_names = Vec::new();
+    let mut phot_col_names = Vec::new();
+    let mut hyper_names = Vec::new();
+    let mut rad_names = Vec::new();
+
+    for attr in flatten_attrs(attrs) {
+        match *attr {
+            Attr::Spectrometer(ref name, ..) | Attr::CountingSpectrometer(ref name, ..) => {
+                spec_names.push(name.clone())
+            }
+            Attr::Imager(ref name, ..) => img_names.push(name.clone()),
+            Attr::Ccd(ref name, ..) => ccd_names.push(name.clone()),
+            Attr::PhotonCollector(ref name, ..) => phot_col_names.push(name.clone()),
+            Attr::Hyperspectral(ref name, ..) => hyper_names.push(name.clone()),
+            Attr::Radiance(ref name, ..) => rad_names.push(name.clone()),
+            _ => {}
+        }
+    }
+
+    Ok((
+        Register::new_unique(spec_names)?,
+        Register::new_unique(img_names)?,
+        Register::new_unique(ccd_names)?,
+        Register::new_unique(phot_col_names)?,
+        Register::new_unique(hyper_names)?,
+        Register::new_unique(rad_names)?,
+    ))
+}
+
+/// Generate the set used to link photon collectors, hyperspectral volumes and radiance detectors,
+/// which share the first linking stage.
+/// Each name is mapped to its index within the register of its kind.
+/// # Errors
+/// if a name appears in more than one of the registers.
+#[inline]
+pub fn gen_collector_set(
+    phot_col_reg: &Register,
+    hyper_reg: &Register,
+    rad_reg: &Register,
+) -> Result<Set<usize>, Error> {
+    let mut pairs = Vec::with_capacity(phot_col_reg.len() + hyper_reg.len() + rad_reg.len());
+    for reg in &[phot_col_reg, hyper_reg, rad_reg] {
+        pairs.extend(
+            reg.set()
+                .map()
+                .iter()
+                .map(|(name, index)| (name.clone(), *index)),
+        );
+    }
+
+    Set::from_pairs(pairs)
+}
+
+/// List the attributes of a set, including those nested within attribute chains.
+fn flatten_attrs(attrs: &Set<Attr>) -> Vec<&Attr> {
+    fn push<'b>(attr: &'b Attr, list: &mut Vec<&'b Attr>) {
+        list.push(attr);
+        if let Attr::AttributeChain(ref chain) = *attr {
+            for attr in chain {
+                push(attr, list);
+            }
+        }
+    }
+
+    let mut list = Vec::with_capacity(attrs.len());
+    for attr in attrs.values() {
+        push(attr, &mut list);
+    }
+    list
+}
+
+/// Generate the base output instance.
+/// # Errors
+/// if a photon collector can not be built.
+#[allow(clippy::too_many_arguments)]
+#[inline]
+pub fn gen_base_output<'a>(
+    engine: &Engine,
+    grid: &Grid,
+    spec_reg: &'a Register,
+    img_reg: &'a Register,
+    ccd_reg: &'a Register,
+    phot_col_reg: &'a Register,
+    hyper_reg: &'a Register,
+    rad_reg: &'a Register,
+    attrs: &Set<Attr>,
+) -> Result<Output<'a>, Error> {
+    let res = *grid.res();
+    let attrs = flatten_attrs(attrs);
+
+    let mut specs = Vec::with_capacity(spec_reg.len());
+    let mut spec_noise = Vec::with_capacity(spec_reg.len());
+    for name in spec_reg.set().map().keys() {
+        for attr in &attrs {
+            let (spec_name, [min, max], bins, noisy) = match attr {
+                Attr::Spectrometer(spec_name, range, bins) => (spec_name, range, bins, false),
+                Attr::CountingSpectrometer(spec_name, range, bins) => {
+                    (spec_name, range, bins, true)
+                }
+                _ => continue,
+            };
+            if name == spec_name {
+                specs.push(Histogram::new(*min, *max, *bins));
+                spec_noise.push(noisy);
+                continue;
+            }
+        }
+    }
+
+    let mut imgs = Vec::with_capacity(img_reg.len());
+    let background = Colour::new(0.0, 0.0, 0.0, 1.0);
+    for name in img_reg.set().map().keys() {
+        for attr in &attrs {
+            if let Attr::Imager(img_name, res, _width, _center, _forward) = attr {
+                if name == img_name {
+                    imgs.push(Image::new_blank(*res, background));
+                    continue;
+                }
+            }
+        }
+    }
+
+    let mut ccds = Vec::with_capacity(ccd_reg.len());
+    for name in ccd_reg.set().map().keys() {
+        for attr in &attrs {
+            if let Attr::Ccd(ccd_name, res, _width, _center, _forward, channels) = attr {
+                if name == ccd_name {
+                    ccds.push(Array3::zeros([res[X], res[Y], channels.num_channels()]));
+                    continue;
+                }
+            }
+        }
+    }
+
+    let mut photos = Vec::new();
+    let mut photo_vars = Vec::new();
+    if let Engine::Photo(frames, _res) = engine {
+        photos.reserve(frames.len());
+        photo_vars.reserve(frames.len());
+        for frame in frames {
+            photos.push(Image::new_blank(*frame.tile_res(), background));
+            photo_vars.push(
+                frame
+                    .track_variance()
+                    .then(|| PixelVariance::new(*frame.tile_res())),
+            );
+        }
+    }
+
+    let mut phot_cols: Vec<PhotonCollector> = Vec::new();
+    for name in phot_col_reg.set().map().keys() {
+        for attr in &attrs {
+            if let Attr::PhotonCollector(phot_col_id, phot_col) = attr {
+                if name == phot_col_id {
+                    phot_cols.push(
+                        phot_col
+                            .build()
+                            .map_err(|err| err.with_context(&name.as_string()))?,
+                    );
+                    continue;
+                }
+            }
+        }
+    }
+
+    let mut hypers = Vec::with_capacity(hyper_reg.len());
+    for name in hyper_reg.set().map().keys() {
+        for attr in &attrs {
+            if let Attr::Hyperspectral(hyper_name, hyper_grid, [min, max], bins) = attr {
+                if name == hyper_name {
+                    hypers.push(Hyperspectral::new(
+                        hyper_grid.clone().build(),
+                        Binner::new(Range::new(*min, *max), *bins),
+                    ));
+                    continue;
+                }
+            }
+        }
+    }
+
+    let mut radiances = Vec::with_capacity(rad_reg.len());
+    for name in rad_reg.set().map().keys() {
+        for attr in &attrs {
+            if let Attr::Radiance(rad_name, bins) = attr {
+                if name == rad_name {
+                    radiances.push(Radiance::new(
+                        Binner::new(Range::new(0.0, 2.0 * PI), bins[X]),
+                        Binner::new(Range::new(0.0, PI), bins[Y]),
+                    ));
+                    continue;
+                }
+            }
+        }
+    }
+
+    let mut output = Output::new(
+        grid.boundary().clone(),
+        res,
+        spec_reg,
+        img_reg,
+        ccd_reg,
+        phot_col_reg,
+        hyper_reg,
+        rad_reg,
+        specs,
+        imgs,
+        ccds,
+        photos,
+        phot_cols,
+    );
+    output.photo_vars = photo_vars;
+    output.spec_noise = spec_noise;
+    output.hypers = hypers;
+    output.radiances = radiances;
+    Ok(output)
+}
+
+/// Generate the stacks of output planes.
+/// # Errors
+/// if a plane stack can not be built.
+#[inline]
+pub fn gen_plane_stacks(planes: &Set<PlaneStackBuilder>) -> Result<Vec<PlaneStack>, Error> {
+    planes
+        .map()
+        .iter()
+        .map(|(name, builder)| {
+            builder
+                .build()
+                .map_err(|err| err.with_context(&name.as_string()).with_context("planes"))
+        })
+        .collect()
+}
 
 /// Run a multi-threaded MCRT simulation.
+/// Photography frames which move the light source are each captured in a separate run,
+/// with the lights steered to match, over which the remaining output data accumulate.
+/// Photography frames with adaptive sampling are then refined by further passes of the photons.
+/// # Errors
+/// if the progress bar can not be locked, or the emission spectrum can not be divided into wavelength bands.
+#[inline]
+pub fn multi_thread<'a>(
+    engine: &Engine,
+    mut input: Input<'a>,
+    output: &Output<'a>,
+) -> Result<Output<'a>, Error> {
+    if let Some(num_bands) = input.sett.wavelength_bands() {
+        input.light = input.light.with_bands(num_bands)?;
+        input.lights = input
+            .lights
+            .into_iter()
+            .map(|light| light.with_bands(num_bands))
+            .collect::<Result<_, _>>()?;
+    }
+
+    let mut data = capture(engine, &input, output, 0)?;
+    if let Engine::Photo(ref frames, res) = *engine {
+        refine(frames, res, &input, output, &mut data)?;
+    }
+
+    Ok(data)
+}
+
+/// Run a single pass of the photons, numbered from the given photon.
+/// Photography frames which move the light source are each captured in a separate run.
+/// # Errors
+/// if the progress bar can not be locked.
+#[inline]
+fn capture<'a>(
+    engine: &Engine,
+    input: &Input<'a>,
+    output: &Output<'a>,
+    first_phot: usize,
+) -> Result<Output<'a>, Error> {
+    let (frames, res) = match *engine {
+        Engine::Photo(ref frames, res) if frames.iter().any(|frame| frame.source().is_some()) => {
+            (frames, res)
+        }
+        _ => return run_threads(engine, input.clone(), output, first_phot),
+    };
+
+    let mut data: Option<Output<'a>> = None;
+    let mut photos = Vec::with_capacity(frames.len());
+    let mut photo_vars = Vec::with_capacity(frames.len());
+    for (n, frame) in frames.iter().enumerate() {
+        let mut frame_input = input.clone();
+        if let Some(ref source) = *frame.source() {
+            frame_input.light = frame_input.light.steered(source);
+            frame_input.lights = frame_input
+                .lights
+                .iter()
+                .map(|light| light.steered(source))
+                .collect();
+        }
+
+        let mut frame_output = output.clone();
+        frame_output.photos = output.photos.get(n).cloned().into_iter().collect();
+        frame_output.photo_vars = output.photo_vars.get(n).cloned().into_iter().collect();
+
+        let mut frame_data = run_threads(
+            &Engine::Photo(vec![frame.clone()], res),
+            frame_input,
+            &frame_output,
+            first_phot,
+        )?;
+        photos.append(&mut frame_data.photos);
+        photo_vars.append(&mut frame_data.photo_vars);
+        match data {
+            Some(ref mut data) => data.combine(frame_data),
+            None => data = Some(frame_data),
+        }
+    }
+
+    let mut data = data.ok_or("No frames given.")?;
+    data.photos = photos;
+    data.photo_vars = photo_vars;
+    Ok(data)
+}
+
+/// Refine the photographs of frames with adaptive sampling,
+/// running further passes of the photons until every pixel of each such frame has converged.
+/// Each pass continues the photon numbering of those before, and the photographs are averaged over their passes.
+/// Only the photographs, and their variances, are refined; the remaining output data are those of the first pass.
+/// # Errors
+/// if the progress bar can not be locked.
+#[inline]
+fn refine<'a>(
+    frames: &[Frame],
+    res: [usize; 2],
+    input: &Input<'a>,
+    output: &Output<'a>,
+    data: &mut Output<'a>,
+) -> Result<(), Error> {
+    // Photons of the refinement passes are not collected.
+    let mut pass_output = output.clone();
+    for phot_col in &mut pass_output.phot_cols {
+        phot_col.stream = None;
+        phot_col.discard_photons = true;
+    }
+
+    let mut passes = vec![1_usize; frames.len()];
+    for pass in 1.. {
+        let active: Vec<_> = frames
+            .iter()
+            .enumerate()
+            .filter(
+                |&(n, frame)| match (frame.adaptive(), data.photo_vars.get(n)) {
+                    (Some(adaptive), Some(Some(stats))) => !adaptive.converged(stats),
+                    _ => false,
+                },
+            )
+            .map(|(n, _frame)| n)
+            .collect();
+        if active.is_empty() {
+            break;
+        }
+
+        let first_phot = pass * input.sett.num_phot();
+        pass_output.photos = active.iter().map(|n| output.photos[*n].clone()).collect();
+        pass_output.photo_vars = active
+            .iter()
+            .map(|n| output.photo_vars[*n].clone())
+            .collect();
+        let pass_frames = active.iter().map(|n| frames[*n].clone()).collect();
+        let pass_data = capture(
+            &Engine::Photo(pass_frames, res),
+            input,
+            &pass_output,
+            first_phot,
+        )?;
+
+        for (m, n) in active.iter().enumerate() {
+            data.photos[*n] += &pass_data.photos[m];
+            if let (Some(a), Some(b)) = (&mut data.photo_vars[*n], &pass_data.photo_vars[m]) {
+                *a += b;
+            }
+            passes[*n] += 1;
+        }
+    }
+
+    for (photo, num_passes) in data.photos.iter_mut().zip(passes) {
+        if num_passes > 1 {
+            let scale = 1.0 / num_passes as f32;
+            photo.pixels_mut().mapv_inplace(|col| col * scale);
+        }
+    }
+
+    Ok(())
+}
+
+/// Run the photons of a simulation, numbered from the given photon, shared between multiple threads.
 /// # Errors
 /// if the progress bar can not be locked.
 #[allow(clippy::expect_used)]
 #[inline]
-pub fn multi_thread<'a>(
+fn run_threads<'a>(
     engine: &Engine,
     input: Input<'a>,
     output: &Output<'a>,
+    first_phot: usize,
 ) -> Result<Output<'a>, Error> {
     let pb = ProgressBar::new("MCRT", input.sett.num_phot());
     let pb = Arc::new(Mutex::new(pb));
@@ -27,16 +613,50 @@ pub fn multi_thread<'a>(
         .num_threads()
         .unwrap_or(std::usize::MAX)
         .min(num_cpus::get());
+    // Seeded runs sum detector writes in block order, so images are reproducible bit-for-bit.
+    // Each thread may hold blocks awaiting their predecessors, up to a window of one block per thread.
+    let reduction = input.sett.seed().map(|_| {
+        Arc::new((
+            Mutex::new(BlockReduction::new(&output.imgs, &output.ccds, num_threads)),
+            Condvar::new(),
+        ))
+    });
+
     let threads: Vec<_> = (0..num_threads).collect();
-    let mut out: Vec<_> = threads
+    // Thread outputs are combined pairwise, in parallel, as they complete.
+    let mut data = threads
         .par_iter()
-        .map(|_id| thread(engine, input.clone(), output.clone(), &Arc::clone(&pb)))
-        .collect();
+        .map(|_id| {
+            thread(
+                engine,
+                input.clone(),
+                output.clone(),
+                &Arc::clone(&pb),
+                reduction.as_ref(),
+                first_phot,
+            )
+        })
+        .reduce_with(|mut a, b| {
+            a.combine(b);
+            a
+        })
+        .expect("No data received.");
     pb.lock()?.finish_with_message("Simulation complete.");
 
-    let mut data = out.pop().expect("No data received.");
-    while let Some(o) = out.pop() {
-        data += &o;
+    if let Some(reduction) = reduction {
+        let (imgs, ccds) = Arc::try_unwrap(reduction)
+            .ok()
+            .expect("Block reduction still shared.")
+            .0
+            .into_inner()
+            .expect("Could not lock block reduction.")
+            .finish();
+        for (a, b) in data.imgs.iter_mut().zip(&imgs) {
+            *a += b;
+        }
+        for (a, b) in data.ccds.iter_mut().zip(&ccds) {
+            *a += b;
+        }
     }
 
     Ok(data)
@@ -51,10 +671,33 @@ fn thread<'a>(
     input: Input<'a>,
     mut output: Output<'a>,
     pb: &Arc<Mutex<ProgressBar>>,
+    reduction: Option<&Arc<(Mutex<BlockReduction>, Condvar)>>,
+    first_phot: usize,
 ) -> Output<'a> {
     let mut rng = thread_rng();
 
-    let phot_energy = input.light.power() / input.sett.num_phot() as f64;
+    let phot_energy = input.total_power() / input.sett.num_phot() as f64;
+    let sources = input.sources();
+
+    if let Some(interval) = input.sett.trace_interval() {
+        output.log = Some(EventLog::new(interval));
+    }
+
+    if let Some(tol) = input.sett.energy_tol() {
+        output.energy_check = Some(EnergyCheck::new(tol));
+    }
+
+    if input.sett.compensated_sum().unwrap_or(false) {
+        output.compensate();
+    }
+
+    if input.sett.tree_stats().unwrap_or(false) {
+        output.traversal = Some(TraversalStats::default());
+    }
+
+    if input.sett.material_tallies().unwrap_or(false) {
+        output.tallies = Some(MaterialTallies::new(input.mats));
+    }
 
     let block_size = input.sett.block_size();
     while let Some((start, end)) = {
@@ -63,11 +706,187 @@ fn thread<'a>(
         std::mem::drop(pb);
         b
     } {
-        for _ in start..end {
-            let phot = input.light.emit(&mut rng, phot_energy);
-            engine.run(&input, &mut output, &mut rng, phot);
+        let phot_ids = (first_phot + start)..(first_phot + end);
+
+        // Unseeded photons of the block are emitted up front from the thread-local generator,
+        // keeping emission sampling out of the transport loop.
+        if input.sett.seed().is_none() {
+            let batch = input.emit_batch(&mut rng, phot_energy, phot_ids.start, phot_ids.end);
+            for (phot_id, (index, phot)) in phot_ids.zip(batch) {
+                if let Some(ref mut log) = output.log {
+                    log.begin(phot_id);
+                }
+                engine.run(&sources[index], &mut output, &mut rng, phot);
+            }
+            continue;
+        }
+
+        // Detector writes of the block are made to blank buffers, and submitted for ordered reduction.
+        let thread_bufs = reduction.map(|reduction| {
+            let (imgs, ccds) = reduction
+                .0
+                .lock()
+                .expect("Could not lock block reduction.")
+                .take_buffers();
+            (
+                std::mem::replace(&mut output.imgs, imgs),
+                std::mem::replace(&mut output.ccds, ccds),
+            )
+        });
+
+        for phot_id in phot_ids {
+            if let Some(ref mut log) = output.log {
+                log.begin(phot_id);
+            }
+
+            // Seeded photons draw from their own stream, independent of the thread.
+            if let Some((mut emit_rng, mut phot_rng)) = input.sett.phase_streams(phot_id as u64) {
+                let source = &sources[input.select_light(&mut emit_rng)];
+                let phot = source.light.emit_nth(&mut emit_rng, phot_energy, phot_id);
+                engine.run(source, &mut output, &mut phot_rng, phot);
+            } else if let Some(seed) = input.sett.seed() {
+                let mut phot_rng = substream(seed, phot_id as u64);
+                let source = &sources[input.select_light(&mut phot_rng)];
+                let phot = source.light.emit_nth(&mut phot_rng, phot_energy, phot_id);
+                engine.run(source, &mut output, &mut phot_rng, phot);
+            }
+        }
+
+        if let (Some(reduction), Some((imgs, ccds))) = (reduction, thread_bufs) {
+            let block_imgs = std::mem::replace(&mut output.imgs, imgs);
+            let block_ccds = std::mem::replace(&mut output.ccds, ccds);
+
+            // Blocks beyond the pending window wait for their predecessors to be added.
+            let (ref lock, ref added) = **reduction;
+            let mut reduction = lock.lock().expect("Could not lock block reduction.");
+            while !reduction.accepts(start) {
+                reduction = added
+                    .wait(reduction)
+                    .expect("Could not lock block reduction.");
+            }
+            reduction.submit(start, end, block_imgs, block_ccds);
+            added.notify_all();
         }
     }
 
     output
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        geom::{Cube, Orient, Ray},
+        math::{Dir3, Mat4, Point3, Vec3},
+        ord::{Map, Name},
+        sim::{fixture, Adaptive, Attribute, EventKind},
+    };
+
+    #[test]
+    fn test_frames_steer_the_light() {
+        // A single mirror well away from the domain.
+        let norm = Dir3::new(0.0, 0.0, 1.0);
+        let mirror = Attribute::Mirror(0.5);
+        let surfs = fixture::far_mirror(&mirror);
+        let tree = fixture::single_cell_tree(&surfs);
+
+        let name = Name::new("air");
+        let mats = fixture::material_set(&name, fixture::material(0.1, None, 0.0));
+        let mat = mats.get(&name).unwrap();
+
+        let reg = Register::new(vec![]);
+        let attrs = Set::new(Map::new());
+        let light = fixture::beam(1.0, Ray::new(Point3::new(0.0, 0.0, 0.0), norm), mat);
+        let bounds = Cube::new(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0));
+        let grid = Grid::new(bounds.clone(), [1, 1, 1]);
+        let sett = fixture::settings("num_phot: 4, num_threads: 1, trace_interval: 1");
+        let input = Input::new(&reg, &mats, &attrs, light, &tree, &grid, &sett);
+
+        // Each frame moves the source to a different position.
+        let cam = Point3::new(0.0, 0.0, 5.0);
+        let view = Mat4::look_at_rh(
+            &cam,
+            &Point3::new(0.0, 0.0, 0.0),
+            &Dir3::from(Vec3::new(0.0, 1.0, 0.0)),
+        );
+        let proj = Mat4::new_perspective(1.0, 0.5, 1.0e-3, 1.0e3);
+        let sources = [Point3::new(0.5, 0.0, 0.0), Point3::new(-0.5, 0.0, 0.0)];
+        let frames: Vec<_> = sources
+            .iter()
+            .map(|pos| {
+                Frame::new(cam, view, proj, [4, 4])
+                    .with_source(Orient::new_tar(*pos, &Point3::new(pos.x(), 0.0, 1.0)))
+            })
+            .collect();
+
+        let mut base = fixture::blank_output(bounds, [1, 1, 1], &reg);
+        base.photos = vec![Image::new_blank([4, 4], Colour::new(0.0, 0.0, 0.0, 0.0)); 2];
+        base.photo_vars = vec![None, None];
+
+        let data = multi_thread(&Engine::Photo(frames, [4, 4]), input, &base).unwrap();
+        assert_eq!(data.photos.len(), 2);
+
+        // Every photon of each frame's run is emitted from that frame's source.
+        let emissions: Vec<_> = data
+            .log
+            .unwrap()
+            .records()
+            .iter()
+            .filter(|rec| rec.kind() == EventKind::Emission)
+            .map(|rec| *rec.pos())
+            .collect();
+        assert_eq!(emissions.len(), 8);
+        for source in &sources {
+            assert_eq!(emissions.iter().filter(|pos| *pos == source).count(), 4);
+        }
+    }
+
+    #[test]
+    fn test_adaptive_frames_are_refined() {
+        // A single mirror well away from the domain.
+        let norm = Dir3::new(0.0, 0.0, 1.0);
+        let mirror = Attribute::Mirror(0.5);
+        let surfs = fixture::far_mirror(&mirror);
+        let tree = fixture::single_cell_tree(&surfs);
+
+        let name = Name::new("air");
+        let mats = fixture::material_set(&name, fixture::material(0.1, None, 0.0));
+        let mat = mats.get(&name).unwrap();
+
+        let reg = Register::new(vec![]);
+        let attrs = Set::new(Map::new());
+        let light = fixture::beam(1.0, Ray::new(Point3::new(0.0, 0.0, 0.0), norm), mat);
+        let bounds = Cube::new(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0));
+        let grid = Grid::new(bounds.clone(), [1, 1, 1]);
+        let sett = fixture::settings("num_phot: 4, num_threads: 1, seed: 7");
+        let input = Input::new(&reg, &mats, &attrs, light, &tree, &grid, &sett);
+
+        // Only the first frame is sampled adaptively, requiring at least twelve photons in each pixel.
+        let cam = Point3::new(0.0, 0.0, 5.0);
+        let view = Mat4::look_at_rh(
+            &cam,
+            &Point3::new(0.0, 0.0, 0.0),
+            &Dir3::from(Vec3::new(0.0, 1.0, 0.0)),
+        );
+        let proj = Mat4::new_perspective(1.0, 0.5, 1.0e-3, 1.0e3);
+        let frames = vec![
+            Frame::new(cam, view, proj, [4, 4]).with_adaptive(Adaptive::new(12, 1, 12, 1.0)),
+            Frame::new(cam, view, proj, [4, 4]).with_variance(),
+        ];
+
+        let mut base = fixture::blank_output(bounds, [1, 1, 1], &reg);
+        base.photos = vec![Image::new_blank([4, 4], Colour::new(0.0, 0.0, 0.0, 0.0)); 2];
+        base.photo_vars = vec![
+            Some(PixelVariance::new([4, 4])),
+            Some(PixelVariance::new([4, 4])),
+        ];
+
+        let data = multi_thread(&Engine::Photo(frames, [4, 4]), input, &base).unwrap();
+        assert_eq!(data.photos.len(), 2);
+
+        let refined = data.photo_vars[0].as_ref().unwrap().counts();
+        let single = data.photo_vars[1].as_ref().unwrap().counts();
+        assert!(refined.iter().all(|n| *n == 12.0));
+        assert!(single.iter().all(|n| *n == 4.0));
+    }
+}
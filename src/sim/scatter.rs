@@ -2,14 +2,14 @@
 
 use crate::{
     math::sample_henyey_greenstein,
-    phys::{Local, Photon},
+    phys::{Local, Photon, RamanSpectrum},
 };
-use rand::{rngs::ThreadRng, Rng};
+use rand::Rng;
 use std::f64::consts::PI;
 
 /// Perform a photon scattering event.
 #[inline]
-pub fn scatter(rng: &mut ThreadRng, phot: &mut Photon, env: &Local) {
+pub fn scatter<R: Rng>(rng: &mut R, phot: &mut Photon, env: &Local) {
     // Part of the weight is absorbed.
     *phot.weight_mut() *= env.albedo();
 
@@ -19,9 +19,32 @@ pub fn scatter(rng: &mut ThreadRng, phot: &mut Photon, env: &Local) {
     phot.ray_mut().rotate(phi, theta);
 }
 
+/// Probability of a photon interacting within a given distance [m] of the local environment, 1 - exp(-tau).
+#[inline]
+#[must_use]
+pub fn interaction_prob(env: &Local, dist: f64) -> f64 {
+    debug_assert!(dist >= 0.0);
+
+    -(-env.inter_coeff() * dist).exp_m1()
+}
+
+/// Sample the distance [m] to an interaction which is forced to occur within a given distance,
+/// typically the path length remaining within a thin medium.
+/// The distance is drawn from the exponential distribution truncated at the maximum distance.
+/// For an unbiased estimate, the photon weight must be reduced once by the `interaction_prob` of the full path,
+/// and the remaining weight must cross the path without interacting.
+#[inline]
+#[must_use]
+pub fn forced_scatter_dist<R: Rng>(rng: &mut R, env: &Local, max_dist: f64) -> f64 {
+    debug_assert!(env.inter_coeff() > 0.0);
+
+    let prob = interaction_prob(env, max_dist);
+    -(-rng.gen::<f64>() * prob).ln_1p() / env.inter_coeff()
+}
+
 /// Perform a photon scattering event with a probability of shifting wavelength.
 #[inline]
-pub fn shift_scatter(rng: &mut ThreadRng, phot: &mut Photon, env: &Local) {
+pub fn shift_scatter<R: Rng>(rng: &mut R, phot: &mut Photon, env: &Local) {
     // Part of the weight is absorbed.
     *phot.weight_mut() *= env.albedo();
 
@@ -39,3 +62,99 @@ pub fn shift_scatter(rng: &mut ThreadRng, phot: &mut Photon, env: &Local) {
     let theta = rng.gen_range(0.0..(PI * 2.0));
     phot.ray_mut().rotate(phi, theta);
 }
+
+/// Perform a photon scattering event with a probability of a Raman wavelength shift.
+/// Shifted photons keep their number, so their energy weight scales inversely with wavelength.
+/// Photons which no line of the spectrum can shift are scattered unshifted.
+#[inline]
+pub fn raman_scatter<R: Rng>(rng: &mut R, phot: &mut Photon, env: &Local, spec: &RamanSpectrum) {
+    // Part of the weight is absorbed.
+    *phot.weight_mut() *= env.albedo();
+
+    // The remaining weight may be Raman shifted.
+    let r = rng.gen::<f64>();
+    if r <= env.shift_prob() {
+        let wavelength = phot.wavelength();
+        if let Some(shifted) = spec.sample_shifted(rng, wavelength) {
+            *phot.wavelength_mut() = shifted;
+            *phot.weight_mut() *= wavelength / shifted;
+        }
+    }
+
+    // The remaining weight is scattered.
+    let phi = sample_henyey_greenstein(rng, env.asym());
+    let theta = rng.gen_range(0.0..(PI * 2.0));
+    phot.ray_mut().rotate(phi, theta);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        geom::Ray,
+        math::{Dir3, Point3},
+    };
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn test_raman_scatter_shifts() {
+        let mut rng = rand::thread_rng();
+        let spec = RamanSpectrum::new(vec![1000.0, -1000.0], &[3.0, 1.0]).unwrap();
+
+        // Every interaction is a shift.
+        let env = Local::new(1.0, 1.0e-12, 0.0, 1.0, 0.0);
+        let wavelength = 500.0e-9;
+        let stokes = 1.0 / ((1.0 / wavelength) - 1.0e5);
+        let anti_stokes = 1.0 / ((1.0 / wavelength) + 1.0e5);
+
+        let num_phot = 100_000;
+        let mut num_stokes = 0;
+        for _ in 0..num_phot {
+            let ray = Ray::new(Point3::new(0.0, 0.0, 0.0), Dir3::new(0.0, 0.0, 1.0));
+            let mut phot = Photon::new(ray, wavelength, 1.0);
+            raman_scatter(&mut rng, &mut phot, &env, &spec);
+
+            if (phot.wavelength() - stokes).abs() < 1.0e-15 {
+                num_stokes += 1;
+                assert_approx_eq!(phot.weight(), wavelength / stokes, 1.0e-6);
+            } else {
+                assert_approx_eq!(phot.wavelength(), anti_stokes, 1.0e-15);
+                assert_approx_eq!(phot.weight(), wavelength / anti_stokes, 1.0e-6);
+            }
+        }
+
+        assert_approx_eq!(f64::from(num_stokes) / f64::from(num_phot), 0.75, 0.01);
+    }
+
+    #[test]
+    fn test_forced_scattering_thin_slab() {
+        let mut rng = rand::thread_rng();
+
+        // Optically thin, purely scattering slab.
+        let env = Local::new(1.0, 10.0, 0.0, 0.0, 0.0);
+        let thickness = 1.0e-3;
+
+        // Tally the weighted depth of the first scattering event of normally incident photons.
+        let num_unforced = 1_000_000;
+        let mut unforced = 0.0;
+        for _ in 0..num_unforced {
+            let dist = -(rng.gen::<f64>()).ln() / env.inter_coeff();
+            if dist < thickness {
+                unforced += dist;
+            }
+        }
+
+        let num_forced = 10_000;
+        let mut forced = 0.0;
+        for _ in 0..num_forced {
+            let weight = interaction_prob(&env, thickness);
+            let dist = forced_scatter_dist(&mut rng, &env, thickness);
+            assert!(dist < thickness);
+            forced += weight * dist;
+        }
+
+        let unforced = unforced / f64::from(num_unforced);
+        let forced = forced / f64::from(num_forced);
+        assert_approx_eq!(forced / unforced, 1.0, 0.05);
+    }
+}
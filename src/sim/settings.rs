@@ -1,8 +1,12 @@
 //! MCRT settings.
 
-use crate::{clone, fmt_report};
+use crate::{clone, err::Error, fmt_report, math::substream};
 use arctk_attr::file;
-use std::fmt::{Display, Error, Formatter};
+use rand::rngs::StdRng;
+use std::fmt::{Display, Formatter};
+
+/// Stream index reserved for finalising the output of a seeded run.
+const FINALISE_STREAM: u64 = u64::MAX;
 
 /// General settings structure.
 #[file]
@@ -23,6 +27,29 @@ pub struct Settings {
     roulette_barrels: u64,
     /// Whether or not to output each night.
     output_individual_lights: Option<bool>,
+    /// Optional interval at which photons are sampled for event tracing.
+    trace_interval: Option<usize>,
+    /// Optional global seed, giving each photon a reproducible random number stream.
+    seed: Option<u64>,
+    /// Optional tolerance for checking energy conservation at optical interfaces.
+    energy_tol: Option<f64>,
+    /// Optional seed overriding the global seed for photon emission. Requires the global seed.
+    emission_seed: Option<u64>,
+    /// Optional seed overriding the global seed for photon transport. Requires the global seed.
+    /// Transport covers scattering and every surface interaction, so reflection shares this stream.
+    scatter_seed: Option<u64>,
+    /// Optional number of wavelength bands used to stratify emission.
+    wavelength_bands: Option<usize>,
+    /// Optionally record the cost of tracing rays through the hit-scan tree.
+    tree_stats: Option<bool>,
+    /// Optional limit on the number of packets each emitted photon may split into at partially reflective interfaces.
+    max_splits: Option<usize>,
+    /// Optionally force each photon to scatter before leaving the medium it is emitted into.
+    forced_scattering: Option<bool>,
+    /// Optionally accumulate volume data using compensated summation, doubling its memory use.
+    compensated_sum: Option<bool>,
+    /// Optionally count the scattering and absorption events occurring within each material.
+    material_tallies: Option<bool>,
 }
 
 impl Settings {
@@ -34,6 +61,17 @@ impl Settings {
     clone!(min_weight: f64);
     clone!(roulette_barrels: u64);
     clone!(output_individual_lights: Option<bool>);
+    clone!(trace_interval: Option<usize>);
+    clone!(seed: Option<u64>);
+    clone!(energy_tol: Option<f64>);
+    clone!(emission_seed: Option<u64>);
+    clone!(scatter_seed: Option<u64>);
+    clone!(wavelength_bands: Option<usize>);
+    clone!(tree_stats: Option<bool>);
+    clone!(max_splits: Option<usize>);
+    clone!(forced_scattering: Option<bool>);
+    clone!(compensated_sum: Option<bool>);
+    clone!(material_tallies: Option<bool>);
 
     /// Construct a new instance.
     #[inline]
@@ -47,6 +85,17 @@ impl Settings {
         min_weight: f64,
         roulette_barrels: u64,
         output_individual_lights: Option<bool>,
+        trace_interval: Option<usize>,
+        seed: Option<u64>,
+        energy_tol: Option<f64>,
+        emission_seed: Option<u64>,
+        scatter_seed: Option<u64>,
+        wavelength_bands: Option<usize>,
+        tree_stats: Option<bool>,
+        max_splits: Option<usize>,
+        forced_scattering: Option<bool>,
+        compensated_sum: Option<bool>,
+        material_tallies: Option<bool>,
     ) -> Self {
         debug_assert!(num_threads.is_none() || num_threads.unwrap() >= 1);
         debug_assert!(num_phot > 0);
@@ -54,6 +103,10 @@ impl Settings {
         debug_assert!(bump_dist > 0.0);
         debug_assert!(min_weight >= 0.0);
         debug_assert!(roulette_barrels > 1);
+        debug_assert!(trace_interval.is_none() || trace_interval.unwrap() > 0);
+        debug_assert!(energy_tol.is_none() || energy_tol.unwrap() > 0.0);
+        debug_assert!(wavelength_bands.is_none() || wavelength_bands.unwrap() > 0);
+        debug_assert!(seed.is_some() || (emission_seed.is_none() && scatter_seed.is_none()));
 
         Self {
             num_threads,
@@ -64,13 +117,81 @@ impl Settings {
             min_weight,
             roulette_barrels,
             output_individual_lights,
+            trace_interval,
+            seed,
+            energy_tol,
+            emission_seed,
+            scatter_seed,
+            wavelength_bands,
+            tree_stats,
+            max_splits,
+            forced_scattering,
+            compensated_sum,
+            material_tallies,
+        }
+    }
+
+    /// Check that the settings describe a runnable simulation.
+    /// Settings are loaded directly from file, so are checked once loaded rather than on construction.
+    /// # Errors
+    /// if a count or interval which must be positive is zero,
+    /// or a phase seed is given without the global seed.
+    #[inline]
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.num_threads == Some(0) {
+            return Err("Settings num_threads must be at least one.".into());
+        }
+        if self.num_phot == 0 {
+            return Err("Settings num_phot must be positive.".into());
+        }
+        if self.block_size == 0 {
+            return Err("Settings block_size must be positive.".into());
+        }
+        if self.trace_interval == Some(0) {
+            return Err("Settings trace_interval must be positive.".into());
+        }
+        if self.wavelength_bands == Some(0) {
+            return Err("Settings wavelength_bands must be positive.".into());
+        }
+        if self.seed.is_none() && (self.emission_seed.is_some() || self.scatter_seed.is_some()) {
+            return Err("Settings emission_seed and scatter_seed require a global seed.".into());
+        }
+
+        Ok(())
+    }
+
+    /// Generate the random number stream used to finalise the output of a seeded run, such as applying detector noise.
+    /// The stream takes the last index, which no photon reaches,
+    /// even when adaptive refinement numbers further passes of photons beyond the first.
+    #[inline]
+    #[must_use]
+    pub fn finalise_stream(&self) -> Option<StdRng> {
+        self.seed.map(|seed| substream(seed, FINALISE_STREAM))
+    }
+
+    /// Generate separate emission and transport random number streams for a photon.
+    /// Streams are only separated when the global seed and at least one phase seed are given,
+    /// with any unspecified phase falling back to the global seed.
+    /// Reflection at surfaces is part of transport, so draws from the transport stream.
+    #[inline]
+    #[must_use]
+    pub fn phase_streams(&self, phot_id: u64) -> Option<(StdRng, StdRng)> {
+        let seed = self.seed?;
+        if self.emission_seed.is_none() && self.scatter_seed.is_none() {
+            return None;
         }
+
+        // Interleave the stream indices, so the phases never share a stream.
+        Some((
+            substream(self.emission_seed.unwrap_or(seed), 2 * phot_id),
+            substream(self.scatter_seed.unwrap_or(seed), (2 * phot_id) + 1),
+        ))
     }
 }
 
 impl Display for Settings {
     #[inline]
-    fn fmt(&self, fmt: &mut Formatter) -> Result<(), Error> {
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
         writeln!(fmt, "...")?;
         if let Some(num_threads) = self.num_threads {
             fmt_report!(fmt, num_threads, "num_threads");
@@ -86,6 +207,142 @@ impl Display for Settings {
         if let Some(output_individual_lights) = self.output_individual_lights {
             fmt_report!(fmt, output_individual_lights, "output individual lights");
         }
+        if let Some(trace_interval) = self.trace_interval {
+            fmt_report!(fmt, trace_interval, "event trace interval");
+        }
+        if let Some(seed) = self.seed {
+            fmt_report!(fmt, seed, "seed");
+        }
+        if let Some(energy_tol) = self.energy_tol {
+            fmt_report!(fmt, energy_tol, "interface energy tolerance");
+        }
+        if let Some(emission_seed) = self.emission_seed {
+            fmt_report!(fmt, emission_seed, "emission seed");
+        }
+        if let Some(scatter_seed) = self.scatter_seed {
+            fmt_report!(fmt, scatter_seed, "scattering seed");
+        }
+        if let Some(wavelength_bands) = self.wavelength_bands {
+            fmt_report!(fmt, wavelength_bands, "stratified wavelength bands");
+        }
+        if let Some(tree_stats) = self.tree_stats {
+            fmt_report!(fmt, tree_stats, "tree traversal statistics");
+        }
+        if let Some(max_splits) = self.max_splits {
+            fmt_report!(fmt, max_splits, "maximum interface splits");
+        }
+        if let Some(forced_scattering) = self.forced_scattering {
+            fmt_report!(fmt, forced_scattering, "forced first scattering");
+        }
+        if let Some(compensated_sum) = self.compensated_sum {
+            fmt_report!(fmt, compensated_sum, "compensated volume summation");
+        }
+        if let Some(material_tallies) = self.material_tallies {
+            fmt_report!(fmt, material_tallies, "material event tallies");
+        }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        geom::Emitter,
+        math::{Dir3, Point3},
+        phys::{Local, Photon},
+        sim::scatter,
+    };
+    use rand::Rng;
+
+    /// Construct settings with the given phase seeds.
+    fn settings(emission_seed: Option<u64>, scatter_seed: Option<u64>) -> Settings {
+        Settings::new(
+            None,
+            1,
+            1,
+            1.0e-9,
+            1000,
+            0.0,
+            2,
+            None,
+            None,
+            Some(42),
+            None,
+            emission_seed,
+            scatter_seed,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_zero_trace_interval_rejected() {
+        assert!(settings(None, None).validate().is_ok());
+
+        let sett: Settings = crate::fs::from_json_str(
+            "{ num_phot: 10, block_size: 1, bump_dist: 1.0e-9, loop_limit: 1000, min_weight: 0.0, \
+             roulette_barrels: 2, trace_interval: 0 }",
+        )
+        .unwrap();
+        assert!(sett.validate().is_err());
+    }
+
+    #[test]
+    fn test_phase_seed_without_seed_rejected() {
+        assert!(settings(Some(1), Some(2)).validate().is_ok());
+
+        for field in &["emission_seed: 1", "scatter_seed: 2"] {
+            let sett: Settings = crate::fs::from_json_str(&format!(
+                "{{ num_phot: 10, block_size: 1, bump_dist: 1.0e-9, loop_limit: 1000, min_weight: 0.0, \
+                 roulette_barrels: 2, {} }}",
+                field
+            ))
+            .unwrap();
+            assert!(sett.validate().is_err());
+        }
+    }
+
+    #[test]
+    fn test_finalise_stream_distinct_from_photons() {
+        let sett = settings(None, None);
+        let finalise = sett.finalise_stream().unwrap().gen::<u64>();
+
+        // Including photons of later adaptive refinement passes.
+        for phot_id in 0..(8 * sett.num_phot() as u64) {
+            assert_ne!(finalise, substream(42, phot_id).gen::<u64>());
+        }
+    }
+
+    #[test]
+    fn test_scatter_seed_leaves_emission_unchanged() {
+        let emitter = Emitter::new_points(
+            (0..100)
+                .map(|i| Point3::new(f64::from(i), 0.0, 0.0))
+                .collect(),
+        );
+        let env = Local::new(1.0, 1.0, 0.0, 0.0, 0.0);
+
+        assert!(settings(None, None).phase_streams(0).is_none());
+
+        let mut emitted = Vec::new();
+        let mut scattered = Vec::new();
+        for sett in &[settings(None, Some(1)), settings(None, Some(2))] {
+            let (mut emit_rng, mut scatter_rng) = sett.phase_streams(7).unwrap();
+            let ray = emitter.emit(&mut emit_rng);
+            emitted.push(*ray.pos());
+
+            let mut phot = Photon::new(ray, 500.0e-9, 1.0);
+            *phot.ray_mut().dir_mut() = Dir3::new(0.0, 0.0, 1.0);
+            scatter(&mut scatter_rng, &mut phot, &env);
+            scattered.push(*phot.ray().dir());
+        }
+
+        assert_eq!(emitted[0], emitted[1]);
+        assert_ne!(scattered[0], scattered[1]);
+    }
+}
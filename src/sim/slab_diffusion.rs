@@ -0,0 +1,321 @@
+//! Analytic diffusion approximation cross-check for homogeneous slabs.
+
+use crate::{
+    clone, fmt_report,
+    geom::{Emitter, Surface},
+    math::Probability,
+    ord::Set,
+    phys::{Light, Material},
+    sim::{Boundary, BoundaryCondition, BoundaryDirection, Output},
+};
+use std::fmt::{Display, Formatter};
+
+/// Fraction of the slab thickness within which beams are considered to start on the bottom face.
+const FACE_TOL: f64 = 1.0e-6;
+
+/// Homogeneous, index-matched slab, illuminated by a collimated beam at normal incidence.
+/// The diffuse reflectance and transmittance are found with the diffusion approximation,
+/// treating the scattered light as an isotropic planar source one transport mean free path deep,
+/// with the fluence vanishing at extrapolated boundaries beyond each face.
+/// The approximation is accurate for optically thick slabs of high albedo.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlabDiffusion {
+    /// Absorption coefficient [1/m].
+    abs_coeff: f64,
+    /// Scattering coefficient [1/m].
+    scat_coeff: f64,
+    /// Asymmetry factor.
+    asym: f64,
+    /// Slab thickness [m].
+    thickness: f64,
+}
+
+impl SlabDiffusion {
+    clone!(abs_coeff: f64);
+    clone!(scat_coeff: f64);
+    clone!(asym: f64);
+    clone!(thickness: f64);
+
+    /// Construct a new instance.
+    #[inline]
+    #[must_use]
+    pub fn new(abs_coeff: f64, scat_coeff: f64, asym: f64, thickness: f64) -> Self {
+        debug_assert!(abs_coeff > 0.0);
+        debug_assert!(scat_coeff >= 0.0);
+        debug_assert!(asym.abs() < 1.0);
+        debug_assert!(thickness > 0.0);
+
+        Self {
+            abs_coeff,
+            scat_coeff,
+            asym,
+            thickness,
+        }
+    }
+
+    /// Construct a new instance from the optical properties of a material at a given wavelength.
+    #[inline]
+    #[must_use]
+    pub fn from_material(mat: &Material, w: f64, thickness: f64) -> Self {
+        let env = mat.sample_environment(w);
+        Self::new(env.abs_coeff(), env.scat_coeff(), env.asym(), thickness)
+    }
+
+    /// Construct a new instance describing a simulation setup, if the approximation applies to it.
+    /// The setup must hold a single material, of non-zero absorption, filling a domain with open bottom and top faces,
+    /// and no surfaces.
+    /// Every light must be a beam, entering the bottom face at normal incidence, at the same single wavelength.
+    #[inline]
+    #[must_use]
+    pub fn from_setup<T>(
+        mats: &Set<Material>,
+        lights: &Set<Light>,
+        surfs: &Set<Surface<T>>,
+        boundary: &Boundary,
+    ) -> Option<Self> {
+        let open = |dir| *boundary.condition(dir) == BoundaryCondition::Open;
+        if mats.len() != 1
+            || !surfs.is_empty()
+            || !open(BoundaryDirection::Bottom)
+            || !open(BoundaryDirection::Top)
+        {
+            return None;
+        }
+
+        let bounds = boundary.bounds();
+        let tol = FACE_TOL * bounds.widths().z();
+        let normal_beam = |light: &Light| match *light.emitter() {
+            Emitter::Beam(ref ray) => {
+                (ray.dir().z() - 1.0).abs() <= FACE_TOL
+                    && (ray.pos().z() - bounds.mins().z()).abs() <= tol
+                    && bounds.contains_eps(ray.pos(), tol)
+            }
+            _ => false,
+        };
+        if !lights.values().all(normal_beam) {
+            return None;
+        }
+
+        let mut wavelengths = lights.values().map(|light| match *light.spec() {
+            Probability::Point { c } => Some(c),
+            _ => None,
+        });
+        let w = wavelengths.next()??;
+        if !wavelengths.all(|other| other == Some(w)) {
+            return None;
+        }
+
+        let mat = mats.values().next()?;
+        let env = mat.sample_environment(w);
+        if env.abs_coeff() <= 0.0 || env.asym().abs() >= 1.0 {
+            return None;
+        }
+
+        Some(Self::from_material(mat, w, bounds.widths().z()))
+    }
+
+    /// Diffuse reflectance and transmittance of the scattered light.
+    #[inline]
+    #[must_use]
+    fn diffuse(&self) -> [f64; 2] {
+        let red_scat = self.scat_coeff * (1.0 - self.asym);
+        let red_inter = self.abs_coeff + red_scat;
+        let albedo = red_scat / red_inter;
+
+        let diff = 1.0 / (3.0 * red_inter);
+        let eff = (self.abs_coeff / diff).sqrt();
+        let extrap = 2.0 * diff;
+
+        // Source depth, and slab thickness, measured between the extrapolated boundaries.
+        let depth = (1.0 / red_inter) + extrap;
+        let width = self.thickness + (2.0 * extrap);
+
+        let scale = albedo * (eff * extrap).cosh() / (eff * width).sinh();
+        [
+            scale * (eff * (width - depth)).sinh(),
+            scale * (eff * depth).sinh(),
+        ]
+    }
+
+    /// Fraction of the incident power diffusely reflected.
+    #[inline]
+    #[must_use]
+    pub fn reflectance(&self) -> f64 {
+        self.diffuse()[0]
+    }
+
+    /// Fraction of the incident power transmitted, both diffusely and unscattered.
+    #[inline]
+    #[must_use]
+    pub fn transmittance(&self) -> f64 {
+        let ballistic = (-(self.abs_coeff + self.scat_coeff) * self.thickness).exp();
+        self.diffuse()[1] + ballistic
+    }
+
+    /// Compare the analytic values to those of a simulation.
+    /// The slab is taken to span the grid along the z-axis, illuminated from the bottom face,
+    /// with open boundary faces recording the escaping energy.
+    #[inline]
+    #[must_use]
+    pub fn compare(&self, data: &Output) -> DiffusionComparison {
+        let emitted = data.emission.total().sum();
+        DiffusionComparison {
+            analytic: [self.reflectance(), self.transmittance()],
+            simulated: [
+                data.escaped[BoundaryDirection::Bottom.index()] / emitted,
+                data.escaped[BoundaryDirection::Top.index()] / emitted,
+            ],
+        }
+    }
+}
+
+impl Display for SlabDiffusion {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        writeln!(fmt, "...")?;
+        fmt_report!(fmt, self.abs_coeff, "absorption coefficient (m^-1)");
+        fmt_report!(fmt, self.scat_coeff, "scattering coefficient (m^-1)");
+        fmt_report!(fmt, self.asym, "asymmetry factor");
+        fmt_report!(fmt, self.thickness, "thickness (m)");
+        Ok(())
+    }
+}
+
+/// Simulated and analytic reflectance and transmittance of a slab.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffusionComparison {
+    /// Analytic reflectance and transmittance.
+    pub analytic: [f64; 2],
+    /// Simulated reflectance and transmittance.
+    pub simulated: [f64; 2],
+}
+
+impl DiffusionComparison {
+    /// Simulated minus analytic reflectance and transmittance.
+    #[inline]
+    #[must_use]
+    pub fn difference(&self) -> [f64; 2] {
+        [
+            self.simulated[0] - self.analytic[0],
+            self.simulated[1] - self.analytic[1],
+        ]
+    }
+}
+
+impl Display for DiffusionComparison {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+        let [dr, dt] = self.difference();
+        writeln!(fmt, "...")?;
+        fmt_report!(fmt, self.simulated[0], "simulated reflectance");
+        fmt_report!(fmt, self.analytic[0], "analytic reflectance");
+        fmt_report!(fmt, dr, "reflectance difference");
+        fmt_report!(fmt, self.simulated[1], "simulated transmittance");
+        fmt_report!(fmt, self.analytic[1], "analytic transmittance");
+        fmt_report!(fmt, dt, "transmittance difference");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        geom::{Cube, Grid, Ray, Surface},
+        math::{Dir3, Point3},
+        ord::{Map, Name, Register, Set},
+        phys::Photon,
+        sim::{
+            fixture::{self, WAVELENGTH},
+            standard, Attribute, Boundary, BoundaryCondition, Input,
+        },
+    };
+
+    #[test]
+    fn test_semi_infinite_limit() {
+        // Farrell et al. (1992), total diffuse reflectance of a semi-infinite medium.
+        let slab = SlabDiffusion::new(0.1, 10.0, 0.0, 20.0);
+        let albedo: f64 = 10.0 / 10.1;
+        let root = (3.0 * (1.0 - albedo)).sqrt();
+        let farrell = 0.5 * albedo * (1.0 + (-4.0 * root / 3.0).exp()) * (-root).exp();
+
+        assert!((slab.reflectance() - farrell).abs() < 1.0e-9);
+        assert!(slab.transmittance() < 1.0e-9);
+    }
+
+    #[test]
+    fn test_slab_diffuse_reflectance() {
+        let mirror = Attribute::Mirror(0.5);
+        let surfs = fixture::far_mirror(&mirror);
+        let tree = fixture::single_cell_tree(&surfs);
+
+        // An isotropically scattering slab, of albedo 0.99 and optical depth 10.1.
+        let name = Name::new("slab");
+        let mats = fixture::material_set(&name, fixture::material(10.0, Some(0.1), 0.0));
+        let mat = mats.get(&name).unwrap();
+        let slab = SlabDiffusion::from_material(mat, WAVELENGTH, 1.0);
+
+        // The slab is laterally wide enough for no photons to escape through the sides.
+        let reg = Register::new(vec![]);
+        let attrs = Set::new(Map::new());
+        let ray = Ray::new(Point3::new(0.0, 0.0, 1.0e-9), Dir3::new(0.0, 0.0, 1.0));
+        let light = fixture::beam(1.0, ray.clone(), mat);
+        let bounds = Cube::new(
+            Point3::new(-100.0, -100.0, 0.0),
+            Point3::new(100.0, 100.0, 1.0),
+        );
+        let grid = Grid::new(bounds.clone(), [1, 1, 1]);
+        let boundary = Boundary::new(bounds.clone(), BoundaryCondition::Open);
+        let sett = fixture::settings("num_phot: 1");
+        let input =
+            Input::new(&reg, &mats, &attrs, light, &tree, &grid, &sett).with_boundary(&boundary);
+
+        // The same approximation is found from the setup itself, without the mirror.
+        let mut lights_map = Map::new();
+        lights_map.insert(Name::new("beam"), input.light.clone());
+        let lights = Set::new(lights_map);
+        let no_surfs: Set<Surface<Attribute>> = Set::new(Map::new());
+        assert_eq!(
+            SlabDiffusion::from_setup(&mats, &lights, &no_surfs, &boundary),
+            Some(slab.clone())
+        );
+        assert_eq!(
+            SlabDiffusion::from_setup(&mats, &lights, &surfs, &boundary),
+            None
+        );
+
+        // Oblique beams, and beams starting inside the slab, are not described by the approximation.
+        for start in &[
+            Ray::new(Point3::new(0.0, 0.0, 0.0), Dir3::new(0.0, 1.0, 1.0)),
+            Ray::new(Point3::new(0.0, 0.0, 0.5), Dir3::new(0.0, 0.0, 1.0)),
+        ] {
+            let mut lights_map = Map::new();
+            lights_map.insert(Name::new("beam"), fixture::beam(1.0, start.clone(), mat));
+            let lights = Set::new(lights_map);
+            assert_eq!(
+                SlabDiffusion::from_setup(&mats, &lights, &no_surfs, &boundary),
+                None
+            );
+        }
+
+        let mut data = fixture::blank_output(bounds, [1, 1, 1], &reg);
+
+        let num_phot = 10_000;
+        let mut rng = rand::thread_rng();
+        for _ in 0..num_phot {
+            standard(
+                &input,
+                &mut data,
+                &mut rng,
+                Photon::new(ray.clone(), WAVELENGTH, 1.0),
+            );
+        }
+
+        // Agreement within three standard errors, allowing for the error of the diffusion approximation itself.
+        let comparison = slab.compare(&data);
+        let reflectance = comparison.simulated[0];
+        let std_err = (reflectance * (1.0 - reflectance) / f64::from(num_phot)).sqrt();
+        assert!(comparison.difference()[0].abs() < (3.0 * std_err) + 0.01);
+        assert!(comparison.difference()[1].abs() < 0.02);
+    }
+}
@@ -1,19 +1,21 @@
 //! Photon scattering function.
 
 use crate::{
-    geom::Hit,
+    geom::{Hit, Orient, OrientedPlane},
     img::Colour,
+    math::Point3,
     ord::{X, Y},
     phys::{Crossing, Local, Photon},
-    sim::{Attribute, Output},
+    sim::{Attribute, DetectorKind, Output},
 };
-use rand::{rngs::ThreadRng, Rng};
+use ndarray::s;
+use rand::Rng;
 
 /// Handle a surface collision.
 #[allow(clippy::expect_used)]
 #[inline]
-pub fn surface(
-    rng: &mut ThreadRng,
+pub fn surface<R: Rng>(
+    rng: &mut R,
     hit: &Hit<Attribute>,
     phot: &mut Photon,
     env: &mut Local,
@@ -44,67 +46,154 @@ pub fn surface(
                 next_ref_index,
             );
 
+            // Check that the Fresnel coefficients account for all of the incident energy.
+            if let Some(ref mut check) = data.energy_check {
+                check.check(1.0, crossing.ref_prob(), crossing.trans_prob(), 0.0);
+            }
+
+            // Split partially reflective crossings into both a reflected and a transmitted packet.
+            let ref_prob = crossing.ref_prob();
+            if data.split_budget > 0 && ref_prob > 0.0 && ref_prob < 1.0 {
+                data.split_budget -= 1;
+
+                let mut reflected = phot.clone();
+                *reflected.weight_mut() *= ref_prob;
+                *reflected.ray_mut().dir_mut() = *crossing.ref_dir();
+                reflected.ray_mut().offset_from_surface(hit.side().norm());
+                data.spawned.push((reflected, env.clone(), 0.0));
+                if let Some(ref mut tallies) = data.tallies {
+                    tallies.spawn();
+                    tallies.enter(next_mat);
+                }
+
+                *phot.weight_mut() *= 1.0 - ref_prob;
+                *phot.ray_mut().dir_mut() = crossing.trans_dir().expect("Invalid refraction.");
+                phot.ray_mut().offset_from_surface(hit.side().norm());
+                *env = next_env;
+                return;
+            }
+
             // Determine if a reflection or transmission occurs.
             let r = rng.gen::<f64>();
-            if r <= crossing.ref_prob() {
+            if r <= ref_prob {
                 // Reflect.
                 *phot.ray_mut().dir_mut() = *crossing.ref_dir();
             } else {
                 // Refract.
                 *phot.ray_mut().dir_mut() = crossing.trans_dir().expect("Invalid refraction.");
                 *env = next_env;
+                if let Some(ref mut tallies) = data.tallies {
+                    tallies.enter(next_mat);
+                }
             }
+            phot.ray_mut().offset_from_surface(hit.side().norm());
         }
         Attribute::Mirror(abs) => {
             *phot.weight_mut() *= abs;
             *phot.ray_mut().dir_mut() = Crossing::calc_ref_dir(phot.ray().dir(), hit.side().norm());
+            phot.ray_mut().offset_from_surface(hit.side().norm());
         }
         Attribute::Spectrometer(id) => {
             data.specs[id].try_collect_weight(phot.wavelength(), phot.weight());
+            data.detect(DetectorKind::Spectrometer, id, phot);
             phot.kill();
         }
         Attribute::Imager(id, width, ref orient) => {
-            let projection = orient.pos() - phot.ray().pos();
-            let x = ((orient.right().dot_vec(&projection) / width) + 1.0) / 2.0;
-            let y = ((orient.up().dot_vec(&projection) / width) + 1.0) / 2.0;
-
-            if (0.0..=1.0).contains(&x) && (0.0..=1.0).contains(&y) {
-                let res = data.imgs[id].pixels().raw_dim();
-                data.imgs[id].pixels_mut()
-                    [[(res[X] as f64 * x) as usize, (res[Y] as f64 * y) as usize]] +=
+            let res = data.imgs[id].pixels().raw_dim();
+            if let Some(pixel) = detector_pixel(orient, width, phot.ray().pos(), [res[X], res[Y]]) {
+                data.imgs[id].pixels_mut()[pixel] +=
                     wavelength_to_col(phot.wavelength()) * (phot.weight() * phot.power()) as f32;
             }
 
             phot.kill();
         }
-        Attribute::Ccd(id, width, ref orient, ref binner) => {
-            let projection = orient.pos() - phot.ray().pos();
-            let x = ((orient.right().dot_vec(&projection) / width) + 1.0) / 2.0;
-            let y = ((orient.up().dot_vec(&projection) / width) + 1.0) / 2.0;
-
-            if (0.0..=1.0).contains(&x) && (0.0..=1.0).contains(&y) {
-                let res = data.ccds[id].raw_dim();
-                if let Some(bin) = binner.try_bin(phot.wavelength()) {
-                    data.ccds[id][[
-                        (res[X] as f64 * x) as usize,
-                        (res[Y] as f64 * y) as usize,
-                        bin,
-                    ]] += phot.weight() * phot.power();
-                }
+        Attribute::Ccd(id, width, ref orient, ref channels) => {
+            let res = data.ccds[id].raw_dim();
+            if let Some([px, py]) =
+                detector_pixel(orient, width, phot.ray().pos(), [res[X], res[Y]])
+            {
+                channels.collect(
+                    &mut data.ccds[id].slice_mut(s![px, py, ..]),
+                    phot.wavelength(),
+                    phot.weight() * phot.power(),
+                );
+                data.detect(DetectorKind::Ccd, id, phot);
             }
 
             phot.kill();
         }
         Attribute::Reflector(ref reflectance) => match reflectance.reflect(rng, &phot, hit) {
-            Some(ray) => *phot.ray_mut() = ray,
+            Some((ray, weight)) => {
+                *phot.ray_mut() = ray;
+                *phot.weight_mut() *= weight;
+            }
+            None => phot.kill(),
+        },
+        Attribute::TexturedReflector(ref reflectance, ref texture) => {
+            if rng.gen::<f64>() < texture.value(hit.uv()) {
+                match reflectance.reflect(rng, &phot, hit) {
+                    Some((ray, weight)) => {
+                        *phot.ray_mut() = ray;
+                        *phot.weight_mut() *= weight;
+                    }
+                    None => phot.kill(),
+                }
+            } else {
+                phot.kill();
+            }
+        }
+        Attribute::Translucent(ref sheet) => match sheet.interact(rng, &phot, hit) {
+            Some((ray, weight)) => {
+                *phot.ray_mut() = ray;
+                *phot.weight_mut() *= weight;
+            }
             None => phot.kill(),
         },
         Attribute::PhotonCollector(id) => {
+            data.detect(DetectorKind::PhotonCollector, id, phot);
             data.phot_cols[id].collect_photon(phot);
         }
+        Attribute::Hyperspectral(id) => {
+            data.hypers[id].collect(
+                phot.ray().pos(),
+                phot.wavelength(),
+                phot.weight() * phot.power(),
+            );
+            phot.kill();
+        }
+        Attribute::Radiance(id) => {
+            data.radiances[id].collect(phot.ray().dir(), phot.weight() * phot.power());
+            phot.kill();
+        }
+        Attribute::AttributeChain(ref attrs) => {
+            for attr in attrs {
+                let link = Hit::new(attr, hit.dist(), hit.side().clone())
+                    .with_uv(hit.uv())
+                    .with_object(hit.object());
+                surface(rng, &link, phot, env, data);
+
+                // Once killed, later attributes in the chain must not tally the photon.
+                if phot.weight() <= 0.0 {
+                    break;
+                }
+            }
+        }
     }
 }
 
+/// Determine the pixel of a square detector, of the given half-width, struck at a position.
+/// The pixel grid lies in the detector's local frame, spanned by the right and up directions of its orientation.
+#[inline]
+#[must_use]
+fn detector_pixel(
+    orient: &Orient,
+    half_width: f64,
+    pos: &Point3,
+    res: [usize; 2],
+) -> Option<[usize; 2]> {
+    OrientedPlane::new(orient.clone(), 2.0 * half_width, 2.0 * half_width).pixel(pos, res)
+}
+
 /// Determine the colour for a given wavelength.
 #[inline]
 #[must_use]
@@ -153,3 +242,240 @@ fn wavelength_to_col(wavelength: f64) -> Colour {
 
     Colour::new(r as f32, g as f32, b as f32, 1.0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        data::Histogram,
+        geom::{Cube, Orient, Ray, Side},
+        math::{Dir3, Formula, Point3},
+        ord::Register,
+        phys::{EnergyCheck, Material, Spectrum},
+        sim::{fixture, DetectionCallback, PhotonCollector},
+    };
+    use assert_approx_eq::assert_approx_eq;
+    use std::sync::{Arc, Mutex};
+
+    /// Construct an empty output.
+    fn empty_output(reg: &Register) -> Output {
+        fixture::blank_output(
+            Cube::new(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0)),
+            [1, 1, 1],
+            reg,
+        )
+    }
+
+    /// Construct a non-absorbing material of the given refractive index.
+    fn dielectric(ref_index: f64) -> Material {
+        Material::new(
+            Formula::Constant { c: ref_index },
+            Formula::Constant { c: 1.0 },
+            None,
+            None,
+            Spectrum::new_constant(0.0),
+        )
+    }
+
+    #[test]
+    fn test_chain_stops_after_kill() {
+        let reg = Register::new(vec![]);
+        let mut col = PhotonCollector::new();
+        col.kill_photon = true;
+        let mut data = empty_output(&reg);
+        data.specs = vec![Histogram::new(400.0e-9, 600.0e-9, 10)];
+        data.phot_cols = vec![col];
+
+        let chain = Attribute::AttributeChain(vec![
+            Attribute::PhotonCollector(0),
+            Attribute::Spectrometer(0),
+        ]);
+        let hit = Hit::new(&chain, 1.0, Side::Outside(Dir3::new(0.0, 0.0, 1.0)));
+        let mut phot = Photon::new(
+            Ray::new(Point3::new(0.0, 0.0, 0.0), Dir3::new(0.0, 0.0, -1.0)),
+            500.0e-9,
+            1.0,
+        );
+        let mut env = Local::new(1.0, 1.0, 0.0, 0.0, 0.0);
+        let mut rng = rand::thread_rng();
+
+        surface(&mut rng, &hit, &mut phot, &mut env, &mut data);
+
+        assert_eq!(phot.weight(), 0.0);
+        assert_eq!(data.phot_cols[0].nphoton(), 1);
+        assert_eq!(data.specs[0].counts().sum(), 0.0);
+    }
+
+    #[test]
+    fn test_tilted_detector_pixel() {
+        let tilt = 30.0_f64.to_radians();
+        let orient = Orient::new_up(
+            Ray::new(
+                Point3::new(0.0, 0.0, 0.0),
+                Dir3::new(tilt.sin(), 0.0, tilt.cos()),
+            ),
+            &Dir3::new(0.0, 1.0, 0.0),
+        );
+
+        // A hit half a unit along the detector's right axis, and a quarter unit down its up axis.
+        let pos = Point3::new(0.0, 0.0, 0.0) + (0.5 * orient.right()) - (0.25 * orient.up());
+        assert_eq!(detector_pixel(&orient, 1.0, &pos, [4, 4]), Some([3, 1]));
+
+        // Hits beyond the detector edge are not recorded.
+        let off = Point3::new(0.0, 0.0, 0.0) + (1.5 * orient.right());
+        assert_eq!(detector_pixel(&orient, 1.0, &off, [4, 4]), None);
+    }
+
+    #[test]
+    fn test_interface_splitting() {
+        let reg = Register::new(vec![]);
+        let (glass, air) = (dielectric(1.5), dielectric(1.0));
+        let interface = Attribute::Interface(&glass, &air);
+        let hit = Hit::new(&interface, 1.0, Side::Outside(Dir3::new(0.0, 0.0, 1.0)));
+        let incident = || {
+            Photon::new(
+                Ray::new(Point3::new(0.0, 0.0, 0.0), Dir3::new(0.0, 0.0, -1.0)),
+                500.0e-9,
+                1.0,
+            )
+        };
+        let mut rng = rand::thread_rng();
+
+        // Normal incidence reflectance.
+        let ref_prob = (0.5_f64 / 2.5).powi(2);
+
+        // Estimate the transmitted weight of each photon, with and without splitting.
+        let num_phot = 1000;
+        let mut stochastic = Vec::with_capacity(num_phot);
+        let mut split = Vec::with_capacity(num_phot);
+        for _ in 0..num_phot {
+            let mut data = empty_output(&reg);
+            let mut phot = incident();
+            let mut env = air.sample_environment(phot.wavelength());
+            surface(&mut rng, &hit, &mut phot, &mut env, &mut data);
+            assert!(data.spawned.is_empty());
+            let transmitted = phot.ray().dir().z() < 0.0;
+            stochastic.push(if transmitted { phot.weight() } else { 0.0 });
+
+            let mut data = empty_output(&reg);
+            data.split_budget = 1;
+            let mut phot = incident();
+            let mut env = air.sample_environment(phot.wavelength());
+            surface(&mut rng, &hit, &mut phot, &mut env, &mut data);
+            assert_eq!(data.split_budget, 0);
+            assert_eq!(data.spawned.len(), 1);
+            assert!(phot.ray().dir().z() < 0.0);
+            assert_approx_eq!(env.ref_index(), 1.5);
+
+            // Splitting conserves the total weight.
+            let (reflected, reflected_env, free_dist) = &data.spawned[0];
+            assert_approx_eq!(*free_dist, 0.0);
+            assert!(reflected.ray().dir().z() > 0.0);
+            assert_approx_eq!(reflected_env.ref_index(), 1.0);
+            assert_approx_eq!(reflected.weight(), ref_prob);
+            assert_approx_eq!(phot.weight() + reflected.weight(), 1.0);
+            split.push(phot.weight());
+        }
+
+        let mean = |xs: &[f64]| xs.iter().sum::<f64>() / xs.len() as f64;
+        let var = |xs: &[f64]| {
+            let mu = mean(xs);
+            xs.iter().map(|x| (x - mu).powi(2)).sum::<f64>() / (xs.len() - 1) as f64
+        };
+        assert_approx_eq!(mean(&split), 1.0 - ref_prob);
+        assert_approx_eq!(mean(&stochastic), 1.0 - ref_prob, 0.05);
+        assert!(var(&split) < var(&stochastic));
+    }
+
+    #[test]
+    fn test_interface_conserves_weight() {
+        let reg = Register::new(vec![]);
+        let (glass, air) = (dielectric(1.5), dielectric(1.0));
+        let interface = Attribute::Interface(&glass, &air);
+        let mut rng = rand::thread_rng();
+
+        // Packets crossing both ways, including beyond the critical angle, with and without splitting.
+        let mut data = empty_output(&reg);
+        data.energy_check = Some(EnergyCheck::new(1.0e-9));
+        for z in &[-1.0_f64, 1.0] {
+            for ang in &[0.0_f64, 0.3, 0.6, 0.9, 1.2, 1.5] {
+                for split_budget in 0..2 {
+                    let dir = Dir3::new(ang.sin(), 0.0, z * ang.cos());
+                    let side = Side::new(&dir, Dir3::new(0.0, 0.0, 1.0));
+                    let hit = Hit::new(&interface, 1.0, side);
+                    let mut phot =
+                        Photon::new(Ray::new(Point3::new(0.0, 0.0, 0.0), dir), 500.0e-9, 1.0);
+                    *phot.weight_mut() = 2.0;
+                    let mut env = air.sample_environment(phot.wavelength());
+                    data.split_budget = split_budget;
+                    surface(&mut rng, &hit, &mut phot, &mut env, &mut data);
+                }
+            }
+        }
+
+        let check = data.energy_check.unwrap();
+        assert_eq!(check.checked(), 24);
+        assert_eq!(check.violations(), 0);
+    }
+
+    #[test]
+    fn test_interface_bad_ref_index_flagged() {
+        let reg = Register::new(vec![]);
+        let (glass, bad) = (dielectric(1.5), dielectric(f64::NAN));
+        let interface = Attribute::Interface(&glass, &bad);
+        let mut rng = rand::thread_rng();
+
+        let mut data = empty_output(&reg);
+        data.energy_check = Some(EnergyCheck::new(1.0e-9));
+        for split_budget in 0..2 {
+            let dir = Dir3::new(0.3_f64.sin(), 0.0, -0.3_f64.cos());
+            let hit = Hit::new(&interface, 1.0, Side::new(&dir, Dir3::new(0.0, 0.0, 1.0)));
+            let mut phot = Photon::new(Ray::new(Point3::new(0.0, 0.0, 0.0), dir), 500.0e-9, 1.0);
+            let mut env = bad.sample_environment(phot.wavelength());
+            data.split_budget = split_budget;
+            surface(&mut rng, &hit, &mut phot, &mut env, &mut data);
+        }
+
+        // Fresnel coefficients for an undefined refractive index do not sum to unity.
+        let check = data.energy_check.unwrap();
+        assert_eq!(check.checked(), 2);
+        assert_eq!(check.violations(), 2);
+    }
+
+    #[test]
+    fn test_collector_detection_callback() {
+        let reg = Register::new(vec![]);
+        let mut data = empty_output(&reg);
+        data.phot_cols = vec![PhotonCollector::new()];
+
+        let detections = Arc::new(Mutex::new(Vec::new()));
+        let record = Arc::clone(&detections);
+        data.on_detect = Some(DetectionCallback::new(move |det| {
+            record.lock().unwrap().push(det.clone());
+        }));
+
+        let attrib = Attribute::PhotonCollector(0);
+        let hit = Hit::new(&attrib, 1.0, Side::Outside(Dir3::new(0.0, 0.0, 1.0)));
+        let mut env = Local::new(1.0, 1.0, 0.0, 0.0, 0.0);
+        let mut rng = rand::thread_rng();
+
+        let weights = [1.0, 0.5, 0.25];
+        for weight in &weights {
+            let mut phot = Photon::new(
+                Ray::new(Point3::new(0.0, 0.0, 0.0), Dir3::new(0.0, 0.0, -1.0)),
+                500.0e-9,
+                1.0,
+            );
+            *phot.weight_mut() = *weight;
+            surface(&mut rng, &hit, &mut phot, &mut env, &mut data);
+        }
+
+        let detections = detections.lock().unwrap();
+        assert_eq!(detections.len(), weights.len());
+        for (det, weight) in detections.iter().zip(&weights) {
+            assert_eq!(det.kind, DetectorKind::PhotonCollector);
+            assert_eq!(det.id, 0);
+            assert_approx_eq!(det.weight, *weight);
+        }
+    }
+}
@@ -1,7 +1,8 @@
 //! Photon movement function.
 
 use crate::{
-    phys::{Local, Photon},
+    geom::{Grid, Ray},
+    phys::{Local, Material, Photon},
     sim::Output,
 };
 use physical_constants::SPEED_OF_LIGHT_IN_VACUUM;
@@ -11,10 +12,107 @@ use physical_constants::SPEED_OF_LIGHT_IN_VACUUM;
 pub fn travel(data: &mut Output, phot: &mut Photon, env: &Local, index: [usize; 3], dist: f64) {
     debug_assert!(dist > 0.0);
 
+    let start = *phot.ray().pos();
+
     let weight_power_dist = phot.weight() * phot.power() * dist;
-    data.energy[index] += weight_power_dist * env.ref_index() / SPEED_OF_LIGHT_IN_VACUUM;
-    data.absorptions[index] += weight_power_dist * env.abs_coeff();
-    data.shifts[index] += weight_power_dist * env.shift_coeff();
+    data.energy.add(
+        index,
+        weight_power_dist * env.ref_index() / SPEED_OF_LIGHT_IN_VACUUM,
+    );
+    data.absorptions
+        .add(index, weight_power_dist * env.abs_coeff());
+    data.shifts
+        .add(index, weight_power_dist * env.shift_coeff());
+    data.flux.add(index, phot.weight() * dist);
 
     phot.ray_mut().travel(dist);
+
+    for stack in &mut data.plane_stacks {
+        stack.collect(&start, phot.ray().pos(), phot.weight() * phot.power());
+    }
+}
+
+/// Calculate the Beer-Lambert transmittance along a ray crossing the cells of a grid.
+/// The extinction of each cell is taken from the material returned by `mat` for its index.
+/// Any part of the path lying outside of the grid is treated as non-attenuating.
+/// Returns `None` if the material of a crossed cell can not be determined.
+#[inline]
+#[must_use]
+pub fn transmittance<'a, F>(
+    grid: &Grid,
+    ray: &Ray,
+    dist: f64,
+    wavelength: f64,
+    mat: F,
+) -> Option<f64>
+where
+    F: Fn([usize; 3]) -> Option<&'a Material>,
+{
+    debug_assert!(dist >= 0.0);
+
+    let optical_depth: f64 = grid
+        .voxel_traversal(ray)
+        .limit(dist)
+        .map(|(index, len)| mat(index).map(|mat| mat.extinction(wavelength) * len))
+        .sum::<Option<f64>>()?;
+
+    Some((-optical_depth).exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        geom::Cube,
+        math::{Dir3, Formula, Point3},
+        phys::Spectrum,
+    };
+
+    /// Construct a purely absorbing material.
+    fn absorber(abs_coeff: f64) -> Material {
+        Material::new(
+            Formula::Constant { c: 1.0 },
+            Formula::Constant { c: 0.0 },
+            Some(Formula::Constant { c: abs_coeff }),
+            None,
+            Spectrum::new_constant(0.0),
+        )
+    }
+
+    fn grid() -> Grid {
+        Grid::new(
+            Cube::new(Point3::new(0.0, 0.0, 0.0), Point3::new(2.0, 1.0, 1.0)),
+            [4, 1, 1],
+        )
+    }
+
+    #[test]
+    fn test_transmittance_homogeneous() {
+        let mat = absorber(1.5);
+        let ray = Ray::new(Point3::new(0.0, 0.5, 0.5), Dir3::new(1.0, 0.0, 0.0));
+
+        let trans = transmittance(&grid(), &ray, 1.7, 500.0e-9, |_| Some(&mat)).unwrap();
+        assert!((trans - (-1.5 * 1.7_f64).exp()).abs() < 1.0e-6);
+        assert!((trans - mat.transmittance_over(1.7, 500.0e-9)).abs() < 1.0e-6);
+    }
+
+    #[test]
+    fn test_transmittance_two_materials() {
+        let left = absorber(0.5);
+        let right = absorber(3.0);
+        let ray = Ray::new(Point3::new(0.0, 0.5, 0.5), Dir3::new(1.0, 0.0, 0.0));
+
+        let trans = transmittance(&grid(), &ray, 2.0, 500.0e-9, |index| {
+            Some(if index[0] < 2 { &left } else { &right })
+        })
+        .unwrap();
+        let product =
+            left.transmittance_over(1.0, 500.0e-9) * right.transmittance_over(1.0, 500.0e-9);
+        assert!((trans - product).abs() < 1.0e-6);
+
+        // Paths crossing a cell of unknown material have no transmittance.
+        let partial = |index: [usize; 3]| if index[0] < 3 { Some(&left) } else { None };
+        assert!(transmittance(&grid(), &ray, 1.0, 500.0e-9, partial).is_some());
+        assert!(transmittance(&grid(), &ray, 2.0, 500.0e-9, partial).is_none());
+    }
 }
@@ -4,6 +4,30 @@ use crate::{access, clone, tools::Range};
 use arctk_attr::file;
 use std::fmt::{Display, Error, Formatter};
 
+/// Treatment of values falling outside of a binning range.
+#[file]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutOfRange {
+    /// Place the value in the nearest end bin.
+    Clamp,
+    /// Discard the value.
+    Drop,
+    /// Count the value separately as underflow or overflow.
+    Overflow,
+}
+
+impl Display for OutOfRange {
+    #[inline]
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), Error> {
+        let kind = match *self {
+            Self::Clamp => "clamp",
+            Self::Drop => "drop",
+            Self::Overflow => "overflow",
+        };
+        write!(fmt, "{}", kind)
+    }
+}
+
 /// One-dimensional binning structure.
 #[file]
 #[derive(Clone, PartialEq)]
@@ -12,6 +36,8 @@ pub struct Binner {
     range: Range,
     /// Total number of bins.
     bins: usize,
+    /// Optional out-of-range policy, clamping if not set.
+    policy: Option<OutOfRange>,
 }
 
 impl Binner {
@@ -24,7 +50,26 @@ impl Binner {
     pub fn new(range: Range, bins: usize) -> Self {
         debug_assert!(bins > 0);
 
-        Self { range, bins }
+        Self {
+            range,
+            bins,
+            policy: None,
+        }
+    }
+
+    /// Set the out-of-range policy.
+    #[inline]
+    #[must_use]
+    pub fn with_policy(mut self, policy: OutOfRange) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// Get the out-of-range policy.
+    #[inline]
+    #[must_use]
+    pub fn policy(&self) -> OutOfRange {
+        self.policy.unwrap_or(OutOfRange::Clamp)
     }
 
     /// Calculate the bin width.
@@ -34,6 +79,39 @@ impl Binner {
         self.range.width() / self.bins as f64
     }
 
+    /// Calculate the lower and upper edges of a bin.
+    #[inline]
+    #[must_use]
+    pub fn bin_edges(&self, index: usize) -> (f64, f64) {
+        debug_assert!(index < self.bins);
+
+        let width = self.bin_width();
+        (
+            width.mul_add(index as f64, self.range.min()),
+            width.mul_add((index + 1) as f64, self.range.min()),
+        )
+    }
+
+    /// Calculate the central value of a bin.
+    #[inline]
+    #[must_use]
+    pub fn bin_centre(&self, index: usize) -> f64 {
+        debug_assert!(index < self.bins);
+
+        self.bin_width()
+            .mul_add(index as f64 + 0.5, self.range.min())
+    }
+
+    /// Calculate the edges of all bins, from the range minimum to the range maximum.
+    #[inline]
+    #[must_use]
+    pub fn edges(&self) -> Vec<f64> {
+        let width = self.bin_width();
+        (0..=self.bins)
+            .map(|index| width.mul_add(index as f64, self.range.min()))
+            .collect()
+    }
+
     /// Determine the corresponding bin.
     #[inline]
     #[must_use]
@@ -56,6 +134,26 @@ impl Binner {
 impl Display for Binner {
     #[inline]
     fn fmt(&self, fmt: &mut Formatter) -> Result<(), Error> {
-        write!(fmt, "[{}] ({})", self.range, self.bins)
+        write!(fmt, "[{}] ({}, {})", self.range, self.bins, self.policy())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bin_centres_and_edges() {
+        let binner = Binner::new(Range::new(400.0, 700.0), 3);
+
+        assert_eq!(binner.bin_edges(0), (400.0, 500.0));
+        assert_eq!(binner.bin_edges(2), (600.0, 700.0));
+        assert_eq!(binner.bin_centre(1), 550.0);
+        assert_eq!(binner.edges(), vec![400.0, 500.0, 600.0, 700.0]);
+
+        // Each centre maps back to its own bin.
+        for index in 0..binner.bins() {
+            assert_eq!(binner.bin(binner.bin_centre(index)), index);
+        }
     }
 }
@@ -3,6 +3,7 @@ pub mod index;
 pub mod progress_bar;
 pub mod range;
 pub mod silent_progress_bar;
+pub mod throughput;
 pub mod valid;
 
-pub use {binner::*, index::*, progress_bar::*, range::*, silent_progress_bar::*, valid::*};
+pub use {binner::*, index::*, progress_bar::*, range::*, silent_progress_bar::*, throughput::*, valid::*};
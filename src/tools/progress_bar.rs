@@ -1,5 +1,14 @@
 //! Progress-Bar implementation.
 
+use crate::tools::Throughput;
+use std::time::{Duration, Instant};
+
+/// Default number of samples used to estimate the throughput.
+const THROUGHPUT_WINDOW: usize = 32;
+
+/// Minimum time between refreshes of the throughput and ETA message.
+const MESSAGE_INTERVAL: Duration = Duration::from_millis(250);
+
 /// Progress-bar structure.
 pub struct ProgressBar {
     /// Graphics.
@@ -8,6 +17,14 @@ pub struct ProgressBar {
     count: usize,
     /// Total target value.
     total: usize,
+    /// Message displayed alongside the bar.
+    msg: &'static str,
+    /// Time at which the bar was created.
+    start: Instant,
+    /// Recent throughput.
+    throughput: Throughput,
+    /// Time since creation at which the message was last refreshed.
+    refreshed: Duration,
 }
 
 impl ProgressBar {
@@ -21,7 +38,7 @@ impl ProgressBar {
 
         pb.set_style(
             indicatif::ProgressStyle::default_bar()
-            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.green/red}] [{pos}/{len}] {percent}% ({eta}) {msg}")
+            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.green/red}] [{pos}/{len}] {percent}% {msg}")
             .progress_chars("\\/")
         );
         pb.set_message(msg);
@@ -30,14 +47,58 @@ impl ProgressBar {
             pb,
             count: 0,
             total,
+            msg,
+            start: Instant::now(),
+            throughput: Throughput::new(THROUGHPUT_WINDOW),
+            refreshed: Duration::from_secs(0),
         }
     }
 
+    /// Set the number of recent updates used to estimate the throughput.
+    #[inline]
+    #[must_use]
+    pub fn with_window(mut self, window: usize) -> Self {
+        self.throughput = Throughput::new(window);
+        self
+    }
+
     /// Tick the bar forward a single increment.
     #[inline]
     pub fn tick(&mut self) {
         self.count += 1;
         self.pb.inc(1);
+        self.update();
+    }
+
+    /// Record the current progress, and refresh the throughput and ETA display
+    /// if it has not been refreshed within the message interval.
+    #[inline]
+    fn update(&mut self) {
+        self.update_at(self.start.elapsed());
+    }
+
+    /// Record the current progress at the given time since creation,
+    /// refreshing the display if it has not been refreshed within the message interval.
+    #[inline]
+    fn update_at(&mut self, elapsed: Duration) {
+        self.throughput.record(elapsed, self.count);
+
+        if elapsed < self.refreshed + MESSAGE_INTERVAL {
+            return;
+        }
+        self.refreshed = elapsed;
+
+        if let (Some(rate), Some(eta)) = (
+            self.throughput.rate(),
+            self.throughput.eta(self.total.saturating_sub(self.count)),
+        ) {
+            self.pb.set_message(format!(
+                "({:.0}/s, ETA {}s) {}",
+                rate,
+                eta.as_secs(),
+                self.msg
+            ));
+        }
     }
 
     /// Request a block of values to work on.
@@ -59,6 +120,7 @@ impl ProgressBar {
 
             self.count += alloc;
             self.pb.inc(alloc as u64);
+            self.update();
 
             Some((start, end))
         }
@@ -109,6 +171,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_message_throttled() {
+        let msg = "test";
+        let total = 1000;
+        let mut pb = ProgressBar::new(msg, total);
+
+        // Rapid updates do not refresh the message until the interval has passed.
+        for n in 1..=(total / 2) {
+            pb.count = n;
+            pb.update_at(Duration::from_micros(n as u64));
+        }
+        assert_eq!(pb.refreshed, Duration::from_secs(0));
+
+        pb.update_at(MESSAGE_INTERVAL);
+        assert_eq!(pb.refreshed, MESSAGE_INTERVAL);
+
+        pb.update_at(MESSAGE_INTERVAL + (MESSAGE_INTERVAL / 2));
+        assert_eq!(pb.refreshed, MESSAGE_INTERVAL);
+
+        pb.update_at(MESSAGE_INTERVAL * 2);
+        assert_eq!(pb.refreshed, MESSAGE_INTERVAL * 2);
+    }
+
     #[test]
     fn test_block() {
         let msg = "test";
@@ -0,0 +1,84 @@
+//! Rolling throughput estimator.
+
+use std::{collections::VecDeque, time::Duration};
+
+/// Estimates the rate of progress from a rolling window of recent samples.
+#[derive(Clone, Debug)]
+pub struct Throughput {
+    /// Maximum number of samples retained.
+    window: usize,
+    /// Recent samples of elapsed time and completed count.
+    samples: VecDeque<(Duration, usize)>,
+}
+
+impl Throughput {
+    /// Construct a new instance retaining the given number of samples.
+    #[inline]
+    #[must_use]
+    pub fn new(window: usize) -> Self {
+        debug_assert!(window > 1);
+
+        Self {
+            window,
+            samples: VecDeque::with_capacity(window),
+        }
+    }
+
+    /// Record the completed count at a given elapsed time.
+    #[inline]
+    pub fn record(&mut self, elapsed: Duration, count: usize) {
+        if self.samples.len() == self.window {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((elapsed, count));
+    }
+
+    /// Rate of progress over the window [counts per second].
+    /// Returns None until two samples, separated in time, have been recorded.
+    #[inline]
+    #[must_use]
+    pub fn rate(&self) -> Option<f64> {
+        let (first_time, first_count) = self.samples.front()?;
+        let (last_time, last_count) = self.samples.back()?;
+
+        let secs = last_time.checked_sub(*first_time)?.as_secs_f64();
+        if secs <= 0.0 {
+            return None;
+        }
+
+        Some(last_count.saturating_sub(*first_count) as f64 / secs)
+    }
+
+    /// Estimated time to complete the remaining count, at the current rate.
+    #[inline]
+    #[must_use]
+    pub fn eta(&self, remaining: usize) -> Option<Duration> {
+        let rate = self.rate()?;
+        if rate <= 0.0 {
+            return None;
+        }
+
+        Some(Duration::from_secs_f64(remaining as f64 / rate))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Throughput;
+    use assert_approx_eq::assert_approx_eq;
+    use std::time::Duration;
+
+    #[test]
+    fn test_constant_throughput_eta() {
+        let mut throughput = Throughput::new(4);
+        assert!(throughput.eta(100).is_none());
+
+        // 50 counts per second, over more samples than the window holds.
+        for n in 0..10 {
+            throughput.record(Duration::from_millis(200 * n), 10 * n as usize);
+        }
+
+        assert_approx_eq!(throughput.rate().unwrap(), 50.0, 1.0e-9);
+        assert_approx_eq!(throughput.eta(400).unwrap().as_secs_f64(), 8.0, 1.0e-9);
+    }
+}
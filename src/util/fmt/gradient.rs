@@ -1,13 +1,13 @@
 //! Gradient formatting function.
 
+use crate::img::Gradient;
 use ansi_rgb::Background;
-use palette::{Gradient, LinSrgba};
 use rgb::RGB8;
 
 /// Create a string of a gradients colour.
 #[inline]
 #[must_use]
-pub fn to_string(grad: &Gradient<LinSrgba>, len: usize) -> String {
+pub fn to_string(grad: &Gradient, len: usize) -> String {
     let mut scale = String::new();
 
     for i in 0..len {
@@ -1 +1,3 @@
 mod pi_estimator;
+mod reproducible_images;
+mod run_from_config;
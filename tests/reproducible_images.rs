@@ -0,0 +1,66 @@
+use std::fs::write;
+use Aetherus as aether;
+
+/// Run a seeded, multi-threaded simulation twice, and check that the images match bit-for-bit.
+///
+/// A laser fires up into a scattering medium, beneath an imager plane,
+/// so that many photons, simulated by different threads, land in each pixel.
+#[test]
+fn reproducible_images() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path().join("plane.obj"),
+        "v -1.0 -1.0 0.5\nv 3.0 -1.0 0.5\nv -1.0 3.0 0.5\nvn 0.0 0.0 1.0\nf 1//1 2//1 3//1\n",
+    )
+    .unwrap();
+    write(
+        dir.path().join("parameters.json5"),
+        r#"{
+    sett: { Here: {
+        num_threads: 4,
+        num_phot: 20000,
+        block_size: 50,
+        bump_dist: 1e-6,
+        loop_limit: 1000,
+        min_weight: 0.0,
+        roulette_barrels: 2,
+        seed: 42,
+    } },
+    tree: { Here: { tar_tris: 1, max_depth: 1, padding: 0.01 } },
+    grid: { Here: { boundary: { mins: [-1, -1, -1], maxs: [1, 1, 1] }, res: [1, 1, 1] } },
+    surfs: { Here: { detector: { mesh: [["plane.obj"], null], attr: "cam" } } },
+    attrs: { Here: { cam: { Imager: ["cam", [4, 4], 1.0, [0, 0, 0.5], [0, 0, -1]] } } },
+    mats: { Here: { fog: { Here: {
+        ref_index: { Constant: 1.0 },
+        scat_coeff: { Constant: 5.0 },
+        asym_fact: { Constant: 0.0 },
+    } } } },
+    lights: { Here: { laser: {
+        power: 1.0,
+        emit: { Beam: [[0, 0, 0], [0, 0, 1]] },
+        spec: { Here: { Point: 5e-7 } },
+        mat: "fog",
+    } } },
+    engine: "Standard",
+}"#,
+    )
+    .unwrap();
+
+    let path = dir.path().join("parameters.json5");
+    let name = aether::ord::Name::new("img_cam");
+    let pixels = || {
+        let reg = aether::sim::run::run_from_config(&path).unwrap();
+        let id = reg.id(&name).expect("Missing imager output.");
+        reg.get_imager(id).unwrap().pixels().clone()
+    };
+
+    let first = pixels();
+    let second = pixels();
+    assert!(first.iter().any(|col| col.green > 0.0));
+    for (a, b) in first.iter().zip(second.iter()) {
+        assert_eq!(a.red.to_bits(), b.red.to_bits());
+        assert_eq!(a.green.to_bits(), b.green.to_bits());
+        assert_eq!(a.blue.to_bits(), b.blue.to_bits());
+        assert_eq!(a.alpha.to_bits(), b.alpha.to_bits());
+    }
+}
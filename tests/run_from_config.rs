@@ -0,0 +1,82 @@
+use std::fs::write;
+use Aetherus as aether;
+
+/// Run a minimal simulation from a parameters file, without writing any output.
+///
+/// A single laser fires straight up through a vacuum-like material into a
+/// spectrometer plane, so every emitted photon should be collected by the
+/// spectrometer with unit weight.
+#[test]
+fn run_from_config() {
+    let num_phot = 1000;
+
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path().join("plane.obj"),
+        "v -1.0 -1.0 0.5\nv 3.0 -1.0 0.5\nv -1.0 3.0 0.5\nvn 0.0 0.0 1.0\nf 1//1 2//1 3//1\n",
+    )
+    .unwrap();
+    write(
+        dir.path().join("parameters.json5"),
+        format!(
+            r#"{{
+    sett: {{ Here: {{
+        num_threads: 1,
+        num_phot: {},
+        block_size: 100,
+        bump_dist: 1e-6,
+        loop_limit: 1000,
+        min_weight: 0.0,
+        roulette_barrels: 2,
+    }} }},
+    tree: {{ Here: {{ tar_tris: 1, max_depth: 1, padding: 0.01 }} }},
+    grid: {{ Here: {{ boundary: {{ mins: [-1, -1, -1], maxs: [1, 1, 1] }}, res: [1, 1, 1] }} }},
+    surfs: {{ Here: {{ detector: {{ mesh: [["plane.obj"], null], attr: "spec" }} }} }},
+    attrs: {{ Here: {{ spec: {{ Spectrometer: ["spec", [4e-7, 6e-7], 10] }} }} }},
+    mats: {{ Here: {{ air: {{ Here: {{
+        ref_index: {{ Constant: 1.0 }},
+        scat_coeff: {{ Constant: 0.0 }},
+        asym_fact: {{ Constant: 0.0 }},
+    }} }} }} }},
+    lights: {{ Here: {{ laser: {{
+        power: 1.0,
+        emit: {{ Beam: [[0, 0, 0], [0, 0, 1]] }},
+        spec: {{ Here: {{ Point: 5e-7 }} }},
+        mat: "air",
+    }} }} }},
+    engine: "Standard",
+    planes: {{ Here: {{ depths: {{
+        orient: {{ pos: [0, 0, 0.25], yaw: 0, pitch: 90 }},
+        size: [1, 1],
+        offsets: [0.0, 0.1],
+        res: [2, 2],
+    }} }} }},
+}}"#,
+            num_phot
+        ),
+    )
+    .unwrap();
+
+    let reg = aether::sim::run::run_from_config(&dir.path().join("parameters.json5")).unwrap();
+
+    let spec_id = reg
+        .id(&aether::ord::Name::new("spectrometer_spec"))
+        .expect("Missing spectrometer output.");
+    let spec = reg.get_spectrometer(spec_id).unwrap();
+    assert!((spec.counts().sum() - num_phot as f64).abs() < 1.0e-6);
+
+    for name in &["emission_density", "energy_density", "absorption_density"] {
+        let id = reg
+            .id(&aether::ord::Name::new(name))
+            .expect("Missing volume output.");
+        assert!(reg.get_volume(id).is_some());
+    }
+
+    // Every photon crosses both layers of the plane stack on its way to the spectrometer.
+    let stacks = reg.ids_of_type(aether::sim::OutputType::PlaneStack);
+    assert_eq!(stacks.len(), 1);
+    let stack = reg.get_plane_stack(stacks[0]).unwrap();
+    assert_eq!(stack.num_layers(), 2);
+    assert!(stack.layers[0].sum() > 0.0);
+    assert!((stack.layers[0].sum() - stack.layers[1].sum()).abs() < 1.0e-9);
+}